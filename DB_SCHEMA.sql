@@ -3,6 +3,7 @@
 
 -- Prices (candles)
 CREATE TABLE IF NOT EXISTS prices (
+    exchange TEXT NOT NULL,
     symbol TEXT NOT NULL,
     interval TEXT NOT NULL,
     time TIMESTAMPTZ NOT NULL,
@@ -11,7 +12,7 @@ CREATE TABLE IF NOT EXISTS prices (
     low DOUBLE PRECISION NOT NULL,
     close DOUBLE PRECISION NOT NULL,
     volume DOUBLE PRECISION NOT NULL,
-    PRIMARY KEY (symbol, interval, time)
+    PRIMARY KEY (exchange, symbol, interval, time)
 );
 
 -- Orders
@@ -19,6 +20,7 @@ CREATE TABLE IF NOT EXISTS orders (
     id TEXT PRIMARY KEY,
     time TIMESTAMPTZ NOT NULL,
     mode TEXT NOT NULL CHECK (mode IN ('backtest', 'paper', 'live')),
+    account TEXT NOT NULL DEFAULT 'default',
     symbol TEXT NOT NULL,
     side TEXT NOT NULL CHECK (side IN ('buy', 'sell')),
     order_type TEXT NOT NULL CHECK (order_type IN ('market', 'limit')),
@@ -34,6 +36,7 @@ CREATE TABLE IF NOT EXISTS trades (
     id TEXT PRIMARY KEY,
     order_id TEXT NOT NULL REFERENCES orders(id),
     time TIMESTAMPTZ NOT NULL,
+    account TEXT NOT NULL DEFAULT 'default',
     symbol TEXT NOT NULL,
     side TEXT NOT NULL CHECK (side IN ('buy', 'sell')),
     price DOUBLE PRECISION NOT NULL,
@@ -46,20 +49,22 @@ CREATE TABLE IF NOT EXISTS trades (
 CREATE TABLE IF NOT EXISTS positions (
     time TIMESTAMPTZ NOT NULL,
     mode TEXT NOT NULL CHECK (mode IN ('backtest', 'paper', 'live')),
+    account TEXT NOT NULL DEFAULT 'default',
     symbol TEXT NOT NULL,
     qty DOUBLE PRECISION NOT NULL,
     avg_price DOUBLE PRECISION NOT NULL,
-    PRIMARY KEY (time, mode, symbol)
+    PRIMARY KEY (time, mode, account, symbol)
 );
 
 -- Balances (snapshots)
 CREATE TABLE IF NOT EXISTS balances (
     time TIMESTAMPTZ NOT NULL,
     mode TEXT NOT NULL CHECK (mode IN ('backtest', 'paper', 'live')),
+    account TEXT NOT NULL DEFAULT 'default',
     asset TEXT NOT NULL,
     free DOUBLE PRECISION NOT NULL,
     locked DOUBLE PRECISION NOT NULL,
-    PRIMARY KEY (time, mode, asset)
+    PRIMARY KEY (time, mode, account, asset)
 );
 
 -- Backtest runs
@@ -91,7 +96,52 @@ CREATE TABLE IF NOT EXISTS signals (
     PRIMARY KEY (time, mode, symbol)
 );
 
+-- Account tagging for databases created before multi-account support
+-- 舊版資料庫補上帳戶欄位
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS account TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS account TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS account TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE balances ADD COLUMN IF NOT EXISTS account TEXT NOT NULL DEFAULT 'default';
+DROP INDEX IF EXISTS positions_account_key;
+DROP INDEX IF EXISTS balances_account_key;
+
+-- Rebuild legacy snapshot primary keys to include the account
+-- 重建舊版快照主鍵以納入帳戶
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.key_column_usage
+        WHERE table_name = 'positions' AND constraint_name = 'positions_pkey' AND column_name = 'account'
+    ) THEN
+        ALTER TABLE positions DROP CONSTRAINT IF EXISTS positions_pkey;
+        ALTER TABLE positions ADD PRIMARY KEY (time, mode, account, symbol);
+    END IF;
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.key_column_usage
+        WHERE table_name = 'balances' AND constraint_name = 'balances_pkey' AND column_name = 'account'
+    ) THEN
+        ALTER TABLE balances DROP CONSTRAINT IF EXISTS balances_pkey;
+        ALTER TABLE balances ADD PRIMARY KEY (time, mode, account, asset);
+    END IF;
+END $$;
+
+-- Exchange tagging for candles stored before per-exchange prices, legacy rows keep 'default'
+-- 舊版 K 線補上交易所欄位，既有資料標為 'default'
+ALTER TABLE prices ADD COLUMN IF NOT EXISTS exchange TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE prices ALTER COLUMN exchange DROP DEFAULT;
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.key_column_usage
+        WHERE table_name = 'prices' AND constraint_name = 'prices_pkey' AND column_name = 'exchange'
+    ) THEN
+        ALTER TABLE prices DROP CONSTRAINT IF EXISTS prices_pkey;
+        ALTER TABLE prices ADD PRIMARY KEY (exchange, symbol, interval, time);
+    END IF;
+END $$;
+
 -- Indexes
 CREATE INDEX IF NOT EXISTS prices_time_idx ON prices (time);
 CREATE INDEX IF NOT EXISTS orders_symbol_time_idx ON orders (symbol, time);
 CREATE INDEX IF NOT EXISTS trades_symbol_time_idx ON trades (symbol, time);
+CREATE INDEX IF NOT EXISTS orders_account_time_idx ON orders (account, time);
@@ -1,22 +1,55 @@
 use crate::app::report::write_output;
-use crate::backtest::BacktestEngine;
-use crate::config::Config;
-use crate::core::build_engine_bundle;
-use crate::data::csv_loader::{load_candles_from_csv, parse_time};
-use crate::data::exchange_loader::load_candles_from_exchange;
+use crate::backtest::{run_routed, BacktestEngine, BacktestOrder, BacktestResult, BacktestVenue};
+use crate::config::{AccountConfig, Config};
+use crate::core::circuit_breaker::{
+    load_breaker_state, save_breaker_state, BreakerState, HaltReason, KillSwitch,
+};
+use crate::core::execution::{
+    load_execution_state, save_execution_state, ExecutionAlgo, ExecutionScheduler,
+};
+use crate::core::order_builder::OrderBuilder;
+use crate::core::order_router::{
+    load_route_state, save_route_state, RouterConfig, RouterVenue, SmartOrderRouter,
+};
+use crate::core::journal::{IntentJournal, IntentOutcome};
+use crate::core::reconciliation::{
+    load_expected_state, reconcile, save_expected_state, save_reconciliation_report,
+    ExpectedState, ReconcileScope,
+};
+use crate::core::repricing::RepricePolicy;
+use crate::core::{build_engine_bundle, EngineBundle};
+use crate::data::bars::{build_bars, BarKind, BarSpec};
+use crate::data::csv_loader::parse_time;
+use crate::data::exchange_loader::parse_interval_seconds;
+use crate::data::market_data::{provider_from_config, ExchangeProvider, MarketDataProvider};
+use crate::data::fetch::{run_fetch_job, CandleSink, CsvSink, FetchJob};
+use crate::data::quality::{check_candles, forward_fill, GapPolicy, QualityThresholds};
+use crate::data::trade_loader::load_backtest_trades;
+use crate::data::resample::build_timeframes;
 use crate::exchange::Exchange;
 use crate::exchange::binance::{BinanceConfig, BinanceExchange};
+use crate::exchange::binance_futures::{BinanceFuturesConfig, BinanceFuturesExchange};
 use crate::exchange::bybit::{BybitConfig, BybitExchange};
+use crate::exchange::coinbase::{CoinbaseConfig, CoinbaseExchange};
+use crate::exchange::kraken::{KrakenConfig, KrakenExchange};
 use crate::exchange::okx::{OkxConfig, OkxExchange};
+use crate::exchange::rest::{RestExchange, RestExchangeConfig};
+use crate::exchange::open_orders::{cancel_orders, classify_open_orders, OrphanPolicy};
 use crate::exchange::sync::sync_account;
-use crate::exchange::CandleRequest;
+use crate::exchange::time_sync::TimeSyncConfig;
+use crate::exchange::{CandleRequest, OrderCheck};
 use crate::core::strategy::Strategy;
 use crate::paper::run_paper_with_state;
 use crate::app::metrics;
+use crate::models::{Candle, OrderAck, OrderRequest, OrderStatus, OrderType};
+use crate::storage::keystore::{Credential, Keystore};
 use crate::storage::postgres::PostgresStorage;
 use crate::{Error, Result};
 use std::sync::OnceLock;
 use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
 use std::thread::sleep;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{info, warn};
@@ -50,53 +83,68 @@ pub fn run() -> Result<()> {
         let _ = CLI_PG_ENABLED_OVERRIDE.set(value);
     }
 
-    if config.mode == "backtest" {
-        let mut bundle = build_engine_bundle(&config)?;
-        let candles = match config.data.source.as_str() {
-            "csv" => {
-                let path = config
-                    .data
-                    .csv_path
-                    .as_ref()
-                    .ok_or_else(|| Error::new("data.csv_path must be set"))?;
-                load_candles_from_csv(path)?
-            }
-            "exchange" => load_candles_from_exchange(&config)?,
-            _ => return Err(Error::new("unknown data source")),
-        };
+    if cli.reset_halt {
+        save_breaker_state(&config.circuit_breaker.state_path, &BreakerState::default())?;
+        println!("circuit_breaker_reset: {}", config.circuit_breaker.state_path);
+    }
 
-        let start = parse_time(
-            config
-                .backtest
-                .start_time
-                .as_ref()
-                .ok_or_else(|| Error::new("backtest.start_time must be set"))?,
-        )?;
-        let end = parse_time(
-            config
-                .backtest
-                .end_time
-                .as_ref()
-                .ok_or_else(|| Error::new("backtest.end_time must be set"))?,
-        )?;
-        if start > end {
-            return Err(Error::new("backtest.start_time must be <= end_time"));
-        }
+    if let CliCommand::Keystore(action) = cli.command {
+        return run_keystore(&config, action, cli.exchange_override, cli.keystore_label);
+    }
 
-        let filtered: Vec<_> = candles
-            .into_iter()
-            .filter(|candle| candle.time >= start && candle.time <= end)
-            .collect();
+    if cli.command == CliCommand::DataCheck {
+        return run_data_check(&config);
+    }
 
-        let engine = BacktestEngine;
-        let result = engine.run_strategy(
-            &filtered,
+    if cli.command == CliCommand::Fetch {
+        return run_fetch(&config, cli.exchange_override, &cli.fetch);
+    }
+
+    if cli.command == CliCommand::CancelAll {
+        return run_live_accounts(
             &config,
-            &bundle.trigger_engine,
-            &mut bundle.strategy,
-            &mut bundle.order_flow,
-            config.backtest.initial_cash,
-        )?;
+            false,
+            LiveAction::CancelAll {
+                include_foreign: cli.include_foreign,
+            },
+            cli.account.as_deref(),
+        );
+    }
+
+    if config.mode == "backtest" {
+        let mut bundle = build_engine_bundle(&config)?;
+        let engine = BacktestEngine;
+        let (prices, result) = if config.backtest.granularity == "trade" {
+            let prints = load_backtest_trades(&config)?;
+            info!(
+                prints = prints.len(),
+                latency_ms = config.backtest.latency_ms,
+                "trade_prints_loaded"
+            );
+            let tick = engine.run_strategy_on_prints(
+                &prints,
+                &config,
+                &bundle.trigger_engine,
+                &mut bundle.strategy,
+                &mut bundle.order_flow,
+                config.backtest.initial_cash,
+            )?;
+            (tick.bars, tick.result)
+        } else {
+            let (prices, filtered) = load_backtest_bars(&config)?;
+            let result = engine.run_strategy(
+                &filtered,
+                &config,
+                &bundle.trigger_engine,
+                &mut bundle.strategy,
+                &mut bundle.order_flow,
+                config.backtest.initial_cash,
+            )?;
+            if !config.routing.venues.is_empty() {
+                run_routed_backtest(&config, &result)?;
+            }
+            (prices, result)
+        };
 
         println!("trades: {}", result.metrics.trade_count);
         println!("return_rate: {:.6}", result.metrics.return_rate);
@@ -132,6 +180,18 @@ pub fn run() -> Result<()> {
             println!("trade_pnls: [{pnl_list}]");
         }
 
+        let filled: Vec<_> = result
+            .executions
+            .iter()
+            .filter(|report| report.filled_qty > 0.0)
+            .collect();
+        if !filled.is_empty() {
+            let average_shortfall = filled.iter().map(|report| report.shortfall_bps).sum::<f64>()
+                / filled.len() as f64;
+            println!("execution_algo: {}", config.execution.algo);
+            println!("shortfall_bps_average: {:.6}", average_shortfall);
+        }
+
         if config.output.format != "none" {
             write_output(&config.output.path, &config.output.format, &result)?;
             println!(
@@ -143,39 +203,115 @@ pub fn run() -> Result<()> {
         metrics::record_backtest(&result.metrics, result.trades.len());
         metrics::write_if_configured()?;
 
-        maybe_persist_backtest(&config, &filtered, &result)?;
+        maybe_persist_backtest(&config, &prices, &result)?;
     } else if config.mode == "paper" {
         run_paper_mode(&config)?;
     } else if config.mode == "live" {
-        run_live(&config, cli.live_execute)?;
+        run_live_accounts(&config, cli.live_execute, LiveAction::Cycle, cli.account.as_deref())?;
     }
     Ok(())
 }
 
 static CLI_PG_ENABLED_OVERRIDE: OnceLock<bool> = OnceLock::new();
+static LIVE_KEYSTORE: OnceLock<Keystore> = OnceLock::new();
+
+const KEYSTORE_EXCHANGES: [&str; 5] = ["binance", "bybit", "coinbase", "kraken", "okx"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CliCommand {
+    Run,
+    CancelAll,
+    Fetch,
+    DataCheck,
+    Keystore(KeystoreAction),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum KeystoreAction {
+    Add,
+    List,
+    Remove,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LiveAction {
+    Cycle,
+    CancelAll { include_foreign: bool },
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct FetchArgs {
+    symbols: Vec<String>,
+    intervals: Vec<String>,
+    ranges: Vec<(String, String)>,
+    sink: Option<String>,
+    dir: Option<String>,
+}
 
 struct CliArgs {
+    command: CliCommand,
     config_path: String,
     symbol_override: Option<String>,
     output_format: Option<String>,
     output_path: Option<String>,
     initial_cash_override: Option<f64>,
     live_execute: bool,
+    reset_halt: bool,
     pg_enabled_override: Option<bool>,
+    include_foreign: bool,
+    exchange_override: Option<String>,
+    keystore_label: Option<String>,
+    account: Option<String>,
+    fetch: FetchArgs,
     show_help: bool,
 }
 
 fn parse_args(args: &[String]) -> Result<CliArgs> {
+    let mut command = CliCommand::Run;
     let mut config_path = "config.toml".to_string();
     let mut symbol_override = None;
     let mut output_format = None;
     let mut output_path = None;
     let mut initial_cash_override = None;
     let mut live_execute = false;
+    let mut reset_halt = false;
     let mut pg_enabled_override = None;
+    let mut include_foreign = false;
+    let mut exchange_override = None;
+    let mut keystore_label = None;
+    let mut account = None;
+    let mut fetch = FetchArgs::default();
     let mut show_help = false;
 
     let mut index = 1;
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("cancel-all") => {
+            command = CliCommand::CancelAll;
+            index = 2;
+        }
+        Some("fetch") => {
+            command = CliCommand::Fetch;
+            index = 2;
+        }
+        Some("data") => {
+            if args.get(2).map(|arg| arg.as_str()) != Some("check") {
+                return Err(Error::new("data requires check"));
+            }
+            command = CliCommand::DataCheck;
+            index = 3;
+        }
+        Some("keystore") => {
+            let action = match args.get(2).map(|arg| arg.as_str()) {
+                Some("add") => KeystoreAction::Add,
+                Some("list") => KeystoreAction::List,
+                Some("remove") => KeystoreAction::Remove,
+                _ => return Err(Error::new("keystore requires add, list, or remove")),
+            };
+            command = CliCommand::Keystore(action);
+            index = 3;
+        }
+        _ => {}
+    }
     while index < args.len() {
         match args[index].as_str() {
             "--help" | "-h" => {
@@ -224,6 +360,77 @@ fn parse_args(args: &[String]) -> Result<CliArgs> {
                 live_execute = true;
                 index += 1;
             }
+            "--reset-halt" => {
+                reset_halt = true;
+                index += 1;
+            }
+            "--include-foreign" => {
+                include_foreign = true;
+                index += 1;
+            }
+            "--exchange" => {
+                let value = args
+                    .get(index + 1)
+                    .ok_or_else(|| Error::new("missing value for --exchange"))?;
+                exchange_override = Some(value.to_lowercase());
+                index += 2;
+            }
+            "--account" => {
+                let value = args
+                    .get(index + 1)
+                    .ok_or_else(|| Error::new("missing value for --account"))?;
+                account = Some(value.to_string());
+                index += 2;
+            }
+            "--label" => {
+                let value = args
+                    .get(index + 1)
+                    .ok_or_else(|| Error::new("missing value for --label"))?;
+                keystore_label = Some(value.to_string());
+                index += 2;
+            }
+            "--symbols" => {
+                let value = args
+                    .get(index + 1)
+                    .ok_or_else(|| Error::new("missing value for --symbols"))?;
+                fetch.symbols.extend(split_list(value));
+                index += 2;
+            }
+            "--intervals" => {
+                let value = args
+                    .get(index + 1)
+                    .ok_or_else(|| Error::new("missing value for --intervals"))?;
+                fetch.intervals.extend(split_list(value));
+                index += 2;
+            }
+            "--range" => {
+                let value = args
+                    .get(index + 1)
+                    .ok_or_else(|| Error::new("missing value for --range"))?;
+                let (start, end) = value
+                    .split_once("..")
+                    .ok_or_else(|| Error::new("--range must look like <start>..<end>"))?;
+                fetch.ranges.push((start.trim().to_string(), end.trim().to_string()));
+                index += 2;
+            }
+            "--to" => {
+                let value = args
+                    .get(index + 1)
+                    .ok_or_else(|| Error::new("missing value for --to"))?;
+                let value = value.to_lowercase();
+                if value != "csv" && value != "postgres" {
+                    return Err(Error::new("--to must be csv or postgres"));
+                }
+                fetch.sink = Some(value);
+                index += 2;
+            }
+            "--dir" => {
+                let value = args
+                    .get(index + 1)
+                    .ok_or_else(|| Error::new("missing value for --dir"))?;
+                fetch.dir = Some(value.to_string());
+                index += 2;
+            }
             "--pg-enabled" => {
                 let value = args
                     .get(index + 1)
@@ -237,28 +444,564 @@ fn parse_args(args: &[String]) -> Result<CliArgs> {
         }
     }
 
+    if include_foreign && command != CliCommand::CancelAll {
+        return Err(Error::new("--include-foreign requires the cancel-all command"));
+    }
+    if command != CliCommand::Fetch && fetch != FetchArgs::default() {
+        return Err(Error::new(
+            "--symbols, --intervals, --range, --to and --dir require the fetch command",
+        ));
+    }
+    match command {
+        CliCommand::Keystore(KeystoreAction::Add | KeystoreAction::Remove) => {
+            if exchange_override.is_none() {
+                return Err(Error::new("--exchange is required for keystore add/remove"));
+            }
+        }
+        CliCommand::Keystore(KeystoreAction::List) => {}
+        CliCommand::Fetch => {
+            if keystore_label.is_some() {
+                return Err(Error::new("--label requires the keystore command"));
+            }
+        }
+        _ => {
+            if exchange_override.is_some() {
+                return Err(Error::new("--exchange requires the keystore or fetch command"));
+            }
+            if keystore_label.is_some() {
+                return Err(Error::new("--label requires the keystore command"));
+            }
+        }
+    }
+
     Ok(CliArgs {
+        command,
         config_path,
         symbol_override,
         output_format,
         output_path,
         initial_cash_override,
         live_execute,
+        reset_halt,
         pg_enabled_override,
+        include_foreign,
+        exchange_override,
+        keystore_label,
+        account,
+        fetch,
         show_help,
     })
 }
 
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| item.to_string())
+        .collect()
+}
+
 fn print_usage() {
-    println!("usage: merrow [--config <path>] [--symbol <SYMBOL>] [--output-format <fmt>] [--output-path <path>] [--initial-cash <amount>] [--live-execute] [--pg-enabled <bool>]");
+    println!("usage: merrow [--config <path>] [--symbol <SYMBOL>] [--output-format <fmt>] [--output-path <path>] [--initial-cash <amount>] [--live-execute] [--reset-halt] [--pg-enabled <bool>] [--account <name>]");
     println!("  -c, --config   Path to config.toml (default: config.toml)");
     println!("  -s, --symbol   Override symbol from config");
-    println!("  -f, --output-format   Override output format (none|json|csv)");
+    println!("  -f, --output-format   Override output format (none|json|csv|parquet)");
     println!("  -o, --output-path     Override output path");
     println!("  -i, --initial-cash    Override backtest initial cash");
     println!("      --live-execute    Execute live orders (default: dry-run)");
+    println!("      --reset-halt      Clear persisted circuit breaker halt state");
     println!("      --pg-enabled      Enable PGSQL persistence (true/false)");
+    println!("      --account         Run only this [[accounts]] profile in live mode");
     println!("  -h, --help     Show this help");
+    println!();
+    println!("usage: merrow cancel-all [--config <path>] [--symbol <SYMBOL>] [--account <name>] [--include-foreign]");
+    println!("  Cancel every open order carrying orders.client_id_prefix and stop working parents");
+    println!("      --include-foreign Also cancel open orders not placed by merrow");
+    println!();
+    println!("usage: merrow keystore <add|list|remove> [--config <path>] [--exchange <name>] [--label <label>]");
+    println!("  Manage encrypted exchange credentials in keystore.path");
+    println!("      --exchange        Exchange the credential belongs to (binance|bybit|coinbase|kraken|okx)");
+    println!("      --label           Account label (default: keystore.label)");
+    println!();
+    println!("usage: merrow fetch [--config <path>] [--exchange <name>] [--symbols <A,B>] [--intervals <1h,4h>] [--range <start>..<end>]... [--to <csv|postgres>] [--dir <path>]");
+    println!("  Download historical candles; reruns resume after the last stored candle");
+    println!("      --exchange        Data source (default: exchange from config)");
+    println!("      --symbols         Comma-separated symbols (default: symbol from config)");
+    println!("      --intervals       Comma-separated intervals (default: data.candle_interval)");
+    println!("      --range           Time range, repeatable (default: backtest.start_time..end_time)");
+    println!("      --to              Write csv files or the postgres prices table (default: csv)");
+    println!("      --dir             Directory for csv files (default: data)");
+    println!();
+    println!("usage: merrow data check [--config <path>] [--symbol <SYMBOL>]");
+    println!("  Report gaps, misaligned bars, zero-volume and stale stretches and price spikes in the backtest data");
+}
+
+fn load_backtest_candles(config: &Config) -> Result<Vec<Candle>> {
+    let start = parse_time(
+        config
+            .backtest
+            .start_time
+            .as_ref()
+            .ok_or_else(|| Error::new("backtest.start_time must be set"))?,
+    )?;
+    let end = parse_time(
+        config
+            .backtest
+            .end_time
+            .as_ref()
+            .ok_or_else(|| Error::new("backtest.end_time must be set"))?,
+    )?;
+    if start > end {
+        return Err(Error::new("backtest.start_time must be <= end_time"));
+    }
+
+    provider_from_config(config)?.load_candles(
+        &config.symbol,
+        &config.data.candle_interval,
+        start,
+        end,
+    )
+}
+
+fn load_backtest_bars(config: &Config) -> Result<(Vec<Candle>, Vec<Candle>)> {
+    let loaded = load_backtest_candles(config)?;
+    let candles = apply_gap_policy(config, loaded.clone())?;
+    Ok((loaded, apply_bars(config, candles)?))
+}
+
+fn apply_gap_policy(config: &Config, candles: Vec<Candle>) -> Result<Vec<Candle>> {
+    let interval_secs = parse_interval_seconds(&config.data.candle_interval)? as i64;
+    let report = check_candles(
+        &candles,
+        interval_secs,
+        &QualityThresholds::from_config(&config.data),
+    );
+    if report.outlier_count() > 0 {
+        warn!(
+            zero_volume_runs = report.zero_volume_runs.len(),
+            stale_runs = report.stale_runs.len(),
+            spikes = report.spikes.len(),
+            "data_quality_outliers"
+        );
+    }
+    if !report.has_holes() {
+        return Ok(candles);
+    }
+    match GapPolicy::parse(&config.data.gap_policy)? {
+        GapPolicy::Fail => Err(Error::new(format!(
+            "data has {} missing bars in {} gaps and {} misaligned bars; run `merrow data check` or set data.gap_policy to fill or warn",
+            report.missing_bars(),
+            report.gaps.len(),
+            report.misaligned.len()
+        ))),
+        GapPolicy::Fill => {
+            let filled = forward_fill(&candles, interval_secs);
+            warn!(
+                gaps = report.gaps.len(),
+                filled = filled.len() - candles.len(),
+                "data_gaps_forward_filled"
+            );
+            Ok(filled)
+        }
+        GapPolicy::Warn => {
+            warn!(
+                gaps = report.gaps.len(),
+                missing = report.missing_bars(),
+                misaligned = report.misaligned.len(),
+                "data_gaps_ignored"
+            );
+            Ok(candles)
+        }
+    }
+}
+
+fn run_routed_backtest(config: &Config, result: &BacktestResult) -> Result<()> {
+    if config.data.source != "exchange" {
+        return Err(Error::new("routed backtest requires data.source=exchange"));
+    }
+    let cash_asset = live_cash_asset(config)?;
+    let quote_share = config.backtest.initial_cash / config.routing.venues.len() as f64;
+    let mut venues = Vec::with_capacity(config.routing.venues.len());
+    for venue in &config.routing.venues {
+        let name = venue.to_lowercase();
+        let venue_config = routing_venue_config(config, &name, &cash_asset);
+        venues.push(BacktestVenue {
+            candles: load_backtest_candles(&venue_config)?,
+            fee_rate: routing_venue_fee(config, venue),
+            slippage_bps: config.orders.slippage_bps,
+            quote_balance: quote_share,
+            base_balance: 0.0,
+            name,
+        });
+    }
+
+    let mut times: Vec<i64> = venues
+        .iter()
+        .flat_map(|venue| venue.candles.iter().map(|candle| candle.time))
+        .collect();
+    times.sort_unstable();
+    times.dedup();
+    let orders = result
+        .trades
+        .iter()
+        .enumerate()
+        .map(|(index, trade)| BacktestOrder {
+            submit_index: times
+                .partition_point(|time| *time < trade.time)
+                .saturating_sub(1),
+            order: OrderRequest {
+                client_order_id: format!("routed-{}", index + 1),
+                symbol: config.symbol.clone(),
+                side: trade.side.clone(),
+                order_type: OrderType::Market,
+                quantity: trade.quantity,
+            },
+        })
+        .collect();
+
+    let routed = run_routed(&venues, orders, config.routing.max_participation)?;
+    for balance in &routed.balances {
+        let trades = routed
+            .trades
+            .iter()
+            .filter(|trade| trade.venue == balance.venue)
+            .count();
+        println!(
+            "routed_venue: {} trades={} quote={:.6} base={:.6}",
+            balance.venue, trades, balance.quote_balance, balance.base_balance
+        );
+    }
+    let unrouted: f64 = routed.unrouted.iter().map(|(_, quantity)| quantity).sum();
+    println!("routed_unrouted_qty: {:.6}", unrouted);
+    Ok(())
+}
+
+fn apply_bars(config: &Config, candles: Vec<Candle>) -> Result<Vec<Candle>> {
+    let spec = BarSpec::from_config(&config.data)?;
+    if spec.kind == BarKind::Time {
+        return Ok(candles);
+    }
+    let bars = build_bars(&candles, spec);
+    info!(
+        bars = %config.data.bars,
+        bar_size = spec.size,
+        candles = candles.len(),
+        built = bars.len(),
+        "bars_built"
+    );
+    Ok(bars)
+}
+
+fn run_data_check(config: &Config) -> Result<()> {
+    let candles = load_backtest_candles(config)?;
+    let interval_secs = parse_interval_seconds(&config.data.candle_interval)? as i64;
+    let report = check_candles(
+        &candles,
+        interval_secs,
+        &QualityThresholds::from_config(&config.data),
+    );
+    println!(
+        "data_check: {} {} candles={} first={} last={}",
+        config.symbol,
+        config.data.candle_interval,
+        report.candles,
+        report.first.map(|time| time.to_string()).unwrap_or_else(|| "-".to_string()),
+        report.last.map(|time| time.to_string()).unwrap_or_else(|| "-".to_string())
+    );
+    for gap in &report.gaps {
+        println!("gap: after={} before={} missing={}", gap.after, gap.before, gap.missing);
+    }
+    for time in &report.misaligned {
+        println!("misaligned: time={time}");
+    }
+    for run in &report.zero_volume_runs {
+        println!("zero_volume: start={} end={} bars={}", run.start, run.end, run.bars);
+    }
+    for run in &report.stale_runs {
+        println!("stale_close: start={} end={} bars={}", run.start, run.end, run.bars);
+    }
+    for spike in &report.spikes {
+        println!(
+            "spike: time={} previous_close={} close={} ratio={:.4}",
+            spike.time, spike.previous_close, spike.close, spike.ratio
+        );
+    }
+    println!(
+        "data_check_summary: gaps={} missing_bars={} misaligned={} outliers={}",
+        report.gaps.len(),
+        report.missing_bars(),
+        report.misaligned.len(),
+        report.outlier_count()
+    );
+    if report.has_holes() {
+        return Err(Error::new("data check found holes in the candle series"));
+    }
+    Ok(())
+}
+
+fn run_fetch(config: &Config, exchange: Option<String>, args: &FetchArgs) -> Result<()> {
+    let exchange = exchange.unwrap_or_else(|| config.exchange.to_lowercase());
+    let symbols = if args.symbols.is_empty() {
+        vec![config.symbol.clone()]
+    } else {
+        args.symbols.clone()
+    };
+    let intervals = if args.intervals.is_empty() {
+        vec![config.data.candle_interval.clone()]
+    } else {
+        args.intervals.clone()
+    };
+    let mut ranges = Vec::new();
+    if args.ranges.is_empty() {
+        let start = config
+            .backtest
+            .start_time
+            .as_ref()
+            .ok_or_else(|| Error::new("fetch requires --range or backtest.start_time"))?;
+        let end = config
+            .backtest
+            .end_time
+            .as_ref()
+            .ok_or_else(|| Error::new("fetch requires --range or backtest.end_time"))?;
+        ranges.push((parse_time(start)?, parse_time(end)?));
+    } else {
+        for (start, end) in &args.ranges {
+            ranges.push((parse_time(start)?, parse_time(end)?));
+        }
+    }
+
+    let mut sink: Box<dyn CandleSink> = match args.sink.as_deref().unwrap_or("csv") {
+        "postgres" => {
+            let storage = PostgresStorage::new(&config.storage.postgres_dsn);
+            if pg_init_schema() {
+                storage.ensure_schema()?;
+            }
+            Box::new(storage)
+        }
+        _ => Box::new(CsvSink::new(args.dir.as_deref().unwrap_or("data"))),
+    };
+    let now = now_ms()? / 1000;
+    let mut source = config.clone();
+    if exchange != config.exchange.to_lowercase() {
+        source.data.exchange_base_url = None;
+    }
+    let provider = ExchangeProvider::new(source, &exchange);
+
+    for symbol in &symbols {
+        for interval in &intervals {
+            let interval_secs = parse_interval_seconds(interval)? as i64;
+            for &(start, end) in &ranges {
+                let job = FetchJob {
+                    exchange: exchange.clone(),
+                    symbol: symbol.clone(),
+                    interval: interval.clone(),
+                    interval_secs,
+                    start,
+                    end,
+                };
+                let summary = run_fetch_job(
+                    &job,
+                    now - interval_secs,
+                    sink.as_mut(),
+                    |from, to| provider.load_candles(symbol, interval, from, to),
+                    |progress| {
+                        println!(
+                            "fetch_progress: {exchange} {symbol} {interval} {:.1}% ({} candles)",
+                            progress.percent(),
+                            progress.written
+                        );
+                    },
+                )?;
+                if let Some(last) = summary.resumed_from {
+                    println!("fetch_resumed: {exchange} {symbol} {interval} after {last}");
+                }
+                info!(
+                    exchange = %exchange,
+                    symbol = %symbol,
+                    interval = %interval,
+                    chunks = summary.chunks,
+                    written = summary.written,
+                    "fetch_completed"
+                );
+                println!(
+                    "fetch_done: {exchange} {symbol} {interval} {} candles",
+                    summary.written
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_keystore(
+    config: &Config,
+    action: KeystoreAction,
+    exchange: Option<String>,
+    label: Option<String>,
+) -> Result<()> {
+    let path = config
+        .keystore
+        .path
+        .as_ref()
+        .ok_or_else(|| Error::new("keystore.path must be set"))?;
+    let creating = !std::path::Path::new(path).exists();
+    if creating && action != KeystoreAction::Add {
+        return Err(Error::new(format!("keystore not found: {path}")));
+    }
+    let passphrase = keystore_passphrase(config, creating)?;
+    let mut keystore = Keystore::open(path, &passphrase)?;
+    let label = label.unwrap_or_else(|| config.keystore.label.clone());
+
+    match action {
+        KeystoreAction::List => {
+            for credential in keystore.credentials() {
+                println!("{}\t{}", credential.exchange, credential.label);
+            }
+        }
+        KeystoreAction::Add => {
+            let exchange = exchange.unwrap_or_default();
+            if !KEYSTORE_EXCHANGES.contains(&exchange.as_str()) {
+                return Err(Error::new(format!(
+                    "keystore exchange must be one of {}",
+                    KEYSTORE_EXCHANGES.join(", ")
+                )));
+            }
+            let api_key = prompt_secret("api key: ")?;
+            let api_secret = prompt_secret("api secret: ")?;
+            let passphrase_value = if exchange == "okx" {
+                Some(prompt_secret("api passphrase: ")?)
+            } else {
+                None
+            };
+            keystore.insert(Credential {
+                exchange: exchange.clone(),
+                label: label.clone(),
+                api_key,
+                api_secret,
+                passphrase: passphrase_value,
+            });
+            keystore.save(&passphrase)?;
+            println!("keystore_added: {exchange}/{label}");
+            info!(exchange = %exchange, label = %label, "keystore_credential_added");
+        }
+        KeystoreAction::Remove => {
+            let exchange = exchange.unwrap_or_default();
+            if !keystore.remove(&exchange, &label) {
+                return Err(Error::new(format!(
+                    "keystore has no credential for {exchange}/{label}"
+                )));
+            }
+            keystore.save(&passphrase)?;
+            println!("keystore_removed: {exchange}/{label}");
+            info!(exchange = %exchange, label = %label, "keystore_credential_removed");
+        }
+    }
+    Ok(())
+}
+
+fn keystore_passphrase(config: &Config, confirm: bool) -> Result<String> {
+    if let Some(path) = &config.keystore.passphrase_file {
+        let content = fs::read_to_string(path)
+            .map_err(|err| Error::new(format!("keystore passphrase file read failed: {err}")))?;
+        return Ok(content.trim_end_matches(['\r', '\n']).to_string());
+    }
+    let passphrase = prompt_secret("keystore passphrase: ")?;
+    if confirm && prompt_secret("confirm passphrase: ")? != passphrase {
+        return Err(Error::new("keystore passphrases do not match"));
+    }
+    Ok(passphrase)
+}
+
+fn prompt_secret(prompt: &str) -> Result<String> {
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        eprint!("{prompt}");
+        io::stderr().flush().ok();
+        set_terminal_echo(false);
+    }
+    let mut line = String::new();
+    let read = io::stdin().lock().read_line(&mut line);
+    if interactive {
+        set_terminal_echo(true);
+        eprintln!();
+    }
+    read.map_err(|err| Error::new(format!("secret read failed: {err}")))?;
+    let value = line.trim_end_matches(['\r', '\n']).to_string();
+    if value.is_empty() {
+        return Err(Error::new(format!("{} must be non-empty", prompt.trim_end_matches([':', ' ']))));
+    }
+    Ok(value)
+}
+
+fn set_terminal_echo(enabled: bool) {
+    if cfg!(unix) {
+        let _ = Command::new("stty")
+            .arg(if enabled { "echo" } else { "-echo" })
+            .stdin(Stdio::inherit())
+            .status();
+    }
+}
+
+fn exchange_credential(config: &Config, exchange: &str, env_prefix: &str) -> Result<Credential> {
+    let needs_passphrase = exchange == "okx";
+    if let Some(path) = &config.keystore.path {
+        let keystore = match LIVE_KEYSTORE.get() {
+            Some(keystore) => keystore,
+            None => {
+                let passphrase = keystore_passphrase(config, false)?;
+                let _ = LIVE_KEYSTORE.set(Keystore::open(path, &passphrase)?);
+                LIVE_KEYSTORE
+                    .get()
+                    .ok_or_else(|| Error::new("keystore unavailable"))?
+            }
+        };
+        let label = &config.keystore.label;
+        let credential = keystore.get(exchange, label).ok_or_else(|| {
+            Error::new(format!("keystore has no credential for {exchange}/{label}"))
+        })?;
+        if needs_passphrase && credential.passphrase.is_none() {
+            return Err(Error::new(format!(
+                "keystore credential {exchange}/{label} is missing its passphrase"
+            )));
+        }
+        info!(exchange = %exchange, label = %label, "credentials_loaded_from_keystore");
+        return Ok(credential.clone());
+    }
+
+    let profile = config
+        .accounts
+        .iter()
+        .any(|account| account.name == config.account);
+    let env_prefix = if !profile {
+        env_prefix.to_string()
+    } else {
+        format!(
+            "{}_{env_prefix}",
+            config.account.to_uppercase().replace('-', "_")
+        )
+    };
+    let key_var = format!("MERROW_{env_prefix}_API_KEY");
+    let secret_var = format!("MERROW_{env_prefix}_API_SECRET");
+    let passphrase_var = format!("MERROW_{env_prefix}_PASSPHRASE");
+    let api_key = env::var(&key_var).map_err(|_| Error::new(format!("{key_var} must be set")))?;
+    let api_secret =
+        env::var(&secret_var).map_err(|_| Error::new(format!("{secret_var} must be set")))?;
+    let passphrase = if needs_passphrase {
+        Some(
+            env::var(&passphrase_var)
+                .map_err(|_| Error::new(format!("{passphrase_var} must be set")))?,
+        )
+    } else {
+        None
+    };
+    Ok(Credential {
+        exchange: exchange.to_string(),
+        label: config.keystore.label.clone(),
+        api_key,
+        api_secret,
+        passphrase,
+    })
 }
 
 fn parse_bool(value: &str, flag: &str) -> Result<bool> {
@@ -340,7 +1083,7 @@ fn jitter_amount(base_delay: u64, jitter_pct: u32) -> u64 {
         .duration_since(UNIX_EPOCH)
         .map(|duration| duration.subsec_nanos() as u64)
         .unwrap_or(0);
-    let bucket = (nanos % 10_000) as u64;
+    let bucket = nanos % 10_000;
     let jitter_max = base_delay.saturating_mul(jitter_pct as u64) / 100;
     if jitter_max == 0 {
         0
@@ -364,103 +1107,96 @@ fn is_transient_error(message: &str) -> bool {
         || lowered.contains("response status: 504")
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{backoff_delay_ms, is_transient_error, parse_args};
-
-    #[test]
-    fn parses_defaults() {
-        let args = vec!["merrow".to_string()];
-        let parsed = parse_args(&args).expect("parse");
-        assert_eq!(parsed.config_path, "config.toml");
-        assert!(parsed.symbol_override.is_none());
-        assert!(!parsed.show_help);
+fn run_live_accounts(
+    config: &Config,
+    live_execute: bool,
+    action: LiveAction,
+    only: Option<&str>,
+) -> Result<()> {
+    if config.accounts.is_empty() {
+        if let Some(name) = only.filter(|name| *name != config.account) {
+            return Err(Error::new(format!("account not found: {name}")));
+        }
+        return run_live(config, live_execute, action);
     }
-
-    #[test]
-    fn parses_overrides() {
-        let args = vec![
-            "merrow".to_string(),
-            "--config".to_string(),
-            "custom.toml".to_string(),
-            "--symbol".to_string(),
-            "ETHUSDT".to_string(),
-            "--output-format".to_string(),
-            "json".to_string(),
-            "--output-path".to_string(),
-            "out/report.json".to_string(),
-            "--initial-cash".to_string(),
-            "5000".to_string(),
-            "--live-execute".to_string(),
-            "--pg-enabled".to_string(),
-            "true".to_string(),
-        ];
-        let parsed = parse_args(&args).expect("parse");
-        assert_eq!(parsed.config_path, "custom.toml");
-        assert_eq!(parsed.symbol_override.as_deref(), Some("ETHUSDT"));
-        assert_eq!(parsed.output_format.as_deref(), Some("json"));
-        assert_eq!(parsed.output_path.as_deref(), Some("out/report.json"));
-        assert_eq!(parsed.initial_cash_override, Some(5000.0));
-        assert!(parsed.live_execute);
-        assert_eq!(parsed.pg_enabled_override, Some(true));
+    let selected: Vec<&AccountConfig> = config
+        .accounts
+        .iter()
+        .filter(|account| only.is_none_or(|name| account.name == name))
+        .collect();
+    if selected.is_empty() {
+        return Err(Error::new(format!(
+            "account not found: {}",
+            only.unwrap_or_default()
+        )));
     }
 
-    #[test]
-    fn detects_transient_errors() {
-        assert!(is_transient_error("binance response status: 429"));
-        assert!(is_transient_error("http request failed: timeout"));
-        assert!(is_transient_error("response status: 503"));
-        assert!(!is_transient_error("invalid api key"));
+    let mut failed = Vec::new();
+    for account in selected {
+        for symbol in config.account_symbols(account) {
+            let account_config = config.for_account(account, &symbol);
+            let span = tracing::info_span!("account", account = %account.name, symbol = %symbol);
+            let _entered = span.enter();
+            println!("account: {} {} {}", account.name, account_config.exchange, symbol);
+            if let Err(err) = run_live(&account_config, live_execute, action) {
+                metrics::inc_error();
+                warn!(error = %err.message, "account_run_failed");
+                println!("account_failed: {} {}: {}", account.name, symbol, err.message);
+                failed.push(format!("{}/{}", account.name, symbol));
+            }
+        }
     }
-
-    #[test]
-    fn backoff_clamps_with_defaults() {
-        let delay = backoff_delay_ms(500, 10);
-        assert!(delay <= 8_000);
+    if !failed.is_empty() {
+        return Err(Error::new(format!(
+            "live run failed for accounts: {}",
+            failed.join(", ")
+        )));
     }
+    Ok(())
 }
 
-fn run_live(config: &Config, live_execute: bool) -> Result<()> {
-    if config.data.source != "exchange" {
+fn run_live(config: &Config, live_execute: bool, action: LiveAction) -> Result<()> {
+    if action == LiveAction::Cycle && config.data.source != "exchange" {
         return Err(Error::new("live mode requires data.source=exchange"));
     }
+    if !config.routing.venues.is_empty() {
+        return run_live_routed(config, live_execute, action);
+    }
     let exchange = config.exchange.to_lowercase();
     match exchange.as_str() {
-        "binance" => run_live_binance(config, live_execute),
-        "bybit" => run_live_bybit(config, live_execute),
-        "okx" => run_live_okx(config, live_execute),
-        _ => Err(Error::new("live mode only supports binance/bybit/okx currently")),
+        "binance" => run_live_binance(config, live_execute, action),
+        "binance-futures" => run_live_binance_futures(config, live_execute, action),
+        "bybit" => run_live_bybit(config, live_execute, action),
+        "okx" => run_live_okx(config, live_execute, action),
+        "kraken" => run_live_kraken(config, live_execute, action),
+        "coinbase" => run_live_coinbase(config, live_execute, action),
+        "sim" => run_live_sim(config, live_execute, action),
+        _ => Err(Error::new(
+            "live mode only supports binance/bybit/okx/kraken/coinbase/sim currently",
+        )),
     }
 }
 
+fn lookback_bars(config: &Config) -> Result<usize> {
+    let base_secs = parse_interval_seconds(&config.data.candle_interval)?;
+    let mut ratio = 1;
+    for interval in &config.data.timeframes {
+        ratio = ratio.max(parse_interval_seconds(interval)? / base_secs);
+    }
+    let ratio = ratio as usize;
+    Ok((config.triggers.ma_window as usize).max(1) * ratio + ratio + 1)
+}
+
 fn run_paper_mode(config: &Config) -> Result<()> {
-    let lookback = (config.triggers.ma_window as usize).max(1) + 2;
+    let lookback = lookback_bars(config)?;
     let state_path =
         env::var("MERROW_PAPER_STATE_PATH").unwrap_or_else(|_| "output/paper_state.json".to_string());
-    let candles = match config.data.source.as_str() {
-        "csv" => {
-            let path = config
-                .data
-                .csv_path
-                .as_ref()
-                .ok_or_else(|| Error::new("data.csv_path must be set"))?;
-            let mut all = load_candles_from_csv(path)?;
-            if all.len() > lookback {
-                all = all[all.len() - lookback..].to_vec();
-            }
-            all
-        }
-        "exchange" => {
-            let interval_secs = parse_interval_seconds(&config.data.candle_interval)? as i64;
-            let end_sec = now_ms()? / 1000;
-            let start_sec = end_sec.saturating_sub(interval_secs * lookback as i64);
-            let mut temp = config.clone();
-            temp.backtest.start_time = Some(start_sec.to_string());
-            temp.backtest.end_time = Some(end_sec.to_string());
-            load_candles_from_exchange(&temp)?
-        }
-        _ => return Err(Error::new("unknown data source")),
-    };
+    let candles = provider_from_config(config)?.load_recent(
+        &config.symbol,
+        &config.data.candle_interval,
+        lookback,
+        now_ms()? / 1000,
+    )?;
 
     let result = run_paper_with_state(&candles, config, &state_path)?;
     println!("paper_trades: {}", result.metrics.trade_count);
@@ -537,11 +1273,164 @@ fn maybe_persist_paper(
     Ok(())
 }
 
-fn run_live_binance(config: &Config, live_execute: bool) -> Result<()> {
-    let api_key = env::var("MERROW_BINANCE_API_KEY")
-        .map_err(|_| Error::new("MERROW_BINANCE_API_KEY must be set"))?;
-    let api_secret = env::var("MERROW_BINANCE_API_SECRET")
-        .map_err(|_| Error::new("MERROW_BINANCE_API_SECRET must be set"))?;
+fn maybe_persist_live_snapshot(
+    config: &Config,
+    snapshot: &crate::exchange::sync::AccountSnapshot,
+    time: i64,
+) -> Result<()> {
+    if !pg_enabled() {
+        return Ok(());
+    }
+    let storage = PostgresStorage::new(&config.storage.postgres_dsn);
+    if pg_init_schema() {
+        storage.ensure_schema()?;
+    }
+    storage.persist_account_snapshot(snapshot, time)?;
+    info!(account = %snapshot.account, "pg_account_snapshot_saved");
+    Ok(())
+}
+
+fn run_live_binance(config: &Config, live_execute: bool, action: LiveAction) -> Result<()> {
+    let exchange = binance_exchange(config)?;
+    let cash_asset = live_cash_asset(config)?;
+    run_live_with_exchange(config, live_execute, action, &exchange, &cash_asset)
+}
+
+fn run_live_binance_futures(config: &Config, live_execute: bool, action: LiveAction) -> Result<()> {
+    let exchange = binance_futures_exchange(config, live_execute)?;
+    let cash_asset = live_cash_asset(config)?;
+    run_live_with_exchange(config, live_execute, action, &exchange, &cash_asset)
+}
+
+fn run_live_bybit(config: &Config, live_execute: bool, action: LiveAction) -> Result<()> {
+    let exchange = bybit_exchange(config)?;
+    let cash_asset = live_cash_asset(config)?;
+    run_live_with_exchange(config, live_execute, action, &exchange, &cash_asset)
+}
+
+fn run_live_okx(config: &Config, live_execute: bool, action: LiveAction) -> Result<()> {
+    let cash_asset = live_cash_asset(config)?;
+    let mut live_config = config.clone();
+    live_config.symbol = normalize_dashed_symbol(&config.symbol, &cash_asset);
+    let exchange = okx_exchange(&live_config)?;
+    run_live_with_exchange(&live_config, live_execute, action, &exchange, &cash_asset)
+}
+
+fn run_live_kraken(config: &Config, live_execute: bool, action: LiveAction) -> Result<()> {
+    let exchange = kraken_exchange(config)?;
+    let cash_asset = live_cash_asset(config)?;
+    run_live_with_exchange(config, live_execute, action, &exchange, &cash_asset)
+}
+
+fn run_live_coinbase(config: &Config, live_execute: bool, action: LiveAction) -> Result<()> {
+    let cash_asset = live_cash_asset(config)?;
+    let mut live_config = config.clone();
+    live_config.symbol = normalize_dashed_symbol(&config.symbol, &cash_asset);
+    let exchange = coinbase_exchange(&live_config)?;
+    run_live_with_exchange(&live_config, live_execute, action, &exchange, &cash_asset)
+}
+
+fn run_live_sim(config: &Config, live_execute: bool, action: LiveAction) -> Result<()> {
+    let exchange = sim_exchange(config)?;
+    let cash_asset = live_cash_asset(config)?;
+    run_live_with_exchange(config, live_execute, action, &exchange, &cash_asset)
+}
+
+fn run_live_routed(config: &Config, live_execute: bool, action: LiveAction) -> Result<()> {
+    let cash_asset = live_cash_asset(config)?;
+    let base_asset = config
+        .symbol
+        .strip_suffix(cash_asset.as_str())
+        .unwrap_or(&config.symbol)
+        .trim_end_matches(['-', '_', '/'])
+        .to_string();
+
+    let mut venues = Vec::with_capacity(config.routing.venues.len());
+    for venue in &config.routing.venues {
+        let name = venue.to_lowercase();
+        let venue_config = routing_venue_config(config, &name, &cash_asset);
+        let exchange: Box<dyn Exchange> = match name.as_str() {
+            "binance" => Box::new(binance_exchange(&venue_config)?),
+            "binance-futures" => Box::new(binance_futures_exchange(&venue_config, live_execute)?),
+            "bybit" => Box::new(bybit_exchange(&venue_config)?),
+            "okx" => Box::new(okx_exchange(&venue_config)?),
+            "kraken" => Box::new(kraken_exchange(&venue_config)?),
+            "coinbase" => Box::new(coinbase_exchange(&venue_config)?),
+            "sim" => Box::new(sim_exchange(&venue_config)?),
+            _ => return Err(Error::new(format!("routing venue not supported: {venue}"))),
+        };
+        venues.push(RouterVenue {
+            fee_rate: routing_venue_fee(config, venue),
+            name,
+            exchange,
+            symbol: venue_config.symbol,
+            base_asset: base_asset.clone(),
+            quote_asset: cash_asset.clone(),
+        });
+    }
+
+    let interval_secs = parse_interval_seconds(&config.data.candle_interval)? as i64;
+    let router = SmartOrderRouter::new(
+        RouterConfig {
+            symbol: config.symbol.clone(),
+            max_participation: config.routing.max_participation,
+            max_venue_failures: config.routing.max_venue_failures,
+            quote_interval: config.data.candle_interval.clone(),
+            quote_window_secs: interval_secs * 2,
+        },
+        venues,
+    )?;
+    info!(venues = ?router.venue_names(), "live routing enabled");
+    let route_path = config.routing.state_path.as_str();
+    if let Some(children) = load_route_state(route_path)? {
+        router.restore_children(children)?;
+    }
+    let result = run_live_with_exchange(config, live_execute, action, &router, &cash_asset);
+    if live_execute {
+        let scheduler = load_execution_state(&config.execution.state_path)?.unwrap_or_default();
+        let pending = IntentJournal::new(&config.execution.journal_path).pending()?;
+        router.retain_children(|parent_id| {
+            scheduler.tracks(parent_id)
+                || pending
+                    .iter()
+                    .any(|intent| intent.order.client_order_id == parent_id)
+        })?;
+        save_route_state(route_path, &router.routed_children()?)?;
+    }
+    result
+}
+
+fn routing_venue_config(config: &Config, name: &str, cash_asset: &str) -> Config {
+    let mut venue_config = config.clone();
+    if name != config.exchange.to_lowercase() {
+        venue_config.exchange = name.to_string();
+        venue_config.data.exchange_base_url = None;
+    }
+    if name == "okx" || name == "coinbase" {
+        venue_config.symbol = normalize_dashed_symbol(&config.symbol, cash_asset);
+    }
+    venue_config
+}
+
+fn routing_venue_fee(config: &Config, venue: &str) -> f64 {
+    config
+        .routing
+        .venue_fees
+        .get(venue)
+        .or_else(|| config.routing.venue_fees.get(&venue.to_lowercase()))
+        .copied()
+        .unwrap_or(config.orders.fee_rate)
+}
+
+fn live_cash_asset(config: &Config) -> Result<String> {
+    env::var("MERROW_CASH_ASSET")
+        .ok()
+        .or_else(|| infer_cash_asset(&config.symbol))
+        .ok_or_else(|| Error::new("cash asset not found; set MERROW_CASH_ASSET"))
+}
+
+fn binance_exchange(config: &Config) -> Result<BinanceExchange> {
+    let credential = exchange_credential(config, "binance", "BINANCE")?;
     let base_url = env::var("MERROW_BINANCE_BASE_URL")
         .ok()
         .or_else(|| config.data.exchange_base_url.clone())
@@ -551,28 +1440,61 @@ fn run_live_binance(config: &Config, live_execute: bool) -> Result<()> {
         .and_then(|value| value.parse::<u64>().ok())
         .unwrap_or(5000);
 
-    let exchange = BinanceExchange::new(BinanceConfig {
+    BinanceExchange::new(BinanceConfig {
         base_url,
-        api_key,
-        api_secret,
+        api_key: credential.api_key,
+        api_secret: credential.api_secret,
         recv_window,
         timeout_secs: 30,
         default_symbol: Some(config.symbol.clone()),
-    })?;
+        time_sync: time_sync_config(),
+    })
+}
 
-    let cash_asset = env::var("MERROW_CASH_ASSET")
+fn binance_futures_exchange(config: &Config, live_execute: bool) -> Result<BinanceFuturesExchange> {
+    let credential = exchange_credential(config, "binance", "BINANCE")?;
+    let base_url = env::var("MERROW_BINANCE_FUTURES_BASE_URL")
         .ok()
-        .or_else(|| infer_cash_asset(&config.symbol))
-        .ok_or_else(|| Error::new("cash asset not found; set MERROW_CASH_ASSET"))?;
+        .or_else(|| config.data.exchange_base_url.clone())
+        .unwrap_or_else(|| "https://fapi.binance.com".to_string());
+    let recv_window = env::var("MERROW_BINANCE_RECV_WINDOW")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(5000);
+    let leverage = match env::var("MERROW_BINANCE_FUTURES_LEVERAGE") {
+        Ok(value) => Some(
+            value
+                .parse::<u32>()
+                .map_err(|_| Error::new("MERROW_BINANCE_FUTURES_LEVERAGE must be an integer"))?,
+        ),
+        Err(_) => None,
+    };
+    let margin_type = env::var("MERROW_BINANCE_FUTURES_MARGIN_TYPE").ok();
+    let reduce_only_sells = match env::var("MERROW_BINANCE_FUTURES_REDUCE_ONLY") {
+        Ok(value) => parse_bool(&value, "MERROW_BINANCE_FUTURES_REDUCE_ONLY")?,
+        Err(_) => true,
+    };
 
-    run_live_with_exchange(config, live_execute, &exchange, &cash_asset)
+    let exchange = BinanceFuturesExchange::new(BinanceFuturesConfig {
+        base_url,
+        api_key: credential.api_key,
+        api_secret: credential.api_secret,
+        recv_window,
+        timeout_secs: 30,
+        default_symbol: Some(config.symbol.clone()),
+        leverage,
+        margin_type,
+        reduce_only_sells,
+        time_sync: time_sync_config(),
+    })?;
+    if live_execute {
+        exchange.configure_symbol(&config.symbol)?;
+    }
+    Ok(exchange)
 }
 
-fn run_live_bybit(config: &Config, live_execute: bool) -> Result<()> {
-    let api_key = env::var("MERROW_BYBIT_API_KEY")
-        .map_err(|_| Error::new("MERROW_BYBIT_API_KEY must be set"))?;
-    let api_secret = env::var("MERROW_BYBIT_API_SECRET")
-        .map_err(|_| Error::new("MERROW_BYBIT_API_SECRET must be set"))?;
+fn bybit_exchange(config: &Config) -> Result<BybitExchange> {
+    let credential = exchange_credential(config, "bybit", "BYBIT")?;
     let base_url = env::var("MERROW_BYBIT_BASE_URL")
         .ok()
         .or_else(|| config.data.exchange_base_url.clone())
@@ -589,73 +1511,209 @@ fn run_live_bybit(config: &Config, live_execute: bool) -> Result<()> {
         .unwrap_or("spot")
         .to_string();
 
-    let exchange = BybitExchange::new(BybitConfig {
+    BybitExchange::new(BybitConfig {
         base_url,
-        api_key,
-        api_secret,
+        api_key: credential.api_key,
+        api_secret: credential.api_secret,
         recv_window,
         timeout_secs: 30,
         category,
         account_type,
         default_symbol: Some(config.symbol.clone()),
-    })?;
+        time_sync: time_sync_config(),
+    })
+}
 
-    let cash_asset = env::var("MERROW_CASH_ASSET")
+fn okx_exchange(config: &Config) -> Result<OkxExchange> {
+    let credential = exchange_credential(config, "okx", "OKX")?;
+    let base_url = env::var("MERROW_OKX_BASE_URL")
         .ok()
-        .or_else(|| infer_cash_asset(&config.symbol))
-        .ok_or_else(|| Error::new("cash asset not found; set MERROW_CASH_ASSET"))?;
+        .or_else(|| config.data.exchange_base_url.clone())
+        .unwrap_or_else(|| "https://www.okx.com".to_string());
 
-    run_live_with_exchange(config, live_execute, &exchange, &cash_asset)
+    OkxExchange::new(OkxConfig {
+        base_url,
+        api_key: credential.api_key,
+        api_secret: credential.api_secret,
+        passphrase: credential.passphrase.unwrap_or_default(),
+        timeout_secs: 30,
+        default_symbol: Some(config.symbol.clone()),
+        time_sync: time_sync_config(),
+    })
 }
 
-fn run_live_okx(config: &Config, live_execute: bool) -> Result<()> {
-    let api_key =
-        env::var("MERROW_OKX_API_KEY").map_err(|_| Error::new("MERROW_OKX_API_KEY must be set"))?;
-    let api_secret = env::var("MERROW_OKX_API_SECRET")
-        .map_err(|_| Error::new("MERROW_OKX_API_SECRET must be set"))?;
-    let passphrase = env::var("MERROW_OKX_PASSPHRASE")
-        .map_err(|_| Error::new("MERROW_OKX_PASSPHRASE must be set"))?;
-    let base_url = env::var("MERROW_OKX_BASE_URL")
+fn kraken_exchange(config: &Config) -> Result<KrakenExchange> {
+    let credential = exchange_credential(config, "kraken", "KRAKEN")?;
+    let base_url = env::var("MERROW_KRAKEN_BASE_URL")
         .ok()
         .or_else(|| config.data.exchange_base_url.clone())
-        .unwrap_or_else(|| "https://www.okx.com".to_string());
+        .unwrap_or_else(|| "https://api.kraken.com".to_string());
 
-    let cash_asset = env::var("MERROW_CASH_ASSET")
+    KrakenExchange::new(KrakenConfig {
+        base_url,
+        api_key: credential.api_key,
+        api_secret: credential.api_secret,
+        timeout_secs: 30,
+        default_symbol: Some(config.symbol.clone()),
+    })
+}
+
+fn coinbase_exchange(config: &Config) -> Result<CoinbaseExchange> {
+    let credential = exchange_credential(config, "coinbase", "COINBASE")?;
+    let base_url = env::var("MERROW_COINBASE_BASE_URL")
         .ok()
-        .or_else(|| infer_cash_asset(&config.symbol))
-        .ok_or_else(|| Error::new("cash asset not found; set MERROW_CASH_ASSET"))?;
-    let okx_symbol = normalize_okx_symbol(&config.symbol, &cash_asset);
-    let mut live_config = config.clone();
-    live_config.symbol = okx_symbol;
+        .or_else(|| config.data.exchange_base_url.clone())
+        .unwrap_or_else(|| "https://api.coinbase.com".to_string());
 
-    let exchange = OkxExchange::new(OkxConfig {
+    CoinbaseExchange::new(CoinbaseConfig {
         base_url,
-        api_key,
-        api_secret,
-        passphrase,
+        api_key: credential.api_key,
+        api_secret: credential.api_secret,
         timeout_secs: 30,
-        default_symbol: Some(live_config.symbol.clone()),
-    })?;
+        default_symbol: Some(config.symbol.clone()),
+        time_sync: time_sync_config(),
+    })
+}
 
-    run_live_with_exchange(&live_config, live_execute, &exchange, &cash_asset)
+fn sim_exchange(config: &Config) -> Result<RestExchange> {
+    let base_url = env::var("MERROW_SIM_BASE_URL")
+        .ok()
+        .or_else(|| config.data.exchange_base_url.clone())
+        .unwrap_or_else(|| "http://127.0.0.1:8090".to_string());
+    let api_key = env::var("MERROW_SIM_API_KEY").unwrap_or_else(|_| "sim".to_string());
+    let api_secret = env::var("MERROW_SIM_API_SECRET").unwrap_or_else(|_| "sim".to_string());
+
+    RestExchange::new(RestExchangeConfig {
+        base_url,
+        api_key: Some(api_key),
+        api_secret: Some(api_secret),
+        passphrase: None,
+        timeout_secs: 30,
+    })
+}
+
+fn time_sync_config() -> TimeSyncConfig {
+    let defaults = TimeSyncConfig::default();
+    let interval_secs = env::var("MERROW_TIME_SYNC_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(defaults.interval_secs);
+    let alert_threshold_ms = env::var("MERROW_CLOCK_DRIFT_ALERT_MS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(defaults.alert_threshold_ms);
+    TimeSyncConfig {
+        interval_secs,
+        alert_threshold_ms,
+    }
 }
 
 fn run_live_with_exchange<E: Exchange>(
     config: &Config,
     live_execute: bool,
+    action: LiveAction,
     exchange: &E,
     cash_asset: &str,
 ) -> Result<()> {
+    if let LiveAction::CancelAll { include_foreign } = action {
+        return cancel_all_open_orders(config, exchange, include_foreign);
+    }
+    let mut bundle = build_engine_bundle(config)?;
+    let session = now_ms()? / 1000;
+    bundle.order_flow.set_order_builder(OrderBuilder::with_prefix(format!(
+        "{}-{session}",
+        config.orders.client_id_prefix
+    )));
+    let breaker_path = config.circuit_breaker.state_path.as_str();
+    if let Some(state) = load_breaker_state(breaker_path)? {
+        bundle.order_flow.circuit_breaker_mut().restore(state);
+    }
+    let kill_switch = KillSwitch::new(
+        Some(config.circuit_breaker.kill_switch_path.clone()),
+        config.circuit_breaker.kill_switch,
+    );
+
+    let execution_path = config.execution.state_path.as_str();
+    let mut books = LiveBooks {
+        scheduler: load_execution_state(execution_path)?.unwrap_or_default(),
+        expected: if config.reconciliation.enabled {
+            load_expected_state(&config.reconciliation.state_path)?
+        } else {
+            None
+        },
+        ledger: None,
+        journal: IntentJournal::new(&config.execution.journal_path),
+    };
+
+    let result = run_live_cycle(
+        config,
+        live_execute,
+        exchange,
+        cash_asset,
+        &mut bundle,
+        &kill_switch,
+        &mut books,
+    );
+    save_breaker_state(breaker_path, bundle.order_flow.circuit_breaker().state())?;
+    if live_execute {
+        save_execution_state(execution_path, &books.scheduler)?;
+    }
+    if let Some(ledger) = books.ledger.filter(|_| config.reconciliation.enabled) {
+        save_expected_state(&config.reconciliation.state_path, &ledger)?;
+    }
+    result
+}
+
+struct LiveBooks {
+    scheduler: ExecutionScheduler,
+    expected: Option<ExpectedState>,
+    ledger: Option<ExpectedState>,
+    journal: IntentJournal,
+}
+
+fn run_live_cycle<E: Exchange>(
+    config: &Config,
+    live_execute: bool,
+    exchange: &E,
+    cash_asset: &str,
+    bundle: &mut EngineBundle,
+    kill_switch: &KillSwitch,
+    books: &mut LiveBooks,
+) -> Result<()> {
+    let LiveBooks {
+        scheduler,
+        expected,
+        ledger,
+        journal,
+    } = books;
+    if live_execute {
+        recover_intents(exchange, journal, scheduler, expected)?;
+    }
+    let expected = expected.as_ref();
     let mut triggered = false;
     let mut signals_count = 0usize;
     let mut orders_count = 0usize;
     let mut orders_sent = 0usize;
-    let snapshot = retry_with_backoff("sync_account", || sync_account(exchange))?;
+    let snapshot =
+        retry_with_backoff("sync_account", || sync_account(exchange, &config.account))?;
     let account = account_from_snapshot(&snapshot, &config.symbol, cash_asset);
+    let synced_at = now_ms()? / 1000;
+    maybe_persist_live_snapshot(config, &snapshot, synced_at)?;
+    let scope = reconcile_scope(config, cash_asset);
+    if config.reconciliation.enabled {
+        run_reconciliation(config, &scope, expected, &snapshot, bundle, synced_at)?;
+    }
+    let ledger = ledger.insert(ExpectedState::from_snapshot(
+        &snapshot, &scope, expected, synced_at,
+    ));
+    if live_execute {
+        reconcile_executions(exchange, scheduler, &snapshot.open_orders, bundle, synced_at);
+        sweep_orphaned_orders(config, exchange, scheduler, ledger, &snapshot.open_orders)?;
+    }
 
     let now_ms = now_ms()?;
     let interval_secs = parse_interval_seconds(&config.data.candle_interval)? as i64;
-    let lookback = config.triggers.ma_window as i64 + 2;
+    let lookback = lookback_bars(config)? as i64;
     let start_ms = now_ms - interval_secs * 1000 * lookback;
 
     let candle_request = CandleRequest {
@@ -670,47 +1728,134 @@ fn run_live_with_exchange<E: Exchange>(
         return Err(Error::new("no candles returned for live evaluation"));
     }
 
-    let mut bundle = build_engine_bundle(config)?;
     let last = candles.last().ok_or_else(|| Error::new("no candle data"))?;
-    let trigger_ctx = crate::core::TriggerContext {
-        candle: last,
-        history: &candles,
-        now: last.time,
-    };
+    let equity = account.cash
+        + account
+            .positions
+            .iter()
+            .filter(|pos| pos.symbol == config.symbol)
+            .map(|pos| pos.quantity * last.close)
+            .sum::<f64>();
+    bundle.order_flow.observe_equity(last.time, equity);
+    if let Some(reason) = bundle.order_flow.circuit_breaker().halt_reason() {
+        warn!(reason = reason.as_str(), "live: circuit breaker halted");
+    }
+    if let Some(order) = bundle.order_flow.flatten_order(&account, config, last.time)? {
+        orders_count += 1;
+        if live_execute {
+            cancel_working_children(exchange, scheduler, ledger, &order.symbol);
+            let ack = place_live_order(exchange, &order, bundle, kill_switch, journal, last)?;
+            if !matches!(ack.status, OrderStatus::Rejected) {
+                ledger.record_order(&order, last.close);
+                if let Err(err) = scheduler.track_placed(&order, last.close, last.time) {
+                    warn!(client_id = %order.client_order_id, error = %err.message, "live: flatten not tracked");
+                }
+            }
+            info!(
+                client_id = %ack.client_order_id,
+                status = ?ack.status,
+                "flatten_ack"
+            );
+            orders_sent += 1;
+        } else {
+            info!(
+                symbol = %order.symbol,
+                qty = order.quantity,
+                "dry_flatten"
+            );
+            if config.orders.validate_dry_run {
+                validate_dry_order(exchange, &order, last.close);
+            }
+        }
+    }
 
-    if !bundle.trigger_engine.should_fire(&trigger_ctx) {
-        info!("live: trigger not fired");
-        metrics::record_live(triggered, signals_count, orders_count, orders_sent);
-        metrics::write_if_configured()?;
-        return Ok(());
+    let reprice = RepricePolicy::from_config(config);
+    if live_execute && reprice.is_enabled() && !kill_switch.is_engaged() {
+        reprice_working_children(
+            exchange,
+            scheduler,
+            ledger,
+            &reprice,
+            &config.symbol,
+            last.close,
+        )?;
     }
-    triggered = true;
 
-    let strategy_ctx = crate::core::StrategyContext {
+    let trigger_ctx = crate::core::TriggerContext {
         candle: last,
         history: &candles,
-        account: &account,
         now: last.time,
     };
-    let signals = bundle.strategy.on_tick(&strategy_ctx);
-    signals_count = signals.len();
+
     let mut orders = Vec::new();
-    for signal in signals {
-        orders.extend(bundle.order_flow.plan(signal, &strategy_ctx, config)?);
+    if bundle.trigger_engine.should_fire(&trigger_ctx) {
+        triggered = true;
+        let mut timeframes =
+            build_timeframes(&config.data.timeframes, &config.data.candle_interval)?;
+        for candle in &candles {
+            for timeframe in &mut timeframes {
+                timeframe.push(candle);
+            }
+        }
+        let strategy_ctx = crate::core::StrategyContext {
+            candle: last,
+            history: &candles,
+            account: &account,
+            timeframes: &timeframes,
+            now: last.time,
+        };
+        let signals = bundle.strategy.on_tick(&strategy_ctx);
+        signals_count = signals.len();
+        for signal in signals {
+            orders.extend(bundle.order_flow.plan(signal, &strategy_ctx, config)?);
+        }
+        orders_count += orders.len();
+    } else {
+        info!("live: trigger not fired");
     }
-    orders_count = orders.len();
 
-    if orders.is_empty() {
-        info!("live: no orders to place");
-        metrics::record_live(triggered, signals_count, orders_count, orders_sent);
-        metrics::write_if_configured()?;
-        return Ok(());
+    let algo = ExecutionAlgo::from_config(&config.execution)?;
+    let now = now_ms / 1000;
+    if !orders.is_empty() {
+        let profile = match algo {
+            ExecutionAlgo::Vwap { .. } => {
+                let profile_request = CandleRequest {
+                    symbol: config.symbol.clone(),
+                    interval: config.data.candle_interval.clone(),
+                    start_time: now_ms - config.execution.vwap_lookback_days as i64 * 86_400_000,
+                    end_time: now_ms,
+                };
+                retry_with_backoff("fetch_profile", || exchange.fetch_candles(&profile_request))?
+            }
+            _ => Vec::new(),
+        };
+        for order in &orders {
+            scheduler.submit(order, &algo, last.close, now, &profile)?;
+        }
     }
+    let children = scheduler.due_children(now, last.close)?;
 
-    if live_execute {
-        info!("live: executing {} order(s)", orders.len());
-        for order in orders {
-            let ack = retry_with_backoff("place_order", || exchange.place_order(&order))?;
+    if children.is_empty() {
+        info!("live: no orders to place");
+    } else if live_execute {
+        info!("live: executing {} order(s)", children.len());
+        let mut children = children.into_iter();
+        while let Some(order) = children.next() {
+            let ack = match place_live_order(exchange, &order, bundle, kill_switch, journal, last) {
+                Ok(ack) => ack,
+                Err(err) => {
+                    scheduler.release_child(&order.client_order_id);
+                    for unsent in children {
+                        scheduler.release_child(&unsent.client_order_id);
+                    }
+                    return Err(err);
+                }
+            };
+            if matches!(ack.status, OrderStatus::Rejected) {
+                scheduler.release_child(&order.client_order_id);
+            } else {
+                ledger.record_order(&order, last.close);
+            }
             info!(
                 client_id = %ack.client_order_id,
                 status = ?ack.status,
@@ -720,21 +1865,360 @@ fn run_live_with_exchange<E: Exchange>(
         }
     } else {
         info!("live: dry-run mode (no orders sent)");
-        for order in &orders {
+        for order in &children {
             info!(
                 symbol = %order.symbol,
                 side = ?order.side,
                 qty = order.quantity,
                 "dry_order"
             );
+            if config.orders.validate_dry_run {
+                validate_dry_order(exchange, order, last.close);
+            }
         }
     }
 
-    metrics::record_live(triggered, signals_count, orders_count, orders_sent);
+    for report in scheduler.take_completed() {
+        info!(
+            parent_id = %report.parent_id,
+            algo = %report.algo,
+            filled_qty = report.filled_qty,
+            avg_fill_price = report.avg_fill_price,
+            arrival_price = report.arrival_price,
+            shortfall_bps = report.shortfall_bps,
+            "execution_complete"
+        );
+    }
+
+    metrics::record_live(
+        &config.account,
+        triggered,
+        signals_count,
+        orders_count,
+        orders_sent,
+    );
     metrics::write_if_configured()?;
     Ok(())
 }
 
+fn reconcile_executions<E: Exchange>(
+    exchange: &E,
+    scheduler: &mut ExecutionScheduler,
+    open_orders: &[OrderAck],
+    bundle: &mut EngineBundle,
+    now: i64,
+) {
+    scheduler.retain_adopted(|client_order_id| {
+        open_orders
+            .iter()
+            .any(|order| order.client_order_id == client_order_id)
+    });
+    for child in scheduler.working_children() {
+        let still_open = open_orders
+            .iter()
+            .any(|order| order.client_order_id == child.client_order_id);
+        if still_open {
+            continue;
+        }
+        let ack = match retry_with_backoff("fetch_order", || {
+            exchange.fetch_order(&child.client_order_id)
+        }) {
+            Ok(Some(ack)) => ack,
+            Ok(None) => {
+                warn!(client_id = %child.client_order_id, "live: child not found; releasing");
+                scheduler.release_child(&child.client_order_id);
+                continue;
+            }
+            Err(err) => {
+                warn!(client_id = %child.client_order_id, error = %err.message, "live: child status unknown");
+                continue;
+            }
+        };
+        let reported = match ack.status {
+            OrderStatus::Filled if ack.filled_qty <= 0.0 => child.quantity,
+            _ => ack.filled_qty,
+        };
+        let filled = reported.min(child.quantity);
+        if filled > 0.0 {
+            let price = ack.avg_fill_price.unwrap_or(child.reference_price);
+            let pnl = scheduler.book_fill(&child.client_order_id, filled, price);
+            scheduler.record_fill(&child.client_order_id, filled, price, 0.0);
+            if let Some(pnl) = pnl {
+                bundle.order_flow.record_trade_pnl(now, pnl);
+            }
+        }
+        if !matches!(ack.status, OrderStatus::New | OrderStatus::PartiallyFilled) {
+            scheduler.release_child(&child.client_order_id);
+        }
+    }
+}
+
+fn cancel_working_children<E: Exchange>(
+    exchange: &E,
+    scheduler: &mut ExecutionScheduler,
+    ledger: &mut ExpectedState,
+    symbol: &str,
+) {
+    let working: Vec<String> = scheduler
+        .parents()
+        .iter()
+        .filter(|parent| parent.symbol == symbol)
+        .flat_map(|parent| parent.working().iter().map(|child| child.client_order_id.clone()))
+        .collect();
+    for client_order_id in working.iter().chain(scheduler.adopted()) {
+        match exchange.cancel_order(client_order_id) {
+            Ok(()) => ledger.forget_order(client_order_id),
+            Err(err) => {
+                warn!(client_id = %client_order_id, error = %err.message, "live: child cancel failed");
+            }
+        }
+    }
+    scheduler.retain_adopted(|_| false);
+    scheduler.halt_symbol(symbol);
+}
+
+fn sweep_orphaned_orders<E: Exchange>(
+    config: &Config,
+    exchange: &E,
+    scheduler: &mut ExecutionScheduler,
+    ledger: &mut ExpectedState,
+    open_orders: &[OrderAck],
+) -> Result<()> {
+    let policy = OrphanPolicy::parse(&config.orders.orphan_policy)?;
+    let split = classify_open_orders(open_orders, &config.orders.client_id_prefix);
+    if !split.foreign.is_empty() {
+        info!(count = split.foreign.len(), "live: foreign open orders left untouched");
+    }
+    let orphans: Vec<OrderAck> = split
+        .ours
+        .into_iter()
+        .filter(|order| !scheduler.tracks(&order.client_order_id))
+        .collect();
+    if orphans.is_empty() {
+        return Ok(());
+    }
+    match policy {
+        OrphanPolicy::Cancel => {
+            let report = cancel_orders(exchange, &orphans);
+            for client_order_id in &report.canceled {
+                ledger.forget_order(client_order_id);
+                info!(client_id = %client_order_id, "orphan_canceled");
+            }
+            for (client_order_id, message) in &report.failed {
+                warn!(client_id = %client_order_id, error = %message, "live: orphan cancel failed");
+            }
+        }
+        OrphanPolicy::Adopt => {
+            for order in &orphans {
+                scheduler.adopt(&order.client_order_id);
+                info!(client_id = %order.client_order_id, "orphan_adopted");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn cancel_all_open_orders<E: Exchange>(
+    config: &Config,
+    exchange: &E,
+    include_foreign: bool,
+) -> Result<()> {
+    let open_orders = retry_with_backoff("fetch_open_orders", || exchange.fetch_open_orders())?;
+    let split = classify_open_orders(&open_orders, &config.orders.client_id_prefix);
+    let skipped = if include_foreign { 0 } else { split.foreign.len() };
+    let mut targets = split.ours;
+    if include_foreign {
+        targets.extend(split.foreign);
+    }
+    let report = cancel_orders(exchange, &targets);
+
+    let execution_path = config.execution.state_path.as_str();
+    if let Some(mut scheduler) = load_execution_state(execution_path)? {
+        scheduler.retain_adopted(|_| false);
+        scheduler.halt_symbol(&config.symbol);
+        let stopped = scheduler.take_completed();
+        save_execution_state(execution_path, &scheduler)?;
+        println!("cancel_all_parents_stopped: {}", stopped.len());
+    }
+
+    println!("cancel_all_canceled: {}", report.canceled.len());
+    println!("cancel_all_foreign_skipped: {skipped}");
+    for (client_order_id, message) in &report.failed {
+        warn!(client_id = %client_order_id, error = %message, "cancel_all: cancel failed");
+    }
+    if !report.failed.is_empty() {
+        return Err(Error::new(format!(
+            "cancel-all failed for {} order(s)",
+            report.failed.len()
+        )));
+    }
+    Ok(())
+}
+
+fn reprice_working_children<E: Exchange>(
+    exchange: &E,
+    scheduler: &mut ExecutionScheduler,
+    ledger: &mut ExpectedState,
+    policy: &RepricePolicy,
+    symbol: &str,
+    reference_price: f64,
+) -> Result<()> {
+    for request in scheduler.reprice_requests(policy, symbol, reference_price)? {
+        let client_order_id = request.order.client_order_id.clone();
+        let new_price = request.new_price.unwrap_or(reference_price);
+        match exchange.amend_order(&request) {
+            Ok(ack) if !matches!(ack.status, OrderStatus::Rejected) => {
+                scheduler.record_amend(&client_order_id, new_price);
+                ledger.record_amend(&client_order_id, new_price);
+                info!(client_id = %client_order_id, price = new_price, "order_amended");
+            }
+            Ok(_) => {
+                warn!(client_id = %client_order_id, "live: amend rejected");
+            }
+            Err(err) => {
+                warn!(client_id = %client_order_id, error = %err.message, "live: amend failed");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn place_live_order<E: Exchange>(
+    exchange: &E,
+    order: &OrderRequest,
+    bundle: &mut EngineBundle,
+    kill_switch: &KillSwitch,
+    journal: &IntentJournal,
+    last: &Candle,
+) -> Result<OrderAck> {
+    let breaker = bundle.order_flow.circuit_breaker_mut();
+    if kill_switch.is_engaged() {
+        breaker.trip(HaltReason::KillSwitch, last.time);
+    }
+    if breaker.halt_reason() == Some(HaltReason::KillSwitch) {
+        metrics::inc_error();
+        return Err(Error::new("kill switch engaged; order not sent"));
+    }
+    if breaker.halt_reason() == Some(HaltReason::Reconciliation) {
+        metrics::inc_error();
+        return Err(Error::new("reconciliation breach; order not sent"));
+    }
+    let sent_at = now_ms()? / 1000;
+    journal.record_intent(order, last.close, sent_at)?;
+    let result = retry_with_backoff("place_order", || exchange.place_order(order));
+    match &result {
+        Ok(ack) => journal.record_ack(ack, sent_at)?,
+        Err(err) => journal.record_error(&order.client_order_id, &err.message, sent_at)?,
+    }
+    result
+}
+
+fn validate_dry_order<E: Exchange>(exchange: &E, order: &OrderRequest, reference_price: f64) {
+    match exchange.test_order(order, reference_price) {
+        Ok(OrderCheck::Accepted) => {
+            info!(client_id = %order.client_order_id, "dry_order_accepted");
+        }
+        Ok(OrderCheck::Rejected(reason)) => {
+            warn!(client_id = %order.client_order_id, reason = %reason, "dry_order_rejected");
+        }
+        Err(err) => {
+            warn!(client_id = %order.client_order_id, error = %err.message, "dry_order_unchecked");
+        }
+    }
+}
+
+fn recover_intents<E: Exchange>(
+    exchange: &E,
+    journal: &IntentJournal,
+    scheduler: &mut ExecutionScheduler,
+    expected: &mut Option<ExpectedState>,
+) -> Result<()> {
+    let recovered = journal.recover(exchange, now_ms()? / 1000)?;
+    for item in &recovered {
+        let order = &item.intent.order;
+        match &item.outcome {
+            IntentOutcome::Sent(ack) => {
+                if item.is_open() {
+                    scheduler.adopt(&order.client_order_id);
+                }
+                if let Some(expected) = expected.as_mut() {
+                    expected.record_order(order, item.intent.reference_price);
+                }
+                info!(
+                    client_id = %order.client_order_id,
+                    status = ?ack.status,
+                    "intent_recovered"
+                );
+            }
+            IntentOutcome::Unsent => {
+                scheduler.release_child(&order.client_order_id);
+                warn!(client_id = %order.client_order_id, "intent_unsent");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn reconcile_scope(config: &Config, cash_asset: &str) -> ReconcileScope {
+    ReconcileScope {
+        symbol: config.symbol.clone(),
+        base_asset: base_asset_for(&config.symbol, cash_asset),
+        quote_asset: cash_asset.to_string(),
+        client_id_prefix: config.orders.client_id_prefix.clone(),
+        fee_rate: config.orders.fee_rate,
+        max_drift_ratio: config.reconciliation.max_drift_ratio,
+        derivatives: config.routing.venues.is_empty()
+            && config.exchange.eq_ignore_ascii_case("binance-futures"),
+        shared_quote: config
+            .accounts
+            .iter()
+            .find(|account| account.name == config.account)
+            .is_some_and(|account| config.account_symbols(account).len() > 1),
+    }
+}
+
+fn run_reconciliation(
+    config: &Config,
+    scope: &ReconcileScope,
+    expected: Option<&ExpectedState>,
+    snapshot: &crate::exchange::sync::AccountSnapshot,
+    bundle: &mut EngineBundle,
+    now: i64,
+) -> Result<()> {
+    let report = reconcile(expected, snapshot, scope, now);
+    save_reconciliation_report(&config.reconciliation.report_path, &report)?;
+    for item in &report.breaks {
+        warn!(
+            kind = ?item.kind,
+            key = %item.key,
+            expected = item.expected,
+            actual = item.actual,
+            drift_ratio = item.drift_ratio,
+            cause = %item.cause,
+            breach = item.breach,
+            "reconciliation_break"
+        );
+    }
+    if report.has_breach() && config.reconciliation.block_on_breach {
+        bundle
+            .order_flow
+            .circuit_breaker_mut()
+            .trip(HaltReason::Reconciliation, now);
+        warn!("live: reconciliation drift exceeds tolerance; trading blocked");
+    }
+    Ok(())
+}
+
+fn base_asset_for(symbol: &str, cash_asset: &str) -> String {
+    match symbol.split_once('-') {
+        Some((base, _)) => base.to_string(),
+        None => symbol
+            .strip_suffix(cash_asset)
+            .map(|value| value.to_string())
+            .unwrap_or_else(|| symbol.to_string()),
+    }
+}
+
 fn account_from_snapshot(
     snapshot: &crate::exchange::sync::AccountSnapshot,
     symbol: &str,
@@ -746,21 +2230,7 @@ fn account_from_snapshot(
         .find(|balance| balance.asset == cash_asset)
         .map(|balance| balance.free)
         .unwrap_or(0.0);
-    let base_asset = if let Some(base) = symbol.split('-').next() {
-        if symbol.contains('-') {
-            base.to_string()
-        } else {
-            symbol
-                .strip_suffix(cash_asset)
-                .map(|value| value.to_string())
-                .unwrap_or_else(|| symbol.to_string())
-        }
-    } else {
-        symbol
-            .strip_suffix(cash_asset)
-            .map(|value| value.to_string())
-            .unwrap_or_else(|| symbol.to_string())
-    };
+    let base_asset = base_asset_for(symbol, cash_asset);
     let base_qty = snapshot
         .balances
         .iter()
@@ -778,7 +2248,7 @@ fn account_from_snapshot(
     crate::models::Account { cash, positions }
 }
 
-fn infer_cash_asset(symbol: &str) -> Option<String> {
+pub(crate) fn infer_cash_asset(symbol: &str) -> Option<String> {
     let candidates = ["USDT", "USDC", "USD", "BUSD", "EUR"];
     if symbol.contains('-') {
         let parts: Vec<&str> = symbol.split('-').collect();
@@ -802,43 +2272,7 @@ fn infer_cash_asset(symbol: &str) -> Option<String> {
     None
 }
 
-fn parse_interval_seconds(interval: &str) -> Result<u64> {
-    let trimmed = interval.trim();
-    match trimmed {
-        "1" => return Ok(60),
-        "3" => return Ok(180),
-        "5" => return Ok(300),
-        "15" => return Ok(900),
-        "30" => return Ok(1800),
-        "60" => return Ok(3600),
-        "120" => return Ok(7200),
-        "240" => return Ok(14400),
-        "360" => return Ok(21600),
-        "720" => return Ok(43200),
-        "D" | "d" => return Ok(86400),
-        "W" | "w" => return Ok(604800),
-        "M" => return Ok(2592000),
-        _ => {}
-    }
-
-    match trimmed.to_lowercase().as_str() {
-        "1m" => Ok(60),
-        "3m" => Ok(180),
-        "5m" => Ok(300),
-        "15m" => Ok(900),
-        "30m" => Ok(1800),
-        "1h" => Ok(3600),
-        "2h" => Ok(7200),
-        "4h" => Ok(14400),
-        "6h" => Ok(21600),
-        "12h" => Ok(43200),
-        "1d" => Ok(86400),
-        "1w" => Ok(604800),
-        _ => Err(Error::new("unsupported interval for live mode")),
-    }
-}
-
-fn normalize_okx_symbol(symbol: &str, cash_asset: &str) -> String {
+fn normalize_dashed_symbol(symbol: &str, cash_asset: &str) -> String {
     if symbol.contains('-') {
         return symbol.to_string();
     }
@@ -854,3 +2288,252 @@ fn now_ms() -> Result<i64> {
         .map_err(|_| Error::new("system time before unix epoch"))?;
     Ok(now.as_millis() as i64)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        backoff_delay_ms, is_transient_error, load_backtest_bars, lookback_bars, parse_args,
+        CliCommand, KeystoreAction,
+    };
+    use crate::config::Config;
+
+    #[test]
+    fn parses_defaults() {
+        let args = vec!["merrow".to_string()];
+        let parsed = parse_args(&args).expect("parse");
+        assert_eq!(parsed.config_path, "config.toml");
+        assert!(parsed.symbol_override.is_none());
+        assert!(!parsed.show_help);
+    }
+
+    #[test]
+    fn parses_overrides() {
+        let args = vec![
+            "merrow".to_string(),
+            "--config".to_string(),
+            "custom.toml".to_string(),
+            "--symbol".to_string(),
+            "ETHUSDT".to_string(),
+            "--output-format".to_string(),
+            "json".to_string(),
+            "--output-path".to_string(),
+            "out/report.json".to_string(),
+            "--initial-cash".to_string(),
+            "5000".to_string(),
+            "--live-execute".to_string(),
+            "--reset-halt".to_string(),
+            "--pg-enabled".to_string(),
+            "true".to_string(),
+        ];
+        let parsed = parse_args(&args).expect("parse");
+        assert_eq!(parsed.config_path, "custom.toml");
+        assert_eq!(parsed.symbol_override.as_deref(), Some("ETHUSDT"));
+        assert_eq!(parsed.output_format.as_deref(), Some("json"));
+        assert_eq!(parsed.output_path.as_deref(), Some("out/report.json"));
+        assert_eq!(parsed.initial_cash_override, Some(5000.0));
+        assert!(parsed.live_execute);
+        assert!(parsed.reset_halt);
+        assert_eq!(parsed.pg_enabled_override, Some(true));
+    }
+
+    #[test]
+    fn parses_cancel_all_command() {
+        let args = vec![
+            "merrow".to_string(),
+            "cancel-all".to_string(),
+            "--config".to_string(),
+            "live.toml".to_string(),
+            "--include-foreign".to_string(),
+        ];
+        let parsed = parse_args(&args).expect("parse");
+        assert_eq!(parsed.command, CliCommand::CancelAll);
+        assert_eq!(parsed.config_path, "live.toml");
+        assert!(parsed.include_foreign);
+
+        let args = vec!["merrow".to_string(), "--include-foreign".to_string()];
+        assert!(parse_args(&args).is_err());
+    }
+
+    #[test]
+    fn lookback_covers_largest_timeframe() {
+        let mut config = Config::default();
+        config.data.candle_interval = "1h".to_string();
+        config.triggers.ma_window = 20;
+        assert_eq!(lookback_bars(&config).expect("lookback"), 22);
+        config.data.timeframes = vec!["4h".to_string(), "1d".to_string()];
+        assert_eq!(lookback_bars(&config).expect("lookback"), 20 * 24 + 25);
+    }
+
+    #[test]
+    fn parses_keystore_commands() {
+        let args = vec![
+            "merrow".to_string(),
+            "keystore".to_string(),
+            "add".to_string(),
+            "--exchange".to_string(),
+            "OKX".to_string(),
+            "--label".to_string(),
+            "sub-1".to_string(),
+        ];
+        let parsed = parse_args(&args).expect("parse");
+        assert_eq!(parsed.command, CliCommand::Keystore(KeystoreAction::Add));
+        assert_eq!(parsed.exchange_override.as_deref(), Some("okx"));
+        assert_eq!(parsed.keystore_label.as_deref(), Some("sub-1"));
+
+        let args = vec!["merrow".to_string(), "keystore".to_string(), "list".to_string()];
+        let parsed = parse_args(&args).expect("parse");
+        assert_eq!(parsed.command, CliCommand::Keystore(KeystoreAction::List));
+
+        let args = vec!["merrow".to_string(), "keystore".to_string(), "remove".to_string()];
+        assert!(parse_args(&args).is_err());
+        let args = vec!["merrow".to_string(), "keystore".to_string()];
+        assert!(parse_args(&args).is_err());
+        let args = vec![
+            "merrow".to_string(),
+            "--exchange".to_string(),
+            "binance".to_string(),
+        ];
+        assert!(parse_args(&args).is_err());
+    }
+
+    #[test]
+    fn parses_fetch_command() {
+        let args = vec![
+            "merrow".to_string(),
+            "fetch".to_string(),
+            "--exchange".to_string(),
+            "Bybit".to_string(),
+            "--symbols".to_string(),
+            "BTCUSDT, ETHUSDT".to_string(),
+            "--intervals".to_string(),
+            "1h,4h".to_string(),
+            "--range".to_string(),
+            "2024-01-01..2024-02-01".to_string(),
+            "--range".to_string(),
+            "2024-03-01..2024-04-01".to_string(),
+            "--to".to_string(),
+            "postgres".to_string(),
+        ];
+        let parsed = parse_args(&args).expect("parse");
+        assert_eq!(parsed.command, CliCommand::Fetch);
+        assert_eq!(parsed.exchange_override.as_deref(), Some("bybit"));
+        assert_eq!(parsed.fetch.symbols, vec!["BTCUSDT", "ETHUSDT"]);
+        assert_eq!(parsed.fetch.intervals, vec!["1h", "4h"]);
+        assert_eq!(parsed.fetch.ranges.len(), 2);
+        assert_eq!(
+            parsed.fetch.ranges[0],
+            ("2024-01-01".to_string(), "2024-02-01".to_string())
+        );
+        assert_eq!(parsed.fetch.sink.as_deref(), Some("postgres"));
+
+        let args = vec![
+            "merrow".to_string(),
+            "fetch".to_string(),
+            "--range".to_string(),
+            "2024-01-01".to_string(),
+        ];
+        assert!(parse_args(&args).is_err());
+        let args = vec![
+            "merrow".to_string(),
+            "fetch".to_string(),
+            "--to".to_string(),
+            "parquet".to_string(),
+        ];
+        assert!(parse_args(&args).is_err());
+        let args = vec![
+            "merrow".to_string(),
+            "--symbols".to_string(),
+            "BTCUSDT".to_string(),
+        ];
+        assert!(parse_args(&args).is_err());
+    }
+
+    #[test]
+    fn parses_data_check_command() {
+        let args = vec![
+            "merrow".to_string(),
+            "data".to_string(),
+            "check".to_string(),
+            "--config".to_string(),
+            "bt.toml".to_string(),
+        ];
+        let parsed = parse_args(&args).expect("parse");
+        assert_eq!(parsed.command, CliCommand::DataCheck);
+        assert_eq!(parsed.config_path, "bt.toml");
+
+        let args = vec!["merrow".to_string(), "data".to_string()];
+        assert!(parse_args(&args).is_err());
+    }
+
+    #[test]
+    fn parses_account_filter() {
+        let args = vec![
+            "merrow".to_string(),
+            "cancel-all".to_string(),
+            "--account".to_string(),
+            "book-a".to_string(),
+        ];
+        let parsed = parse_args(&args).expect("parse");
+        assert_eq!(parsed.account.as_deref(), Some("book-a"));
+
+        let args = vec!["merrow".to_string(), "--account".to_string()];
+        assert!(parse_args(&args).is_err());
+    }
+
+    #[test]
+    fn detects_transient_errors() {
+        assert!(is_transient_error("binance response status: 429"));
+        assert!(is_transient_error("http request failed: timeout"));
+        assert!(is_transient_error("response status: 503"));
+        assert!(!is_transient_error("invalid api key"));
+    }
+
+    #[test]
+    fn backoff_clamps_with_defaults() {
+        let delay = backoff_delay_ms(500, 10);
+        assert!(delay <= 8_000);
+    }
+
+    fn gapped_csv_config(name: &str) -> Config {
+        let dir = std::env::temp_dir().join("merrow_cli_tests");
+        std::fs::create_dir_all(&dir).expect("dir");
+        let path = dir.join(name);
+        let mut content = String::from("time,open,high,low,close,volume\n");
+        for (index, time) in [0, 60, 120, 300, 360].iter().enumerate() {
+            let close = 100.0 + index as f64 * 10.0;
+            content.push_str(&format!("{time},{close},{},{},{close},1\n", close + 1.0, close - 1.0));
+        }
+        std::fs::write(&path, content).expect("write csv");
+        let mut config = Config::default();
+        config.data.source = "csv".to_string();
+        config.data.csv_path = Some(path.to_string_lossy().to_string());
+        config.data.candle_interval = "1m".to_string();
+        config.backtest.start_time = Some("0".to_string());
+        config.backtest.end_time = Some("360".to_string());
+        config
+    }
+
+    #[test]
+    fn gap_filled_bars_are_not_persisted_as_prices() {
+        let mut config = gapped_csv_config("gap_fill.csv");
+        config.data.gap_policy = "fill".to_string();
+        let (prices, bars) = load_backtest_bars(&config).expect("load");
+        assert_eq!(bars.len(), 7);
+        let times: Vec<i64> = prices.iter().map(|candle| candle.time).collect();
+        assert_eq!(times, vec![0, 60, 120, 300, 360]);
+    }
+
+    #[test]
+    fn renko_bars_are_not_persisted_as_prices() {
+        let mut config = gapped_csv_config("renko.csv");
+        config.data.gap_policy = "warn".to_string();
+        config.data.bars = "renko".to_string();
+        config.data.bar_size = 5.0;
+        let (prices, bars) = load_backtest_bars(&config).expect("load");
+        assert!(bars.len() > prices.len());
+        let closes: Vec<f64> = prices.iter().map(|candle| candle.close).collect();
+        assert_eq!(closes, vec![100.0, 110.0, 120.0, 130.0, 140.0]);
+        let times: Vec<i64> = prices.iter().map(|candle| candle.time).collect();
+        assert_eq!(times, vec![0, 60, 120, 300, 360]);
+    }
+}
@@ -1,9 +1,10 @@
 use crate::backtest::BacktestMetrics;
 use crate::{Error, Result};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 static START_TIME: OnceLock<i64> = OnceLock::new();
@@ -21,6 +22,7 @@ static TRIGGER_FIRE_TOTAL: AtomicU64 = AtomicU64::new(0);
 static LIVE_RETRY_TOTAL: AtomicU64 = AtomicU64::new(0);
 static LIVE_RETRY_EXHAUSTED_TOTAL: AtomicU64 = AtomicU64::new(0);
 static ERRORS_TOTAL: AtomicU64 = AtomicU64::new(0);
+static CLOCK_DRIFT_ALERTS_TOTAL: AtomicU64 = AtomicU64::new(0);
 
 static LAST_RUN_TIMESTAMP: AtomicU64 = AtomicU64::new(0);
 static LAST_RUN_MODE_BACKTEST: AtomicU64 = AtomicU64::new(0);
@@ -31,6 +33,18 @@ static LAST_RETURN_RATE_BITS: AtomicU64 = AtomicU64::new(0);
 static LAST_MAX_DRAWDOWN_BITS: AtomicU64 = AtomicU64::new(0);
 static LAST_WIN_RATE_BITS: AtomicU64 = AtomicU64::new(0);
 static LAST_SHARPE_BITS: AtomicU64 = AtomicU64::new(0);
+static CLOCK_OFFSET_MS_BITS: AtomicU64 = AtomicU64::new(0);
+static CLOCK_LATENCY_MS_BITS: AtomicU64 = AtomicU64::new(0);
+
+static ACCOUNT_LIVE: Mutex<BTreeMap<String, AccountLiveCounters>> = Mutex::new(BTreeMap::new());
+
+#[derive(Clone, Copy, Default)]
+struct AccountLiveCounters {
+    runs: u64,
+    signals: u64,
+    orders_planned: u64,
+    orders_sent: u64,
+}
 
 pub fn init_start_time() {
     let _ = START_TIME.set(now_epoch());
@@ -50,7 +64,14 @@ pub fn record_paper(metrics: &BacktestMetrics, trade_count: usize) {
     set_last_run_mode("paper");
 }
 
-pub fn record_live(triggered: bool, signals: usize, orders: usize, sent: usize) {
+pub fn record_live(account: &str, triggered: bool, signals: usize, orders: usize, sent: usize) {
+    if let Ok(mut accounts) = ACCOUNT_LIVE.lock() {
+        let counters = accounts.entry(account.to_string()).or_default();
+        counters.runs += 1;
+        counters.signals += signals as u64;
+        counters.orders_planned += orders as u64;
+        counters.orders_sent += sent as u64;
+    }
     LIVE_RUNS_TOTAL.fetch_add(1, Ordering::Relaxed);
     SIGNALS_TOTAL.fetch_add(signals as u64, Ordering::Relaxed);
     ORDERS_PLANNED_TOTAL.fetch_add(orders as u64, Ordering::Relaxed);
@@ -73,6 +94,14 @@ pub fn inc_error() {
     ERRORS_TOTAL.fetch_add(1, Ordering::Relaxed);
 }
 
+pub fn record_clock_sync(offset_ms: i64, latency_ms: i64, drift_alert: bool) {
+    store_f64(&CLOCK_OFFSET_MS_BITS, offset_ms as f64);
+    store_f64(&CLOCK_LATENCY_MS_BITS, latency_ms as f64);
+    if drift_alert {
+        CLOCK_DRIFT_ALERTS_TOTAL.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
 pub fn write_if_configured() -> Result<()> {
     let path = match std::env::var("MERROW_METRICS_PATH") {
         Ok(value) if !value.trim().is_empty() => value,
@@ -158,6 +187,7 @@ pub fn render() -> String {
             LIVE_ORDERS_SENT_TOTAL.load(Ordering::Relaxed)
         ),
     );
+    push_account_live(&mut output);
     push_line(&mut output, "# HELP merrow_trigger_fire_total Trigger fires");
     push_line(&mut output, "# TYPE merrow_trigger_fire_total counter");
     push_line(
@@ -269,9 +299,94 @@ pub fn render() -> String {
         &mut output,
         &format!("merrow_last_sharpe {}", load_f64(&LAST_SHARPE_BITS)),
     );
+    push_line(
+        &mut output,
+        "# HELP merrow_clock_offset_ms Exchange server time minus local time (ms)",
+    );
+    push_line(&mut output, "# TYPE merrow_clock_offset_ms gauge");
+    push_line(
+        &mut output,
+        &format!("merrow_clock_offset_ms {}", load_f64(&CLOCK_OFFSET_MS_BITS)),
+    );
+    push_line(
+        &mut output,
+        "# HELP merrow_clock_latency_ms Round trip of the last time sync (ms)",
+    );
+    push_line(&mut output, "# TYPE merrow_clock_latency_ms gauge");
+    push_line(
+        &mut output,
+        &format!("merrow_clock_latency_ms {}", load_f64(&CLOCK_LATENCY_MS_BITS)),
+    );
+    push_line(
+        &mut output,
+        "# HELP merrow_clock_drift_alerts_total Time syncs with drift above threshold",
+    );
+    push_line(&mut output, "# TYPE merrow_clock_drift_alerts_total counter");
+    push_line(
+        &mut output,
+        &format!(
+            "merrow_clock_drift_alerts_total {}",
+            CLOCK_DRIFT_ALERTS_TOTAL.load(Ordering::Relaxed)
+        ),
+    );
     output
 }
 
+fn push_account_live(output: &mut String) {
+    let accounts = match ACCOUNT_LIVE.lock() {
+        Ok(accounts) => accounts.clone(),
+        Err(_) => return,
+    };
+    if accounts.is_empty() {
+        return;
+    }
+    push_account_series(
+        output,
+        "merrow_account_live_runs_total",
+        "Live runs per account",
+        &accounts,
+        |counters| counters.runs,
+    );
+    push_account_series(
+        output,
+        "merrow_account_signals_total",
+        "Live signals per account",
+        &accounts,
+        |counters| counters.signals,
+    );
+    push_account_series(
+        output,
+        "merrow_account_orders_planned_total",
+        "Live planned orders per account",
+        &accounts,
+        |counters| counters.orders_planned,
+    );
+    push_account_series(
+        output,
+        "merrow_account_orders_sent_total",
+        "Live orders sent per account",
+        &accounts,
+        |counters| counters.orders_sent,
+    );
+}
+
+fn push_account_series(
+    output: &mut String,
+    name: &str,
+    help: &str,
+    accounts: &BTreeMap<String, AccountLiveCounters>,
+    value: impl Fn(&AccountLiveCounters) -> u64,
+) {
+    push_line(output, &format!("# HELP {name} {help}"));
+    push_line(output, &format!("# TYPE {name} counter"));
+    for (account, counters) in accounts {
+        push_line(
+            output,
+            &format!("{name}{{account=\"{account}\"}} {}", value(counters)),
+        );
+    }
+}
+
 fn set_last_metrics(metrics: &BacktestMetrics) {
     store_f64(&LAST_RETURN_RATE_BITS, metrics.return_rate);
     store_f64(&LAST_MAX_DRAWDOWN_BITS, metrics.max_drawdown);
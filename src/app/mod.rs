@@ -2,4 +2,5 @@ pub mod cli;
 pub mod logging;
 pub mod metrics;
 pub mod report;
+pub mod sim_server;
 pub mod ui_server;
@@ -1,8 +1,42 @@
 use crate::backtest::{BacktestMetrics, BacktestResult, EquityPoint};
+use crate::core::execution::ExecutionReport;
+use crate::models::Side;
 use crate::{Error, Result};
+use parquet::basic::Compression;
+use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
 use serde::Serialize;
-use std::fs;
+use std::collections::HashMap;
+use std::fs::{self, File};
 use std::path::Path;
+use std::sync::Arc;
+
+const TRADES_SCHEMA: &str = "message trades {
+    REQUIRED INT64 time;
+    REQUIRED BYTE_ARRAY symbol (UTF8);
+    REQUIRED BYTE_ARRAY side (UTF8);
+    REQUIRED DOUBLE price;
+    REQUIRED DOUBLE quantity;
+    REQUIRED DOUBLE fee;
+    OPTIONAL DOUBLE pnl;
+}";
+const EQUITY_SCHEMA: &str = "message equity_curve {
+    REQUIRED INT64 time;
+    REQUIRED DOUBLE equity;
+}";
+const ROUND_TRIPS_SCHEMA: &str = "message round_trips {
+    REQUIRED BYTE_ARRAY symbol (UTF8);
+    REQUIRED INT64 entry_time;
+    REQUIRED INT64 exit_time;
+    REQUIRED DOUBLE quantity;
+    REQUIRED DOUBLE entry_price;
+    REQUIRED DOUBLE exit_price;
+    REQUIRED DOUBLE fees;
+    REQUIRED DOUBLE pnl;
+}";
+const FLAT_EPSILON: f64 = 1e-9;
 
 #[derive(Serialize)]
 struct TradeReport {
@@ -30,7 +64,9 @@ struct BacktestReport {
     ending_cash: f64,
     trades: Vec<TradeReport>,
     equity_curve: Vec<EquityReport>,
+    round_trips: Vec<RoundTrip>,
     costs: CostsReport,
+    executions: Vec<ExecutionReport>,
 }
 
 #[derive(Serialize)]
@@ -39,6 +75,37 @@ struct EquityReport {
     equity: f64,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RoundTrip {
+    pub symbol: String,
+    pub entry_time: i64,
+    pub exit_time: i64,
+    pub quantity: f64,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub fees: f64,
+    pub pnl: f64,
+}
+
+#[derive(Default)]
+struct OpenTrip {
+    entry_time: i64,
+    position: f64,
+    bought: f64,
+    buy_notional: f64,
+    sold: f64,
+    sell_notional: f64,
+    fees: f64,
+    pnl: f64,
+}
+
+enum ParquetColumn {
+    Int64(Vec<i64>),
+    Double(Vec<f64>),
+    OptionalDouble(Vec<Option<f64>>),
+    Text(Vec<String>),
+}
+
 #[derive(Serialize)]
 struct CostsReport {
     total_fees: f64,
@@ -49,8 +116,11 @@ pub fn write_output(path: &str, format: &str, result: &BacktestResult) -> Result
     match format {
         "json" => write_json(path, result),
         "csv" => write_csv(path, result),
+        "parquet" => write_parquet(path, result),
         "none" => Ok(()),
-        _ => Err(Error::new("output.format must be none, json, or csv")),
+        _ => Err(Error::new(
+            "output.format must be none, json, csv, or parquet",
+        )),
     }
 }
 
@@ -87,6 +157,167 @@ fn write_csv(path: &str, result: &BacktestResult) -> Result<()> {
     Ok(())
 }
 
+pub fn parquet_table_path(path: &str, table: &str) -> String {
+    let path = Path::new(path);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "backtest_report".to_string());
+    path.with_file_name(format!("{stem}_{table}.parquet"))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn write_parquet(path: &str, result: &BacktestResult) -> Result<()> {
+    ensure_parent_dir(path)?;
+    let trades = &result.trades;
+    write_parquet_table(
+        &parquet_table_path(path, "trades"),
+        TRADES_SCHEMA,
+        vec![
+            ParquetColumn::Int64(trades.iter().map(|trade| trade.time).collect()),
+            ParquetColumn::Text(trades.iter().map(|trade| trade.symbol.clone()).collect()),
+            ParquetColumn::Text(trades.iter().map(|trade| side_label(&trade.side)).collect()),
+            ParquetColumn::Double(trades.iter().map(|trade| trade.price).collect()),
+            ParquetColumn::Double(trades.iter().map(|trade| trade.quantity).collect()),
+            ParquetColumn::Double(trades.iter().map(|trade| trade.fee).collect()),
+            ParquetColumn::OptionalDouble(
+                (0..trades.len())
+                    .map(|index| result.trade_pnls.get(index).copied().flatten())
+                    .collect(),
+            ),
+        ],
+    )?;
+
+    let curve = &result.equity_curve;
+    write_parquet_table(
+        &parquet_table_path(path, "equity"),
+        EQUITY_SCHEMA,
+        vec![
+            ParquetColumn::Int64(curve.iter().map(|point| point.time).collect()),
+            ParquetColumn::Double(curve.iter().map(|point| point.equity).collect()),
+        ],
+    )?;
+
+    let trips = round_trips(result);
+    write_parquet_table(
+        &parquet_table_path(path, "round_trips"),
+        ROUND_TRIPS_SCHEMA,
+        vec![
+            ParquetColumn::Text(trips.iter().map(|trip| trip.symbol.clone()).collect()),
+            ParquetColumn::Int64(trips.iter().map(|trip| trip.entry_time).collect()),
+            ParquetColumn::Int64(trips.iter().map(|trip| trip.exit_time).collect()),
+            ParquetColumn::Double(trips.iter().map(|trip| trip.quantity).collect()),
+            ParquetColumn::Double(trips.iter().map(|trip| trip.entry_price).collect()),
+            ParquetColumn::Double(trips.iter().map(|trip| trip.exit_price).collect()),
+            ParquetColumn::Double(trips.iter().map(|trip| trip.fees).collect()),
+            ParquetColumn::Double(trips.iter().map(|trip| trip.pnl).collect()),
+        ],
+    )
+}
+
+fn write_parquet_table(path: &str, message: &str, columns: Vec<ParquetColumn>) -> Result<()> {
+    let schema = Arc::new(parse_message_type(message).map_err(parquet_error)?);
+    let props = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build(),
+    );
+    let file = File::create(path).map_err(|err| Error::new(format!("write failed: {err}")))?;
+    let mut writer = SerializedFileWriter::new(file, schema, props).map_err(parquet_error)?;
+    let mut group = writer.next_row_group().map_err(parquet_error)?;
+    let mut columns = columns.into_iter();
+    while let Some(mut column) = group.next_column().map_err(parquet_error)? {
+        let written = match columns.next() {
+            Some(ParquetColumn::Int64(values)) => {
+                column.typed::<Int64Type>().write_batch(&values, None, None)
+            }
+            Some(ParquetColumn::Double(values)) => column
+                .typed::<DoubleType>()
+                .write_batch(&values, None, None),
+            Some(ParquetColumn::OptionalDouble(values)) => {
+                let levels: Vec<i16> = values.iter().map(|value| value.is_some() as i16).collect();
+                let present: Vec<f64> = values.into_iter().flatten().collect();
+                column
+                    .typed::<DoubleType>()
+                    .write_batch(&present, Some(&levels), None)
+            }
+            Some(ParquetColumn::Text(values)) => {
+                let values: Vec<ByteArray> = values
+                    .iter()
+                    .map(|value| ByteArray::from(value.as_str()))
+                    .collect();
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, None, None)
+            }
+            None => return Err(Error::new("parquet schema has more columns than data")),
+        };
+        written.map_err(parquet_error)?;
+        column.close().map_err(parquet_error)?;
+    }
+    group.close().map_err(parquet_error)?;
+    writer.close().map_err(parquet_error)?;
+    Ok(())
+}
+
+fn parquet_error(err: parquet::errors::ParquetError) -> Error {
+    Error::new(format!("parquet write failed: {err}"))
+}
+
+pub fn round_trips(result: &BacktestResult) -> Vec<RoundTrip> {
+    let mut open: HashMap<&str, OpenTrip> = HashMap::new();
+    let mut closed = Vec::new();
+    for (index, trade) in result.trades.iter().enumerate() {
+        let pnl = result
+            .trade_pnls
+            .get(index)
+            .copied()
+            .flatten()
+            .unwrap_or(0.0);
+        match trade.side {
+            Side::Buy => {
+                let trip = open
+                    .entry(trade.symbol.as_str())
+                    .or_insert_with(|| OpenTrip {
+                        entry_time: trade.time,
+                        ..OpenTrip::default()
+                    });
+                trip.position += trade.quantity;
+                trip.bought += trade.quantity;
+                trip.buy_notional += trade.price * trade.quantity;
+                trip.fees += trade.fee;
+                trip.pnl += pnl;
+            }
+            Side::Sell => {
+                let Some(trip) = open.get_mut(trade.symbol.as_str()) else {
+                    continue;
+                };
+                trip.position -= trade.quantity;
+                trip.sold += trade.quantity;
+                trip.sell_notional += trade.price * trade.quantity;
+                trip.fees += trade.fee;
+                trip.pnl += pnl;
+                if trip.position <= FLAT_EPSILON {
+                    if let Some(trip) = open.remove(trade.symbol.as_str()) {
+                        closed.push(RoundTrip {
+                            symbol: trade.symbol.clone(),
+                            entry_time: trip.entry_time,
+                            exit_time: trade.time,
+                            quantity: trip.bought,
+                            entry_price: trip.buy_notional / trip.bought,
+                            exit_price: trip.sell_notional / trip.sold,
+                            fees: trip.fees,
+                            pnl: trip.pnl,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    closed
+}
+
 fn build_report(result: &BacktestResult) -> BacktestReport {
     let total_fees = result.trades.iter().map(|trade| trade.fee).sum::<f64>();
     let average_fee = if result.trades.is_empty() {
@@ -113,10 +344,12 @@ fn build_report(result: &BacktestResult) -> BacktestReport {
             })
             .collect(),
         equity_curve: to_equity_report(&result.equity_curve),
+        round_trips: round_trips(result),
         costs: CostsReport {
             total_fees,
             average_fee,
         },
+        executions: result.executions.clone(),
     }
 }
 
@@ -140,10 +373,10 @@ fn to_equity_report(curve: &[EquityPoint]) -> Vec<EquityReport> {
         .collect()
 }
 
-fn side_label(side: &crate::models::Side) -> String {
+fn side_label(side: &Side) -> String {
     match side {
-        crate::models::Side::Buy => "buy".to_string(),
-        crate::models::Side::Sell => "sell".to_string(),
+        Side::Buy => "buy".to_string(),
+        Side::Sell => "sell".to_string(),
     }
 }
 
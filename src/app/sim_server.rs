@@ -0,0 +1,320 @@
+use crate::app::cli::infer_cash_asset;
+use crate::config::Config;
+use crate::data::csv_loader::load_csv_with_schema;
+use crate::exchange::rest::{
+    hmac_sha256_hex, sign_payload, RestBalance, RestCandle, RestOrder, RestOrderAck,
+    RestPosition, API_KEY_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use crate::exchange::sim::{SimConfig, SimExchange};
+use crate::exchange::{CandleRequest, Exchange};
+use crate::{Error, Result};
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Json;
+use axum::Router;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+#[derive(Clone, Debug, Default)]
+pub struct SimServerAuth {
+    pub api_key: Option<String>,
+    pub api_secret: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimServerOptions {
+    pub csv_path: Option<String>,
+    pub warmup_bars: usize,
+    pub initial_base: f64,
+}
+
+struct SimServerState {
+    exchange: SimExchange,
+    auth: SimServerAuth,
+}
+
+#[derive(Deserialize)]
+struct CandleQuery {
+    symbol: String,
+    interval: Option<String>,
+    start_time: i64,
+    end_time: i64,
+}
+
+pub fn router(exchange: SimExchange, auth: SimServerAuth) -> Router {
+    let state = SimServerState { exchange, auth };
+    Router::new()
+        .route("/api/v1/time", get(server_time))
+        .route("/api/v1/candles", get(candles))
+        .route("/api/v1/orders", post(place_order))
+        .route("/api/v1/orders/open", get(open_orders))
+        .route("/api/v1/orders/:order_id", get(order_status).delete(cancel_order))
+        .route("/api/v1/balances", get(balances))
+        .route("/api/v1/positions", get(positions))
+        .with_state(Arc::new(state))
+}
+
+pub fn build_exchange(config: &Config, options: &SimServerOptions) -> Result<SimExchange> {
+    let csv_path = options
+        .csv_path
+        .clone()
+        .or_else(|| config.data.csv_path.clone())
+        .ok_or_else(|| Error::new("sim requires data.csv_path or --csv"))?;
+    let candles = load_csv_with_schema(&csv_path, &config.data.csv)?.candles;
+    let quote_asset = std::env::var("MERROW_CASH_ASSET")
+        .ok()
+        .or_else(|| infer_cash_asset(&config.symbol))
+        .ok_or_else(|| Error::new("cash asset not found; set MERROW_CASH_ASSET"))?;
+    let base_asset = config
+        .symbol
+        .strip_suffix(&quote_asset)
+        .unwrap_or(&config.symbol)
+        .trim_end_matches('-')
+        .to_string();
+
+    SimExchange::new(
+        SimConfig {
+            symbol: config.symbol.clone(),
+            base_asset,
+            quote_asset,
+            initial_quote: config.backtest.initial_cash,
+            initial_base: options.initial_base,
+            fee_rate: config.orders.fee_rate,
+            slippage_bps: config.orders.slippage_bps,
+            warmup_bars: options.warmup_bars,
+        },
+        candles,
+    )
+}
+
+pub async fn run_from_config(
+    addr: &str,
+    config_path: &str,
+    options: &SimServerOptions,
+) -> Result<()> {
+    let config = Config::load(config_path)?;
+    let exchange = build_exchange(&config, options)?;
+    let auth = SimServerAuth {
+        api_key: std::env::var("MERROW_SIM_API_KEY").ok(),
+        api_secret: std::env::var("MERROW_SIM_API_SECRET").ok(),
+    };
+    run(addr, exchange, auth).await
+}
+
+pub async fn run(addr: &str, exchange: SimExchange, auth: SimServerAuth) -> Result<()> {
+    let app = router(exchange, auth);
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|err| Error::new(format!("bind failed: {err}")))?;
+    info!(addr = %addr, "sim exchange listening");
+    axum::serve(listener, app)
+        .await
+        .map_err(|err| Error::new(format!("server error: {err}")))?;
+    Ok(())
+}
+
+async fn server_time() -> impl IntoResponse {
+    Json(json!({ "server_time": now_ms() }))
+}
+
+async fn candles(
+    State(state): State<Arc<SimServerState>>,
+    Query(query): Query<CandleQuery>,
+) -> Response {
+    let req = CandleRequest {
+        symbol: query.symbol,
+        interval: query.interval.unwrap_or_default(),
+        start_time: query.start_time,
+        end_time: query.end_time,
+    };
+    match state.exchange.fetch_candles(&req) {
+        Ok(candles) => {
+            let rows: Vec<RestCandle> = candles.iter().map(RestCandle::from).collect();
+            Json(rows).into_response()
+        }
+        Err(err) => error_response(StatusCode::BAD_REQUEST, err),
+    }
+}
+
+async fn place_order(
+    State(state): State<Arc<SimServerState>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(err) = verify_signature(&state.auth, &method, &uri, &headers, &body) {
+        return error_response(StatusCode::UNAUTHORIZED, err);
+    }
+    let order: RestOrder = match serde_json::from_slice(&body) {
+        Ok(order) => order,
+        Err(err) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                Error::new(format!("invalid order body: {err}")),
+            )
+        }
+    };
+    let result = order
+        .to_request()
+        .and_then(|request| state.exchange.place_order(&request));
+    match result {
+        Ok(ack) => Json(RestOrderAck::from_ack(&ack)).into_response(),
+        Err(err) => error_response(StatusCode::BAD_REQUEST, err),
+    }
+}
+
+async fn cancel_order(
+    State(state): State<Arc<SimServerState>>,
+    Path(order_id): Path<String>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(err) = verify_signature(&state.auth, &method, &uri, &headers, &[]) {
+        return error_response(StatusCode::UNAUTHORIZED, err);
+    }
+    match state.exchange.cancel_order(&order_id) {
+        Ok(()) => Json(json!({ "client_order_id": order_id, "status": "canceled" }))
+            .into_response(),
+        Err(err) => error_response(StatusCode::NOT_FOUND, err),
+    }
+}
+
+async fn order_status(
+    State(state): State<Arc<SimServerState>>,
+    Path(order_id): Path<String>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(err) = verify_signature(&state.auth, &method, &uri, &headers, &[]) {
+        return error_response(StatusCode::UNAUTHORIZED, err);
+    }
+    match state.exchange.fetch_order(&order_id) {
+        Ok(Some(ack)) => Json(RestOrderAck::from_ack(&ack)).into_response(),
+        Ok(None) => error_response(
+            StatusCode::NOT_FOUND,
+            Error::new(format!("unknown order: {order_id}")),
+        ),
+        Err(err) => error_response(StatusCode::INTERNAL_SERVER_ERROR, err),
+    }
+}
+
+async fn open_orders(
+    State(state): State<Arc<SimServerState>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(err) = verify_signature(&state.auth, &method, &uri, &headers, &[]) {
+        return error_response(StatusCode::UNAUTHORIZED, err);
+    }
+    match state.exchange.fetch_open_orders() {
+        Ok(orders) => {
+            let rows: Vec<RestOrderAck> = orders.iter().map(RestOrderAck::from_ack).collect();
+            Json(rows).into_response()
+        }
+        Err(err) => error_response(StatusCode::INTERNAL_SERVER_ERROR, err),
+    }
+}
+
+async fn balances(
+    State(state): State<Arc<SimServerState>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(err) = verify_signature(&state.auth, &method, &uri, &headers, &[]) {
+        return error_response(StatusCode::UNAUTHORIZED, err);
+    }
+    match state.exchange.fetch_balances() {
+        Ok(balances) => {
+            let rows: Vec<RestBalance> = balances
+                .into_iter()
+                .map(|balance| RestBalance {
+                    asset: balance.asset,
+                    free: balance.free,
+                    locked: balance.locked,
+                })
+                .collect();
+            Json(rows).into_response()
+        }
+        Err(err) => error_response(StatusCode::INTERNAL_SERVER_ERROR, err),
+    }
+}
+
+async fn positions(
+    State(state): State<Arc<SimServerState>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(err) = verify_signature(&state.auth, &method, &uri, &headers, &[]) {
+        return error_response(StatusCode::UNAUTHORIZED, err);
+    }
+    match state.exchange.fetch_positions() {
+        Ok(positions) => {
+            let rows: Vec<RestPosition> = positions
+                .into_iter()
+                .map(|position| RestPosition {
+                    symbol: position.symbol,
+                    quantity: position.quantity,
+                    avg_price: position.avg_price,
+                })
+                .collect();
+            Json(rows).into_response()
+        }
+        Err(err) => error_response(StatusCode::INTERNAL_SERVER_ERROR, err),
+    }
+}
+
+fn verify_signature(
+    auth: &SimServerAuth,
+    method: &Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<()> {
+    let Some(expected_key) = auth.api_key.as_deref() else {
+        return Ok(());
+    };
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("")
+            .to_string()
+    };
+    if header(API_KEY_HEADER) != expected_key {
+        return Err(Error::new("invalid api key"));
+    }
+    let Some(secret) = auth.api_secret.as_deref() else {
+        return Ok(());
+    };
+    let timestamp = header(TIMESTAMP_HEADER);
+    let body = std::str::from_utf8(body).map_err(|_| Error::new("body must be utf-8"))?;
+    let payload = sign_payload(&timestamp, method.as_str(), uri.path(), body);
+    let expected = hmac_sha256_hex(secret, &payload)?;
+    if header(SIGNATURE_HEADER) != expected {
+        return Err(Error::new("invalid signature"));
+    }
+    Ok(())
+}
+
+fn error_response(status: StatusCode, err: Error) -> Response {
+    (status, Json(json!({ "error": err.message }))).into_response()
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
@@ -1,5 +1,4 @@
 use crate::config::Config;
-use crate::storage::postgres::PostgresStorage;
 use crate::{Error, Result};
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
@@ -99,7 +98,7 @@ struct LimitQuery {
 }
 
 pub async fn run(addr: &str, config_path: &str, pg_enabled: Option<bool>) -> Result<()> {
-    let mut config = Config::load(config_path)?;
+    let config = Config::load(config_path)?;
     config.validate()?;
 
     let pg_enabled = pg_enabled.unwrap_or_else(pg_env_enabled);
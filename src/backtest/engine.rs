@@ -1,10 +1,13 @@
 use crate::backtest::fill::{fill_limit, fill_market, ExecutionCosts};
 use crate::config::Config;
+use crate::core::execution::{ExecutionAlgo, ExecutionReport, ExecutionScheduler};
 use crate::core::order_flow::OrderFlow;
+use crate::core::repricing::RepricePolicy;
 use crate::core::strategy::Strategy;
 use crate::core::triggers::TriggerEngine;
 use crate::core::TriggerContext;
-use crate::models::{Account, Candle, OrderRequest, OrderType, Position, Trade};
+use crate::data::resample::build_timeframes;
+use crate::models::{Account, Candle, OrderRequest, OrderType, Position, Side, Trade};
 use crate::{Error, Result};
 
 #[derive(Clone)]
@@ -26,6 +29,7 @@ pub struct BacktestResult {
     pub metrics: BacktestMetrics,
     pub equity_curve: Vec<EquityPoint>,
     pub trade_pnls: Vec<Option<f64>>,
+    pub executions: Vec<ExecutionReport>,
 }
 
 #[derive(Clone, Debug)]
@@ -156,6 +160,7 @@ impl BacktestEngine {
                 },
                 equity_curve: Vec::new(),
                 trade_pnls: Vec::new(),
+                executions: Vec::new(),
             });
         }
 
@@ -165,6 +170,10 @@ impl BacktestEngine {
             }
         }
 
+        let algo = ExecutionAlgo::from_config(&config.execution)?;
+        let reprice = RepricePolicy::from_config(config);
+        let mut scheduler = ExecutionScheduler::new();
+        let mut executions: Vec<ExecutionReport> = Vec::new();
         let mut pending: Vec<PendingOrder> = Vec::new();
         let mut trades: Vec<Trade> = Vec::new();
         let costs = ExecutionCosts {
@@ -183,7 +192,29 @@ impl BacktestEngine {
                 .map(|pos| pos.quantity * candles[0].close)
                 .sum::<f64>();
 
+        let mut timeframes =
+            build_timeframes(&config.data.timeframes, &config.data.candle_interval)?;
         for (index, candle) in candles.iter().enumerate() {
+            for timeframe in &mut timeframes {
+                timeframe.push(candle);
+            }
+            let mark_equity = account.cash
+                + account
+                    .positions
+                    .iter()
+                    .filter(|pos| pos.symbol == config.symbol)
+                    .map(|pos| pos.quantity * candle.close)
+                    .sum::<f64>();
+            order_flow.observe_equity(candle.time, mark_equity);
+            if let Some(order) = order_flow.flatten_order(&account, config, candle.time)? {
+                pending.retain(|pending_order| pending_order.order.symbol != order.symbol);
+                scheduler.halt_symbol(&order.symbol);
+                let ready_index = index.saturating_add(1);
+                if ready_index < candles.len() {
+                    pending.push(PendingOrder { ready_index, order });
+                }
+            }
+
             let history = &candles[..=index];
             let trigger_ctx = TriggerContext {
                 candle,
@@ -196,20 +227,30 @@ impl BacktestEngine {
                     candle,
                     history,
                     account: &account,
+                    timeframes: &timeframes,
                     now: candle.time,
                 };
                 let signals = strategy.on_tick(&strategy_ctx);
                 for signal in signals {
                     let orders = order_flow.plan(signal, &strategy_ctx, config)?;
                     for order in orders {
-                        let ready_index = index.saturating_add(1);
-                        if ready_index < candles.len() {
-                            pending.push(PendingOrder { ready_index, order });
-                        }
+                        scheduler.submit(&order, &algo, candle.close, candle.time, history)?;
                     }
                 }
             }
 
+            for mut order in scheduler.due_children(candle.time, candle.close)? {
+                let ready_index = index.saturating_add(1);
+                if matches!(order.side, Side::Sell) {
+                    order.quantity = order.quantity.min(sellable_quantity(&account, &pending, &order));
+                }
+                if ready_index < candles.len() && order.quantity > 0.0 {
+                    pending.push(PendingOrder { ready_index, order });
+                } else {
+                    scheduler.release_child(&order.client_order_id);
+                }
+            }
+
             if pending.is_empty() {
                 continue;
             }
@@ -228,7 +269,14 @@ impl BacktestEngine {
 
                 match trade {
                     Some(trade) => {
+                        scheduler.record_fill(
+                            &pending_order.order.client_order_id,
+                            trade.quantity,
+                            trade.price,
+                            trade.fee,
+                        );
                         if let Some(pnl) = apply_trade(&mut account, &trade)? {
+                            order_flow.record_trade_pnl(trade.time, pnl);
                             if pnl > 0.0 {
                                 win_count += 1;
                             } else if pnl < 0.0 {
@@ -242,6 +290,19 @@ impl BacktestEngine {
                     }
                     None => {
                         if matches!(pending_order.order.order_type, OrderType::Limit { .. }) {
+                            let mut pending_order = pending_order;
+                            let client_order_id = pending_order.order.client_order_id.clone();
+                            let amend = scheduler
+                                .working_child(&client_order_id)
+                                .and_then(|child| {
+                                    reprice.reprice(&pending_order.order, candle.close, child.amends)
+                                });
+                            if let Some(amend) = amend {
+                                pending_order.order = amend.amended()?;
+                                if let OrderType::Limit { price } = pending_order.order.order_type {
+                                    scheduler.record_amend(&client_order_id, price);
+                                }
+                            }
                             next_pending.push(pending_order);
                         }
                     }
@@ -249,6 +310,7 @@ impl BacktestEngine {
             }
 
             pending = next_pending;
+            executions.extend(scheduler.take_completed());
 
             let equity = account.cash
                 + account
@@ -263,6 +325,7 @@ impl BacktestEngine {
             });
         }
 
+        executions.extend(scheduler.reports());
         let metrics = compute_metrics(
             starting_equity,
             &equity_curve,
@@ -276,11 +339,30 @@ impl BacktestEngine {
             metrics,
             equity_curve,
             trade_pnls,
+            executions,
         })
     }
 }
 
-fn apply_trade(account: &mut Account, trade: &Trade) -> Result<Option<f64>> {
+fn sellable_quantity(account: &Account, pending: &[PendingOrder], order: &OrderRequest) -> f64 {
+    let held = account
+        .positions
+        .iter()
+        .find(|pos| pos.symbol == order.symbol)
+        .map(|pos| pos.quantity)
+        .unwrap_or(0.0);
+    let reserved: f64 = pending
+        .iter()
+        .filter(|pending_order| {
+            pending_order.order.symbol == order.symbol
+                && matches!(pending_order.order.side, Side::Sell)
+        })
+        .map(|pending_order| pending_order.order.quantity)
+        .sum();
+    (held - reserved).max(0.0)
+}
+
+pub(crate) fn apply_trade(account: &mut Account, trade: &Trade) -> Result<Option<f64>> {
     let trade_value = trade.price * trade.quantity;
     let mut realized_pnl = None;
     match trade.side {
@@ -330,7 +412,7 @@ fn apply_trade(account: &mut Account, trade: &Trade) -> Result<Option<f64>> {
     Ok(realized_pnl)
 }
 
-fn compute_metrics(
+pub(crate) fn compute_metrics(
     starting_cash: f64,
     equity_curve: &[EquityPoint],
     trade_count: usize,
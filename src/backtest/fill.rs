@@ -1,4 +1,4 @@
-use crate::models::{Candle, OrderRequest, OrderType, Side, Trade};
+use crate::models::{Candle, OrderRequest, OrderType, Side, Trade, TradePrint};
 
 #[derive(Clone, Copy, Debug)]
 pub struct ExecutionCosts {
@@ -64,3 +64,47 @@ pub fn fill_limit(order: &OrderRequest, candle: &Candle, costs: ExecutionCosts)
         fee,
     })
 }
+
+pub fn fill_market_print(
+    order: &OrderRequest,
+    print: &TradePrint,
+    quantity: f64,
+    costs: ExecutionCosts,
+) -> Trade {
+    let price = apply_slippage(print.price, order.side.clone(), costs.slippage_bps);
+    Trade {
+        time: print.time_ms.div_euclid(1_000),
+        symbol: order.symbol.clone(),
+        side: order.side.clone(),
+        price,
+        quantity,
+        fee: price * quantity * costs.fee_rate,
+    }
+}
+
+pub fn fill_limit_print(
+    order: &OrderRequest,
+    print: &TradePrint,
+    quantity: f64,
+    costs: ExecutionCosts,
+) -> Option<Trade> {
+    let limit_price = match order.order_type {
+        OrderType::Limit { price } => price,
+        _ => return None,
+    };
+    let crossed = match order.side {
+        Side::Buy => print.price <= limit_price,
+        Side::Sell => print.price >= limit_price,
+    };
+    if !crossed || quantity <= 0.0 {
+        return None;
+    }
+    Some(Trade {
+        time: print.time_ms.div_euclid(1_000),
+        symbol: order.symbol.clone(),
+        side: order.side.clone(),
+        price: limit_price,
+        quantity,
+        fee: limit_price * quantity * costs.fee_rate,
+    })
+}
@@ -1,4 +1,8 @@
 pub mod engine;
 pub mod fill;
+pub mod routing;
+pub mod tick;
 
 pub use engine::{BacktestEngine, BacktestMetrics, BacktestOrder, BacktestResult, EquityPoint};
+pub use routing::{run_routed, BacktestVenue, RoutedBacktestResult, VenueBalance, VenueTrade};
+pub use tick::TickBacktestResult;
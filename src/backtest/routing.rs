@@ -0,0 +1,216 @@
+use crate::backtest::engine::BacktestOrder;
+use crate::backtest::fill::{fill_limit, fill_market, ExecutionCosts};
+use crate::core::order_router::{plan_routes, VenueSnapshot};
+use crate::models::{Candle, OrderRequest, OrderType, Side, Trade};
+use crate::{Error, Result};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+pub struct BacktestVenue {
+    pub name: String,
+    pub candles: Vec<Candle>,
+    pub fee_rate: f64,
+    pub slippage_bps: u32,
+    pub quote_balance: f64,
+    pub base_balance: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VenueTrade {
+    pub venue: String,
+    pub parent_id: String,
+    pub trade: Trade,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VenueBalance {
+    pub venue: String,
+    pub quote_balance: f64,
+    pub base_balance: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct RoutedBacktestResult {
+    pub trades: Vec<VenueTrade>,
+    pub balances: Vec<VenueBalance>,
+    pub unrouted: Vec<(String, f64)>,
+}
+
+struct PendingLeg {
+    venue: usize,
+    ready_index: usize,
+    parent_id: String,
+    order: OrderRequest,
+}
+
+pub fn run_routed(
+    venues: &[BacktestVenue],
+    mut orders: Vec<BacktestOrder>,
+    max_participation: f64,
+) -> Result<RoutedBacktestResult> {
+    if venues.is_empty() {
+        return Err(Error::new("routed backtest requires at least one venue"));
+    }
+    if !(0.0..=1.0).contains(&max_participation) {
+        return Err(Error::new("max_participation must be in [0, 1]"));
+    }
+
+    let mut times: Vec<i64> = venues
+        .iter()
+        .flat_map(|venue| venue.candles.iter().map(|candle| candle.time))
+        .collect();
+    times.sort_unstable();
+    times.dedup();
+    for order in &orders {
+        if order.submit_index >= times.len() {
+            return Err(Error::new("order submit_index out of range"));
+        }
+    }
+    orders.sort_by_key(|order| order.submit_index);
+
+    let by_time: Vec<HashMap<i64, &Candle>> = venues
+        .iter()
+        .map(|venue| venue.candles.iter().map(|candle| (candle.time, candle)).collect())
+        .collect();
+    let mut balances: Vec<VenueBalance> = venues
+        .iter()
+        .map(|venue| VenueBalance {
+            venue: venue.name.clone(),
+            quote_balance: venue.quote_balance,
+            base_balance: venue.base_balance,
+        })
+        .collect();
+    let mut last_seen: Vec<Option<&Candle>> = vec![None; venues.len()];
+
+    let mut pending: Vec<PendingLeg> = Vec::new();
+    let mut trades: Vec<VenueTrade> = Vec::new();
+    let mut unrouted: Vec<(String, f64)> = Vec::new();
+    let mut order_cursor = 0;
+
+    for (index, time) in times.iter().enumerate() {
+        for (venue_index, candles) in by_time.iter().enumerate() {
+            if let Some(candle) = candles.get(time) {
+                last_seen[venue_index] = Some(candle);
+            }
+        }
+
+        let mut next_pending: Vec<PendingLeg> = Vec::new();
+        for leg in pending {
+            let candle = match by_time[leg.venue].get(time) {
+                Some(candle) if leg.ready_index <= index => *candle,
+                _ => {
+                    next_pending.push(leg);
+                    continue;
+                }
+            };
+            let venue = &venues[leg.venue];
+            let costs = ExecutionCosts {
+                fee_rate: venue.fee_rate,
+                slippage_bps: venue.slippage_bps,
+            };
+            let trade = match leg.order.order_type {
+                OrderType::Market => fill_market(&leg.order, candle, costs),
+                OrderType::Limit { .. } => fill_limit(&leg.order, candle, costs),
+            };
+            match trade {
+                Some(trade) => {
+                    let balance = &mut balances[leg.venue];
+                    let value = trade.price * trade.quantity;
+                    match trade.side {
+                        Side::Buy => {
+                            balance.quote_balance -= value + trade.fee;
+                            balance.base_balance += trade.quantity;
+                        }
+                        Side::Sell => {
+                            balance.quote_balance += value - trade.fee;
+                            balance.base_balance -= trade.quantity;
+                        }
+                    }
+                    trades.push(VenueTrade {
+                        venue: venue.name.clone(),
+                        parent_id: leg.parent_id,
+                        trade,
+                    });
+                }
+                None => {
+                    if matches!(leg.order.order_type, OrderType::Limit { .. }) {
+                        next_pending.push(leg);
+                    }
+                }
+            }
+        }
+        pending = next_pending;
+
+        while order_cursor < orders.len() && orders[order_cursor].submit_index == index {
+            let order = &orders[order_cursor].order;
+            let snapshots: Vec<VenueSnapshot> = venues
+                .iter()
+                .enumerate()
+                .map(|(venue_index, venue)| {
+                    let balance = &balances[venue_index];
+                    snapshot(venue, last_seen[venue_index], balance, order, max_participation)
+                })
+                .collect();
+            let plan = plan_routes(&order.side, order.quantity, &snapshots);
+            if plan.unrouted > 0.0 {
+                unrouted.push((order.client_order_id.clone(), plan.unrouted));
+            }
+            let split = plan.legs.len() > 1;
+            for (leg_index, leg) in plan.legs.iter().enumerate() {
+                let venue = venues
+                    .iter()
+                    .position(|venue| venue.name == leg.venue)
+                    .ok_or_else(|| Error::new(format!("unknown venue: {}", leg.venue)))?;
+                let client_order_id = if split {
+                    format!("{}-{}", order.client_order_id, leg_index + 1)
+                } else {
+                    order.client_order_id.clone()
+                };
+                pending.push(PendingLeg {
+                    venue,
+                    ready_index: index + 1,
+                    parent_id: order.client_order_id.clone(),
+                    order: OrderRequest {
+                        client_order_id,
+                        quantity: leg.quantity,
+                        ..order.clone()
+                    },
+                });
+            }
+            order_cursor += 1;
+        }
+    }
+
+    Ok(RoutedBacktestResult {
+        trades,
+        balances,
+        unrouted,
+    })
+}
+
+fn snapshot(
+    venue: &BacktestVenue,
+    candle: Option<&Candle>,
+    balance: &VenueBalance,
+    order: &OrderRequest,
+    max_participation: f64,
+) -> VenueSnapshot {
+    let price = candle.map(|candle| candle.close).unwrap_or(0.0);
+    let liquidity = match candle {
+        Some(_) if max_participation == 0.0 => f64::INFINITY,
+        Some(candle) => candle.volume * max_participation,
+        None => 0.0,
+    };
+    let available = match order.side {
+        Side::Buy => balance.quote_balance,
+        Side::Sell => balance.base_balance,
+    };
+    VenueSnapshot {
+        venue: venue.name.clone(),
+        price,
+        fee_rate: venue.fee_rate,
+        liquidity,
+        available: available.max(0.0),
+        healthy: candle.is_some(),
+    }
+}
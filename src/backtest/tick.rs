@@ -0,0 +1,363 @@
+use crate::backtest::engine::{
+    apply_trade, compute_metrics, BacktestEngine, BacktestResult, EquityPoint,
+};
+use crate::backtest::fill::{fill_limit_print, fill_market_print, ExecutionCosts};
+use crate::config::Config;
+use crate::core::execution::{ExecutionAlgo, ExecutionReport, ExecutionScheduler};
+use crate::core::order_flow::OrderFlow;
+use crate::core::repricing::RepricePolicy;
+use crate::core::strategy::Strategy;
+use crate::core::triggers::TriggerEngine;
+use crate::core::{StrategyContext, TriggerContext};
+use crate::data::bars::{BarBuilder, BarKind, BarSpec};
+use crate::data::exchange_loader::parse_interval_seconds;
+use crate::data::resample::{build_timeframes, Timeframe};
+use crate::models::{Account, Candle, OrderRequest, OrderType, Side, Trade, TradePrint};
+use crate::{Error, Result};
+
+pub struct TickBacktestResult {
+    pub result: BacktestResult,
+    pub bars: Vec<Candle>,
+}
+
+struct TickOrder {
+    active_ms: i64,
+    order: OrderRequest,
+}
+
+struct TickRun<'a> {
+    config: &'a Config,
+    trigger_engine: &'a TriggerEngine,
+    algo: ExecutionAlgo,
+    reprice: RepricePolicy,
+    costs: ExecutionCosts,
+    latency_ms: i64,
+    scheduler: ExecutionScheduler,
+    timeframes: Vec<Timeframe>,
+    history: Vec<Candle>,
+    pending: Vec<TickOrder>,
+    account: Account,
+    trades: Vec<Trade>,
+    trade_pnls: Vec<Option<f64>>,
+    win_count: usize,
+    loss_count: usize,
+    equity_curve: Vec<EquityPoint>,
+    executions: Vec<ExecutionReport>,
+}
+
+impl BacktestEngine {
+    pub fn run_strategy_on_prints(
+        &self,
+        prints: &[TradePrint],
+        config: &Config,
+        trigger_engine: &TriggerEngine,
+        strategy: &mut dyn Strategy,
+        order_flow: &mut OrderFlow,
+        starting_cash: f64,
+    ) -> Result<TickBacktestResult> {
+        if starting_cash < 0.0 {
+            return Err(Error::new("starting_cash must be non-negative"));
+        }
+        if prints
+            .windows(2)
+            .any(|pair| pair[1].time_ms < pair[0].time_ms)
+        {
+            return Err(Error::new("trade prints must be sorted by time"));
+        }
+
+        let spec = BarSpec::from_config(&config.data)?;
+        let interval_ms = parse_interval_seconds(&config.data.candle_interval)? as i64 * 1_000;
+        let mut builder = BarBuilder::new(spec);
+        let mut run = TickRun {
+            config,
+            trigger_engine,
+            algo: ExecutionAlgo::from_config(&config.execution)?,
+            reprice: RepricePolicy::from_config(config),
+            costs: ExecutionCosts {
+                fee_rate: config.orders.fee_rate,
+                slippage_bps: config.orders.slippage_bps,
+            },
+            latency_ms: config.backtest.latency_ms as i64,
+            scheduler: ExecutionScheduler::new(),
+            timeframes: build_timeframes(&config.data.timeframes, &config.data.candle_interval)?,
+            history: Vec::new(),
+            pending: Vec::new(),
+            account: Account {
+                cash: starting_cash,
+                positions: Vec::new(),
+            },
+            trades: Vec::new(),
+            trade_pnls: Vec::new(),
+            win_count: 0,
+            loss_count: 0,
+            equity_curve: Vec::new(),
+            executions: Vec::new(),
+        };
+
+        let time_bars = matches!(spec.kind, BarKind::Time | BarKind::HeikinAshi);
+        let mut forming: Option<(i64, Candle)> = None;
+        for print in prints {
+            let bucket = print.time_ms.div_euclid(interval_ms) * interval_ms;
+            if time_bars {
+                let closed = match &forming {
+                    Some((start, _)) if *start != bucket => forming.take(),
+                    _ => None,
+                };
+                if let Some((start, candle)) = closed {
+                    for bar in builder.push(&candle) {
+                        run.close_bar(bar, start + interval_ms, strategy, order_flow)?;
+                    }
+                }
+            }
+
+            run.fill(print, order_flow)?;
+
+            if !time_bars {
+                for bar in builder.push_print(print)? {
+                    run.close_bar(bar, print.time_ms, strategy, order_flow)?;
+                }
+                continue;
+            }
+            match forming.as_mut() {
+                Some((_, candle)) => {
+                    candle.high = candle.high.max(print.price);
+                    candle.low = candle.low.min(print.price);
+                    candle.close = print.price;
+                    candle.volume += print.quantity;
+                }
+                None => {
+                    forming = Some((
+                        bucket,
+                        Candle {
+                            time: bucket.div_euclid(1_000),
+                            open: print.price,
+                            high: print.price,
+                            low: print.price,
+                            close: print.price,
+                            volume: print.quantity,
+                        },
+                    ))
+                }
+            }
+        }
+        if let Some((start, candle)) = forming.take() {
+            for bar in builder.push(&candle) {
+                run.close_bar(bar, start + interval_ms, strategy, order_flow)?;
+            }
+        }
+
+        let last_ms = prints.last().map(|print| print.time_ms).unwrap_or(0);
+        for pending in run
+            .pending
+            .iter()
+            .filter(|pending| pending.active_ms > last_ms)
+        {
+            run.scheduler.release_child(&pending.order.client_order_id);
+        }
+        run.executions.extend(run.scheduler.reports());
+        let metrics = compute_metrics(
+            starting_cash,
+            &run.equity_curve,
+            run.trades.len(),
+            run.win_count,
+            run.loss_count,
+        );
+        Ok(TickBacktestResult {
+            result: BacktestResult {
+                trades: run.trades,
+                account: run.account,
+                metrics,
+                equity_curve: run.equity_curve,
+                trade_pnls: run.trade_pnls,
+                executions: run.executions,
+            },
+            bars: run.history,
+        })
+    }
+}
+
+impl TickRun<'_> {
+    fn close_bar(
+        &mut self,
+        candle: Candle,
+        now_ms: i64,
+        strategy: &mut dyn Strategy,
+        order_flow: &mut OrderFlow,
+    ) -> Result<()> {
+        let now = now_ms.div_euclid(1_000);
+        let active_ms = now_ms + self.latency_ms;
+        for timeframe in &mut self.timeframes {
+            timeframe.push(&candle);
+        }
+        let equity = self.mark_equity(candle.close);
+        order_flow.observe_equity(now, equity);
+        if let Some(order) = order_flow.flatten_order(&self.account, self.config, now)? {
+            self.pending
+                .retain(|pending| pending.order.symbol != order.symbol);
+            self.scheduler.halt_symbol(&order.symbol);
+            self.pending.push(TickOrder { active_ms, order });
+        }
+
+        for pending in &mut self.pending {
+            if pending.active_ms > now_ms
+                || !matches!(pending.order.order_type, OrderType::Limit { .. })
+            {
+                continue;
+            }
+            let client_order_id = pending.order.client_order_id.clone();
+            let amend = self
+                .scheduler
+                .working_child(&client_order_id)
+                .and_then(|child| {
+                    self.reprice
+                        .reprice(&pending.order, candle.close, child.amends)
+                });
+            if let Some(amend) = amend {
+                pending.order = amend.amended()?;
+                if let OrderType::Limit { price } = pending.order.order_type {
+                    self.scheduler.record_amend(&client_order_id, price);
+                }
+            }
+        }
+
+        self.history.push(candle.clone());
+        let trigger_ctx = TriggerContext {
+            candle: &candle,
+            history: &self.history,
+            now,
+        };
+        if self.trigger_engine.should_fire(&trigger_ctx) {
+            let strategy_ctx = StrategyContext {
+                candle: &candle,
+                history: &self.history,
+                account: &self.account,
+                timeframes: &self.timeframes,
+                now,
+            };
+            for signal in strategy.on_tick(&strategy_ctx) {
+                for order in order_flow.plan(signal, &strategy_ctx, self.config)? {
+                    self.scheduler
+                        .submit(&order, &self.algo, candle.close, now, &self.history)?;
+                }
+            }
+        }
+
+        for mut order in self.scheduler.due_children(now, candle.close)? {
+            if matches!(order.side, Side::Sell) {
+                order.quantity = order.quantity.min(self.sellable_quantity(&order));
+            }
+            if order.quantity > 0.0 {
+                self.pending.push(TickOrder { active_ms, order });
+            } else {
+                self.scheduler.release_child(&order.client_order_id);
+            }
+        }
+
+        self.executions.extend(self.scheduler.take_completed());
+        self.equity_curve.push(EquityPoint {
+            time: candle.time,
+            equity,
+        });
+        Ok(())
+    }
+
+    fn fill(&mut self, print: &TradePrint, order_flow: &mut OrderFlow) -> Result<()> {
+        let mut available = print.quantity;
+        let mut index = 0;
+        while index < self.pending.len() {
+            let pending = &self.pending[index];
+            if pending.active_ms > print.time_ms {
+                index += 1;
+                continue;
+            }
+            let quantity = pending.order.quantity;
+            let trade = match pending.order.order_type {
+                OrderType::Market => Some(fill_market_print(
+                    &pending.order,
+                    print,
+                    quantity,
+                    self.costs,
+                )),
+                OrderType::Limit { .. } => {
+                    let trade = fill_limit_print(
+                        &pending.order,
+                        print,
+                        quantity.min(available),
+                        self.costs,
+                    );
+                    if let Some(trade) = &trade {
+                        available -= trade.quantity;
+                    }
+                    trade
+                }
+            };
+            let Some(trade) = trade else {
+                index += 1;
+                continue;
+            };
+
+            self.scheduler.record_fill(
+                &pending.order.client_order_id,
+                trade.quantity,
+                trade.price,
+                trade.fee,
+            );
+            let remaining = quantity - trade.quantity;
+            self.book(trade, order_flow)?;
+            if remaining > 0.0 {
+                self.pending[index].order.quantity = remaining;
+                index += 1;
+            } else {
+                self.pending.remove(index);
+            }
+        }
+        Ok(())
+    }
+
+    fn book(&mut self, trade: Trade, order_flow: &mut OrderFlow) -> Result<()> {
+        match apply_trade(&mut self.account, &trade)? {
+            Some(pnl) => {
+                order_flow.record_trade_pnl(trade.time, pnl);
+                if pnl > 0.0 {
+                    self.win_count += 1;
+                } else if pnl < 0.0 {
+                    self.loss_count += 1;
+                }
+                self.trade_pnls.push(Some(pnl));
+            }
+            None => self.trade_pnls.push(None),
+        }
+        self.trades.push(trade);
+        Ok(())
+    }
+
+    fn mark_equity(&self, price: f64) -> f64 {
+        self.account.cash
+            + self
+                .account
+                .positions
+                .iter()
+                .filter(|pos| pos.symbol == self.config.symbol)
+                .map(|pos| pos.quantity * price)
+                .sum::<f64>()
+    }
+
+    fn sellable_quantity(&self, order: &OrderRequest) -> f64 {
+        let held = self
+            .account
+            .positions
+            .iter()
+            .find(|pos| pos.symbol == order.symbol)
+            .map(|pos| pos.quantity)
+            .unwrap_or(0.0);
+        let reserved: f64 = self
+            .pending
+            .iter()
+            .filter(|pending| {
+                pending.order.symbol == order.symbol && matches!(pending.order.side, Side::Sell)
+            })
+            .map(|pending| pending.order.quantity)
+            .sum();
+        (held - reserved).max(0.0)
+    }
+}
@@ -0,0 +1,84 @@
+use merrow::app::sim_server::SimServerOptions;
+
+#[tokio::main]
+async fn main() {
+    dotenvy::from_filename(".env.local").ok();
+    dotenvy::dotenv().ok();
+    merrow::app::logging::init();
+
+    let args: Vec<String> = std::env::args().collect();
+    let (config_path, addr, options) = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    });
+
+    if let Err(err) = merrow::app::sim_server::run_from_config(&addr, &config_path, &options).await
+    {
+        eprintln!("error: {}", err.message);
+        std::process::exit(1);
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<(String, String, SimServerOptions), String> {
+    let mut config_path = "config.toml".to_string();
+    let mut addr =
+        std::env::var("MERROW_SIM_ADDR").unwrap_or_else(|_| "127.0.0.1:8090".to_string());
+    let mut options = SimServerOptions {
+        csv_path: None,
+        warmup_bars: 50,
+        initial_base: 0.0,
+    };
+
+    let mut index = 1;
+    while index < args.len() {
+        match args[index].as_str() {
+            "--config" | "-c" => {
+                let value = args.get(index + 1).ok_or("missing value for --config")?;
+                config_path = value.to_string();
+                index += 2;
+            }
+            "--addr" => {
+                let value = args.get(index + 1).ok_or("missing value for --addr")?;
+                addr = value.to_string();
+                index += 2;
+            }
+            "--csv" => {
+                let value = args.get(index + 1).ok_or("missing value for --csv")?;
+                options.csv_path = Some(value.to_string());
+                index += 2;
+            }
+            "--warmup" => {
+                let value = args.get(index + 1).ok_or("missing value for --warmup")?;
+                options.warmup_bars = value
+                    .parse::<usize>()
+                    .map_err(|_| "invalid value for --warmup".to_string())?;
+                index += 2;
+            }
+            "--initial-base" => {
+                let value = args.get(index + 1).ok_or("missing value for --initial-base")?;
+                options.initial_base = value
+                    .parse::<f64>()
+                    .map_err(|_| "invalid value for --initial-base".to_string())?;
+                index += 2;
+            }
+            "--help" | "-h" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            unknown => {
+                return Err(format!("unknown argument: {unknown}"));
+            }
+        }
+    }
+
+    Ok((config_path, addr, options))
+}
+
+fn print_usage() {
+    println!("usage: merrow_sim [--config <path>] [--addr <host:port>] [--csv <path>] [--warmup <bars>] [--initial-base <qty>]");
+    println!("  -c, --config   Path to config.toml (default: config.toml)");
+    println!("      --addr     Bind address (default: 127.0.0.1:8090 or MERROW_SIM_ADDR)");
+    println!("      --csv      Candle CSV to replay (default: data.csv_path)");
+    println!("      --warmup   Bars revealed before the first request (default: 50)");
+    println!("      --initial-base  Starting base asset balance (default: 0)");
+}
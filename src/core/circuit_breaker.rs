@@ -0,0 +1,256 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Clone, Debug, Default)]
+pub struct CircuitBreakerLimits {
+    pub max_daily_loss_ratio: f64,
+    pub max_drawdown_ratio: f64,
+    pub max_orders_per_window: u32,
+    pub order_window_secs: i64,
+    pub max_consecutive_losses: u32,
+    pub flatten_on_halt: bool,
+}
+
+impl CircuitBreakerLimits {
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("max_daily_loss_ratio", self.max_daily_loss_ratio),
+            ("max_drawdown_ratio", self.max_drawdown_ratio),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(Error::new(format!("{name} must be in [0, 1]")));
+            }
+        }
+        if self.max_orders_per_window > 0 && self.order_window_secs <= 0 {
+            return Err(Error::new("order_window_secs must be positive"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HaltReason {
+    DailyLoss,
+    Drawdown,
+    ConsecutiveLosses,
+    KillSwitch,
+    Reconciliation,
+}
+
+impl HaltReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HaltReason::DailyLoss => "daily_loss",
+            HaltReason::Drawdown => "drawdown",
+            HaltReason::ConsecutiveLosses => "consecutive_losses",
+            HaltReason::KillSwitch => "kill_switch",
+            HaltReason::Reconciliation => "reconciliation",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BreakerState {
+    pub halted: Option<HaltReason>,
+    pub halted_at: Option<i64>,
+    pub flattened: bool,
+    pub day: Option<i64>,
+    pub day_start_equity: f64,
+    pub high_water_mark: f64,
+    pub consecutive_losses: u32,
+    pub order_times: Vec<i64>,
+}
+
+pub struct CircuitBreaker {
+    limits: CircuitBreakerLimits,
+    state: BreakerState,
+}
+
+impl CircuitBreaker {
+    pub fn new(limits: CircuitBreakerLimits) -> Result<Self> {
+        Self::with_state(limits, BreakerState::default())
+    }
+
+    pub fn with_state(limits: CircuitBreakerLimits, state: BreakerState) -> Result<Self> {
+        limits.validate()?;
+        Ok(Self { limits, state })
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            limits: CircuitBreakerLimits::default(),
+            state: BreakerState::default(),
+        }
+    }
+
+    pub fn limits(&self) -> &CircuitBreakerLimits {
+        &self.limits
+    }
+
+    pub fn state(&self) -> &BreakerState {
+        &self.state
+    }
+
+    pub fn restore(&mut self, state: BreakerState) {
+        self.state = state;
+    }
+
+    pub fn halt_reason(&self) -> Option<HaltReason> {
+        self.state.halted
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.state.halted.is_some()
+    }
+
+    pub fn trip(&mut self, reason: HaltReason, now: i64) {
+        if self.state.halted.is_some() {
+            return;
+        }
+        self.state.halted = Some(reason);
+        self.state.halted_at = Some(now);
+        self.state.flattened = false;
+    }
+
+    pub fn reset(&mut self) {
+        self.state = BreakerState::default();
+    }
+
+    pub fn observe_equity(&mut self, now: i64, equity: f64) {
+        let day = now.div_euclid(SECONDS_PER_DAY);
+        if self.state.day != Some(day) {
+            self.state.day = Some(day);
+            self.state.day_start_equity = equity;
+            if self.state.halted == Some(HaltReason::DailyLoss) {
+                self.state.halted = None;
+                self.state.halted_at = None;
+                self.state.flattened = false;
+            }
+        }
+        if equity > self.state.high_water_mark {
+            self.state.high_water_mark = equity;
+        }
+
+        if self.limits.max_daily_loss_ratio > 0.0 && self.state.day_start_equity > 0.0 {
+            let loss = (self.state.day_start_equity - equity) / self.state.day_start_equity;
+            if loss >= self.limits.max_daily_loss_ratio {
+                self.trip(HaltReason::DailyLoss, now);
+            }
+        }
+        if self.limits.max_drawdown_ratio > 0.0 && self.state.high_water_mark > 0.0 {
+            let drawdown =
+                (self.state.high_water_mark - equity) / self.state.high_water_mark;
+            if drawdown >= self.limits.max_drawdown_ratio {
+                self.trip(HaltReason::Drawdown, now);
+            }
+        }
+    }
+
+    pub fn record_trade_pnl(&mut self, now: i64, pnl: f64) {
+        if pnl < 0.0 {
+            self.state.consecutive_losses += 1;
+        } else if pnl > 0.0 {
+            self.state.consecutive_losses = 0;
+        }
+        let max_losses = self.limits.max_consecutive_losses;
+        if max_losses > 0 && self.state.consecutive_losses >= max_losses {
+            self.trip(HaltReason::ConsecutiveLosses, now);
+        }
+    }
+
+    pub fn record_order(&mut self, now: i64) {
+        if self.limits.max_orders_per_window == 0 {
+            return;
+        }
+        let window_start = now - self.limits.order_window_secs;
+        self.state.order_times.retain(|time| *time > window_start);
+        self.state.order_times.push(now);
+    }
+
+    pub fn check_entry(&self, now: i64) -> Result<()> {
+        if let Some(reason) = self.state.halted {
+            return Err(Error::new(format!(
+                "circuit breaker halted: {}",
+                reason.as_str()
+            )));
+        }
+        let max_orders = self.limits.max_orders_per_window;
+        if max_orders > 0 {
+            let window_start = now - self.limits.order_window_secs;
+            let recent = self
+                .state
+                .order_times
+                .iter()
+                .filter(|time| **time > window_start)
+                .count();
+            if recent >= max_orders as usize {
+                return Err(Error::new("order count exceeds max_orders_per_window"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn take_flatten(&mut self) -> bool {
+        match self.state.halted {
+            Some(HaltReason::KillSwitch) | Some(HaltReason::Reconciliation) | None => false,
+            Some(_) => {
+                if !self.limits.flatten_on_halt || self.state.flattened {
+                    return false;
+                }
+                self.state.flattened = true;
+                true
+            }
+        }
+    }
+}
+
+pub struct KillSwitch {
+    path: Option<String>,
+    forced: bool,
+}
+
+impl KillSwitch {
+    pub fn new(path: Option<String>, forced: bool) -> Self {
+        let path = path.filter(|value| !value.trim().is_empty());
+        Self { path, forced }
+    }
+
+    pub fn is_engaged(&self) -> bool {
+        if self.forced {
+            return true;
+        }
+        match &self.path {
+            Some(path) => Path::new(path).exists(),
+            None => false,
+        }
+    }
+}
+
+pub fn load_breaker_state(path: &str) -> Result<Option<BreakerState>> {
+    let path = Path::new(path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|err| Error::new(format!("breaker state read failed: {err}")))?;
+    let state = serde_json::from_str::<BreakerState>(&content)
+        .map_err(|err| Error::new(format!("breaker state parse failed: {err}")))?;
+    Ok(Some(state))
+}
+
+pub fn save_breaker_state(path: &str, state: &BreakerState) -> Result<()> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|err| Error::new(format!("breaker state serialize failed: {err}")))?;
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| Error::new(format!("breaker state dir create failed: {err}")))?;
+    }
+    fs::write(path, content)
+        .map_err(|err| Error::new(format!("breaker state write failed: {err}")))
+}
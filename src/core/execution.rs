@@ -0,0 +1,658 @@
+use crate::config::ExecutionConfig;
+use crate::core::repricing::RepricePolicy;
+use crate::exchange::AmendRequest;
+use crate::models::{Candle, OrderRequest, OrderType, Side};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const MIN_CHILD_QTY: f64 = 1e-12;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExecutionAlgo {
+    Immediate,
+    Twap { slices: u32, interval_secs: i64 },
+    Vwap { slices: u32, interval_secs: i64 },
+    Iceberg { visible_ratio: f64 },
+}
+
+impl ExecutionAlgo {
+    pub fn from_config(config: &ExecutionConfig) -> Result<Self> {
+        let interval_secs = config.slice_interval_secs as i64;
+        match config.algo.as_str() {
+            "immediate" => Ok(Self::Immediate),
+            "twap" => Ok(Self::Twap {
+                slices: config.slices,
+                interval_secs,
+            }),
+            "vwap" => Ok(Self::Vwap {
+                slices: config.slices,
+                interval_secs,
+            }),
+            "iceberg" => Ok(Self::Iceberg {
+                visible_ratio: config.iceberg_visible_ratio,
+            }),
+            other => Err(Error::new(format!("unknown execution algo: {other}"))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Immediate => "immediate",
+            Self::Twap { .. } => "twap",
+            Self::Vwap { .. } => "vwap",
+            Self::Iceberg { .. } => "iceberg",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChildSlice {
+    pub due_time: i64,
+    pub quantity: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkingChild {
+    pub client_order_id: String,
+    pub quantity: f64,
+    pub reference_price: f64,
+    #[serde(default)]
+    pub amends: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExecutionReport {
+    pub parent_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub algo: String,
+    pub quantity: f64,
+    pub filled_qty: f64,
+    pub avg_fill_price: f64,
+    pub arrival_price: f64,
+    pub shortfall_bps: f64,
+    pub fees: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ParentOrder {
+    pub parent_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub limit_price: Option<f64>,
+    pub quantity: f64,
+    pub algo: String,
+    pub arrival_price: f64,
+    pub start_time: i64,
+    schedule: Vec<ChildSlice>,
+    next_slice: usize,
+    visible_qty: Option<f64>,
+    emitted_qty: f64,
+    child_seq: u32,
+    working: Vec<WorkingChild>,
+    pub filled_qty: f64,
+    pub filled_notional: f64,
+    pub fees: f64,
+    halted: bool,
+}
+
+impl ParentOrder {
+    pub fn new(
+        order: &OrderRequest,
+        algo: &ExecutionAlgo,
+        arrival_price: f64,
+        start_time: i64,
+        history: &[Candle],
+    ) -> Result<Self> {
+        if order.quantity <= 0.0 {
+            return Err(Error::new("parent order quantity must be positive"));
+        }
+        if arrival_price <= 0.0 {
+            return Err(Error::new("arrival price must be positive"));
+        }
+        let (schedule, visible_qty) = match algo {
+            ExecutionAlgo::Immediate => (vec![slice(start_time, order.quantity)], None),
+            ExecutionAlgo::Twap {
+                slices,
+                interval_secs,
+            } => {
+                let weights = vec![1.0 / *slices as f64; *slices as usize];
+                (
+                    build_schedule(order.quantity, start_time, *interval_secs, &weights)?,
+                    None,
+                )
+            }
+            ExecutionAlgo::Vwap {
+                slices,
+                interval_secs,
+            } => {
+                let weights = volume_profile(history, start_time, *interval_secs, *slices);
+                (
+                    build_schedule(order.quantity, start_time, *interval_secs, &weights)?,
+                    None,
+                )
+            }
+            ExecutionAlgo::Iceberg { visible_ratio } => {
+                if *visible_ratio <= 0.0 || *visible_ratio > 1.0 {
+                    return Err(Error::new("iceberg visible_ratio must be in (0, 1]"));
+                }
+                (Vec::new(), Some(order.quantity * visible_ratio))
+            }
+        };
+        let (side, limit_price) = (side_label(&order.side), limit_price(&order.order_type));
+        Ok(Self {
+            parent_id: order.client_order_id.clone(),
+            symbol: order.symbol.clone(),
+            side,
+            limit_price,
+            quantity: order.quantity,
+            algo: algo.name().to_string(),
+            arrival_price,
+            start_time,
+            schedule,
+            next_slice: 0,
+            visible_qty,
+            emitted_qty: 0.0,
+            child_seq: 0,
+            working: Vec::new(),
+            filled_qty: 0.0,
+            filled_notional: 0.0,
+            fees: 0.0,
+            halted: false,
+        })
+    }
+
+    pub fn schedule(&self) -> &[ChildSlice] {
+        &self.schedule
+    }
+
+    pub fn working(&self) -> &[WorkingChild] {
+        &self.working
+    }
+
+    pub fn due_children(&mut self, now: i64, reference_price: f64) -> Result<Vec<OrderRequest>> {
+        if self.halted {
+            return Ok(Vec::new());
+        }
+        let mut quantities = Vec::new();
+        match self.visible_qty {
+            Some(visible) => {
+                let remaining = self.quantity - self.emitted_qty;
+                if self.working.is_empty() && remaining > MIN_CHILD_QTY {
+                    quantities.push(visible.min(remaining));
+                }
+            }
+            None => {
+                while self.next_slice < self.schedule.len()
+                    && self.schedule[self.next_slice].due_time <= now
+                {
+                    quantities.push(self.schedule[self.next_slice].quantity);
+                    self.next_slice += 1;
+                }
+            }
+        }
+
+        let single = self.algo == ExecutionAlgo::Immediate.name();
+        let mut children = Vec::with_capacity(quantities.len());
+        for quantity in quantities {
+            if quantity <= MIN_CHILD_QTY {
+                continue;
+            }
+            self.child_seq += 1;
+            let client_order_id = if single {
+                self.parent_id.clone()
+            } else {
+                format!("{}-{}", self.parent_id, self.child_seq)
+            };
+            self.emitted_qty += quantity;
+            self.working.push(WorkingChild {
+                client_order_id: client_order_id.clone(),
+                quantity,
+                reference_price: self.limit_price.unwrap_or(reference_price),
+                amends: 0,
+            });
+            children.push(OrderRequest {
+                client_order_id,
+                symbol: self.symbol.clone(),
+                side: parse_side(&self.side)?,
+                order_type: match self.limit_price {
+                    Some(price) => OrderType::Limit { price },
+                    None => OrderType::Market,
+                },
+                quantity,
+            });
+        }
+        Ok(children)
+    }
+
+    pub fn owns(&self, client_order_id: &str) -> bool {
+        self.working
+            .iter()
+            .any(|child| child.client_order_id == client_order_id)
+    }
+
+    pub fn record_fill(&mut self, client_order_id: &str, quantity: f64, price: f64, fee: f64) {
+        if let Some(index) = self
+            .working
+            .iter()
+            .position(|child| child.client_order_id == client_order_id)
+        {
+            let child = &mut self.working[index];
+            child.quantity -= quantity;
+            if child.quantity <= MIN_CHILD_QTY {
+                self.working.remove(index);
+            }
+        }
+        self.filled_qty += quantity;
+        self.filled_notional += quantity * price;
+        self.fees += fee;
+    }
+
+    pub fn release_child(&mut self, client_order_id: &str) {
+        if let Some(index) = self
+            .working
+            .iter()
+            .position(|child| child.client_order_id == client_order_id)
+        {
+            let child = self.working.remove(index);
+            self.emitted_qty -= child.quantity;
+        }
+    }
+
+    pub fn record_amend(&mut self, client_order_id: &str, price: f64) -> bool {
+        match self
+            .working
+            .iter_mut()
+            .find(|child| child.client_order_id == client_order_id)
+        {
+            Some(child) => {
+                child.reference_price = price;
+                child.amends += 1;
+                self.limit_price = self.limit_price.map(|_| price);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn halt(&mut self) {
+        self.halted = true;
+        self.working.clear();
+    }
+
+    pub fn is_complete(&self) -> bool {
+        if self.halted {
+            return true;
+        }
+        self.working.is_empty()
+            && self.next_slice >= self.schedule.len()
+            && self.quantity - self.emitted_qty <= MIN_CHILD_QTY
+    }
+
+    pub fn average_fill_price(&self) -> Option<f64> {
+        if self.filled_qty <= 0.0 {
+            return None;
+        }
+        Some(self.filled_notional / self.filled_qty)
+    }
+
+    pub fn report(&self) -> ExecutionReport {
+        let avg_fill_price = self.average_fill_price().unwrap_or(0.0);
+        let shortfall_bps = match (self.average_fill_price(), parse_side(&self.side)) {
+            (Some(avg), Ok(side)) => implementation_shortfall_bps(&side, self.arrival_price, avg),
+            _ => 0.0,
+        };
+        ExecutionReport {
+            parent_id: self.parent_id.clone(),
+            symbol: self.symbol.clone(),
+            side: self.side.clone(),
+            algo: self.algo.clone(),
+            quantity: self.quantity,
+            filled_qty: self.filled_qty,
+            avg_fill_price,
+            arrival_price: self.arrival_price,
+            shortfall_bps,
+            fees: self.fees,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CostBasis {
+    pub symbol: String,
+    pub quantity: f64,
+    pub avg_price: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ExecutionScheduler {
+    parents: Vec<ParentOrder>,
+    #[serde(default)]
+    adopted: Vec<String>,
+    #[serde(default)]
+    cost_basis: Vec<CostBasis>,
+}
+
+impl ExecutionScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parents(&self) -> &[ParentOrder] {
+        &self.parents
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    pub fn submit(
+        &mut self,
+        order: &OrderRequest,
+        algo: &ExecutionAlgo,
+        arrival_price: f64,
+        now: i64,
+        history: &[Candle],
+    ) -> Result<()> {
+        if self
+            .parents
+            .iter()
+            .any(|parent| parent.parent_id == order.client_order_id)
+        {
+            return Err(Error::new(format!(
+                "parent order already active: {}",
+                order.client_order_id
+            )));
+        }
+        let parent = ParentOrder::new(order, algo, arrival_price, now, history)?;
+        self.parents.push(parent);
+        Ok(())
+    }
+
+    pub fn track_placed(&mut self, order: &OrderRequest, reference_price: f64, now: i64) -> Result<()> {
+        self.submit(order, &ExecutionAlgo::Immediate, reference_price, now, &[])?;
+        if let Some(parent) = self.parents.last_mut() {
+            parent.due_children(now, reference_price)?;
+        }
+        Ok(())
+    }
+
+    pub fn due_children(&mut self, now: i64, reference_price: f64) -> Result<Vec<OrderRequest>> {
+        let mut children = Vec::new();
+        for parent in &mut self.parents {
+            children.extend(parent.due_children(now, reference_price)?);
+        }
+        Ok(children)
+    }
+
+    pub fn working_children(&self) -> Vec<WorkingChild> {
+        self.parents
+            .iter()
+            .flat_map(|parent| parent.working.iter().cloned())
+            .collect()
+    }
+
+    pub fn record_fill(&mut self, client_order_id: &str, quantity: f64, price: f64, fee: f64) -> bool {
+        match self
+            .parents
+            .iter_mut()
+            .find(|parent| parent.owns(client_order_id))
+        {
+            Some(parent) => {
+                parent.record_fill(client_order_id, quantity, price, fee);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn book_fill(&mut self, client_order_id: &str, quantity: f64, price: f64) -> Option<f64> {
+        let parent = self
+            .parents
+            .iter()
+            .find(|parent| parent.owns(client_order_id))?;
+        let (symbol, side) = (parent.symbol.clone(), parse_side(&parent.side).ok()?);
+        let index = match self.cost_basis.iter().position(|basis| basis.symbol == symbol) {
+            Some(index) => index,
+            None => {
+                self.cost_basis.push(CostBasis {
+                    symbol,
+                    quantity: 0.0,
+                    avg_price: 0.0,
+                });
+                self.cost_basis.len() - 1
+            }
+        };
+        let basis = &mut self.cost_basis[index];
+        match side {
+            Side::Buy => {
+                let total_qty = basis.quantity + quantity;
+                if total_qty > 0.0 {
+                    basis.avg_price = (basis.avg_price * basis.quantity + price * quantity) / total_qty;
+                }
+                basis.quantity = total_qty;
+                None
+            }
+            Side::Sell => {
+                let matched = quantity.min(basis.quantity);
+                if matched <= MIN_CHILD_QTY {
+                    return None;
+                }
+                basis.quantity -= matched;
+                let pnl = (price - basis.avg_price) * matched;
+                if basis.quantity <= MIN_CHILD_QTY {
+                    self.cost_basis.remove(index);
+                }
+                Some(pnl)
+            }
+        }
+    }
+
+    pub fn cost_basis(&self) -> &[CostBasis] {
+        &self.cost_basis
+    }
+
+    pub fn adopted(&self) -> &[String] {
+        &self.adopted
+    }
+
+    pub fn adopt(&mut self, client_order_id: &str) {
+        if !self.tracks(client_order_id) {
+            self.adopted.push(client_order_id.to_string());
+        }
+    }
+
+    pub fn retain_adopted<F: FnMut(&str) -> bool>(&mut self, mut keep: F) {
+        self.adopted.retain(|client_order_id| keep(client_order_id));
+    }
+
+    pub fn tracks(&self, client_order_id: &str) -> bool {
+        self.working_child(client_order_id).is_some()
+            || self.adopted.iter().any(|adopted| adopted == client_order_id)
+    }
+
+    pub fn working_child(&self, client_order_id: &str) -> Option<&WorkingChild> {
+        self.parents
+            .iter()
+            .flat_map(|parent| parent.working.iter())
+            .find(|child| child.client_order_id == client_order_id)
+    }
+
+    pub fn reprice_requests(
+        &self,
+        policy: &RepricePolicy,
+        symbol: &str,
+        reference_price: f64,
+    ) -> Result<Vec<AmendRequest>> {
+        let mut requests = Vec::new();
+        for parent in self.parents.iter().filter(|parent| parent.symbol == symbol) {
+            if parent.limit_price.is_none() {
+                continue;
+            }
+            let side = parse_side(&parent.side)?;
+            for child in &parent.working {
+                let order = OrderRequest {
+                    client_order_id: child.client_order_id.clone(),
+                    symbol: parent.symbol.clone(),
+                    side: side.clone(),
+                    order_type: OrderType::Limit {
+                        price: child.reference_price,
+                    },
+                    quantity: child.quantity,
+                };
+                if let Some(request) = policy.reprice(&order, reference_price, child.amends) {
+                    requests.push(request);
+                }
+            }
+        }
+        Ok(requests)
+    }
+
+    pub fn record_amend(&mut self, client_order_id: &str, price: f64) -> bool {
+        self.parents
+            .iter_mut()
+            .any(|parent| parent.record_amend(client_order_id, price))
+    }
+
+    pub fn release_child(&mut self, client_order_id: &str) {
+        for parent in &mut self.parents {
+            parent.release_child(client_order_id);
+        }
+    }
+
+    pub fn halt_symbol(&mut self, symbol: &str) {
+        for parent in &mut self.parents {
+            if parent.symbol == symbol {
+                parent.halt();
+            }
+        }
+    }
+
+    pub fn take_completed(&mut self) -> Vec<ExecutionReport> {
+        let (done, active): (Vec<ParentOrder>, Vec<ParentOrder>) = self
+            .parents
+            .drain(..)
+            .partition(|parent| parent.is_complete());
+        self.parents = active;
+        done.iter().map(|parent| parent.report()).collect()
+    }
+
+    pub fn reports(&self) -> Vec<ExecutionReport> {
+        self.parents.iter().map(|parent| parent.report()).collect()
+    }
+}
+
+pub fn implementation_shortfall_bps(side: &Side, arrival_price: f64, avg_fill_price: f64) -> f64 {
+    if arrival_price <= 0.0 {
+        return 0.0;
+    }
+    let diff = match side {
+        Side::Buy => avg_fill_price - arrival_price,
+        Side::Sell => arrival_price - avg_fill_price,
+    };
+    diff / arrival_price * 10_000.0
+}
+
+pub fn volume_profile(history: &[Candle], start_time: i64, slice_secs: i64, slices: u32) -> Vec<f64> {
+    let slices = slices.max(1) as usize;
+    let uniform = vec![1.0 / slices as f64; slices];
+    if slice_secs <= 0 || history.is_empty() {
+        return uniform;
+    }
+    let mut volumes = vec![0.0; slices];
+    for (index, volume) in volumes.iter_mut().enumerate() {
+        let slot_start = (start_time + index as i64 * slice_secs).rem_euclid(SECONDS_PER_DAY);
+        for candle in history {
+            let offset = (candle.time.rem_euclid(SECONDS_PER_DAY) - slot_start)
+                .rem_euclid(SECONDS_PER_DAY);
+            if offset < slice_secs {
+                *volume += candle.volume.max(0.0);
+            }
+        }
+    }
+    let total: f64 = volumes.iter().sum();
+    if total <= 0.0 {
+        return uniform;
+    }
+    volumes.iter().map(|volume| volume / total).collect()
+}
+
+pub fn load_execution_state(path: &str) -> Result<Option<ExecutionScheduler>> {
+    let path = Path::new(path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|err| Error::new(format!("execution state read failed: {err}")))?;
+    let state = serde_json::from_str::<ExecutionScheduler>(&content)
+        .map_err(|err| Error::new(format!("execution state parse failed: {err}")))?;
+    Ok(Some(state))
+}
+
+pub fn save_execution_state(path: &str, state: &ExecutionScheduler) -> Result<()> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|err| Error::new(format!("execution state serialize failed: {err}")))?;
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| Error::new(format!("execution state dir create failed: {err}")))?;
+    }
+    fs::write(path, content)
+        .map_err(|err| Error::new(format!("execution state write failed: {err}")))
+}
+
+fn build_schedule(
+    quantity: f64,
+    start_time: i64,
+    interval_secs: i64,
+    weights: &[f64],
+) -> Result<Vec<ChildSlice>> {
+    if weights.is_empty() {
+        return Err(Error::new("execution slices must be positive"));
+    }
+    if interval_secs < 0 {
+        return Err(Error::new("execution slice interval must be non-negative"));
+    }
+    let mut schedule = Vec::with_capacity(weights.len());
+    let mut allocated = 0.0;
+    for (index, weight) in weights.iter().enumerate() {
+        let due_time = start_time + index as i64 * interval_secs;
+        let qty = if index + 1 == weights.len() {
+            quantity - allocated
+        } else {
+            quantity * weight
+        };
+        allocated += qty;
+        schedule.push(slice(due_time, qty));
+    }
+    Ok(schedule)
+}
+
+fn slice(due_time: i64, quantity: f64) -> ChildSlice {
+    ChildSlice { due_time, quantity }
+}
+
+fn limit_price(order_type: &OrderType) -> Option<f64> {
+    match order_type {
+        OrderType::Market => None,
+        OrderType::Limit { price } => Some(*price),
+    }
+}
+
+fn side_label(side: &Side) -> String {
+    match side {
+        Side::Buy => "buy".to_string(),
+        Side::Sell => "sell".to_string(),
+    }
+}
+
+fn parse_side(value: &str) -> Result<Side> {
+    match value {
+        "buy" => Ok(Side::Buy),
+        "sell" => Ok(Side::Sell),
+        other => Err(Error::new(format!("unknown order side: {other}"))),
+    }
+}
@@ -0,0 +1,231 @@
+use crate::exchange::rest::{RestOrder, RestOrderAck};
+use crate::exchange::Exchange;
+use crate::models::{OrderAck, OrderRequest, OrderStatus};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEntry {
+    Intent {
+        time: i64,
+        order: RestOrder,
+        reference_price: f64,
+    },
+    Ack {
+        time: i64,
+        ack: RestOrderAck,
+    },
+    Error {
+        time: i64,
+        client_order_id: String,
+        error: String,
+    },
+    Missing {
+        time: i64,
+        client_order_id: String,
+    },
+}
+
+impl JournalEntry {
+    pub fn client_order_id(&self) -> &str {
+        match self {
+            JournalEntry::Intent { order, .. } => &order.client_order_id,
+            JournalEntry::Ack { ack, .. } => &ack.client_order_id,
+            JournalEntry::Error {
+                client_order_id, ..
+            }
+            | JournalEntry::Missing {
+                client_order_id, ..
+            } => client_order_id,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PendingIntent {
+    pub time: i64,
+    pub order: OrderRequest,
+    pub reference_price: f64,
+}
+
+#[derive(Clone, Debug)]
+pub enum IntentOutcome {
+    Sent(OrderAck),
+    Unsent,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecoveredIntent {
+    pub intent: PendingIntent,
+    pub outcome: IntentOutcome,
+}
+
+impl RecoveredIntent {
+    pub fn is_open(&self) -> bool {
+        matches!(
+            &self.outcome,
+            IntentOutcome::Sent(ack)
+                if matches!(ack.status, OrderStatus::New | OrderStatus::PartiallyFilled)
+        )
+    }
+}
+
+pub struct IntentJournal {
+    path: PathBuf,
+}
+
+impl IntentJournal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record_intent(&self, order: &OrderRequest, reference_price: f64, time: i64) -> Result<()> {
+        self.append(&JournalEntry::Intent {
+            time,
+            order: RestOrder::from_request(order),
+            reference_price,
+        })
+    }
+
+    pub fn record_ack(&self, ack: &OrderAck, time: i64) -> Result<()> {
+        self.append(&JournalEntry::Ack {
+            time,
+            ack: RestOrderAck::from_ack(ack),
+        })
+    }
+
+    pub fn record_error(&self, client_order_id: &str, error: &str, time: i64) -> Result<()> {
+        self.append(&JournalEntry::Error {
+            time,
+            client_order_id: client_order_id.to_string(),
+            error: error.to_string(),
+        })
+    }
+
+    pub fn record_missing(&self, client_order_id: &str, time: i64) -> Result<()> {
+        self.append(&JournalEntry::Missing {
+            time,
+            client_order_id: client_order_id.to_string(),
+        })
+    }
+
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)
+            .map_err(|err| Error::new(format!("journal read failed: {err}")))?;
+        let lines: Vec<&str> = content.lines().filter(|line| !line.trim().is_empty()).collect();
+        let mut entries = Vec::with_capacity(lines.len());
+        for (index, line) in lines.iter().enumerate() {
+            match serde_json::from_str::<JournalEntry>(line) {
+                Ok(entry) => entries.push(entry),
+                Err(_) if index + 1 == lines.len() && !content.ends_with('\n') => break,
+                Err(err) => {
+                    return Err(Error::new(format!(
+                        "journal parse failed at line {}: {err}",
+                        index + 1
+                    )))
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    pub fn pending(&self) -> Result<Vec<PendingIntent>> {
+        let entries = self.entries()?;
+        let mut pending: Vec<PendingIntent> = Vec::new();
+        for entry in entries {
+            match entry {
+                JournalEntry::Intent {
+                    time,
+                    order,
+                    reference_price,
+                } => {
+                    pending.retain(|intent| intent.order.client_order_id != order.client_order_id);
+                    pending.push(PendingIntent {
+                        time,
+                        order: order.to_request()?,
+                        reference_price,
+                    });
+                }
+                JournalEntry::Error { .. } => {}
+                resolved => {
+                    let client_order_id = resolved.client_order_id();
+                    pending.retain(|intent| intent.order.client_order_id != client_order_id);
+                }
+            }
+        }
+        Ok(pending)
+    }
+
+    pub fn recover(&self, exchange: &dyn Exchange, time: i64) -> Result<Vec<RecoveredIntent>> {
+        let mut recovered = Vec::new();
+        for intent in self.pending()? {
+            let client_order_id = intent.order.client_order_id.clone();
+            let outcome = match exchange.fetch_order(&client_order_id)? {
+                Some(ack) => {
+                    self.record_ack(&ack, time)?;
+                    IntentOutcome::Sent(ack)
+                }
+                None => {
+                    self.record_missing(&client_order_id, time)?;
+                    IntentOutcome::Unsent
+                }
+            };
+            recovered.push(RecoveredIntent { intent, outcome });
+        }
+        self.compact()?;
+        Ok(recovered)
+    }
+
+    pub fn compact(&self) -> Result<()> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+        let mut content = String::new();
+        for intent in self.pending()? {
+            let entry = JournalEntry::Intent {
+                time: intent.time,
+                order: RestOrder::from_request(&intent.order),
+                reference_price: intent.reference_price,
+            };
+            content.push_str(&encode(&entry)?);
+        }
+        let staging = self.path.with_extension("tmp");
+        fs::write(&staging, content)
+            .map_err(|err| Error::new(format!("journal write failed: {err}")))?;
+        fs::rename(&staging, &self.path)
+            .map_err(|err| Error::new(format!("journal rename failed: {err}")))
+    }
+
+    fn append(&self, entry: &JournalEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| Error::new(format!("journal dir create failed: {err}")))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|err| Error::new(format!("journal open failed: {err}")))?;
+        file.write_all(encode(entry)?.as_bytes())
+            .map_err(|err| Error::new(format!("journal write failed: {err}")))?;
+        file.sync_data()
+            .map_err(|err| Error::new(format!("journal sync failed: {err}")))
+    }
+}
+
+fn encode(entry: &JournalEntry) -> Result<String> {
+    let line = serde_json::to_string(entry)
+        .map_err(|err| Error::new(format!("journal serialize failed: {err}")))?;
+    Ok(format!("{line}\n"))
+}
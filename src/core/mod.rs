@@ -1,6 +1,11 @@
+pub mod circuit_breaker;
+pub mod execution;
+pub mod journal;
 pub mod order_router;
 pub mod order_builder;
 pub mod order_flow;
+pub mod reconciliation;
+pub mod repricing;
 pub mod risk;
 pub mod strategy;
 pub mod trigger;
@@ -8,10 +13,13 @@ pub mod triggers;
 pub mod strategies;
 
 use crate::config::Config;
+use crate::core::circuit_breaker::{CircuitBreaker, CircuitBreakerLimits};
+use crate::core::order_builder::OrderBuilder;
 use crate::core::order_flow::OrderFlow;
 use crate::core::risk::{RiskLimits, RiskManager};
 use crate::core::strategies::ThresholdStrategy;
 use crate::core::triggers::{PriceTrigger, TimeTrigger, TriggerEngine};
+use crate::data::resample::Timeframe;
 use crate::Result;
 
 use crate::models::{Account, Candle};
@@ -26,9 +34,19 @@ pub struct StrategyContext<'a> {
     pub candle: &'a Candle,
     pub history: &'a [Candle],
     pub account: &'a Account,
+    pub timeframes: &'a [Timeframe],
     pub now: i64,
 }
 
+impl StrategyContext<'_> {
+    pub fn timeframe(&self, interval: &str) -> Option<&[Candle]> {
+        self.timeframes
+            .iter()
+            .find(|timeframe| timeframe.interval() == interval)
+            .map(|timeframe| timeframe.candles())
+    }
+}
+
 pub struct TriggerContext<'a> {
     pub candle: &'a Candle,
     pub history: &'a [Candle],
@@ -73,7 +91,18 @@ pub fn build_engine_bundle(config: &Config) -> Result<EngineBundle> {
         max_position_value_ratio: config.risk.max_position_value_ratio,
     };
     let risk = RiskManager::new(limits)?;
-    let order_flow = OrderFlow::new(risk);
+
+    let breaker_limits = CircuitBreakerLimits {
+        max_daily_loss_ratio: config.circuit_breaker.max_daily_loss_ratio,
+        max_drawdown_ratio: config.circuit_breaker.max_drawdown_ratio,
+        max_orders_per_window: config.circuit_breaker.max_orders_per_window,
+        order_window_secs: config.circuit_breaker.order_window_minutes as i64 * 60,
+        max_consecutive_losses: config.circuit_breaker.max_consecutive_losses,
+        flatten_on_halt: config.circuit_breaker.flatten_on_halt,
+    };
+    let breaker = CircuitBreaker::new(breaker_limits)?;
+    let mut order_flow = OrderFlow::with_circuit_breaker(risk, breaker);
+    order_flow.set_order_builder(OrderBuilder::with_prefix(config.orders.client_id_prefix.clone()));
 
     Ok(EngineBundle {
         trigger_engine,
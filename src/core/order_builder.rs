@@ -5,12 +5,26 @@ use crate::{Error, Result};
 use super::StrategyContext;
 
 pub struct OrderBuilder {
+    prefix: String,
     next_id: u64,
 }
 
+impl Default for OrderBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl OrderBuilder {
     pub fn new() -> Self {
-        Self { next_id: 1 }
+        Self::with_prefix("order")
+    }
+
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            next_id: 1,
+        }
     }
 
     pub fn build_for_signal(
@@ -87,6 +101,25 @@ impl OrderBuilder {
         }
     }
 
+    pub fn build_flatten(&mut self, symbol: &str, quantity: f64) -> Result<OrderRequest> {
+        if quantity <= 0.0 {
+            return Err(Error::new("order quantity must be positive"));
+        }
+        Ok(OrderRequest {
+            client_order_id: self.next_client_order_id(),
+            symbol: symbol.to_string(),
+            side: Side::Sell,
+            order_type: OrderType::Market,
+            quantity,
+        })
+    }
+
+    fn next_client_order_id(&mut self) -> String {
+        let client_order_id = format!("{}-{}", self.prefix, self.next_id);
+        self.next_id += 1;
+        client_order_id
+    }
+
     fn new_order(
         &mut self,
         symbol: &str,
@@ -111,11 +144,8 @@ impl OrderBuilder {
             _ => return Err(Error::new("orders.order_type must be market or limit")),
         };
 
-        let client_order_id = format!("order-{}", self.next_id);
-        self.next_id += 1;
-
         Ok(OrderRequest {
-            client_order_id,
+            client_order_id: self.next_client_order_id(),
             symbol: symbol.to_string(),
             side,
             order_type,
@@ -2,19 +2,71 @@ use crate::config::Config;
 use crate::models::{Account, OrderRequest, OrderType, Position, Signal, Side};
 use crate::{Error, Result};
 
+use super::circuit_breaker::CircuitBreaker;
 use super::{order_builder::OrderBuilder, risk::RiskManager, StrategyContext};
 
 pub struct OrderFlow {
     builder: OrderBuilder,
     risk: RiskManager,
+    breaker: CircuitBreaker,
 }
 
 impl OrderFlow {
     pub fn new(risk: RiskManager) -> Self {
+        Self::with_circuit_breaker(risk, CircuitBreaker::disabled())
+    }
+
+    pub fn with_circuit_breaker(risk: RiskManager, breaker: CircuitBreaker) -> Self {
         Self {
             builder: OrderBuilder::new(),
             risk,
+            breaker,
+        }
+    }
+
+    pub fn set_order_builder(&mut self, builder: OrderBuilder) {
+        self.builder = builder;
+    }
+
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    pub fn circuit_breaker_mut(&mut self) -> &mut CircuitBreaker {
+        &mut self.breaker
+    }
+
+    pub fn observe_equity(&mut self, now: i64, equity: f64) {
+        self.breaker.observe_equity(now, equity);
+    }
+
+    pub fn record_trade_pnl(&mut self, now: i64, pnl: f64) {
+        self.breaker.record_trade_pnl(now, pnl);
+    }
+
+    pub fn flatten_order(
+        &mut self,
+        account: &Account,
+        config: &Config,
+        now: i64,
+    ) -> Result<Option<OrderRequest>> {
+        if !self.breaker.take_flatten() {
+            return Ok(None);
+        }
+        let position_qty = account
+            .positions
+            .iter()
+            .find(|pos| pos.symbol == config.symbol)
+            .map(|pos| pos.quantity)
+            .unwrap_or(0.0);
+        if position_qty <= 0.0 {
+            return Ok(None);
         }
+        let order = self
+            .builder
+            .build_flatten(&config.symbol, position_qty)?;
+        self.breaker.record_order(now);
+        Ok(Some(order))
     }
 
     pub fn plan(
@@ -26,17 +78,26 @@ impl OrderFlow {
         if ctx.candle.close <= 0.0 {
             return Err(Error::new("reference price must be positive"));
         }
+        if self.breaker.is_halted() && self.breaker.state().flattened {
+            return Ok(Vec::new());
+        }
         let orders = self.builder.build_for_signal(signal, ctx, config)?;
         let mut simulated = Account {
             cash: ctx.account.cash,
             positions: ctx.account.positions.clone(),
         };
-        for order in &orders {
+        let mut accepted = Vec::with_capacity(orders.len());
+        for order in orders {
+            if matches!(order.side, Side::Buy) && self.breaker.check_entry(ctx.now).is_err() {
+                continue;
+            }
             self.risk
-                .check_order(&simulated, order, ctx.candle.close)?;
-            apply_order_to_account(&mut simulated, order, ctx.candle.close)?;
+                .check_order(&simulated, &order, ctx.candle.close)?;
+            apply_order_to_account(&mut simulated, &order, ctx.candle.close)?;
+            self.breaker.record_order(ctx.now);
+            accepted.push(order);
         }
-        Ok(orders)
+        Ok(accepted)
     }
 }
 
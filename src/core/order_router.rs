@@ -1,6 +1,659 @@
-use crate::models::{OrderAck, OrderRequest};
-use crate::Result;
+use crate::exchange::{AmendRequest, CandleRequest, Exchange};
+use crate::exchange::time_sync::local_time_ms;
+use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, Position, Side};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use tracing::warn;
+
+const MIN_LEG_QTY: f64 = 1e-12;
 
 pub trait OrderRouter {
     fn route(&self, req: OrderRequest) -> Result<OrderAck>;
 }
+
+#[derive(Clone, Debug)]
+pub struct VenueSnapshot {
+    pub venue: String,
+    pub price: f64,
+    pub fee_rate: f64,
+    pub liquidity: f64,
+    pub available: f64,
+    pub healthy: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouteLeg {
+    pub venue: String,
+    pub quantity: f64,
+    pub price: f64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RoutePlan {
+    pub legs: Vec<RouteLeg>,
+    pub unrouted: f64,
+}
+
+impl VenueSnapshot {
+    fn effective_price(&self, side: &Side) -> f64 {
+        match side {
+            Side::Buy => self.price * (1.0 + self.fee_rate),
+            Side::Sell => self.price * (1.0 - self.fee_rate),
+        }
+    }
+
+    fn affordable(&self, side: &Side) -> f64 {
+        match side {
+            Side::Buy => self.available / self.effective_price(side),
+            Side::Sell => self.available,
+        }
+    }
+}
+
+pub fn plan_routes(side: &Side, quantity: f64, venues: &[VenueSnapshot]) -> RoutePlan {
+    let mut candidates: Vec<&VenueSnapshot> = venues
+        .iter()
+        .filter(|venue| venue.healthy && venue.price > 0.0)
+        .collect();
+    candidates.sort_by(|a, b| {
+        let ordering = a
+            .effective_price(side)
+            .partial_cmp(&b.effective_price(side))
+            .unwrap_or(std::cmp::Ordering::Equal);
+        match side {
+            Side::Buy => ordering,
+            Side::Sell => ordering.reverse(),
+        }
+    });
+
+    let mut allocated = vec![0.0; candidates.len()];
+    let mut remaining = quantity.max(0.0);
+
+    for (index, venue) in candidates.iter().enumerate() {
+        if remaining <= MIN_LEG_QTY {
+            break;
+        }
+        let take = remaining
+            .min(venue.liquidity.max(0.0))
+            .min(venue.affordable(side).max(0.0));
+        allocated[index] += take;
+        remaining -= take;
+    }
+    for (index, venue) in candidates.iter().enumerate() {
+        if remaining <= MIN_LEG_QTY {
+            break;
+        }
+        let headroom = (venue.affordable(side) - allocated[index]).max(0.0);
+        let take = remaining.min(headroom);
+        allocated[index] += take;
+        remaining -= take;
+    }
+
+    let legs = candidates
+        .iter()
+        .zip(allocated)
+        .filter(|(_, quantity)| *quantity > MIN_LEG_QTY)
+        .map(|(venue, quantity)| RouteLeg {
+            venue: venue.venue.clone(),
+            quantity,
+            price: venue.price,
+        })
+        .collect();
+    RoutePlan {
+        legs,
+        unrouted: if remaining > MIN_LEG_QTY { remaining } else { 0.0 },
+    }
+}
+
+pub struct RouterVenue {
+    pub name: String,
+    pub exchange: Box<dyn Exchange>,
+    pub symbol: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub fee_rate: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct RouterConfig {
+    pub symbol: String,
+    pub max_participation: f64,
+    pub max_venue_failures: u32,
+    pub quote_interval: String,
+    pub quote_window_secs: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoutedChild {
+    pub venue: String,
+    pub client_order_id: String,
+}
+
+pub type RoutedChildren = HashMap<String, Vec<RoutedChild>>;
+
+pub struct RoutedOrder {
+    pub ack: OrderAck,
+    pub legs: Vec<(String, OrderAck)>,
+    pub unrouted: f64,
+}
+
+struct RouterState {
+    failures: Vec<u32>,
+    children: RoutedChildren,
+}
+
+pub struct SmartOrderRouter {
+    config: RouterConfig,
+    venues: Vec<RouterVenue>,
+    state: Mutex<RouterState>,
+}
+
+impl SmartOrderRouter {
+    pub fn new(config: RouterConfig, venues: Vec<RouterVenue>) -> Result<Self> {
+        if venues.is_empty() {
+            return Err(Error::new("router requires at least one venue"));
+        }
+        if !(0.0..=1.0).contains(&config.max_participation) {
+            return Err(Error::new("max_participation must be in [0, 1]"));
+        }
+        if config.max_venue_failures == 0 {
+            return Err(Error::new("max_venue_failures must be positive"));
+        }
+        if config.quote_window_secs <= 0 {
+            return Err(Error::new("quote_window_secs must be positive"));
+        }
+        let failures = vec![0; venues.len()];
+        Ok(Self {
+            config,
+            venues,
+            state: Mutex::new(RouterState {
+                failures,
+                children: HashMap::new(),
+            }),
+        })
+    }
+
+    pub fn routed_children(&self) -> Result<RoutedChildren> {
+        Ok(self.lock()?.children.clone())
+    }
+
+    pub fn restore_children(&self, children: RoutedChildren) -> Result<()> {
+        for child in children.values().flatten() {
+            self.venue_index(&child.venue)?;
+        }
+        self.lock()?.children = children;
+        Ok(())
+    }
+
+    pub fn retain_children<F: FnMut(&str) -> bool>(&self, mut keep: F) -> Result<()> {
+        self.lock()?
+            .children
+            .retain(|parent_id, _| keep(parent_id));
+        Ok(())
+    }
+
+    pub fn venue_names(&self) -> Vec<String> {
+        self.venues.iter().map(|venue| venue.name.clone()).collect()
+    }
+
+    pub fn is_healthy(&self, venue: &str) -> Result<bool> {
+        self.venue_healthy(self.venue_index(venue)?)
+    }
+
+    pub fn snapshots(&self, side: &Side) -> Result<Vec<VenueSnapshot>> {
+        let now_ms = local_time_ms()?;
+        let mut snapshots = Vec::with_capacity(self.venues.len());
+        for (index, venue) in self.venues.iter().enumerate() {
+            let mut snapshot = VenueSnapshot {
+                venue: venue.name.clone(),
+                price: 0.0,
+                fee_rate: venue.fee_rate,
+                liquidity: 0.0,
+                available: 0.0,
+                healthy: false,
+            };
+            if !self.venue_healthy(index)? {
+                snapshots.push(snapshot);
+                continue;
+            }
+            match self.quote_venue(venue, side, now_ms) {
+                Ok((candle, available)) => {
+                    self.record_success(index)?;
+                    snapshot.price = candle.close;
+                    snapshot.liquidity = if self.config.max_participation > 0.0 {
+                        candle.volume * self.config.max_participation
+                    } else {
+                        f64::INFINITY
+                    };
+                    snapshot.available = available;
+                    snapshot.healthy = true;
+                }
+                Err(err) => {
+                    self.record_failure(index)?;
+                    warn!(venue = %venue.name, error = %err.message, "router: venue quote failed");
+                }
+            }
+            snapshots.push(snapshot);
+        }
+        Ok(snapshots)
+    }
+
+    pub fn route_order(&self, req: &OrderRequest) -> Result<RoutedOrder> {
+        if req.symbol != self.config.symbol {
+            return Err(Error::new(format!("router unknown symbol: {}", req.symbol)));
+        }
+        let snapshots = self.snapshots(&req.side)?;
+        let plan = plan_routes(&req.side, req.quantity, &snapshots);
+        if plan.unrouted > 0.0 {
+            warn!(
+                client_id = %req.client_order_id,
+                unrouted = plan.unrouted,
+                "router: insufficient balance across venues"
+            );
+        }
+
+        let split = plan.legs.len() > 1;
+        let mut legs = Vec::with_capacity(plan.legs.len());
+        let mut children = Vec::with_capacity(plan.legs.len());
+        for (leg_index, leg) in plan.legs.iter().enumerate() {
+            let index = self.venue_index(&leg.venue)?;
+            let venue = &self.venues[index];
+            let client_order_id = if split {
+                format!("{}-{}", req.client_order_id, leg_index + 1)
+            } else {
+                req.client_order_id.clone()
+            };
+            let child = OrderRequest {
+                client_order_id: client_order_id.clone(),
+                symbol: venue.symbol.clone(),
+                side: req.side.clone(),
+                order_type: req.order_type.clone(),
+                quantity: leg.quantity,
+            };
+            let ack = match venue.exchange.place_order(&child) {
+                Ok(ack) => {
+                    self.record_success(index)?;
+                    ack
+                }
+                Err(err) => {
+                    self.record_failure(index)?;
+                    warn!(
+                        venue = %venue.name,
+                        client_id = %client_order_id,
+                        error = %err.message,
+                        "router: child order status unknown; kept for fetch_order"
+                    );
+                    OrderAck {
+                        client_order_id: client_order_id.clone(),
+                        exchange_order_id: None,
+                        status: OrderStatus::New,
+                        filled_qty: 0.0,
+                        avg_fill_price: None,
+                    }
+                }
+            };
+            if !matches!(ack.status, OrderStatus::Rejected) {
+                children.push(RoutedChild {
+                    venue: venue.name.clone(),
+                    client_order_id,
+                });
+            }
+            legs.push((venue.name.clone(), ack));
+        }
+
+        let accepted: Vec<String> = legs
+            .iter()
+            .filter(|(_, ack)| !matches!(ack.status, OrderStatus::Rejected))
+            .map(|(venue, ack)| {
+                format!(
+                    "{venue}:{}",
+                    ack.exchange_order_id
+                        .clone()
+                        .unwrap_or_else(|| ack.client_order_id.clone())
+                )
+            })
+            .collect();
+        let ack = OrderAck {
+            client_order_id: req.client_order_id.clone(),
+            exchange_order_id: if accepted.is_empty() {
+                None
+            } else {
+                Some(accepted.join(","))
+            },
+            status: if accepted.is_empty() {
+                OrderStatus::Rejected
+            } else {
+                OrderStatus::New
+            },
+            filled_qty: 0.0,
+            avg_fill_price: None,
+        };
+        if !children.is_empty() {
+            self.lock()?
+                .children
+                .insert(req.client_order_id.clone(), children);
+        }
+        Ok(RoutedOrder {
+            ack,
+            legs,
+            unrouted: plan.unrouted,
+        })
+    }
+
+    fn quote_venue(&self, venue: &RouterVenue, side: &Side, now_ms: i64) -> Result<(Candle, f64)> {
+        let request = CandleRequest {
+            symbol: venue.symbol.clone(),
+            interval: self.config.quote_interval.clone(),
+            start_time: now_ms - self.config.quote_window_secs * 1000,
+            end_time: now_ms,
+        };
+        let candle = venue
+            .exchange
+            .fetch_candles(&request)?
+            .into_iter()
+            .max_by_key(|candle| candle.time)
+            .ok_or_else(|| Error::new(format!("{} returned no candles", venue.name)))?;
+        let asset = match side {
+            Side::Buy => &venue.quote_asset,
+            Side::Sell => &venue.base_asset,
+        };
+        let available = venue
+            .exchange
+            .fetch_balances()?
+            .into_iter()
+            .find(|balance| &balance.asset == asset)
+            .map(|balance| balance.free)
+            .unwrap_or(0.0);
+        Ok((candle, available))
+    }
+
+    fn child_lookups(&self, client_order_id: &str) -> Result<Vec<(usize, String)>> {
+        let children = self.lock()?.children.get(client_order_id).cloned();
+        match children {
+            Some(children) => children
+                .into_iter()
+                .map(|child| Ok((self.venue_index(&child.venue)?, child.client_order_id)))
+                .collect(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn parent_of(&self, venue: &str, client_order_id: &str) -> Result<Option<String>> {
+        Ok(self
+            .lock()?
+            .children
+            .iter()
+            .find(|(_, children)| {
+                children.iter().any(|child| {
+                    child.venue == venue && child.client_order_id == client_order_id
+                })
+            })
+            .map(|(parent_id, _)| parent_id.clone()))
+    }
+
+    fn venue_index(&self, name: &str) -> Result<usize> {
+        self.venues
+            .iter()
+            .position(|venue| venue.name == name)
+            .ok_or_else(|| Error::new(format!("unknown venue: {name}")))
+    }
+
+    fn venue_healthy(&self, index: usize) -> Result<bool> {
+        Ok(self.lock()?.failures[index] < self.config.max_venue_failures)
+    }
+
+    fn record_success(&self, index: usize) -> Result<()> {
+        self.lock()?.failures[index] = 0;
+        Ok(())
+    }
+
+    fn record_failure(&self, index: usize) -> Result<()> {
+        let mut state = self.lock()?;
+        state.failures[index] = state.failures[index].saturating_add(1);
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, RouterState>> {
+        self.state
+            .lock()
+            .map_err(|_| Error::new("router state lock poisoned"))
+    }
+}
+
+impl OrderRouter for SmartOrderRouter {
+    fn route(&self, req: OrderRequest) -> Result<OrderAck> {
+        Ok(self.route_order(&req)?.ack)
+    }
+}
+
+impl Exchange for SmartOrderRouter {
+    fn place_order(&self, order: &OrderRequest) -> Result<OrderAck> {
+        self.route(order.clone())
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let children = self.child_lookups(order_id)?;
+        if !children.is_empty() {
+            self.lock()?.children.remove(order_id);
+            let mut first_error = None;
+            for (index, child_id) in children {
+                if let Err(err) = self.venues[index].exchange.cancel_order(&child_id) {
+                    first_error.get_or_insert(err);
+                }
+            }
+            return match first_error {
+                Some(err) => Err(err),
+                None => Ok(()),
+            };
+        }
+        for venue in &self.venues {
+            if venue.exchange.cancel_order(order_id).is_ok() {
+                return Ok(());
+            }
+        }
+        Err(Error::new(format!("order not found on any venue: {order_id}")))
+    }
+
+    fn amend_order(&self, req: &AmendRequest) -> Result<OrderAck> {
+        let parent_id = req.order.client_order_id.clone();
+        let children = self.child_lookups(&parent_id)?;
+        let (index, child_id) = match children.as_slice() {
+            [child] => child.clone(),
+            [] => return Err(Error::new(format!("order not routed: {parent_id}"))),
+            _ => {
+                return Err(Error::new(format!(
+                    "cannot amend order split across venues: {parent_id}"
+                )))
+            }
+        };
+        let venue = &self.venues[index];
+        let child = AmendRequest {
+            order: OrderRequest {
+                client_order_id: child_id,
+                symbol: venue.symbol.clone(),
+                ..req.order.clone()
+            },
+            new_price: req.new_price,
+            new_quantity: req.new_quantity,
+        };
+        let ack = venue.exchange.amend_order(&child)?;
+        Ok(OrderAck {
+            client_order_id: parent_id,
+            ..ack
+        })
+    }
+
+    fn fetch_balances(&self) -> Result<Vec<Balance>> {
+        let mut totals: Vec<Balance> = Vec::new();
+        for venue in &self.venues {
+            for balance in venue.exchange.fetch_balances()? {
+                match totals.iter_mut().find(|total| total.asset == balance.asset) {
+                    Some(total) => {
+                        total.free += balance.free;
+                        total.locked += balance.locked;
+                    }
+                    None => totals.push(balance),
+                }
+            }
+        }
+        Ok(totals)
+    }
+
+    fn fetch_positions(&self) -> Result<Vec<Position>> {
+        let mut quantity = 0.0;
+        let mut cost = 0.0;
+        for venue in &self.venues {
+            for position in venue.exchange.fetch_positions()? {
+                if position.symbol != venue.symbol {
+                    continue;
+                }
+                quantity += position.quantity;
+                cost += position.quantity * position.avg_price;
+            }
+        }
+        if quantity == 0.0 {
+            return Ok(Vec::new());
+        }
+        Ok(vec![Position {
+            symbol: self.config.symbol.clone(),
+            quantity,
+            avg_price: cost / quantity,
+        }])
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let children = self.child_lookups(client_order_id)?;
+        if children.is_empty() {
+            let mut found: Option<OrderAck> = None;
+            for venue in &self.venues {
+                let Some(ack) = venue.exchange.fetch_order(client_order_id)? else {
+                    continue;
+                };
+                let open = matches!(ack.status, OrderStatus::New | OrderStatus::PartiallyFilled);
+                if found.is_none() || open {
+                    found = Some(ack);
+                }
+                if open {
+                    break;
+                }
+            }
+            return Ok(found);
+        }
+        let mut legs = Vec::with_capacity(children.len());
+        for (index, child_id) in children {
+            if let Some(ack) = self.venues[index].exchange.fetch_order(&child_id)? {
+                legs.push(ack);
+            }
+        }
+        Ok(merge_legs(client_order_id, &legs))
+    }
+
+    fn fetch_open_orders(&self) -> Result<Vec<OrderAck>> {
+        let mut orders: Vec<OrderAck> = Vec::new();
+        for venue in &self.venues {
+            for order in venue.exchange.fetch_open_orders()? {
+                let Some(parent_id) = self.parent_of(&venue.name, &order.client_order_id)? else {
+                    orders.push(order);
+                    continue;
+                };
+                if orders.iter().any(|seen| seen.client_order_id == parent_id) {
+                    continue;
+                }
+                orders.push(OrderAck {
+                    client_order_id: parent_id,
+                    ..order
+                });
+            }
+        }
+        Ok(orders)
+    }
+
+    fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>> {
+        let mut last_error = None;
+        for (index, venue) in self.venues.iter().enumerate() {
+            if !self.venue_healthy(index)? {
+                continue;
+            }
+            let request = CandleRequest {
+                symbol: venue.symbol.clone(),
+                interval: req.interval.clone(),
+                start_time: req.start_time,
+                end_time: req.end_time,
+            };
+            match venue.exchange.fetch_candles(&request) {
+                Ok(candles) => return Ok(candles),
+                Err(err) => {
+                    self.record_failure(index)?;
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| Error::new("no healthy venue for candles")))
+    }
+}
+
+fn merge_legs(client_order_id: &str, legs: &[OrderAck]) -> Option<OrderAck> {
+    if legs.is_empty() {
+        return None;
+    }
+    let filled_qty: f64 = legs.iter().map(|leg| leg.filled_qty).sum();
+    let notional: f64 = legs
+        .iter()
+        .filter_map(|leg| leg.avg_fill_price.map(|price| price * leg.filled_qty))
+        .sum();
+    let all = |status: OrderStatus| legs.iter().all(|leg| leg.status == status);
+    let status = if legs
+        .iter()
+        .any(|leg| matches!(leg.status, OrderStatus::New | OrderStatus::PartiallyFilled))
+    {
+        if filled_qty > 0.0 {
+            OrderStatus::PartiallyFilled
+        } else {
+            OrderStatus::New
+        }
+    } else if all(OrderStatus::Filled) {
+        OrderStatus::Filled
+    } else if all(OrderStatus::Rejected) {
+        OrderStatus::Rejected
+    } else {
+        OrderStatus::Canceled
+    };
+    let exchange_ids: Vec<String> = legs
+        .iter()
+        .filter_map(|leg| leg.exchange_order_id.clone())
+        .collect();
+    Some(OrderAck {
+        client_order_id: client_order_id.to_string(),
+        exchange_order_id: (!exchange_ids.is_empty()).then(|| exchange_ids.join(",")),
+        status,
+        filled_qty,
+        avg_fill_price: (filled_qty > 0.0 && notional > 0.0).then(|| notional / filled_qty),
+    })
+}
+
+pub fn load_route_state(path: &str) -> Result<Option<RoutedChildren>> {
+    let path = Path::new(path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|err| Error::new(format!("route state read failed: {err}")))?;
+    let state = serde_json::from_str::<RoutedChildren>(&content)
+        .map_err(|err| Error::new(format!("route state parse failed: {err}")))?;
+    Ok(Some(state))
+}
+
+pub fn save_route_state(path: &str, state: &RoutedChildren) -> Result<()> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|err| Error::new(format!("route state serialize failed: {err}")))?;
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| Error::new(format!("route state dir create failed: {err}")))?;
+    }
+    fs::write(path, content).map_err(|err| Error::new(format!("route state write failed: {err}")))
+}
@@ -0,0 +1,365 @@
+use crate::exchange::open_orders::is_own_order;
+use crate::exchange::sync::AccountSnapshot;
+use crate::models::{OrderRequest, OrderType, Side};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+const DUST: f64 = 1e-9;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReconcileScope {
+    pub symbol: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub client_id_prefix: String,
+    pub fee_rate: f64,
+    pub max_drift_ratio: f64,
+    pub derivatives: bool,
+    pub shared_quote: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedFill {
+    pub symbol: String,
+    pub side: String,
+    pub quantity: f64,
+    pub price: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedOrder {
+    pub client_order_id: String,
+    pub fill: Option<ExpectedFill>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedState {
+    pub time: i64,
+    pub balances: BTreeMap<String, f64>,
+    pub positions: BTreeMap<String, f64>,
+    pub open_orders: Vec<ExpectedOrder>,
+}
+
+impl ExpectedState {
+    pub fn from_snapshot(
+        snapshot: &AccountSnapshot,
+        scope: &ReconcileScope,
+        previous: Option<&ExpectedState>,
+        time: i64,
+    ) -> Self {
+        let mut balances = BTreeMap::new();
+        for asset in [&scope.base_asset, &scope.quote_asset] {
+            balances.insert(asset.clone(), balance_total(snapshot, asset));
+        }
+        let mut positions = BTreeMap::new();
+        if scope.derivatives {
+            positions.insert(
+                scope.symbol.clone(),
+                position_quantity(snapshot, &scope.symbol).unwrap_or(0.0),
+            );
+        }
+        let open_orders = snapshot
+            .open_orders
+            .iter()
+            .map(|order| ExpectedOrder {
+                client_order_id: order.client_order_id.clone(),
+                fill: previous
+                    .and_then(|state| state.order(&order.client_order_id))
+                    .and_then(|known| known.fill.clone()),
+            })
+            .collect();
+        Self {
+            time,
+            balances,
+            positions,
+            open_orders,
+        }
+    }
+
+    pub fn order(&self, client_order_id: &str) -> Option<&ExpectedOrder> {
+        self.open_orders
+            .iter()
+            .find(|order| order.client_order_id == client_order_id)
+    }
+
+    pub fn record_order(&mut self, order: &OrderRequest, reference_price: f64) {
+        let price = match order.order_type {
+            OrderType::Limit { price } => price,
+            OrderType::Market => reference_price,
+        };
+        self.forget_order(&order.client_order_id);
+        self.open_orders.push(ExpectedOrder {
+            client_order_id: order.client_order_id.clone(),
+            fill: Some(ExpectedFill {
+                symbol: order.symbol.clone(),
+                side: side_label(&order.side).to_string(),
+                quantity: order.quantity,
+                price,
+            }),
+        });
+    }
+
+    pub fn record_amend(&mut self, client_order_id: &str, price: f64) {
+        if let Some(fill) = self
+            .open_orders
+            .iter_mut()
+            .find(|order| order.client_order_id == client_order_id)
+            .and_then(|order| order.fill.as_mut())
+        {
+            fill.price = price;
+        }
+    }
+
+    pub fn forget_order(&mut self, client_order_id: &str) {
+        self.open_orders
+            .retain(|order| order.client_order_id != client_order_id);
+    }
+
+    fn apply_fill(&mut self, fill: &ExpectedFill, scope: &ReconcileScope) {
+        let signed = match fill.side.as_str() {
+            "sell" => -fill.quantity,
+            _ => fill.quantity,
+        };
+        let fee = fill.quantity * fill.price * scope.fee_rate;
+        let quote = self.balances.entry(scope.quote_asset.clone()).or_insert(0.0);
+        if scope.derivatives {
+            *quote -= fee;
+            *self.positions.entry(fill.symbol.clone()).or_insert(0.0) += signed;
+            return;
+        }
+        *quote -= signed * fill.price + fee;
+        *self.balances.entry(scope.base_asset.clone()).or_insert(0.0) += signed;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakKind {
+    Balance,
+    Position,
+    UnknownOrder,
+    MissingOrder,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReconciliationBreak {
+    pub kind: BreakKind,
+    pub key: String,
+    pub expected: f64,
+    pub actual: f64,
+    pub drift_ratio: f64,
+    pub cause: String,
+    pub breach: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReconciliationReport {
+    pub time: i64,
+    pub symbol: String,
+    pub baseline: bool,
+    pub assumed_fills: Vec<String>,
+    pub breaks: Vec<ReconciliationBreak>,
+    pub max_drift_ratio: f64,
+}
+
+impl ReconciliationReport {
+    pub fn has_breach(&self) -> bool {
+        self.breaks.iter().any(|item| item.breach)
+    }
+}
+
+pub fn reconcile(
+    expected: Option<&ExpectedState>,
+    snapshot: &AccountSnapshot,
+    scope: &ReconcileScope,
+    time: i64,
+) -> ReconciliationReport {
+    let mut report = ReconciliationReport {
+        time,
+        symbol: scope.symbol.clone(),
+        baseline: expected.is_none(),
+        assumed_fills: Vec::new(),
+        breaks: Vec::new(),
+        max_drift_ratio: scope.max_drift_ratio,
+    };
+    let expected = match expected {
+        Some(expected) => expected,
+        None => return report,
+    };
+
+    let actual_open: HashSet<&str> = snapshot
+        .open_orders
+        .iter()
+        .map(|order| order.client_order_id.as_str())
+        .collect();
+    let mut projected = expected.clone();
+    for order in &expected.open_orders {
+        if actual_open.contains(order.client_order_id.as_str()) {
+            continue;
+        }
+        match &order.fill {
+            Some(fill) => {
+                projected.apply_fill(fill, scope);
+                report.assumed_fills.push(order.client_order_id.clone());
+            }
+            None => report.breaks.push(order_break(
+                BreakKind::MissingOrder,
+                &order.client_order_id,
+                "closed_untracked",
+            )),
+        }
+    }
+    for order in &snapshot.open_orders {
+        if expected.order(&order.client_order_id).is_none() {
+            let cause = if is_own_order(&order.client_order_id, &scope.client_id_prefix) {
+                "orphan_order"
+            } else {
+                "manual_order"
+            };
+            report.breaks.push(order_break(
+                BreakKind::UnknownOrder,
+                &order.client_order_id,
+                cause,
+            ));
+        }
+    }
+
+    let cause = if report.assumed_fills.is_empty() {
+        "manual_trade"
+    } else {
+        "fill_mismatch"
+    };
+    let assets = if scope.derivatives {
+        Vec::new()
+    } else if scope.shared_quote {
+        vec![&scope.base_asset]
+    } else {
+        vec![&scope.base_asset, &scope.quote_asset]
+    };
+    for asset in assets {
+        let expected_total = projected.balances.get(asset).copied().unwrap_or(0.0);
+        let actual_total = balance_total(snapshot, asset);
+        if let Some(item) = drift_break(
+            BreakKind::Balance,
+            asset,
+            expected_total,
+            actual_total,
+            cause,
+            scope.max_drift_ratio,
+        ) {
+            report.breaks.push(item);
+        }
+    }
+    if scope.derivatives {
+        let expected_qty = projected.positions.get(&scope.symbol).copied().unwrap_or(0.0);
+        let actual_qty = position_quantity(snapshot, &scope.symbol).unwrap_or(0.0);
+        if let Some(item) = drift_break(
+            BreakKind::Position,
+            &scope.symbol,
+            expected_qty,
+            actual_qty,
+            cause,
+            scope.max_drift_ratio,
+        ) {
+            report.breaks.push(item);
+        }
+    }
+    report
+}
+
+pub fn load_expected_state(path: &str) -> Result<Option<ExpectedState>> {
+    let path = Path::new(path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|err| Error::new(format!("reconciliation state read failed: {err}")))?;
+    let state = serde_json::from_str::<ExpectedState>(&content)
+        .map_err(|err| Error::new(format!("reconciliation state parse failed: {err}")))?;
+    Ok(Some(state))
+}
+
+pub fn save_expected_state(path: &str, state: &ExpectedState) -> Result<()> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|err| Error::new(format!("reconciliation state serialize failed: {err}")))?;
+    write_file(path, content)
+}
+
+pub fn save_reconciliation_report(path: &str, report: &ReconciliationReport) -> Result<()> {
+    let content = serde_json::to_string_pretty(report)
+        .map_err(|err| Error::new(format!("reconciliation report serialize failed: {err}")))?;
+    write_file(path, content)
+}
+
+fn write_file(path: &str, content: String) -> Result<()> {
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| Error::new(format!("reconciliation dir create failed: {err}")))?;
+    }
+    fs::write(path, content)
+        .map_err(|err| Error::new(format!("reconciliation write failed: {err}")))
+}
+
+fn order_break(kind: BreakKind, client_order_id: &str, cause: &str) -> ReconciliationBreak {
+    ReconciliationBreak {
+        kind,
+        key: client_order_id.to_string(),
+        expected: 0.0,
+        actual: 0.0,
+        drift_ratio: 0.0,
+        cause: cause.to_string(),
+        breach: false,
+    }
+}
+
+fn drift_break(
+    kind: BreakKind,
+    key: &str,
+    expected: f64,
+    actual: f64,
+    cause: &str,
+    max_drift_ratio: f64,
+) -> Option<ReconciliationBreak> {
+    let diff = (actual - expected).abs();
+    if diff <= DUST {
+        return None;
+    }
+    let drift_ratio = diff / expected.abs().max(actual.abs());
+    Some(ReconciliationBreak {
+        kind,
+        key: key.to_string(),
+        expected,
+        actual,
+        drift_ratio,
+        cause: cause.to_string(),
+        breach: drift_ratio > max_drift_ratio,
+    })
+}
+
+fn balance_total(snapshot: &AccountSnapshot, asset: &str) -> f64 {
+    snapshot
+        .balances
+        .iter()
+        .filter(|balance| balance.asset == asset)
+        .map(|balance| balance.free + balance.locked)
+        .sum()
+}
+
+fn position_quantity(snapshot: &AccountSnapshot, symbol: &str) -> Option<f64> {
+    snapshot
+        .positions
+        .iter()
+        .find(|position| position.symbol == symbol)
+        .map(|position| position.quantity)
+}
+
+fn side_label(side: &Side) -> &'static str {
+    match side {
+        Side::Buy => "buy",
+        Side::Sell => "sell",
+    }
+}
@@ -0,0 +1,65 @@
+use crate::config::Config;
+use crate::exchange::AmendRequest;
+use crate::models::{OrderRequest, OrderType, Side};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RepricePolicy {
+    pub threshold_bps: u32,
+    pub offset_bps: u32,
+    pub max_amends: u32,
+}
+
+impl RepricePolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            threshold_bps: config.orders.reprice_threshold_bps,
+            offset_bps: config.orders.limit_price_offset_bps,
+            max_amends: config.orders.reprice_max_amends,
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            threshold_bps: 0,
+            offset_bps: 0,
+            max_amends: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.threshold_bps > 0 && self.max_amends > 0
+    }
+
+    pub fn target_price(&self, side: &Side, reference_price: f64) -> f64 {
+        let offset = self.offset_bps as f64 / 10_000.0;
+        match side {
+            Side::Buy => reference_price * (1.0 - offset),
+            Side::Sell => reference_price * (1.0 + offset),
+        }
+    }
+
+    pub fn reprice(
+        &self,
+        order: &OrderRequest,
+        reference_price: f64,
+        amends: u32,
+    ) -> Option<AmendRequest> {
+        if !self.is_enabled() || amends >= self.max_amends || reference_price <= 0.0 {
+            return None;
+        }
+        let current = match order.order_type {
+            OrderType::Limit { price } if price > 0.0 => price,
+            _ => return None,
+        };
+        let target = self.target_price(&order.side, reference_price);
+        let drift_bps = (target - current).abs() / current * 10_000.0;
+        if drift_bps < self.threshold_bps as f64 {
+            return None;
+        }
+        Some(AmendRequest {
+            order: order.clone(),
+            new_price: Some(target),
+            new_quantity: None,
+        })
+    }
+}
@@ -0,0 +1,198 @@
+use crate::config::DataConfig;
+use crate::models::{Candle, TradePrint};
+use crate::{Error, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BarKind {
+    Time,
+    Volume,
+    Dollar,
+    Tick,
+    Renko,
+    HeikinAshi,
+}
+
+impl BarKind {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "time" => Ok(Self::Time),
+            "volume" => Ok(Self::Volume),
+            "dollar" => Ok(Self::Dollar),
+            "tick" => Ok(Self::Tick),
+            "renko" => Ok(Self::Renko),
+            "heikin_ashi" => Ok(Self::HeikinAshi),
+            _ => Err(Error::new(
+                "data.bars must be time, volume, dollar, tick, renko, or heikin_ashi",
+            )),
+        }
+    }
+
+    pub fn needs_size(&self) -> bool {
+        matches!(self, Self::Volume | Self::Dollar | Self::Tick | Self::Renko)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BarSpec {
+    pub kind: BarKind,
+    pub size: f64,
+}
+
+impl BarSpec {
+    pub fn new(kind: BarKind, size: f64) -> Result<Self> {
+        if kind.needs_size() && !(size.is_finite() && size > 0.0) {
+            return Err(Error::new("data.bar_size must be > 0 for this bar type"));
+        }
+        Ok(Self { kind, size })
+    }
+
+    pub fn from_config(config: &DataConfig) -> Result<Self> {
+        Self::new(BarKind::parse(&config.bars)?, config.bar_size)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BarBuilder {
+    spec: BarSpec,
+    forming: Option<Candle>,
+    filled: f64,
+    brick: Option<(f64, f64)>,
+    brick_time: Option<i64>,
+    heikin: Option<(f64, f64)>,
+}
+
+impl BarBuilder {
+    pub fn new(spec: BarSpec) -> Self {
+        Self {
+            spec,
+            forming: None,
+            filled: 0.0,
+            brick: None,
+            brick_time: None,
+            heikin: None,
+        }
+    }
+
+    pub fn forming(&self) -> Option<&Candle> {
+        self.forming.as_ref()
+    }
+
+    pub fn push(&mut self, candle: &Candle) -> Vec<Candle> {
+        match self.spec.kind {
+            BarKind::Time => vec![candle.clone()],
+            BarKind::HeikinAshi => vec![self.heikin_ashi(candle)],
+            BarKind::Renko => self.renko(candle),
+            BarKind::Volume => self.accumulate(candle, candle.volume),
+            BarKind::Dollar => self.accumulate(candle, candle.close * candle.volume),
+            BarKind::Tick => self.accumulate(candle, 1.0),
+        }
+    }
+
+    pub fn push_print(&mut self, print: &TradePrint) -> Result<Vec<Candle>> {
+        let candle = Candle {
+            time: print.time_ms.div_euclid(1_000),
+            open: print.price,
+            high: print.price,
+            low: print.price,
+            close: print.price,
+            volume: print.quantity,
+        };
+        match self.spec.kind {
+            BarKind::Time | BarKind::HeikinAshi => Err(Error::new(
+                "time and heikin_ashi bars need candles, not trade prints",
+            )),
+            _ => Ok(self.push(&candle)),
+        }
+    }
+
+    fn accumulate(&mut self, candle: &Candle, amount: f64) -> Vec<Candle> {
+        match self.forming.as_mut() {
+            Some(forming) => {
+                forming.high = forming.high.max(candle.high);
+                forming.low = forming.low.min(candle.low);
+                forming.close = candle.close;
+                forming.volume += candle.volume;
+            }
+            None => self.forming = Some(candle.clone()),
+        }
+        self.filled += amount;
+        if self.filled + f64::EPSILON * self.spec.size < self.spec.size {
+            return Vec::new();
+        }
+        self.filled = 0.0;
+        self.forming.take().into_iter().collect()
+    }
+
+    fn heikin_ashi(&mut self, candle: &Candle) -> Candle {
+        let close = (candle.open + candle.high + candle.low + candle.close) / 4.0;
+        let open = match self.heikin {
+            Some((open, close)) => (open + close) / 2.0,
+            None => (candle.open + candle.close) / 2.0,
+        };
+        self.heikin = Some((open, close));
+        Candle {
+            time: candle.time,
+            open,
+            high: candle.high.max(open).max(close),
+            low: candle.low.min(open).min(close),
+            close,
+            volume: candle.volume,
+        }
+    }
+
+    fn renko(&mut self, candle: &Candle) -> Vec<Candle> {
+        let size = self.spec.size;
+        let (mut low, mut high) = *self.brick.get_or_insert((candle.close, candle.close));
+        let mut volume = self.filled + candle.volume;
+        let mut bricks = Vec::new();
+        while candle.close >= high + size {
+            bricks.push(brick(self.next_brick_time(candle.time), high, high + size, volume));
+            volume = 0.0;
+            low = high;
+            high += size;
+        }
+        while candle.close <= low - size {
+            bricks.push(brick(self.next_brick_time(candle.time), low, low - size, volume));
+            volume = 0.0;
+            high = low;
+            low -= size;
+        }
+        self.brick = Some((low, high));
+        self.filled = volume;
+        bricks
+    }
+
+    fn next_brick_time(&mut self, time: i64) -> i64 {
+        let time = self.brick_time.map_or(time, |last| time.max(last + 1));
+        self.brick_time = Some(time);
+        time
+    }
+}
+
+fn brick(time: i64, open: f64, close: f64, volume: f64) -> Candle {
+    Candle {
+        time,
+        open,
+        high: open.max(close),
+        low: open.min(close),
+        close,
+        volume,
+    }
+}
+
+pub fn build_bars(candles: &[Candle], spec: BarSpec) -> Vec<Candle> {
+    let mut builder = BarBuilder::new(spec);
+    candles
+        .iter()
+        .flat_map(|candle| builder.push(candle))
+        .collect()
+}
+
+pub fn bars_from_prints(prints: &[TradePrint], spec: BarSpec) -> Result<Vec<Candle>> {
+    let mut builder = BarBuilder::new(spec);
+    let mut bars = Vec::new();
+    for print in prints {
+        bars.extend(builder.push_print(print)?);
+    }
+    Ok(bars)
+}
@@ -0,0 +1,289 @@
+use crate::models::Candle;
+use crate::{Error, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+const MAGIC: &[u8; 8] = b"MRWCNDL1";
+const HEADER_LEN: usize = 8 + 4 + 8;
+const RANGE_LEN: usize = 16;
+const CANDLE_LEN: usize = 48;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CacheKey {
+    pub exchange: String,
+    pub market: String,
+    pub symbol: String,
+    pub interval: String,
+}
+
+impl CacheKey {
+    pub fn new(exchange: &str, symbol: &str, interval: &str) -> Self {
+        Self::for_market(exchange, "default", symbol, interval)
+    }
+
+    pub fn for_market(exchange: &str, market: &str, symbol: &str, interval: &str) -> Self {
+        Self {
+            exchange: exchange.to_lowercase(),
+            market: market.to_lowercase(),
+            symbol: symbol.to_string(),
+            interval: interval.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CachedSeries {
+    pub coverage: Vec<(i64, i64)>,
+    pub candles: Vec<Candle>,
+}
+
+impl CachedSeries {
+    pub fn covers(&self, start: i64, end: i64) -> bool {
+        missing_ranges(&self.coverage, start, end).is_empty()
+    }
+
+    pub fn range(&self, start: i64, end: i64) -> Vec<Candle> {
+        let from = self.candles.partition_point(|candle| candle.time < start);
+        let to = self.candles.partition_point(|candle| candle.time <= end);
+        self.candles[from..to.max(from)].to_vec()
+    }
+}
+
+pub struct CandleCache {
+    root: PathBuf,
+}
+
+impl CandleCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn path(&self, key: &CacheKey) -> PathBuf {
+        self.root
+            .join(path_component(&key.exchange))
+            .join(path_component(&key.market))
+            .join(path_component(&key.symbol))
+            .join(format!("{}.bin", path_component(&key.interval)))
+    }
+
+    pub fn read(&self, key: &CacheKey) -> Result<CachedSeries> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(CachedSeries::default());
+        }
+        let bytes = fs::read(&path)
+            .map_err(|err| Error::new(format!("candle cache read failed: {err}")))?;
+        decode(&bytes)
+    }
+
+    pub fn write(&self, key: &CacheKey, series: &CachedSeries) -> Result<()> {
+        write_atomic(&self.path(key), &encode(series))
+    }
+
+    pub fn load_range<F>(
+        &self,
+        key: &CacheKey,
+        start: i64,
+        end: i64,
+        settled_until: i64,
+        mut fetch: F,
+    ) -> Result<Vec<Candle>>
+    where
+        F: FnMut(i64, i64) -> Result<Vec<Candle>>,
+    {
+        if start > end {
+            return Err(Error::new("candle cache range start must be <= end"));
+        }
+        let mut series = self.read(key)?;
+        let missing = missing_ranges(&series.coverage, start, end);
+        if missing.is_empty() {
+            return Ok(series.range(start, end));
+        }
+
+        let mut merged: BTreeMap<i64, Candle> = series
+            .candles
+            .drain(..)
+            .map(|candle| (candle.time, candle))
+            .collect();
+        let mut unsettled = Vec::new();
+        let mut fetched = 0usize;
+        for &(from, to) in &missing {
+            let candles = fetch(from, to)?;
+            fetched += candles.len();
+            for candle in candles {
+                if candle.time > settled_until {
+                    unsettled.push(candle);
+                } else {
+                    merged.insert(candle.time, candle);
+                }
+            }
+            if from <= settled_until {
+                series.coverage.push((from, to.min(settled_until)));
+            }
+        }
+        series.coverage = merge_ranges(series.coverage);
+        series.candles = merged.into_values().collect();
+        self.write(key, &series)?;
+        info!(
+            exchange = %key.exchange,
+            symbol = %key.symbol,
+            interval = %key.interval,
+            ranges = missing.len(),
+            fetched,
+            stored = series.candles.len(),
+            "candle_cache_filled"
+        );
+
+        if unsettled.is_empty() {
+            return Ok(series.range(start, end));
+        }
+        let mut result: BTreeMap<i64, Candle> = series
+            .range(start, end)
+            .into_iter()
+            .map(|candle| (candle.time, candle))
+            .collect();
+        for candle in unsettled {
+            if candle.time >= start && candle.time <= end {
+                result.insert(candle.time, candle);
+            }
+        }
+        Ok(result.into_values().collect())
+    }
+}
+
+pub fn missing_ranges(coverage: &[(i64, i64)], start: i64, end: i64) -> Vec<(i64, i64)> {
+    let mut missing = Vec::new();
+    let mut cursor = start;
+    for &(from, to) in coverage {
+        if to < cursor {
+            continue;
+        }
+        if from > end {
+            break;
+        }
+        if from > cursor {
+            missing.push((cursor, from - 1));
+        }
+        cursor = cursor.max(to.saturating_add(1));
+        if cursor > end {
+            return missing;
+        }
+    }
+    if cursor <= end {
+        missing.push((cursor, end));
+    }
+    missing
+}
+
+pub fn merge_ranges(mut ranges: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+    ranges.retain(|(from, to)| from <= to);
+    ranges.sort_unstable();
+    let mut merged: Vec<(i64, i64)> = Vec::with_capacity(ranges.len());
+    for (from, to) in ranges {
+        match merged.last_mut() {
+            Some(last) if from <= last.1.saturating_add(1) => last.1 = last.1.max(to),
+            _ => merged.push((from, to)),
+        }
+    }
+    merged
+}
+
+fn encode(series: &CachedSeries) -> Vec<u8> {
+    let count = series.candles.len();
+    let mut bytes =
+        Vec::with_capacity(HEADER_LEN + series.coverage.len() * RANGE_LEN + count * CANDLE_LEN);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&(series.coverage.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(count as u64).to_le_bytes());
+    for (from, to) in &series.coverage {
+        bytes.extend_from_slice(&from.to_le_bytes());
+        bytes.extend_from_slice(&to.to_le_bytes());
+    }
+    for candle in &series.candles {
+        bytes.extend_from_slice(&candle.time.to_le_bytes());
+    }
+    let columns: [fn(&Candle) -> f64; 5] = [
+        |candle| candle.open,
+        |candle| candle.high,
+        |candle| candle.low,
+        |candle| candle.close,
+        |candle| candle.volume,
+    ];
+    for column in columns {
+        for candle in &series.candles {
+            bytes.extend_from_slice(&column(candle).to_le_bytes());
+        }
+    }
+    bytes
+}
+
+fn decode(bytes: &[u8]) -> Result<CachedSeries> {
+    if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+        return Err(Error::new("candle cache file has an unknown format"));
+    }
+    let range_count = u32::from_le_bytes(read_bytes(bytes, 8)) as usize;
+    let count = u64::from_le_bytes(read_bytes(bytes, 12)) as usize;
+    let expected = range_count
+        .checked_mul(RANGE_LEN)
+        .and_then(|ranges| count.checked_mul(CANDLE_LEN).map(|candles| ranges + candles))
+        .and_then(|body| body.checked_add(HEADER_LEN));
+    if expected != Some(bytes.len()) {
+        return Err(Error::new("candle cache file is truncated"));
+    }
+
+    let mut offset = HEADER_LEN;
+    let mut coverage = Vec::with_capacity(range_count);
+    for _ in 0..range_count {
+        let from = i64::from_le_bytes(read_bytes(bytes, offset));
+        let to = i64::from_le_bytes(read_bytes(bytes, offset + 8));
+        coverage.push((from, to));
+        offset += RANGE_LEN;
+    }
+    let column = |index: usize, row: usize| offset + (index * count + row) * 8;
+    let mut candles = Vec::with_capacity(count);
+    for row in 0..count {
+        let value = |index: usize| f64::from_le_bytes(read_bytes(bytes, column(index, row)));
+        candles.push(Candle {
+            time: i64::from_le_bytes(read_bytes(bytes, column(0, row))),
+            open: value(1),
+            high: value(2),
+            low: value(3),
+            close: value(4),
+            volume: value(5),
+        });
+    }
+    Ok(CachedSeries { coverage, candles })
+}
+
+fn read_bytes<const N: usize>(bytes: &[u8], offset: usize) -> [u8; N] {
+    let mut array = [0u8; N];
+    array.copy_from_slice(&bytes[offset..offset + N]);
+    array
+}
+
+fn path_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| Error::new(format!("candle cache dir create failed: {err}")))?;
+    }
+    let staging = path.with_extension("tmp");
+    fs::write(&staging, content)
+        .map_err(|err| Error::new(format!("candle cache write failed: {err}")))?;
+    fs::rename(&staging, path)
+        .map_err(|err| Error::new(format!("candle cache rename failed: {err}")))
+}
@@ -1,61 +1,256 @@
+use crate::config::CsvConfig;
 use crate::models::Candle;
 use crate::{Error, Result};
-use chrono::DateTime;
-use csv::ReaderBuilder;
-use std::fs::File;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use csv::{ReaderBuilder, StringRecord};
+use flate2::read::MultiGzDecoder;
+use std::fs;
+use std::io::Read;
 
-#[derive(serde::Deserialize)]
-struct CandleRow {
-    time: String,
-    open: f64,
-    high: f64,
-    low: f64,
-    close: f64,
-    volume: f64,
+const NAIVE_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandleExtras {
+    pub time: i64,
+    pub quote_volume: Option<f64>,
+    pub trade_count: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CsvCandles {
+    pub candles: Vec<Candle>,
+    pub extras: Vec<CandleExtras>,
+}
+
+struct Columns {
+    time: usize,
+    open: usize,
+    high: usize,
+    low: usize,
+    close: usize,
+    volume: usize,
+    quote_volume: Option<usize>,
+    trade_count: Option<usize>,
+}
+
+impl Columns {
+    fn locate(headers: &StringRecord, schema: &CsvConfig) -> Result<Self> {
+        let find = |name: &str| -> Result<usize> {
+            headers
+                .iter()
+                .position(|header| header.trim().eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| Error::new(format!("csv column not found: {name}")))
+        };
+        Ok(Self {
+            time: find(&schema.time_column)?,
+            open: find(&schema.open_column)?,
+            high: find(&schema.high_column)?,
+            low: find(&schema.low_column)?,
+            close: find(&schema.close_column)?,
+            volume: find(&schema.volume_column)?,
+            quote_volume: schema.quote_volume_column.as_deref().map(find).transpose()?,
+            trade_count: schema.trade_count_column.as_deref().map(find).transpose()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DataTimeZone {
+    Fixed(FixedOffset),
+    Named(Tz),
+}
+
+pub struct TimeParser {
+    format: String,
+    unit: i64,
+    timezone: DataTimeZone,
+}
+
+impl TimeParser {
+    pub fn new(schema: &CsvConfig) -> Result<Self> {
+        let unit = match schema.time_unit.trim() {
+            "s" => 1,
+            "ms" => 1_000,
+            "us" => 1_000_000,
+            "ns" => 1_000_000_000,
+            _ => return Err(Error::new("data.csv.time_unit must be s, ms, us, or ns")),
+        };
+        Ok(Self {
+            format: schema.time_format.trim().to_string(),
+            unit,
+            timezone: parse_timezone(&schema.timezone)?,
+        })
+    }
+
+    pub fn from_epoch(&self, epoch: i64) -> i64 {
+        epoch.div_euclid(self.unit)
+    }
+
+    pub fn parse(&self, value: &str) -> Result<i64> {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return Err(Error::new("time value is empty"));
+        }
+        match self.format.as_str() {
+            "auto" => {
+                if let Ok(epoch) = trimmed.parse::<i64>() {
+                    return Ok(self.from_epoch(epoch));
+                }
+                if let Ok(parsed) = DateTime::parse_from_rfc3339(trimmed) {
+                    return Ok(parsed.timestamp());
+                }
+                for format in NAIVE_FORMATS {
+                    if let Ok(parsed) = NaiveDateTime::parse_from_str(trimmed, format) {
+                        return self.localize(parsed, trimmed);
+                    }
+                }
+                if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+                    return self.localize(date.and_hms_opt(0, 0, 0).unwrap_or_default(), trimmed);
+                }
+                Err(Error::new(format!("invalid time format: {trimmed}")))
+            }
+            "epoch" => trimmed
+                .parse::<i64>()
+                .map(|epoch| self.from_epoch(epoch))
+                .map_err(|err| Error::new(format!("invalid epoch time {trimmed}: {err}"))),
+            format if format.contains("%z") || format.contains("%:z") => {
+                DateTime::parse_from_str(trimmed, format)
+                    .map(|parsed| parsed.timestamp())
+                    .map_err(|err| Error::new(format!("invalid time {trimmed}: {err}")))
+            }
+            format => {
+                let parsed = NaiveDateTime::parse_from_str(trimmed, format).or_else(|err| {
+                    NaiveDate::parse_from_str(trimmed, format)
+                        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+                        .map_err(|_| Error::new(format!("invalid time {trimmed}: {err}")))
+                })?;
+                self.localize(parsed, trimmed)
+            }
+        }
+    }
+
+    fn localize(&self, parsed: NaiveDateTime, raw: &str) -> Result<i64> {
+        let local = match self.timezone {
+            DataTimeZone::Fixed(offset) => offset
+                .from_local_datetime(&parsed)
+                .earliest()
+                .map(|local| local.timestamp()),
+            DataTimeZone::Named(zone) => zone
+                .from_local_datetime(&parsed)
+                .earliest()
+                .map(|local| local.timestamp()),
+        };
+        local.ok_or_else(|| Error::new(format!("invalid local time: {raw}")))
+    }
+}
+
+pub fn read_csv_bytes(path: &str) -> Result<Vec<u8>> {
+    let bytes = fs::read(path).map_err(|err| Error::new(format!("csv open failed: {err}")))?;
+    if !bytes.starts_with(&[0x1f, 0x8b]) {
+        return Ok(bytes);
+    }
+    let mut decoded = Vec::new();
+    MultiGzDecoder::new(bytes.as_slice())
+        .read_to_end(&mut decoded)
+        .map_err(|err| Error::new(format!("csv gzip failed: {err}")))?;
+    Ok(decoded)
 }
 
 pub fn load_candles_from_csv(path: &str) -> Result<Vec<Candle>> {
-    let file = File::open(path).map_err(|err| Error::new(format!("csv open failed: {err}")))?;
+    Ok(load_csv_with_schema(path, &CsvConfig::default())?.candles)
+}
+
+pub fn load_csv_with_schema(path: &str, schema: &CsvConfig) -> Result<CsvCandles> {
+    let bytes = read_csv_bytes(path)?;
+    let delimiter = match schema.delimiter.as_bytes() {
+        [byte] => *byte,
+        _ => return Err(Error::new("data.csv.delimiter must be a single byte")),
+    };
+    let parser = TimeParser::new(schema)?;
     let mut reader = ReaderBuilder::new()
         .has_headers(true)
-        .from_reader(file);
-
-    let mut rows: Vec<(i64, usize, Candle)> = Vec::new();
-    for (index, result) in reader.deserialize::<CandleRow>().enumerate() {
-        let row = result.map_err(|err| Error::new(format!("csv parse failed: {err}")))?;
-        let time = parse_time(&row.time)?;
-        validate_row(&row)?;
-        rows.push((
+        .delimiter(delimiter)
+        .from_reader(bytes.as_slice());
+    let headers = reader
+        .headers()
+        .map_err(|err| Error::new(format!("csv parse failed: {err}")))?
+        .clone();
+    let columns = Columns::locate(&headers, schema)?;
+
+    let mut rows: Vec<(usize, Candle, CandleExtras)> = Vec::new();
+    for (index, result) in reader.records().enumerate() {
+        let record = result.map_err(|err| Error::new(format!("csv parse failed: {err}")))?;
+        let time = parser.parse(field(&record, columns.time)?)?;
+        let candle = Candle {
+            time,
+            open: number(&record, columns.open)?,
+            high: number(&record, columns.high)?,
+            low: number(&record, columns.low)?,
+            close: number(&record, columns.close)?,
+            volume: number(&record, columns.volume)?,
+        };
+        validate_candle(&candle)?;
+        let extras = CandleExtras {
             time,
-            index,
-            Candle {
-                time,
-                open: row.open,
-                high: row.high,
-                low: row.low,
-                close: row.close,
-                volume: row.volume,
-            },
-        ));
-    }
-
-    rows.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
-
-    let mut deduped: Vec<Candle> = Vec::new();
-    for (_, _, candle) in rows {
-        if let Some(last) = deduped.last_mut() {
+            quote_volume: columns
+                .quote_volume
+                .map(|column| number(&record, column))
+                .transpose()?,
+            trade_count: columns
+                .trade_count
+                .map(|column| count(&record, column))
+                .transpose()?,
+        };
+        rows.push((index, candle, extras));
+    }
+
+    rows.sort_by(|a, b| a.1.time.cmp(&b.1.time).then_with(|| a.0.cmp(&b.0)));
+
+    let mut loaded = CsvCandles::default();
+    for (_, candle, extras) in rows {
+        if let Some(last) = loaded.candles.last_mut() {
             if last.time == candle.time {
                 *last = candle;
+                if let Some(last) = loaded.extras.last_mut() {
+                    *last = extras;
+                }
                 continue;
             }
         }
-        deduped.push(candle);
+        loaded.candles.push(candle);
+        loaded.extras.push(extras);
     }
 
-    Ok(deduped)
+    Ok(loaded)
 }
 
-fn validate_row(row: &CandleRow) -> Result<()> {
+fn field(record: &StringRecord, column: usize) -> Result<&str> {
+    record
+        .get(column)
+        .ok_or_else(|| Error::new(format!("csv parse failed: missing column {column}")))
+}
+
+fn number(record: &StringRecord, column: usize) -> Result<f64> {
+    let value = field(record, column)?.trim();
+    value
+        .parse::<f64>()
+        .map_err(|err| Error::new(format!("csv parse failed: {value}: {err}")))
+}
+
+fn count(record: &StringRecord, column: usize) -> Result<u64> {
+    let value = field(record, column)?.trim();
+    value
+        .parse::<u64>()
+        .map_err(|err| Error::new(format!("csv parse failed: {value}: {err}")))
+}
+
+pub fn validate_candle(row: &Candle) -> Result<()> {
     if row.open <= 0.0 || row.high <= 0.0 || row.low <= 0.0 || row.close <= 0.0 {
         return Err(Error::new("prices must be positive"));
     }
@@ -73,15 +268,42 @@ fn validate_row(row: &CandleRow) -> Result<()> {
     Ok(())
 }
 
-pub fn parse_time(value: &str) -> Result<i64> {
+pub fn parse_timezone(value: &str) -> Result<DataTimeZone> {
     let trimmed = value.trim();
-    if trimmed.is_empty() {
-        return Err(Error::new("time value is empty"));
+    if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("utc") || trimmed == "Z" {
+        return FixedOffset::east_opt(0)
+            .map(DataTimeZone::Fixed)
+            .ok_or_else(|| Error::new("invalid timezone"));
+    }
+    if trimmed.chars().any(|ch| ch.is_ascii_alphabetic()) {
+        return trimmed.parse::<Tz>().map(DataTimeZone::Named).map_err(|_| {
+            Error::new(format!(
+                "data.csv.timezone {trimmed:?} is not an IANA time zone like America/New_York or an offset like +08:00"
+            ))
+        });
+    }
+    let invalid = || {
+        Error::new("data.csv.timezone must be UTC, an IANA time zone, or an offset like +08:00")
+    };
+    let (sign, rest) = match trimmed.as_bytes()[0] {
+        b'+' => (1, &trimmed[1..]),
+        b'-' => (-1, &trimmed[1..]),
+        _ => return Err(invalid()),
+    };
+    let digits: String = rest.chars().filter(|ch| *ch != ':').collect();
+    if digits.len() != 4 || !digits.chars().all(|ch| ch.is_ascii_digit()) {
+        return Err(invalid());
     }
-    if let Ok(epoch) = trimmed.parse::<i64>() {
-        return Ok(epoch);
+    let hours: i32 = digits[..2].parse().map_err(|_| invalid())?;
+    let minutes: i32 = digits[2..].parse().map_err(|_| invalid())?;
+    if hours > 23 || minutes > 59 {
+        return Err(invalid());
     }
-    let parsed = DateTime::parse_from_rfc3339(trimmed)
-        .map_err(|err| Error::new(format!("invalid time format: {err}")))?;
-    Ok(parsed.timestamp())
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        .map(DataTimeZone::Fixed)
+        .ok_or_else(invalid)
+}
+
+pub fn parse_time(value: &str) -> Result<i64> {
+    TimeParser::new(&CsvConfig::default())?.parse(value)
 }
@@ -1,28 +1,23 @@
 use crate::config::Config;
 use crate::data::csv_loader::parse_time;
-use crate::models::Candle;
+use crate::data::market_data::exchange_provider;
+use crate::exchange::rate_limit::{shared_limiter, RateLimiter};
+use crate::models::{Candle, TradePrint};
 use crate::{Error, Result};
 use reqwest::blocking::Client;
 use reqwest::StatusCode;
 use serde_json::Value;
+use std::collections::HashSet;
 use std::time::Duration;
 
+const BINANCE_TRADE_WINDOW_MS: i64 = 3_600_000;
+
 pub struct ParsedCandles {
     pub candles: Vec<Candle>,
     pub last_close_ms: Option<i64>,
 }
 
 pub fn load_candles_from_exchange(config: &Config) -> Result<Vec<Candle>> {
-    let exchange = config.exchange.to_lowercase();
-    match exchange.as_str() {
-        "binance" => load_binance_candles(config),
-        "bybit" => load_bybit_candles(config),
-        "okx" => load_okx_candles(config),
-        _ => Err(Error::new("exchange data source not implemented")),
-    }
-}
-
-fn load_binance_candles(config: &Config) -> Result<Vec<Candle>> {
     let start = parse_time(
         config
             .backtest
@@ -42,12 +37,101 @@ fn load_binance_candles(config: &Config) -> Result<Vec<Candle>> {
         return Err(Error::new("backtest.start_time must be <= end_time"));
     }
 
+    exchange_provider(config).load_candles(&config.symbol, &config.data.candle_interval, start, end)
+}
+
+pub fn fetch_exchange_candles(
+    config: &Config,
+    exchange: &str,
+    start: i64,
+    end: i64,
+) -> Result<Vec<Candle>> {
+    match exchange {
+        "binance" => load_binance_candles(config, &BINANCE_SPOT_KLINES, start, end),
+        "binance-futures" => load_binance_candles(config, &BINANCE_FUTURES_KLINES, start, end),
+        "bybit" => load_bybit_candles(config, start, end),
+        "okx" => load_okx_candles(config, start, end),
+        "kraken" => load_kraken_candles(config, start, end),
+        "coinbase" => load_coinbase_candles(config, start, end),
+        _ => Err(Error::new("exchange data source not implemented")),
+    }
+}
+
+pub struct ExchangeTrade {
+    pub id: String,
+    pub print: TradePrint,
+}
+
+pub fn fetch_exchange_trades(
+    config: &Config,
+    exchange: &str,
+    start_ms: i64,
+    end_ms: i64,
+) -> Result<Vec<TradePrint>> {
+    let mut prints = match exchange {
+        "binance" => load_binance_trades(config, &BINANCE_SPOT_AGG_TRADES, start_ms, end_ms)?,
+        "binance-futures" => {
+            load_binance_trades(config, &BINANCE_FUTURES_AGG_TRADES, start_ms, end_ms)?
+        }
+        "bybit" => load_bybit_trades(config, start_ms, end_ms)?,
+        "okx" => load_okx_trades(config, start_ms, end_ms)?,
+        _ => return Err(Error::new("exchange trade download not implemented")),
+    };
+    prints.sort_by_key(|print| print.time_ms);
+    Ok(prints)
+}
+
+struct BinanceSource {
+    exchange: &'static str,
+    default_base_url: &'static str,
+    path: &'static str,
+    max_limit: u32,
+}
+
+const BINANCE_SPOT_KLINES: BinanceSource = BinanceSource {
+    exchange: "binance",
+    default_base_url: "https://api.binance.com",
+    path: "/api/v3/klines",
+    max_limit: 1000,
+};
+
+const BINANCE_FUTURES_KLINES: BinanceSource = BinanceSource {
+    exchange: "binance-futures",
+    default_base_url: "https://fapi.binance.com",
+    path: "/fapi/v1/klines",
+    max_limit: 1500,
+};
+
+const BINANCE_SPOT_AGG_TRADES: BinanceSource = BinanceSource {
+    exchange: "binance",
+    default_base_url: "https://api.binance.com",
+    path: "/api/v3/aggTrades",
+    max_limit: 1000,
+};
+
+const BINANCE_FUTURES_AGG_TRADES: BinanceSource = BinanceSource {
+    exchange: "binance-futures",
+    default_base_url: "https://fapi.binance.com",
+    path: "/fapi/v1/aggTrades",
+    max_limit: 1000,
+};
+
+fn load_binance_candles(
+    config: &Config,
+    source: &BinanceSource,
+    start: i64,
+    end: i64,
+) -> Result<Vec<Candle>> {
     let base_url = config
         .data
         .exchange_base_url
         .as_deref()
-        .unwrap_or("https://api.binance.com");
-    let limit = config.data.exchange_limit.unwrap_or(1000).min(1000);
+        .unwrap_or(source.default_base_url);
+    let limit = config
+        .data
+        .exchange_limit
+        .unwrap_or(1000)
+        .min(source.max_limit);
     if limit == 0 {
         return Err(Error::new("data.exchange_limit must be positive"));
     }
@@ -56,13 +140,13 @@ fn load_binance_candles(config: &Config) -> Result<Vec<Candle>> {
         .timeout(Duration::from_secs(30))
         .build()
         .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+    let limiter = shared_limiter(source.exchange)?;
 
     let mut cursor_ms = start * 1000;
     let end_ms = end * 1000;
     let mut all: Vec<Candle> = Vec::new();
 
     loop {
-        let url = format!("{base_url}/api/v3/klines");
         let query = vec![
             ("symbol".to_string(), config.symbol.clone()),
             ("interval".to_string(), config.data.candle_interval.clone()),
@@ -70,7 +154,8 @@ fn load_binance_candles(config: &Config) -> Result<Vec<Candle>> {
             ("endTime".to_string(), end_ms.to_string()),
             ("limit".to_string(), limit.to_string()),
         ];
-        let text = fetch_text_with_retry(&client, &url, &query)?;
+        let text =
+            fetch_text_with_retry(&client, &limiter, base_url, source.path, &query)?;
         let parsed = parse_binance_klines(&text)?;
         if parsed.candles.is_empty() {
             break;
@@ -96,31 +181,86 @@ fn load_binance_candles(config: &Config) -> Result<Vec<Candle>> {
     Ok(all)
 }
 
+fn load_binance_trades(
+    config: &Config,
+    source: &BinanceSource,
+    start_ms: i64,
+    end_ms: i64,
+) -> Result<Vec<TradePrint>> {
+    let base_url = config
+        .data
+        .exchange_base_url
+        .as_deref()
+        .unwrap_or(source.default_base_url);
+    let limit = config
+        .data
+        .exchange_limit
+        .unwrap_or(1000)
+        .min(source.max_limit);
+    if limit == 0 {
+        return Err(Error::new("data.exchange_limit must be positive"));
+    }
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+    let limiter = shared_limiter(source.exchange)?;
+
+    let mut window_start = start_ms;
+    let mut from_id: Option<i64> = None;
+    let mut all: Vec<TradePrint> = Vec::new();
+
+    while window_start <= end_ms {
+        let mut query = vec![
+            ("symbol".to_string(), config.symbol.clone()),
+            ("limit".to_string(), limit.to_string()),
+        ];
+        match from_id {
+            Some(id) => query.push(("fromId".to_string(), id.to_string())),
+            None => {
+                let window_end = (window_start + BINANCE_TRADE_WINDOW_MS - 1).min(end_ms);
+                query.push(("startTime".to_string(), window_start.to_string()));
+                query.push(("endTime".to_string(), window_end.to_string()));
+            }
+        }
+        let text = fetch_text_with_retry(&client, &limiter, base_url, source.path, &query)?;
+        let batch = parse_binance_agg_trades(&text)?;
+        let Some(last) = batch.last() else {
+            if from_id.is_some() {
+                break;
+            }
+            window_start += BINANCE_TRADE_WINDOW_MS;
+            continue;
+        };
+        let last_time = last.print.time_ms;
+        let last_id = last
+            .id
+            .parse::<i64>()
+            .map_err(|err| Error::new(format!("invalid binance trade id: {err}")))?;
+        let full = batch.len() >= limit as usize;
+        all.extend(
+            batch
+                .into_iter()
+                .map(|trade| trade.print)
+                .filter(|print| print.time_ms >= start_ms && print.time_ms <= end_ms),
+        );
+
+        if last_time >= end_ms || (from_id.is_some() && !full) {
+            break;
+        }
+        from_id = Some(last_id + 1);
+    }
+
+    Ok(all)
+}
+
 pub struct BybitParsed {
     pub candles: Vec<Candle>,
     pub oldest_start_ms: Option<i64>,
 }
 
-fn load_bybit_candles(config: &Config) -> Result<Vec<Candle>> {
-    let start = parse_time(
-        config
-            .backtest
-            .start_time
-            .as_ref()
-            .ok_or_else(|| Error::new("backtest.start_time must be set"))?,
-    )?;
-    let end = parse_time(
-        config
-            .backtest
-            .end_time
-            .as_ref()
-            .ok_or_else(|| Error::new("backtest.end_time must be set"))?,
-    )?;
-
-    if start > end {
-        return Err(Error::new("backtest.start_time must be <= end_time"));
-    }
-
+fn load_bybit_candles(config: &Config, start: i64, end: i64) -> Result<Vec<Candle>> {
     let base_url = config
         .data
         .exchange_base_url
@@ -142,13 +282,13 @@ fn load_bybit_candles(config: &Config) -> Result<Vec<Candle>> {
         .timeout(Duration::from_secs(30))
         .build()
         .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+    let limiter = shared_limiter("bybit")?;
 
     let mut end_ms = end * 1000;
     let start_ms = start * 1000;
     let mut all: Vec<Candle> = Vec::new();
 
     loop {
-        let url = format!("{base_url}/v5/market/kline");
         let query = vec![
             ("category".to_string(), category.to_string()),
             ("symbol".to_string(), config.symbol.clone()),
@@ -157,7 +297,8 @@ fn load_bybit_candles(config: &Config) -> Result<Vec<Candle>> {
             ("end".to_string(), end_ms.to_string()),
             ("limit".to_string(), limit.to_string()),
         ];
-        let text = fetch_text_with_retry(&client, &url, &query)?;
+        let text =
+            fetch_text_with_retry(&client, &limiter, base_url, "/v5/market/kline", &query)?;
         let parsed = parse_bybit_klines(&text)?;
         if parsed.candles.is_empty() {
             break;
@@ -183,31 +324,66 @@ fn load_bybit_candles(config: &Config) -> Result<Vec<Candle>> {
     Ok(all)
 }
 
+fn load_bybit_trades(config: &Config, start_ms: i64, end_ms: i64) -> Result<Vec<TradePrint>> {
+    let base_url = config
+        .data
+        .exchange_base_url
+        .as_deref()
+        .unwrap_or("https://api.bybit.com");
+    let category = config
+        .data
+        .exchange_category
+        .as_deref()
+        .unwrap_or("spot");
+    let max_limit = if category == "spot" { 60 } else { 1000 };
+    let limit = config.data.exchange_limit.unwrap_or(1000).min(max_limit);
+    if limit == 0 {
+        return Err(Error::new("data.exchange_limit must be positive"));
+    }
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+    let limiter = shared_limiter("bybit")?;
+
+    let query = vec![
+        ("category".to_string(), category.to_string()),
+        ("symbol".to_string(), config.symbol.clone()),
+        ("limit".to_string(), limit.to_string()),
+    ];
+    let text = fetch_text_with_retry(
+        &client,
+        &limiter,
+        base_url,
+        "/v5/market/recent-trade",
+        &query,
+    )?;
+    let batch = parse_bybit_trades(&text)?;
+    let covered = batch
+        .iter()
+        .map(|trade| trade.print.time_ms)
+        .min()
+        .is_some_and(|oldest| oldest <= start_ms);
+    if !covered {
+        return Err(Error::new(
+            "bybit only serves recent trades; download history into data.trades_path",
+        ));
+    }
+
+    Ok(batch
+        .into_iter()
+        .map(|trade| trade.print)
+        .filter(|print| print.time_ms >= start_ms && print.time_ms <= end_ms)
+        .collect())
+}
+
 pub struct OkxParsed {
     pub candles: Vec<Candle>,
     pub oldest_start_ms: Option<i64>,
 }
 
-fn load_okx_candles(config: &Config) -> Result<Vec<Candle>> {
-    let start = parse_time(
-        config
-            .backtest
-            .start_time
-            .as_ref()
-            .ok_or_else(|| Error::new("backtest.start_time must be set"))?,
-    )?;
-    let end = parse_time(
-        config
-            .backtest
-            .end_time
-            .as_ref()
-            .ok_or_else(|| Error::new("backtest.end_time must be set"))?,
-    )?;
-
-    if start > end {
-        return Err(Error::new("backtest.start_time must be <= end_time"));
-    }
-
+fn load_okx_candles(config: &Config, start: i64, end: i64) -> Result<Vec<Candle>> {
     let base_url = config
         .data
         .exchange_base_url
@@ -224,13 +400,13 @@ fn load_okx_candles(config: &Config) -> Result<Vec<Candle>> {
         .timeout(Duration::from_secs(30))
         .build()
         .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+    let limiter = shared_limiter("okx")?;
 
     let mut end_ms = end * 1000;
     let start_ms = start * 1000;
     let mut all: Vec<Candle> = Vec::new();
 
     loop {
-        let url = format!("{base_url}/api/v5/market/candles");
         let query = vec![
             ("instId".to_string(), config.symbol.clone()),
             ("bar".to_string(), bar.clone()),
@@ -238,7 +414,8 @@ fn load_okx_candles(config: &Config) -> Result<Vec<Candle>> {
             ("before".to_string(), start_ms.to_string()),
             ("limit".to_string(), limit.to_string()),
         ];
-        let text = fetch_text_with_retry(&client, &url, &query)?;
+        let text =
+            fetch_text_with_retry(&client, &limiter, base_url, "/api/v5/market/candles", &query)?;
         let parsed = parse_okx_candles(&text)?;
         if parsed.candles.is_empty() {
             break;
@@ -263,6 +440,163 @@ fn load_okx_candles(config: &Config) -> Result<Vec<Candle>> {
     Ok(all)
 }
 
+fn load_okx_trades(config: &Config, start_ms: i64, end_ms: i64) -> Result<Vec<TradePrint>> {
+    let base_url = config
+        .data
+        .exchange_base_url
+        .as_deref()
+        .unwrap_or("https://www.okx.com");
+    let limit = config.data.exchange_limit.unwrap_or(100).min(100);
+    if limit == 0 {
+        return Err(Error::new("data.exchange_limit must be positive"));
+    }
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+    let limiter = shared_limiter("okx")?;
+
+    let mut after = end_ms + 1;
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut all: Vec<TradePrint> = Vec::new();
+
+    loop {
+        let query = vec![
+            ("instId".to_string(), config.symbol.clone()),
+            ("type".to_string(), "2".to_string()),
+            ("after".to_string(), after.to_string()),
+            ("limit".to_string(), limit.to_string()),
+        ];
+        let text = fetch_text_with_retry(
+            &client,
+            &limiter,
+            base_url,
+            "/api/v5/market/history-trades",
+            &query,
+        )?;
+        let batch = parse_okx_trades(&text)?;
+        let Some(oldest) = batch.iter().map(|trade| trade.print.time_ms).min() else {
+            break;
+        };
+        let full = batch.len() >= limit as usize;
+        for trade in batch {
+            let print = &trade.print;
+            if print.time_ms >= start_ms && print.time_ms <= end_ms && seen.insert(trade.id) {
+                all.push(trade.print);
+            }
+        }
+
+        if oldest < start_ms || !full {
+            break;
+        }
+        after = if oldest + 1 < after { oldest + 1 } else { oldest };
+    }
+
+    Ok(all)
+}
+
+pub struct KrakenParsed {
+    pub candles: Vec<Candle>,
+    pub last: Option<i64>,
+}
+
+fn load_kraken_candles(config: &Config, start: i64, end: i64) -> Result<Vec<Candle>> {
+    let base_url = config
+        .data
+        .exchange_base_url
+        .as_deref()
+        .unwrap_or("https://api.kraken.com");
+    let interval = map_kraken_interval(&config.data.candle_interval)?;
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+    let limiter = shared_limiter("kraken")?;
+
+    let mut since = start - 1;
+    let mut all: Vec<Candle> = Vec::new();
+
+    loop {
+        let query = vec![
+            ("pair".to_string(), config.symbol.clone()),
+            ("interval".to_string(), interval.clone()),
+            ("since".to_string(), since.to_string()),
+        ];
+        let text = fetch_text_with_retry(&client, &limiter, base_url, "/0/public/OHLC", &query)?;
+        let parsed = parse_kraken_ohlc(&text)?;
+        if parsed.candles.is_empty() {
+            break;
+        }
+        all.extend(
+            parsed
+                .candles
+                .into_iter()
+                .filter(|candle| candle.time >= start && candle.time <= end),
+        );
+
+        let Some(last) = parsed.last else {
+            break;
+        };
+        if last >= end || last <= since {
+            break;
+        }
+        since = last;
+    }
+
+    all.sort_by_key(|candle| candle.time);
+    all.dedup_by_key(|candle| candle.time);
+    Ok(all)
+}
+
+pub struct CoinbaseParsed {
+    pub candles: Vec<Candle>,
+    pub oldest_start_ms: Option<i64>,
+}
+
+fn load_coinbase_candles(config: &Config, start: i64, end: i64) -> Result<Vec<Candle>> {
+    let base_url = config
+        .data
+        .exchange_base_url
+        .as_deref()
+        .unwrap_or("https://api.coinbase.com");
+    let limit = config.data.exchange_limit.unwrap_or(350).min(350);
+    if limit == 0 {
+        return Err(Error::new("data.exchange_limit must be positive"));
+    }
+
+    let (granularity, step_secs) = coinbase_granularity(&config.data.candle_interval)?;
+    let path = format!("/api/v3/brokerage/market/products/{}/candles", config.symbol);
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+    let limiter = shared_limiter("coinbase")?;
+
+    let mut cursor = start;
+    let mut all: Vec<Candle> = Vec::new();
+
+    while cursor <= end {
+        let window_end = (cursor + step_secs * (limit as i64 - 1)).min(end);
+        let query = vec![
+            ("start".to_string(), cursor.to_string()),
+            ("end".to_string(), window_end.to_string()),
+            ("granularity".to_string(), granularity.to_string()),
+            ("limit".to_string(), limit.to_string()),
+        ];
+        let text = fetch_text_with_retry(&client, &limiter, base_url, &path, &query)?;
+        let parsed = parse_coinbase_candles(&text)?;
+        all.extend(parsed.candles);
+        cursor = window_end + 1;
+    }
+
+    all.sort_by_key(|candle| candle.time);
+    all.dedup_by_key(|candle| candle.time);
+    Ok(all)
+}
+
 pub fn parse_binance_klines(payload: &str) -> Result<ParsedCandles> {
     let data: Vec<Vec<Value>> = serde_json::from_str(payload)
         .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
@@ -422,6 +756,192 @@ pub fn parse_okx_candles(payload: &str) -> Result<OkxParsed> {
     })
 }
 
+pub fn parse_kraken_ohlc(payload: &str) -> Result<KrakenParsed> {
+    let data: Value = serde_json::from_str(payload)
+        .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+    if let Some(errors) = data.get("error").and_then(|value| value.as_array()) {
+        if let Some(message) = errors.first().and_then(|value| value.as_str()) {
+            return Err(Error::new(format!("kraken error: {message}")));
+        }
+    }
+
+    let result = data
+        .get("result")
+        .and_then(|value| value.as_object())
+        .ok_or_else(|| Error::new("kraken result missing"))?;
+    let last = result.get("last").map(value_to_i64).transpose()?;
+    let list = result
+        .iter()
+        .find(|(key, _)| key.as_str() != "last")
+        .and_then(|(_, value)| value.as_array());
+    let Some(list) = list else {
+        return Ok(KrakenParsed {
+            candles: Vec::new(),
+            last,
+        });
+    };
+
+    let mut candles = Vec::with_capacity(list.len());
+    for row in list {
+        let row = row
+            .as_array()
+            .ok_or_else(|| Error::new("kraken ohlc row is not array"))?;
+        if row.len() < 7 {
+            return Err(Error::new("kraken ohlc row has insufficient fields"));
+        }
+        let time = value_to_i64(&row[0])?;
+        let open = value_to_f64(&row[1])?;
+        let high = value_to_f64(&row[2])?;
+        let low = value_to_f64(&row[3])?;
+        let close = value_to_f64(&row[4])?;
+        let volume = value_to_f64(&row[6])?;
+        candles.push(Candle {
+            time,
+            open,
+            high,
+            low,
+            close,
+            volume,
+        });
+    }
+
+    Ok(KrakenParsed { candles, last })
+}
+
+pub fn parse_coinbase_candles(payload: &str) -> Result<CoinbaseParsed> {
+    let data: Value = serde_json::from_str(payload)
+        .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+    let list = data
+        .get("candles")
+        .and_then(|value| value.as_array())
+        .ok_or_else(|| Error::new("coinbase candles missing"))?;
+
+    if list.is_empty() {
+        return Ok(CoinbaseParsed {
+            candles: Vec::new(),
+            oldest_start_ms: None,
+        });
+    }
+
+    let mut candles = Vec::with_capacity(list.len());
+    let mut oldest_start_ms = None;
+
+    for row in list {
+        let field = |key: &str| {
+            row.get(key)
+                .ok_or_else(|| Error::new(format!("coinbase candle {key} missing")))
+        };
+        let start = value_to_i64(field("start")?)?;
+        let open = value_to_f64(field("open")?)?;
+        let high = value_to_f64(field("high")?)?;
+        let low = value_to_f64(field("low")?)?;
+        let close = value_to_f64(field("close")?)?;
+        let volume = value_to_f64(field("volume")?)?;
+        oldest_start_ms = Some(start * 1000);
+        candles.push(Candle {
+            time: start,
+            open,
+            high,
+            low,
+            close,
+            volume,
+        });
+    }
+
+    Ok(CoinbaseParsed {
+        candles,
+        oldest_start_ms,
+    })
+}
+
+pub fn parse_binance_agg_trades(payload: &str) -> Result<Vec<ExchangeTrade>> {
+    let data: Vec<Value> = serde_json::from_str(payload)
+        .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+    data.iter()
+        .map(|row| {
+            let field = |key: &str| {
+                row.get(key)
+                    .ok_or_else(|| Error::new(format!("binance aggTrade {key} missing")))
+            };
+            Ok(ExchangeTrade {
+                id: value_to_i64(field("a")?)?.to_string(),
+                print: TradePrint {
+                    time_ms: value_to_i64(field("T")?)?,
+                    price: value_to_f64(field("p")?)?,
+                    quantity: value_to_f64(field("q")?)?,
+                },
+            })
+        })
+        .collect()
+}
+
+pub fn parse_bybit_trades(payload: &str) -> Result<Vec<ExchangeTrade>> {
+    let data: Value = serde_json::from_str(payload)
+        .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+    let ret_code = data
+        .get("retCode")
+        .and_then(|value| value.as_i64())
+        .unwrap_or(-1);
+    if ret_code != 0 {
+        return Err(Error::new("bybit retCode is not 0"));
+    }
+
+    let list = data
+        .get("result")
+        .and_then(|value| value.get("list"))
+        .and_then(|value| value.as_array())
+        .ok_or_else(|| Error::new("bybit result.list missing"))?;
+    list.iter()
+        .map(|row| {
+            let field = |key: &str| {
+                row.get(key)
+                    .ok_or_else(|| Error::new(format!("bybit trade {key} missing")))
+            };
+            Ok(ExchangeTrade {
+                id: field("execId")?.as_str().unwrap_or_default().to_string(),
+                print: TradePrint {
+                    time_ms: value_to_i64(field("time")?)?,
+                    price: value_to_f64(field("price")?)?,
+                    quantity: value_to_f64(field("size")?)?,
+                },
+            })
+        })
+        .collect()
+}
+
+pub fn parse_okx_trades(payload: &str) -> Result<Vec<ExchangeTrade>> {
+    let data: Value = serde_json::from_str(payload)
+        .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+    let code = data
+        .get("code")
+        .and_then(|value| value.as_str())
+        .unwrap_or("1");
+    if code != "0" {
+        return Err(Error::new("okx code is not 0"));
+    }
+
+    let list = data
+        .get("data")
+        .and_then(|value| value.as_array())
+        .ok_or_else(|| Error::new("okx data missing"))?;
+    list.iter()
+        .map(|row| {
+            let field = |key: &str| {
+                row.get(key)
+                    .ok_or_else(|| Error::new(format!("okx trade {key} missing")))
+            };
+            Ok(ExchangeTrade {
+                id: field("tradeId")?.as_str().unwrap_or_default().to_string(),
+                print: TradePrint {
+                    time_ms: value_to_i64(field("ts")?)?,
+                    price: value_to_f64(field("px")?)?,
+                    quantity: value_to_f64(field("sz")?)?,
+                },
+            })
+        })
+        .collect()
+}
+
 pub fn map_okx_interval(interval: &str) -> Result<String> {
     let trimmed = interval.trim();
     if trimmed.is_empty() {
@@ -483,20 +1003,104 @@ pub fn map_bybit_interval(interval: &str) -> Result<String> {
     }
 }
 
+pub fn parse_interval_seconds(interval: &str) -> Result<u64> {
+    let trimmed = interval.trim();
+    match trimmed {
+        "1" => return Ok(60),
+        "3" => return Ok(180),
+        "5" => return Ok(300),
+        "15" => return Ok(900),
+        "30" => return Ok(1800),
+        "60" => return Ok(3600),
+        "120" => return Ok(7200),
+        "240" => return Ok(14400),
+        "360" => return Ok(21600),
+        "720" => return Ok(43200),
+        "D" | "d" => return Ok(86400),
+        "W" | "w" => return Ok(604800),
+        "M" | "1M" => return Ok(2592000),
+        _ => {}
+    }
+
+    match trimmed.to_lowercase().as_str() {
+        "1m" => Ok(60),
+        "3m" => Ok(180),
+        "5m" => Ok(300),
+        "15m" => Ok(900),
+        "30m" => Ok(1800),
+        "1h" => Ok(3600),
+        "2h" => Ok(7200),
+        "4h" => Ok(14400),
+        "6h" => Ok(21600),
+        "12h" => Ok(43200),
+        "1d" => Ok(86400),
+        "1w" => Ok(604800),
+        _ => Err(Error::new("unsupported candle interval")),
+    }
+}
+
+pub fn map_kraken_interval(interval: &str) -> Result<String> {
+    let trimmed = interval.trim();
+    if trimmed.is_empty() {
+        return Err(Error::new("kraken interval must be non-empty"));
+    }
+
+    match trimmed.to_lowercase().as_str() {
+        "1m" => Ok("1".to_string()),
+        "5m" => Ok("5".to_string()),
+        "15m" => Ok("15".to_string()),
+        "30m" => Ok("30".to_string()),
+        "1h" => Ok("60".to_string()),
+        "4h" => Ok("240".to_string()),
+        "1d" => Ok("1440".to_string()),
+        "1w" => Ok("10080".to_string()),
+        _ => Err(Error::new("unsupported kraken interval")),
+    }
+}
+
+pub fn map_coinbase_granularity(interval: &str) -> Result<String> {
+    coinbase_granularity(interval).map(|(granularity, _)| granularity.to_string())
+}
+
+fn coinbase_granularity(interval: &str) -> Result<(&'static str, i64)> {
+    let trimmed = interval.trim();
+    if trimmed.is_empty() {
+        return Err(Error::new("coinbase interval must be non-empty"));
+    }
+
+    match trimmed.to_lowercase().as_str() {
+        "1m" => Ok(("ONE_MINUTE", 60)),
+        "5m" => Ok(("FIVE_MINUTE", 300)),
+        "15m" => Ok(("FIFTEEN_MINUTE", 900)),
+        "30m" => Ok(("THIRTY_MINUTE", 1800)),
+        "1h" => Ok(("ONE_HOUR", 3600)),
+        "2h" => Ok(("TWO_HOUR", 7200)),
+        "4h" => Ok(("FOUR_HOUR", 14400)),
+        "6h" => Ok(("SIX_HOUR", 21600)),
+        "1d" => Ok(("ONE_DAY", 86400)),
+        _ => Err(Error::new("unsupported coinbase interval")),
+    }
+}
+
 fn fetch_text_with_retry(
     client: &Client,
-    url: &str,
+    limiter: &RateLimiter,
+    base_url: &str,
+    path: &str,
     query: &[(String, String)],
 ) -> Result<String> {
     const MAX_RETRIES: u32 = 3;
     const BASE_DELAY_MS: u64 = 500;
 
+    let url = format!("{base_url}{path}");
     let mut attempt = 0;
     loop {
-        let response = client.get(url).query(query).send();
+        limiter.acquire(path, query)?;
+        let response = client.get(&url).query(query).send();
         match response {
             Ok(response) => {
                 let status = response.status();
+                limiter.observe(path, status, response.headers())?;
                 if status.is_success() {
                     return response
                         .text()
@@ -0,0 +1,216 @@
+use crate::data::csv_loader::load_candles_from_csv;
+use crate::models::Candle;
+use crate::{Error, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_CHUNK_CANDLES: i64 = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchJob {
+    pub exchange: String,
+    pub symbol: String,
+    pub interval: String,
+    pub interval_secs: i64,
+    pub start: i64,
+    pub end: i64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchProgress {
+    pub cursor: i64,
+    pub start: i64,
+    pub end: i64,
+    pub written: usize,
+}
+
+impl FetchProgress {
+    pub fn percent(&self) -> f64 {
+        if self.end <= self.start {
+            return 100.0;
+        }
+        let done = (self.cursor.min(self.end) - self.start) as f64;
+        (done / (self.end - self.start) as f64 * 100.0).clamp(0.0, 100.0)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FetchSummary {
+    pub resumed_from: Option<i64>,
+    pub chunks: usize,
+    pub written: usize,
+}
+
+pub trait CandleSink {
+    fn last_time(&mut self, job: &FetchJob) -> Result<Option<i64>>;
+    fn stored_times(&mut self, job: &FetchJob) -> Result<Vec<i64>>;
+    fn append(&mut self, job: &FetchJob, candles: &[Candle]) -> Result<usize>;
+}
+
+pub fn run_fetch_job<S, F, P>(
+    job: &FetchJob,
+    settled_until: i64,
+    sink: &mut S,
+    mut fetch: F,
+    mut progress: P,
+) -> Result<FetchSummary>
+where
+    S: CandleSink + ?Sized,
+    F: FnMut(i64, i64) -> Result<Vec<Candle>>,
+    P: FnMut(&FetchProgress),
+{
+    if job.interval_secs <= 0 {
+        return Err(Error::new("fetch interval must be positive"));
+    }
+    if job.start > job.end {
+        return Err(Error::new("fetch start must be <= end"));
+    }
+    let mut summary = FetchSummary::default();
+    let stored = sink.stored_times(job)?;
+    summary.resumed_from = stored.last().copied();
+    let end = job.end.min(settled_until);
+    let chunk_secs = job.interval_secs * DEFAULT_CHUNK_CANDLES;
+
+    for (from, span_end) in missing_spans(job, &stored) {
+        let mut cursor = from;
+        let span_end = span_end.min(end);
+        while cursor <= span_end {
+            let to = (cursor + chunk_secs - 1).min(span_end);
+            let candles: Vec<Candle> = fetch(cursor, to)?
+                .into_iter()
+                .filter(|candle| candle.time >= cursor && candle.time <= to)
+                .collect();
+            summary.written += sink.append(job, &candles)?;
+            summary.chunks += 1;
+            cursor = to + 1;
+            progress(&FetchProgress {
+                cursor,
+                start: job.start,
+                end,
+                written: summary.written,
+            });
+        }
+    }
+    Ok(summary)
+}
+
+pub fn missing_spans(job: &FetchJob, stored: &[i64]) -> Vec<(i64, i64)> {
+    let mut spans = Vec::new();
+    let mut expected = job.start;
+    for &time in stored.iter().filter(|time| **time >= job.start && **time <= job.end) {
+        if time > expected {
+            spans.push((expected, time - 1));
+        }
+        expected = expected.max(time + job.interval_secs);
+    }
+    if expected <= job.end {
+        spans.push((expected, job.end));
+    }
+    spans
+}
+
+pub struct CsvSink {
+    dir: PathBuf,
+}
+
+impl CsvSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn path(&self, job: &FetchJob) -> PathBuf {
+        self.dir.join(format!(
+            "{}_{}_{}.csv",
+            path_component(&job.exchange.to_lowercase()),
+            path_component(&job.symbol),
+            path_component(&job.interval)
+        ))
+    }
+}
+
+impl CandleSink for CsvSink {
+    fn last_time(&mut self, job: &FetchJob) -> Result<Option<i64>> {
+        let path = self.path(job);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let candles = load_candles_from_csv(&path.to_string_lossy())?;
+        Ok(candles.last().map(|candle| candle.time))
+    }
+
+    fn stored_times(&mut self, job: &FetchJob) -> Result<Vec<i64>> {
+        let path = self.path(job);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let candles = load_candles_from_csv(&path.to_string_lossy())?;
+        Ok(candles
+            .iter()
+            .map(|candle| candle.time)
+            .filter(|time| *time >= job.start && *time <= job.end)
+            .collect())
+    }
+
+    fn append(&mut self, job: &FetchJob, candles: &[Candle]) -> Result<usize> {
+        let path = self.path(job);
+        fs::create_dir_all(&self.dir)
+            .map_err(|err| Error::new(format!("fetch dir create failed: {err}")))?;
+        let first = match candles.first() {
+            Some(candle) => candle.time,
+            None => return Ok(0),
+        };
+        if self.last_time(job)?.is_some_and(|last| first <= last) {
+            let mut merged = load_candles_from_csv(&path.to_string_lossy())?;
+            merged.retain(|stored| {
+                candles
+                    .binary_search_by_key(&stored.time, |candle| candle.time)
+                    .is_err()
+            });
+            merged.extend_from_slice(candles);
+            merged.sort_by_key(|candle| candle.time);
+            let tmp = path.with_extension("csv.tmp");
+            let _ = fs::remove_file(&tmp);
+            write_rows(&tmp, &merged)?;
+            fs::rename(&tmp, &path)
+                .map_err(|err| Error::new(format!("fetch csv rename failed: {err}")))?;
+            return Ok(candles.len());
+        }
+        write_rows(&path, candles)?;
+        Ok(candles.len())
+    }
+}
+
+fn write_rows(path: &Path, candles: &[Candle]) -> Result<()> {
+    let mut content = String::new();
+    if !path.exists() {
+        content.push_str("time,open,high,low,close,volume\n");
+    }
+    for candle in candles {
+        content.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            candle.time, candle.open, candle.high, candle.low, candle.close, candle.volume
+        ));
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| Error::new(format!("fetch csv open failed: {err}")))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|err| Error::new(format!("fetch csv write failed: {err}")))
+}
+
+fn path_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
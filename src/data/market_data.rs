@@ -1,6 +1,269 @@
+use crate::config::{Config, CsvConfig};
+use crate::data::candle_cache::{CacheKey, CandleCache};
+use crate::data::csv_loader::load_csv_with_schema;
+use crate::data::exchange_loader::{fetch_exchange_candles, parse_interval_seconds};
+use crate::data::parquet_loader::load_candles_from_parquet;
 use crate::models::Candle;
-use crate::Result;
+use crate::storage::postgres::PostgresStorage;
+use crate::{Error, Result};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub trait MarketDataProvider {
-    fn load_candles(&self, symbol: &str, interval: &str) -> Result<Vec<Candle>>;
+    fn load_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Candle>>;
+
+    fn load_recent(
+        &self,
+        symbol: &str,
+        interval: &str,
+        count: usize,
+        now: i64,
+    ) -> Result<Vec<Candle>> {
+        let interval_secs = parse_interval_seconds(interval)? as i64;
+        let start = now.saturating_sub(interval_secs.saturating_mul(count as i64));
+        let mut candles = self.load_candles(symbol, interval, start, now)?;
+        if candles.len() > count {
+            candles.drain(..candles.len() - count);
+        }
+        Ok(candles)
+    }
+}
+
+pub struct CsvProvider {
+    path: String,
+    schema: CsvConfig,
+}
+
+impl CsvProvider {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self::with_schema(path, CsvConfig::default())
+    }
+
+    pub fn with_schema(path: impl Into<String>, schema: CsvConfig) -> Self {
+        Self {
+            path: path.into(),
+            schema,
+        }
+    }
+}
+
+impl MarketDataProvider for CsvProvider {
+    fn load_candles(
+        &self,
+        _symbol: &str,
+        _interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Candle>> {
+        Ok(load_csv_with_schema(&self.path, &self.schema)?
+            .candles
+            .into_iter()
+            .filter(|candle| candle.time >= start && candle.time <= end)
+            .collect())
+    }
+
+    fn load_recent(
+        &self,
+        _symbol: &str,
+        _interval: &str,
+        count: usize,
+        _now: i64,
+    ) -> Result<Vec<Candle>> {
+        let mut candles = load_csv_with_schema(&self.path, &self.schema)?.candles;
+        if candles.len() > count {
+            candles.drain(..candles.len() - count);
+        }
+        Ok(candles)
+    }
+}
+
+pub struct ParquetProvider {
+    path: String,
+    schema: CsvConfig,
+}
+
+impl ParquetProvider {
+    pub fn new(path: impl Into<String>, schema: CsvConfig) -> Self {
+        Self {
+            path: path.into(),
+            schema,
+        }
+    }
+}
+
+impl MarketDataProvider for ParquetProvider {
+    fn load_candles(
+        &self,
+        _symbol: &str,
+        _interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Candle>> {
+        Ok(load_candles_from_parquet(&self.path, &self.schema)?
+            .into_iter()
+            .filter(|candle| candle.time >= start && candle.time <= end)
+            .collect())
+    }
+
+    fn load_recent(
+        &self,
+        _symbol: &str,
+        _interval: &str,
+        count: usize,
+        _now: i64,
+    ) -> Result<Vec<Candle>> {
+        let mut candles = load_candles_from_parquet(&self.path, &self.schema)?;
+        if candles.len() > count {
+            candles.drain(..candles.len() - count);
+        }
+        Ok(candles)
+    }
+}
+
+pub struct ExchangeProvider {
+    config: Config,
+    exchange: String,
+}
+
+impl ExchangeProvider {
+    pub fn new(config: Config, exchange: &str) -> Self {
+        Self {
+            config,
+            exchange: exchange.to_lowercase(),
+        }
+    }
+}
+
+impl MarketDataProvider for ExchangeProvider {
+    fn load_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Candle>> {
+        let mut config = self.config.clone();
+        config.symbol = symbol.to_string();
+        config.data.candle_interval = interval.to_string();
+        fetch_exchange_candles(&config, &self.exchange, start, end)
+    }
+}
+
+pub struct CachedProvider<P> {
+    cache: CandleCache,
+    exchange: String,
+    market: String,
+    inner: P,
+}
+
+impl<P: MarketDataProvider> CachedProvider<P> {
+    pub fn new(cache: CandleCache, exchange: &str, inner: P) -> Self {
+        Self::for_market(cache, exchange, "default", inner)
+    }
+
+    pub fn for_market(cache: CandleCache, exchange: &str, market: &str, inner: P) -> Self {
+        Self {
+            cache,
+            exchange: exchange.to_lowercase(),
+            market: market.to_string(),
+            inner,
+        }
+    }
+}
+
+impl<P: MarketDataProvider> MarketDataProvider for CachedProvider<P> {
+    fn load_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Candle>> {
+        let interval_secs = parse_interval_seconds(interval)? as i64;
+        let key = CacheKey::for_market(&self.exchange, &self.market, symbol, interval);
+        self.cache
+            .load_range(&key, start, end, now_secs()? - interval_secs, |from, to| {
+                self.inner.load_candles(symbol, interval, from, to)
+            })
+    }
+}
+
+pub fn cache_market(config: &Config) -> String {
+    let category = config
+        .data
+        .exchange_category
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or("default");
+    let host = config
+        .data
+        .exchange_base_url
+        .as_deref()
+        .map(|url| {
+            let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+            rest.split('/').next().unwrap_or(rest).to_string()
+        })
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "default".to_string());
+    format!("{}@{}", category, host).to_lowercase()
+}
+
+pub fn exchange_provider(config: &Config) -> Box<dyn MarketDataProvider> {
+    let exchange = config.exchange.to_lowercase();
+    let provider = ExchangeProvider::new(config.clone(), &exchange);
+    match config.data.cache_dir.as_ref() {
+        Some(dir) => Box::new(CachedProvider::for_market(
+            CandleCache::new(dir),
+            &exchange,
+            &cache_market(config),
+            provider,
+        )),
+        None => Box::new(provider),
+    }
+}
+
+pub fn provider_from_config(config: &Config) -> Result<Box<dyn MarketDataProvider>> {
+    match config.data.source.as_str() {
+        "csv" => {
+            let path = config
+                .data
+                .csv_path
+                .as_ref()
+                .ok_or_else(|| Error::new("data.csv_path must be set"))?;
+            Ok(Box::new(CsvProvider::with_schema(
+                path.clone(),
+                config.data.csv.clone(),
+            )))
+        }
+        "parquet" => {
+            let path = config
+                .data
+                .parquet_path
+                .as_ref()
+                .ok_or_else(|| Error::new("data.parquet_path must be set"))?;
+            Ok(Box::new(ParquetProvider::new(
+                path.clone(),
+                config.data.csv.clone(),
+            )))
+        }
+        "exchange" => Ok(exchange_provider(config)),
+        "postgres" => Ok(Box::new(PostgresStorage::for_exchange(
+            &config.storage.postgres_dsn,
+            &config.exchange,
+        ))),
+        _ => Err(Error::new("unknown data source")),
+    }
+}
+
+fn now_secs() -> Result<i64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| Error::new("system time before unix epoch"))?
+        .as_secs() as i64)
 }
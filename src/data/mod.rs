@@ -1,3 +1,10 @@
 pub mod market_data;
+pub mod bars;
+pub mod candle_cache;
 pub mod csv_loader;
 pub mod exchange_loader;
+pub mod fetch;
+pub mod parquet_loader;
+pub mod quality;
+pub mod resample;
+pub mod trade_loader;
@@ -0,0 +1,103 @@
+use crate::config::CsvConfig;
+use crate::data::csv_loader::{validate_candle, TimeParser};
+use crate::models::Candle;
+use crate::{Error, Result};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::{Field, Row};
+use std::fs::File;
+
+pub fn load_candles_from_parquet(path: &str, schema: &CsvConfig) -> Result<Vec<Candle>> {
+    let file = File::open(path).map_err(|err| Error::new(format!("parquet open failed: {err}")))?;
+    let reader = SerializedFileReader::new(file).map_err(parquet_error)?;
+    let names: Vec<String> = reader
+        .metadata()
+        .file_metadata()
+        .schema()
+        .get_fields()
+        .iter()
+        .map(|field| field.name().to_string())
+        .collect();
+    let find = |name: &str| -> Result<usize> {
+        names
+            .iter()
+            .position(|column| column.trim().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| Error::new(format!("parquet column not found: {name}")))
+    };
+    let columns = [
+        find(&schema.time_column)?,
+        find(&schema.open_column)?,
+        find(&schema.high_column)?,
+        find(&schema.low_column)?,
+        find(&schema.close_column)?,
+        find(&schema.volume_column)?,
+    ];
+    let parser = TimeParser::new(schema)?;
+
+    let mut candles = Vec::new();
+    for row in reader.get_row_iter(None).map_err(parquet_error)? {
+        let row = row.map_err(parquet_error)?;
+        let candle = Candle {
+            time: time_value(&parser, column(&row, columns[0])?)?,
+            open: number(column(&row, columns[1])?)?,
+            high: number(column(&row, columns[2])?)?,
+            low: number(column(&row, columns[3])?)?,
+            close: number(column(&row, columns[4])?)?,
+            volume: number(column(&row, columns[5])?)?,
+        };
+        validate_candle(&candle)?;
+        candles.push(candle);
+    }
+
+    candles.sort_by_key(|candle| candle.time);
+    let mut deduped: Vec<Candle> = Vec::with_capacity(candles.len());
+    for candle in candles {
+        match deduped.last_mut() {
+            Some(last) if last.time == candle.time => *last = candle,
+            _ => deduped.push(candle),
+        }
+    }
+    Ok(deduped)
+}
+
+fn column(row: &Row, index: usize) -> Result<&Field> {
+    row.get_column_iter()
+        .nth(index)
+        .map(|(_, field)| field)
+        .ok_or_else(|| Error::new(format!("parquet row missing column {index}")))
+}
+
+fn number(field: &Field) -> Result<f64> {
+    match field {
+        Field::Double(value) => Ok(*value),
+        Field::Float(value) => Ok(*value as f64),
+        Field::Float16(value) => Ok(value.to_f64()),
+        Field::Int(value) => Ok(*value as f64),
+        Field::Long(value) => Ok(*value as f64),
+        Field::UInt(value) => Ok(*value as f64),
+        Field::ULong(value) => Ok(*value as f64),
+        Field::Str(value) => value
+            .trim()
+            .parse::<f64>()
+            .map_err(|err| Error::new(format!("parquet value {value}: {err}"))),
+        other => Err(Error::new(format!("parquet value is not numeric: {other}"))),
+    }
+}
+
+fn time_value(parser: &TimeParser, field: &Field) -> Result<i64> {
+    match field {
+        Field::Long(value) => Ok(parser.from_epoch(*value)),
+        Field::Int(value) => Ok(parser.from_epoch(*value as i64)),
+        Field::ULong(value) => Ok(parser.from_epoch(*value as i64)),
+        Field::TimestampMillis(value) => Ok(value.div_euclid(1_000)),
+        Field::TimestampMicros(value) => Ok(value.div_euclid(1_000_000)),
+        Field::Date(days) => Ok(*days as i64 * 86_400),
+        Field::Str(value) => parser.parse(value),
+        other => Err(Error::new(format!(
+            "parquet time value not supported: {other}"
+        ))),
+    }
+}
+
+fn parquet_error(err: parquet::errors::ParquetError) -> Error {
+    Error::new(format!("parquet read failed: {err}"))
+}
@@ -0,0 +1,203 @@
+use crate::config::DataConfig;
+use crate::data::resample::{align_to_bucket, bucket_end};
+use crate::models::Candle;
+use crate::{Error, Result};
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GapPolicy {
+    Fail,
+    Fill,
+    Warn,
+}
+
+impl GapPolicy {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "fail" => Ok(Self::Fail),
+            "fill" => Ok(Self::Fill),
+            "warn" => Ok(Self::Warn),
+            _ => Err(Error::new("data.gap_policy must be fail, fill, or warn")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QualityThresholds {
+    pub max_spike_ratio: f64,
+    pub max_stale_bars: usize,
+    pub max_zero_volume_bars: usize,
+}
+
+impl QualityThresholds {
+    pub fn from_config(config: &DataConfig) -> Self {
+        Self {
+            max_spike_ratio: config.max_spike_ratio,
+            max_stale_bars: config.max_stale_bars as usize,
+            max_zero_volume_bars: config.max_zero_volume_bars as usize,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Gap {
+    pub after: i64,
+    pub before: i64,
+    pub missing: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Run {
+    pub start: i64,
+    pub end: i64,
+    pub bars: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Spike {
+    pub time: i64,
+    pub previous_close: f64,
+    pub close: f64,
+    pub ratio: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct QualityReport {
+    pub interval_secs: i64,
+    pub candles: usize,
+    pub first: Option<i64>,
+    pub last: Option<i64>,
+    pub gaps: Vec<Gap>,
+    pub misaligned: Vec<i64>,
+    pub zero_volume_runs: Vec<Run>,
+    pub stale_runs: Vec<Run>,
+    pub spikes: Vec<Spike>,
+}
+
+impl QualityReport {
+    pub fn missing_bars(&self) -> i64 {
+        self.gaps.iter().map(|gap| gap.missing).sum()
+    }
+
+    pub fn has_holes(&self) -> bool {
+        !self.gaps.is_empty() || !self.misaligned.is_empty()
+    }
+
+    pub fn outlier_count(&self) -> usize {
+        self.zero_volume_runs.len() + self.stale_runs.len() + self.spikes.len()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        !self.has_holes() && self.outlier_count() == 0
+    }
+}
+
+pub fn check_candles(
+    candles: &[Candle],
+    interval_secs: i64,
+    thresholds: &QualityThresholds,
+) -> QualityReport {
+    let mut report = QualityReport {
+        interval_secs,
+        candles: candles.len(),
+        first: candles.first().map(|candle| candle.time),
+        last: candles.last().map(|candle| candle.time),
+        ..QualityReport::default()
+    };
+    if interval_secs <= 0 {
+        return report;
+    }
+
+    for candle in candles {
+        if align_to_bucket(candle.time, interval_secs) != candle.time {
+            report.misaligned.push(candle.time);
+        }
+    }
+    for pair in candles.windows(2) {
+        let (previous, current) = (&pair[0], &pair[1]);
+        let mut expected = bucket_end(previous.time, interval_secs);
+        if current.time > expected {
+            let mut missing = 0;
+            while expected < current.time {
+                missing += 1;
+                expected = bucket_end(expected, interval_secs);
+            }
+            report.gaps.push(Gap {
+                after: previous.time,
+                before: current.time,
+                missing,
+            });
+        }
+        if previous.close > 0.0 && thresholds.max_spike_ratio > 0.0 {
+            let ratio = (current.close / previous.close - 1.0).abs();
+            if ratio > thresholds.max_spike_ratio {
+                report.spikes.push(Spike {
+                    time: current.time,
+                    previous_close: previous.close,
+                    close: current.close,
+                    ratio,
+                });
+            }
+        }
+    }
+    report.zero_volume_runs = runs(candles, thresholds.max_zero_volume_bars, |candle| {
+        (candle.volume == 0.0).then_some(())
+    });
+    report.stale_runs = runs(candles, thresholds.max_stale_bars, |candle| {
+        Some(candle.close.to_bits())
+    });
+    report
+}
+
+pub fn forward_fill(candles: &[Candle], interval_secs: i64) -> Vec<Candle> {
+    let mut filled: Vec<Candle> = Vec::with_capacity(candles.len());
+    for candle in candles {
+        if let Some((last_time, close)) = filled.last().map(|last| (last.time, last.close)) {
+            let mut time = bucket_end(last_time, interval_secs);
+            while interval_secs > 0 && time < candle.time {
+                filled.push(Candle {
+                    time,
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 0.0,
+                });
+                time = bucket_end(time, interval_secs);
+            }
+        }
+        filled.push(candle.clone());
+    }
+    filled
+}
+
+fn runs<K, F>(candles: &[Candle], min_bars: usize, key: F) -> Vec<Run>
+where
+    K: PartialEq,
+    F: Fn(&Candle) -> Option<K>,
+{
+    let mut found = Vec::new();
+    if min_bars == 0 {
+        return found;
+    }
+    let mut from = 0;
+    while from < candles.len() {
+        let Some(first) = key(&candles[from]) else {
+            from += 1;
+            continue;
+        };
+        let mut to = from + 1;
+        while to < candles.len() && key(&candles[to]).as_ref() == Some(&first) {
+            to += 1;
+        }
+        if to - from >= min_bars {
+            found.push(Run {
+                start: candles[from].time,
+                end: candles[to - 1].time,
+                bars: to - from,
+            });
+        }
+        from = to;
+    }
+    found
+}
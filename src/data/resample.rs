@@ -0,0 +1,132 @@
+use crate::data::exchange_loader::parse_interval_seconds;
+use crate::models::Candle;
+use crate::{Error, Result};
+use chrono::{DateTime, Datelike, NaiveDate};
+
+const WEEK_SECS: i64 = 604_800;
+const WEEK_OFFSET_SECS: i64 = 345_600;
+const MONTH_SECS: i64 = 2_592_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timeframe {
+    interval: String,
+    secs: i64,
+    base_secs: i64,
+    candles: Vec<Candle>,
+    forming: Option<Candle>,
+    partial: bool,
+}
+
+impl Timeframe {
+    pub fn new(interval: &str, base_interval: &str) -> Result<Self> {
+        let secs = parse_interval_seconds(interval)? as i64;
+        let base_secs = parse_interval_seconds(base_interval)? as i64;
+        if secs < base_secs || secs % base_secs != 0 {
+            return Err(Error::new(format!(
+                "timeframe {interval} must be a multiple of {base_interval}"
+            )));
+        }
+        Ok(Self {
+            interval: interval.to_string(),
+            secs,
+            base_secs,
+            candles: Vec::new(),
+            forming: None,
+            partial: false,
+        })
+    }
+
+    pub fn interval(&self) -> &str {
+        &self.interval
+    }
+
+    pub fn candles(&self) -> &[Candle] {
+        &self.candles
+    }
+
+    pub fn forming(&self) -> Option<&Candle> {
+        self.forming.as_ref()
+    }
+
+    pub fn bucket_start(&self, time: i64) -> i64 {
+        align_to_bucket(time, self.secs)
+    }
+
+    pub fn push(&mut self, candle: &Candle) {
+        let bucket = self.bucket_start(candle.time);
+        if let Some(forming) = self.forming.as_mut().filter(|forming| forming.time == bucket) {
+            forming.high = forming.high.max(candle.high);
+            forming.low = forming.low.min(candle.low);
+            forming.close = candle.close;
+            forming.volume += candle.volume;
+        } else {
+            let latest = self.forming.as_ref().or(self.candles.last()).map(|latest| latest.time);
+            if latest.is_some_and(|latest| latest >= bucket) {
+                return;
+            }
+            self.close_forming();
+            self.partial = latest.is_none() && candle.time != bucket;
+            self.forming = Some(Candle {
+                time: bucket,
+                ..candle.clone()
+            });
+        }
+        if bucket_end(candle.time, self.base_secs) >= bucket_end(bucket, self.secs) {
+            self.close_forming();
+        }
+    }
+
+    fn close_forming(&mut self) {
+        if let Some(complete) = self.forming.take() {
+            if !self.partial {
+                self.candles.push(complete);
+            }
+            self.partial = false;
+        }
+    }
+}
+
+pub fn align_to_bucket(time: i64, secs: i64) -> i64 {
+    if secs == MONTH_SECS {
+        return month_start(time, 0);
+    }
+    let offset = if secs % WEEK_SECS == 0 {
+        WEEK_OFFSET_SECS
+    } else {
+        0
+    };
+    (time - offset).div_euclid(secs) * secs + offset
+}
+
+pub fn bucket_end(time: i64, secs: i64) -> i64 {
+    if secs == MONTH_SECS {
+        return month_start(time, 1);
+    }
+    align_to_bucket(time, secs) + secs
+}
+
+fn month_start(time: i64, months_ahead: i32) -> i64 {
+    let Some(date) = DateTime::from_timestamp(time, 0).map(|date| date.date_naive()) else {
+        return time;
+    };
+    let index = date.year() * 12 + date.month0() as i32 + months_ahead;
+    NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc().timestamp())
+        .unwrap_or(time)
+}
+
+pub fn resample(candles: &[Candle], base_interval: &str, interval: &str) -> Result<Vec<Candle>> {
+    let mut timeframe = Timeframe::new(interval, base_interval)?;
+    for candle in candles {
+        timeframe.push(candle);
+    }
+    Ok(timeframe.candles)
+}
+
+pub fn build_timeframes(intervals: &[String], base_interval: &str) -> Result<Vec<Timeframe>> {
+    intervals
+        .iter()
+        .map(|interval| Timeframe::new(interval, base_interval))
+        .collect()
+}
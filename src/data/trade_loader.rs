@@ -0,0 +1,151 @@
+use crate::config::Config;
+use crate::data::csv_loader::{parse_time, read_csv_bytes};
+use crate::data::exchange_loader::fetch_exchange_trades;
+use crate::models::TradePrint;
+use crate::{Error, Result};
+use chrono::DateTime;
+use csv::{ReaderBuilder, StringRecord};
+
+const TIME_COLUMNS: [&str; 4] = ["time", "timestamp", "transact_time", "ts"];
+const PRICE_COLUMNS: [&str; 2] = ["price", "px"];
+const QUANTITY_COLUMNS: [&str; 5] = ["quantity", "qty", "size", "sz", "amount"];
+const BINANCE_AGG_TRADE_COLUMNS: (usize, usize, usize) = (5, 1, 2);
+
+pub fn load_backtest_trades(config: &Config) -> Result<Vec<TradePrint>> {
+    let start = parse_time(
+        config
+            .backtest
+            .start_time
+            .as_ref()
+            .ok_or_else(|| Error::new("backtest.start_time must be set"))?,
+    )?;
+    let end = parse_time(
+        config
+            .backtest
+            .end_time
+            .as_ref()
+            .ok_or_else(|| Error::new("backtest.end_time must be set"))?,
+    )?;
+    if start > end {
+        return Err(Error::new("backtest.start_time must be <= end_time"));
+    }
+    let (start_ms, end_ms) = (start * 1_000, end * 1_000);
+
+    match config.data.trades_path.as_deref() {
+        Some(path) if !path.trim().is_empty() => {
+            let delimiter = match config.data.csv.delimiter.as_bytes() {
+                [byte] => *byte,
+                _ => return Err(Error::new("data.csv.delimiter must be a single byte")),
+            };
+            Ok(load_trades_from_csv(path, delimiter)?
+                .into_iter()
+                .filter(|print| print.time_ms >= start_ms && print.time_ms <= end_ms)
+                .collect())
+        }
+        _ if config.data.source == "exchange" => {
+            fetch_exchange_trades(config, &config.exchange.to_lowercase(), start_ms, end_ms)
+        }
+        _ => Err(Error::new(
+            "backtest.granularity = trade needs data.trades_path or data.source = exchange",
+        )),
+    }
+}
+
+pub fn load_trades_from_csv(path: &str, delimiter: u8) -> Result<Vec<TradePrint>> {
+    let bytes = read_csv_bytes(path)?;
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(delimiter)
+        .from_reader(bytes.as_slice());
+
+    let mut records = reader.records();
+    let Some(first) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let first = first.map_err(|err| Error::new(format!("csv parse failed: {err}")))?;
+    let headerless = first
+        .get(0)
+        .is_some_and(|value| value.trim().parse::<i64>().is_ok());
+    let (time, price, quantity) = if headerless {
+        BINANCE_AGG_TRADE_COLUMNS
+    } else {
+        (
+            locate(&first, &TIME_COLUMNS)?,
+            locate(&first, &PRICE_COLUMNS)?,
+            locate(&first, &QUANTITY_COLUMNS)?,
+        )
+    };
+
+    let mut prints = Vec::new();
+    let rows = headerless.then_some(Ok(first)).into_iter().chain(records);
+    for result in rows {
+        let record = result.map_err(|err| Error::new(format!("csv parse failed: {err}")))?;
+        let print = TradePrint {
+            time_ms: parse_time_ms(field(&record, time)?)?,
+            price: number(&record, price)?,
+            quantity: number(&record, quantity)?,
+        };
+        if !(print.price.is_finite() && print.price > 0.0) {
+            return Err(Error::new(format!(
+                "trade price must be > 0: {}",
+                print.price
+            )));
+        }
+        if !(print.quantity.is_finite() && print.quantity >= 0.0) {
+            return Err(Error::new(format!(
+                "trade quantity must be >= 0: {}",
+                print.quantity
+            )));
+        }
+        prints.push(print);
+    }
+
+    prints.sort_by_key(|print| print.time_ms);
+    Ok(prints)
+}
+
+pub fn parse_time_ms(value: &str) -> Result<i64> {
+    let trimmed = value.trim();
+    if let Ok(epoch) = trimmed.parse::<i64>() {
+        return Ok(match epoch.abs() {
+            abs if abs >= 100_000_000_000_000_000 => epoch.div_euclid(1_000_000),
+            abs if abs >= 100_000_000_000_000 => epoch.div_euclid(1_000),
+            abs if abs >= 100_000_000_000 => epoch,
+            _ => epoch * 1_000,
+        });
+    }
+    if let Ok(seconds) = trimmed.parse::<f64>() {
+        if seconds.is_finite() {
+            return Ok((seconds * 1_000.0).round() as i64);
+        }
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(parsed.timestamp_millis());
+    }
+    Ok(parse_time(trimmed)? * 1_000)
+}
+
+fn locate(headers: &StringRecord, names: &[&str]) -> Result<usize> {
+    headers
+        .iter()
+        .position(|header| {
+            names
+                .iter()
+                .any(|name| header.trim().eq_ignore_ascii_case(name))
+        })
+        .ok_or_else(|| Error::new(format!("trade csv column not found: {}", names[0])))
+}
+
+fn field(record: &StringRecord, column: usize) -> Result<&str> {
+    record
+        .get(column)
+        .ok_or_else(|| Error::new(format!("trade csv row missing column {column}")))
+}
+
+fn number(record: &StringRecord, column: usize) -> Result<f64> {
+    let value = field(record, column)?;
+    value
+        .trim()
+        .parse::<f64>()
+        .map_err(|err| Error::new(format!("invalid number {value}: {err}")))
+}
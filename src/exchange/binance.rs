@@ -1,12 +1,17 @@
-use crate::exchange::{CandleRequest, Exchange};
+use crate::exchange::rate_limit::{shared_limiter, RateLimiter};
+use crate::exchange::time_sync::{ClockSync, TimeSyncConfig};
+use crate::exchange::{AmendRequest, CandleRequest, Exchange, OrderCheck};
 use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side};
 use crate::{Error, Result};
 use hmac::{Hmac, Mac};
 use reqwest::blocking::Client;
-use reqwest::Method;
+use reqwest::{Method, StatusCode};
 use serde_json::Value;
 use sha2::Sha256;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::time::Duration;
+
+const ORDER_NOT_FOUND: i64 = -2013;
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -18,11 +23,14 @@ pub struct BinanceConfig {
     pub recv_window: u64,
     pub timeout_secs: u64,
     pub default_symbol: Option<String>,
+    pub time_sync: TimeSyncConfig,
 }
 
 pub struct BinanceExchange {
     client: Client,
     config: BinanceConfig,
+    limiter: Arc<RateLimiter>,
+    clock: ClockSync,
 }
 
 impl BinanceExchange {
@@ -40,14 +48,31 @@ impl BinanceExchange {
             .timeout(Duration::from_secs(config.timeout_secs.max(1)))
             .build()
             .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
-        Ok(Self { client, config })
+        let limiter = shared_limiter("binance")?;
+        let clock = ClockSync::new("binance", config.time_sync.clone());
+        Ok(Self {
+            client,
+            config,
+            limiter,
+            clock,
+        })
+    }
+
+    pub fn clock(&self) -> &ClockSync {
+        &self.clock
+    }
+
+    pub fn server_time_ms(&self) -> Result<i64> {
+        let json = self.public_request("/api/v3/time", Vec::new())?;
+        let server_time = json
+            .get("serverTime")
+            .ok_or_else(|| Error::new("binance serverTime missing"))?;
+        value_to_i64(server_time)
     }
 
-    fn timestamp_ms() -> Result<u64> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|_| Error::new("system time before unix epoch"))?;
-        Ok(now.as_millis() as u64)
+    fn timestamp_ms(&self) -> Result<u64> {
+        self.clock.sync_if_due(|| self.server_time_ms())?;
+        Ok(self.clock.now_ms()?.max(0) as u64)
     }
 
     pub fn hmac_sha256_hex(secret: &str, message: &str) -> Result<String> {
@@ -58,13 +83,14 @@ impl BinanceExchange {
         Ok(bytes_to_hex(&result))
     }
 
-    fn signed_request(
+    fn send_signed(
         &self,
         method: Method,
         path: &str,
         mut params: Vec<(String, String)>,
-    ) -> Result<Value> {
-        let timestamp = Self::timestamp_ms()?;
+    ) -> Result<(StatusCode, Value)> {
+        self.limiter.acquire(path, &params)?;
+        let timestamp = self.timestamp_ms()?;
         params.push(("timestamp".to_string(), timestamp.to_string()));
         if self.config.recv_window > 0 {
             params.push(("recvWindow".to_string(), self.config.recv_window.to_string()));
@@ -80,20 +106,34 @@ impl BinanceExchange {
             .header("X-MBX-APIKEY", self.config.api_key.as_str())
             .send()
             .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
 
-        if !response.status().is_success() {
-            return Err(Error::new(format!(
-                "binance response status: {}",
-                response.status()
-            )));
+        let status = response.status();
+        let json = response
+            .json::<Value>()
+            .map_err(|err| Error::new(format!("json parse failed: {err}")));
+        if !status.is_success() {
+            return Ok((status, json.unwrap_or(Value::Null)));
         }
+        Ok((status, json?))
+    }
 
-        response
-            .json::<Value>()
-            .map_err(|err| Error::new(format!("json parse failed: {err}")))
+    fn signed_request(
+        &self,
+        method: Method,
+        path: &str,
+        params: Vec<(String, String)>,
+    ) -> Result<Value> {
+        let (status, json) = self.send_signed(method, path, params)?;
+        if !status.is_success() {
+            return Err(Error::new(format!("binance response status: {status}")));
+        }
+        Ok(json)
     }
 
     fn public_request(&self, path: &str, params: Vec<(String, String)>) -> Result<Value> {
+        self.limiter.acquire(path, &params)?;
         let query = build_query_string(&params);
         let url = if query.is_empty() {
             format!("{}{}", self.config.base_url, path)
@@ -105,6 +145,8 @@ impl BinanceExchange {
             .get(url)
             .send()
             .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
         if !response.status().is_success() {
             return Err(Error::new(format!(
                 "binance response status: {}",
@@ -119,28 +161,7 @@ impl BinanceExchange {
 
 impl Exchange for BinanceExchange {
     fn place_order(&self, order: &OrderRequest) -> Result<OrderAck> {
-        let mut params = vec![
-            ("symbol".to_string(), order.symbol.clone()),
-            ("side".to_string(), side_label(&order.side).to_string()),
-        ];
-
-        match order.order_type {
-            OrderType::Market => {
-                params.push(("type".to_string(), "MARKET".to_string()));
-                params.push(("quantity".to_string(), order.quantity.to_string()));
-            }
-            OrderType::Limit { price } => {
-                params.push(("type".to_string(), "LIMIT".to_string()));
-                params.push(("timeInForce".to_string(), "GTC".to_string()));
-                params.push(("quantity".to_string(), order.quantity.to_string()));
-                params.push(("price".to_string(), price.to_string()));
-            }
-        }
-
-        params.push(("newClientOrderId".to_string(), order.client_order_id.clone()));
-        params.push(("newOrderRespType".to_string(), "ACK".to_string()));
-
-        let json = self.signed_request(Method::POST, "/api/v3/order", params)?;
+        let json = self.signed_request(Method::POST, "/api/v3/order", order_params(order))?;
         let exchange_order_id = json
             .get("orderId")
             .and_then(|value| value.as_i64())
@@ -150,9 +171,23 @@ impl Exchange for BinanceExchange {
             client_order_id: order.client_order_id.clone(),
             exchange_order_id,
             status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
         })
     }
 
+    fn test_order(&self, order: &OrderRequest, _reference_price: f64) -> Result<OrderCheck> {
+        let (status, json) =
+            self.send_signed(Method::POST, "/api/v3/order/test", order_params(order))?;
+        if status.is_success() {
+            return Ok(OrderCheck::Accepted);
+        }
+        match rejection_reason(&json) {
+            Some(reason) => Ok(OrderCheck::Rejected(reason)),
+            None => Err(Error::new(format!("binance response status: {status}"))),
+        }
+    }
+
     fn cancel_order(&self, order_id: &str) -> Result<()> {
         let symbol = self
             .config
@@ -167,6 +202,12 @@ impl Exchange for BinanceExchange {
         Ok(())
     }
 
+    fn amend_order(&self, req: &AmendRequest) -> Result<OrderAck> {
+        let params = cancel_replace_params(req)?;
+        let json = self.signed_request(Method::POST, "/api/v3/order/cancelReplace", params)?;
+        parse_cancel_replace(&json, &req.order.client_order_id)
+    }
+
     fn fetch_balances(&self) -> Result<Vec<Balance>> {
         let json = self.signed_request(Method::GET, "/api/v3/account", Vec::new())?;
         let balances = json
@@ -209,32 +250,27 @@ impl Exchange for BinanceExchange {
         let array = json
             .as_array()
             .ok_or_else(|| Error::new("openOrders should be array"))?;
-        let mut result = Vec::new();
-        for item in array {
-            let client_order_id = item
-                .get("clientOrderId")
-                .and_then(|value| value.as_str())
-                .unwrap_or("")
-                .to_string();
-            let exchange_order_id = item
-                .get("orderId")
-                .and_then(|value| value.as_i64())
-                .map(|id| id.to_string());
-            let status = item
-                .get("status")
-                .and_then(|value| value.as_str())
-                .map(parse_status)
-                .unwrap_or(OrderStatus::New);
-            if client_order_id.is_empty() {
-                continue;
-            }
-            result.push(OrderAck {
-                client_order_id,
-                exchange_order_id,
-                status,
-            });
+        Ok(array.iter().filter_map(parse_order).collect())
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let symbol = self
+            .config
+            .default_symbol
+            .as_ref()
+            .ok_or_else(|| Error::new("default_symbol must be set for fetch_order"))?;
+        let params = vec![
+            ("symbol".to_string(), symbol.clone()),
+            ("origClientOrderId".to_string(), client_order_id.to_string()),
+        ];
+        let (status, json) = self.send_signed(Method::GET, "/api/v3/order", params)?;
+        if status.is_success() {
+            return Ok(parse_order(&json));
         }
-        Ok(result)
+        if json.get("code").and_then(|value| value.as_i64()) == Some(ORDER_NOT_FOUND) {
+            return Ok(None);
+        }
+        Err(Error::new(format!("binance response status: {status}")))
     }
 
     fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>> {
@@ -275,6 +311,53 @@ impl Exchange for BinanceExchange {
     }
 }
 
+pub fn cancel_replace_params(req: &AmendRequest) -> Result<Vec<(String, String)>> {
+    let (price, quantity) = req.amended_limit()?;
+    Ok(vec![
+        ("symbol".to_string(), req.order.symbol.clone()),
+        ("side".to_string(), side_label(&req.order.side).to_string()),
+        ("type".to_string(), "LIMIT".to_string()),
+        ("timeInForce".to_string(), "GTC".to_string()),
+        ("quantity".to_string(), quantity.to_string()),
+        ("price".to_string(), price.to_string()),
+        ("cancelReplaceMode".to_string(), "STOP_ON_FAILURE".to_string()),
+        (
+            "cancelOrigClientOrderId".to_string(),
+            req.order.client_order_id.clone(),
+        ),
+        ("newClientOrderId".to_string(), req.order.client_order_id.clone()),
+        ("newOrderRespType".to_string(), "ACK".to_string()),
+    ])
+}
+
+pub fn parse_cancel_replace(json: &Value, client_order_id: &str) -> Result<OrderAck> {
+    let new_result = json
+        .get("newOrderResult")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    if new_result != "SUCCESS" {
+        return Err(Error::new(format!(
+            "binance cancelReplace failed: cancel={}, new={}",
+            json.get("cancelResult")
+                .and_then(|value| value.as_str())
+                .unwrap_or("unknown"),
+            if new_result.is_empty() { "unknown" } else { new_result }
+        )));
+    }
+    let exchange_order_id = json
+        .get("newOrderResponse")
+        .and_then(|value| value.get("orderId"))
+        .and_then(|value| value.as_i64())
+        .map(|id| id.to_string());
+    Ok(OrderAck {
+        client_order_id: client_order_id.to_string(),
+        exchange_order_id,
+        status: OrderStatus::New,
+        filled_qty: 0.0,
+        avg_fill_price: None,
+    })
+}
+
 fn build_query_string(params: &[(String, String)]) -> String {
     params
         .iter()
@@ -298,6 +381,70 @@ fn side_label(side: &Side) -> &'static str {
     }
 }
 
+pub fn order_params(order: &OrderRequest) -> Vec<(String, String)> {
+    let mut params = vec![
+        ("symbol".to_string(), order.symbol.clone()),
+        ("side".to_string(), side_label(&order.side).to_string()),
+    ];
+
+    match order.order_type {
+        OrderType::Market => {
+            params.push(("type".to_string(), "MARKET".to_string()));
+            params.push(("quantity".to_string(), order.quantity.to_string()));
+        }
+        OrderType::Limit { price } => {
+            params.push(("type".to_string(), "LIMIT".to_string()));
+            params.push(("timeInForce".to_string(), "GTC".to_string()));
+            params.push(("quantity".to_string(), order.quantity.to_string()));
+            params.push(("price".to_string(), price.to_string()));
+        }
+    }
+
+    params.push(("newClientOrderId".to_string(), order.client_order_id.clone()));
+    params.push(("newOrderRespType".to_string(), "ACK".to_string()));
+    params
+}
+
+pub fn rejection_reason(json: &Value) -> Option<String> {
+    let code = json.get("code").and_then(|value| value.as_i64())?;
+    let msg = json
+        .get("msg")
+        .and_then(|value| value.as_str())
+        .unwrap_or("rejected");
+    Some(format!("code {code}: {msg}"))
+}
+
+pub fn parse_order(item: &Value) -> Option<OrderAck> {
+    let client_order_id = item
+        .get("clientOrderId")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.is_empty())?
+        .to_string();
+    let exchange_order_id = item
+        .get("orderId")
+        .and_then(|value| value.as_i64())
+        .map(|id| id.to_string());
+    let status = item
+        .get("status")
+        .and_then(|value| value.as_str())
+        .map(parse_status)
+        .unwrap_or(OrderStatus::New);
+    let number = |key: &str| {
+        item.get(key)
+            .and_then(|value| value_to_f64(value).ok())
+            .unwrap_or(0.0)
+    };
+    let filled_qty = number("executedQty");
+    let quote_qty = number("cummulativeQuoteQty");
+    Some(OrderAck {
+        client_order_id,
+        exchange_order_id,
+        status,
+        filled_qty,
+        avg_fill_price: (filled_qty > 0.0 && quote_qty > 0.0).then(|| quote_qty / filled_qty),
+    })
+}
+
 fn parse_status(status: &str) -> OrderStatus {
     match status {
         "NEW" => OrderStatus::New,
@@ -0,0 +1,588 @@
+use crate::data::exchange_loader::parse_binance_klines;
+use crate::exchange::binance::{rejection_reason, BinanceExchange};
+use crate::exchange::rate_limit::{shared_limiter, RateLimiter};
+use crate::exchange::time_sync::{ClockSync, TimeSyncConfig};
+use crate::exchange::{AmendRequest, CandleRequest, Exchange, OrderCheck};
+use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side};
+use crate::{Error, Result};
+use reqwest::blocking::Client;
+use reqwest::{Method, StatusCode};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+const MARGIN_TYPE_UNCHANGED: i64 = -4046;
+const ORDER_NOT_FOUND: i64 = -2013;
+
+#[derive(Clone, Debug)]
+pub struct BinanceFuturesConfig {
+    pub base_url: String,
+    pub api_key: String,
+    pub api_secret: String,
+    pub recv_window: u64,
+    pub timeout_secs: u64,
+    pub default_symbol: Option<String>,
+    pub leverage: Option<u32>,
+    pub margin_type: Option<String>,
+    pub reduce_only_sells: bool,
+    pub time_sync: TimeSyncConfig,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FundingPayment {
+    pub symbol: String,
+    pub asset: String,
+    pub amount: f64,
+    pub time: i64,
+}
+
+pub struct BinanceFuturesExchange {
+    client: Client,
+    config: BinanceFuturesConfig,
+    limiter: Arc<RateLimiter>,
+    clock: ClockSync,
+}
+
+impl BinanceFuturesExchange {
+    pub fn new(config: BinanceFuturesConfig) -> Result<Self> {
+        if config.base_url.trim().is_empty() {
+            return Err(Error::new("base_url must be set"));
+        }
+        if config.api_key.trim().is_empty() {
+            return Err(Error::new("api_key must be set"));
+        }
+        if config.api_secret.trim().is_empty() {
+            return Err(Error::new("api_secret must be set"));
+        }
+        if let Some(leverage) = config.leverage {
+            if !(1..=125).contains(&leverage) {
+                return Err(Error::new("leverage must be between 1 and 125"));
+            }
+        }
+        if let Some(margin_type) = config.margin_type.as_deref() {
+            normalize_margin_type(margin_type)?;
+        }
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+        let limiter = shared_limiter("binance-futures")?;
+        let clock = ClockSync::new("binance-futures", config.time_sync.clone());
+        Ok(Self {
+            client,
+            config,
+            limiter,
+            clock,
+        })
+    }
+
+    pub fn clock(&self) -> &ClockSync {
+        &self.clock
+    }
+
+    pub fn server_time_ms(&self) -> Result<i64> {
+        let json = self.public_request("/fapi/v1/time", Vec::new())?;
+        let server_time = json
+            .get("serverTime")
+            .ok_or_else(|| Error::new("binance futures serverTime missing"))?;
+        value_to_i64(server_time)
+    }
+
+    pub fn configure_symbol(&self, symbol: &str) -> Result<()> {
+        if let Some(margin_type) = self.config.margin_type.as_deref() {
+            self.set_margin_type(symbol, margin_type)?;
+        }
+        if let Some(leverage) = self.config.leverage {
+            self.set_leverage(symbol, leverage)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_leverage(&self, symbol: &str, leverage: u32) -> Result<()> {
+        if !(1..=125).contains(&leverage) {
+            return Err(Error::new("leverage must be between 1 and 125"));
+        }
+        let params = vec![
+            ("symbol".to_string(), symbol.to_string()),
+            ("leverage".to_string(), leverage.to_string()),
+        ];
+        let _ = self.signed_request(Method::POST, "/fapi/v1/leverage", params)?;
+        Ok(())
+    }
+
+    pub fn set_margin_type(&self, symbol: &str, margin_type: &str) -> Result<()> {
+        let params = vec![
+            ("symbol".to_string(), symbol.to_string()),
+            (
+                "marginType".to_string(),
+                normalize_margin_type(margin_type)?.to_string(),
+            ),
+        ];
+        let (status, json) = self.send_signed(Method::POST, "/fapi/v1/marginType", params)?;
+        if status.is_success() || error_code(&json) == Some(MARGIN_TYPE_UNCHANGED) {
+            return Ok(());
+        }
+        Err(response_error(status, &json))
+    }
+
+    pub fn place_order_with(&self, order: &OrderRequest, reduce_only: bool) -> Result<OrderAck> {
+        let params = order_params(order, reduce_only);
+        let json = self.signed_request(Method::POST, "/fapi/v1/order", params)?;
+        let exchange_order_id = json
+            .get("orderId")
+            .and_then(|value| value.as_i64())
+            .map(|id| id.to_string());
+
+        Ok(OrderAck {
+            client_order_id: order.client_order_id.clone(),
+            exchange_order_id,
+            status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
+        })
+    }
+
+    pub fn fetch_funding_history(
+        &self,
+        symbol: &str,
+        start_time_ms: i64,
+        end_time_ms: i64,
+    ) -> Result<Vec<FundingPayment>> {
+        let params = vec![
+            ("symbol".to_string(), symbol.to_string()),
+            ("incomeType".to_string(), "FUNDING_FEE".to_string()),
+            ("startTime".to_string(), start_time_ms.to_string()),
+            ("endTime".to_string(), end_time_ms.to_string()),
+            ("limit".to_string(), "1000".to_string()),
+        ];
+        let json = self.signed_request(Method::GET, "/fapi/v1/income", params)?;
+        parse_funding_income(&json)
+    }
+
+    fn timestamp_ms(&self) -> Result<u64> {
+        self.clock.sync_if_due(|| self.server_time_ms())?;
+        Ok(self.clock.now_ms()?.max(0) as u64)
+    }
+
+    fn send_signed(
+        &self,
+        method: Method,
+        path: &str,
+        mut params: Vec<(String, String)>,
+    ) -> Result<(StatusCode, Value)> {
+        self.limiter.acquire(path, &params)?;
+        let timestamp = self.timestamp_ms()?;
+        params.push(("timestamp".to_string(), timestamp.to_string()));
+        if self.config.recv_window > 0 {
+            params.push(("recvWindow".to_string(), self.config.recv_window.to_string()));
+        }
+        let query = build_query_string(&params);
+        let signature = BinanceExchange::hmac_sha256_hex(&self.config.api_secret, &query)?;
+        let url = format!(
+            "{}{}?{}&signature={}",
+            self.config.base_url, path, query, signature
+        );
+
+        let response = self
+            .client
+            .request(method, url)
+            .header("X-MBX-APIKEY", self.config.api_key.as_str())
+            .send()
+            .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
+        let status = response.status();
+        let text = response
+            .text()
+            .map_err(|err| Error::new(format!("http read failed: {err}")))?;
+        let json = serde_json::from_str::<Value>(&text).unwrap_or(Value::String(text));
+        Ok((status, json))
+    }
+
+    fn signed_request(
+        &self,
+        method: Method,
+        path: &str,
+        params: Vec<(String, String)>,
+    ) -> Result<Value> {
+        let (status, json) = self.send_signed(method, path, params)?;
+        if !status.is_success() {
+            return Err(response_error(status, &json));
+        }
+        Ok(json)
+    }
+
+    fn public_request(&self, path: &str, params: Vec<(String, String)>) -> Result<Value> {
+        self.limiter.acquire(path, &params)?;
+        let query = build_query_string(&params);
+        let url = if query.is_empty() {
+            format!("{}{}", self.config.base_url, path)
+        } else {
+            format!("{}{}?{}", self.config.base_url, path, query)
+        };
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
+        if !response.status().is_success() {
+            return Err(Error::new(format!(
+                "binance futures response status: {}",
+                response.status()
+            )));
+        }
+        response
+            .json::<Value>()
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))
+    }
+}
+
+impl Exchange for BinanceFuturesExchange {
+    fn place_order(&self, order: &OrderRequest) -> Result<OrderAck> {
+        let reduce_only = self.config.reduce_only_sells && matches!(order.side, Side::Sell);
+        self.place_order_with(order, reduce_only)
+    }
+
+    fn test_order(&self, order: &OrderRequest, _reference_price: f64) -> Result<OrderCheck> {
+        let reduce_only = self.config.reduce_only_sells && matches!(order.side, Side::Sell);
+        let params = order_params(order, reduce_only);
+        let (status, json) = self.send_signed(Method::POST, "/fapi/v1/order/test", params)?;
+        if status.is_success() {
+            return Ok(OrderCheck::Accepted);
+        }
+        match rejection_reason(&json) {
+            Some(reason) => Ok(OrderCheck::Rejected(reason)),
+            None => Err(response_error(status, &json)),
+        }
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let symbol = self
+            .config
+            .default_symbol
+            .as_ref()
+            .ok_or_else(|| Error::new("default_symbol must be set for cancel_order"))?;
+        let params = vec![
+            ("symbol".to_string(), symbol.clone()),
+            ("origClientOrderId".to_string(), order_id.to_string()),
+        ];
+        let _ = self.signed_request(Method::DELETE, "/fapi/v1/order", params)?;
+        Ok(())
+    }
+
+    fn amend_order(&self, req: &AmendRequest) -> Result<OrderAck> {
+        let params = modify_order_params(req)?;
+        let json = self.signed_request(Method::PUT, "/fapi/v1/order", params)?;
+        let exchange_order_id = json
+            .get("orderId")
+            .and_then(|value| value.as_i64())
+            .map(|id| id.to_string());
+        let status = json
+            .get("status")
+            .and_then(|value| value.as_str())
+            .map(parse_status)
+            .unwrap_or(OrderStatus::New);
+
+        Ok(OrderAck {
+            client_order_id: req.order.client_order_id.clone(),
+            exchange_order_id,
+            status,
+            filled_qty: 0.0,
+            avg_fill_price: None,
+        })
+    }
+
+    fn fetch_balances(&self) -> Result<Vec<Balance>> {
+        let json = self.signed_request(Method::GET, "/fapi/v2/balance", Vec::new())?;
+        parse_futures_balances(&json)
+    }
+
+    fn fetch_positions(&self) -> Result<Vec<Position>> {
+        let mut params = Vec::new();
+        if let Some(symbol) = self.config.default_symbol.as_ref() {
+            params.push(("symbol".to_string(), symbol.clone()));
+        }
+        let json = self.signed_request(Method::GET, "/fapi/v2/positionRisk", params)?;
+        parse_position_risk(&json)
+    }
+
+    fn fetch_open_orders(&self) -> Result<Vec<OrderAck>> {
+        let mut params = Vec::new();
+        if let Some(symbol) = self.config.default_symbol.as_ref() {
+            params.push(("symbol".to_string(), symbol.clone()));
+        }
+        let json = self.signed_request(Method::GET, "/fapi/v1/openOrders", params)?;
+        let array = json
+            .as_array()
+            .ok_or_else(|| Error::new("openOrders should be array"))?;
+        Ok(array.iter().filter_map(parse_order).collect())
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let symbol = self
+            .config
+            .default_symbol
+            .as_ref()
+            .ok_or_else(|| Error::new("default_symbol must be set for fetch_order"))?;
+        let params = vec![
+            ("symbol".to_string(), symbol.clone()),
+            ("origClientOrderId".to_string(), client_order_id.to_string()),
+        ];
+        let (status, json) = self.send_signed(Method::GET, "/fapi/v1/order", params)?;
+        if status.is_success() {
+            return Ok(parse_order(&json));
+        }
+        if error_code(&json) == Some(ORDER_NOT_FOUND) {
+            return Ok(None);
+        }
+        Err(response_error(status, &json))
+    }
+
+    fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>> {
+        let params = vec![
+            ("symbol".to_string(), req.symbol.clone()),
+            ("interval".to_string(), req.interval.clone()),
+            ("startTime".to_string(), req.start_time.to_string()),
+            ("endTime".to_string(), req.end_time.to_string()),
+        ];
+        let json = self.public_request("/fapi/v1/klines", params)?;
+        let parsed = parse_binance_klines(
+            &serde_json::to_string(&json).map_err(|err| Error::new(format!("json encode failed: {err}")))?,
+        )?;
+        Ok(parsed.candles)
+    }
+}
+
+pub fn parse_position_risk(json: &Value) -> Result<Vec<Position>> {
+    let array = json
+        .as_array()
+        .ok_or_else(|| Error::new("positionRisk should be array"))?;
+    let mut positions = Vec::new();
+    for item in array {
+        let symbol = item
+            .get("symbol")
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+            .to_string();
+        if symbol.is_empty() {
+            continue;
+        }
+        let quantity = value_to_f64(item.get("positionAmt").unwrap_or(&Value::Null))?;
+        if quantity == 0.0 {
+            continue;
+        }
+        let avg_price = value_to_f64(item.get("entryPrice").unwrap_or(&Value::Null))?;
+        positions.push(Position {
+            symbol,
+            quantity,
+            avg_price,
+        });
+    }
+    Ok(positions)
+}
+
+pub fn parse_futures_balances(json: &Value) -> Result<Vec<Balance>> {
+    let array = json
+        .as_array()
+        .ok_or_else(|| Error::new("balance response should be array"))?;
+    let mut balances = Vec::new();
+    for item in array {
+        let asset = item
+            .get("asset")
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+            .to_string();
+        if asset.is_empty() {
+            continue;
+        }
+        let total = value_to_f64(item.get("balance").unwrap_or(&Value::Null))?;
+        let free = value_to_f64(item.get("availableBalance").unwrap_or(&Value::Null))?;
+        balances.push(Balance {
+            asset,
+            free,
+            locked: (total - free).max(0.0),
+        });
+    }
+    Ok(balances)
+}
+
+pub fn parse_funding_income(json: &Value) -> Result<Vec<FundingPayment>> {
+    let array = json
+        .as_array()
+        .ok_or_else(|| Error::new("income response should be array"))?;
+    let mut payments = Vec::new();
+    for item in array {
+        let income_type = item
+            .get("incomeType")
+            .and_then(|value| value.as_str())
+            .unwrap_or("");
+        if income_type != "FUNDING_FEE" {
+            continue;
+        }
+        let symbol = item
+            .get("symbol")
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+            .to_string();
+        let asset = item
+            .get("asset")
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+            .to_string();
+        let amount = value_to_f64(item.get("income").unwrap_or(&Value::Null))?;
+        let time_ms = value_to_i64(item.get("time").unwrap_or(&Value::Null))?;
+        payments.push(FundingPayment {
+            symbol,
+            asset,
+            amount,
+            time: time_ms / 1000,
+        });
+    }
+    payments.sort_by_key(|payment| payment.time);
+    Ok(payments)
+}
+
+pub fn modify_order_params(req: &AmendRequest) -> Result<Vec<(String, String)>> {
+    let (price, quantity) = req.amended_limit()?;
+    Ok(vec![
+        ("symbol".to_string(), req.order.symbol.clone()),
+        ("side".to_string(), side_label(&req.order.side).to_string()),
+        ("quantity".to_string(), quantity.to_string()),
+        ("price".to_string(), price.to_string()),
+        ("origClientOrderId".to_string(), req.order.client_order_id.clone()),
+    ])
+}
+
+fn normalize_margin_type(margin_type: &str) -> Result<&'static str> {
+    match margin_type.to_uppercase().as_str() {
+        "ISOLATED" => Ok("ISOLATED"),
+        "CROSSED" | "CROSS" => Ok("CROSSED"),
+        _ => Err(Error::new("margin_type must be isolated or crossed")),
+    }
+}
+
+fn error_code(json: &Value) -> Option<i64> {
+    json.get("code").and_then(|value| value.as_i64())
+}
+
+fn response_error(status: StatusCode, json: &Value) -> Error {
+    let msg = json
+        .get("msg")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    match error_code(json) {
+        Some(code) => Error::new(format!(
+            "binance futures response status: {status} code {code}: {msg}"
+        )),
+        None => Error::new(format!("binance futures response status: {status}")),
+    }
+}
+
+fn build_query_string(params: &[(String, String)]) -> String {
+    params
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+fn side_label(side: &Side) -> &'static str {
+    match side {
+        Side::Buy => "BUY",
+        Side::Sell => "SELL",
+    }
+}
+
+pub fn order_params(order: &OrderRequest, reduce_only: bool) -> Vec<(String, String)> {
+    let mut params = vec![
+        ("symbol".to_string(), order.symbol.clone()),
+        ("side".to_string(), side_label(&order.side).to_string()),
+    ];
+
+    match order.order_type {
+        OrderType::Market => {
+            params.push(("type".to_string(), "MARKET".to_string()));
+            params.push(("quantity".to_string(), order.quantity.to_string()));
+        }
+        OrderType::Limit { price } => {
+            params.push(("type".to_string(), "LIMIT".to_string()));
+            params.push(("timeInForce".to_string(), "GTC".to_string()));
+            params.push(("quantity".to_string(), order.quantity.to_string()));
+            params.push(("price".to_string(), price.to_string()));
+        }
+    }
+    if reduce_only {
+        params.push(("reduceOnly".to_string(), "true".to_string()));
+    }
+
+    params.push(("newClientOrderId".to_string(), order.client_order_id.clone()));
+    params.push(("newOrderRespType".to_string(), "ACK".to_string()));
+    params
+}
+
+pub fn parse_order(item: &Value) -> Option<OrderAck> {
+    let client_order_id = item
+        .get("clientOrderId")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.is_empty())?
+        .to_string();
+    let exchange_order_id = item
+        .get("orderId")
+        .and_then(|value| value.as_i64())
+        .map(|id| id.to_string());
+    let status = item
+        .get("status")
+        .and_then(|value| value.as_str())
+        .map(parse_status)
+        .unwrap_or(OrderStatus::New);
+    let number = |key: &str| {
+        item.get(key)
+            .and_then(|value| value_to_f64(value).ok())
+            .unwrap_or(0.0)
+    };
+    let (filled_qty, avg_price) = (number("executedQty"), number("avgPrice"));
+    Some(OrderAck {
+        client_order_id,
+        exchange_order_id,
+        status,
+        filled_qty,
+        avg_fill_price: (filled_qty > 0.0 && avg_price > 0.0).then_some(avg_price),
+    })
+}
+
+fn parse_status(status: &str) -> OrderStatus {
+    match status {
+        "NEW" => OrderStatus::New,
+        "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
+        "FILLED" => OrderStatus::Filled,
+        "CANCELED" | "CANCELLED" => OrderStatus::Canceled,
+        "REJECTED" | "EXPIRED" => OrderStatus::Rejected,
+        _ => OrderStatus::New,
+    }
+}
+
+fn value_to_i64(value: &Value) -> Result<i64> {
+    match value {
+        Value::Number(number) => number
+            .as_i64()
+            .ok_or_else(|| Error::new("number is not i64")),
+        Value::String(text) => text
+            .parse::<i64>()
+            .map_err(|err| Error::new(format!("invalid i64: {err}"))),
+        _ => Err(Error::new("unexpected value type for i64")),
+    }
+}
+
+fn value_to_f64(value: &Value) -> Result<f64> {
+    match value {
+        Value::Number(number) => number
+            .as_f64()
+            .ok_or_else(|| Error::new("number is not f64")),
+        Value::String(text) => text
+            .parse::<f64>()
+            .map_err(|err| Error::new(format!("invalid f64: {err}"))),
+        _ => Err(Error::new("unexpected value type for f64")),
+    }
+}
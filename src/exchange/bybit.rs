@@ -1,5 +1,8 @@
 use crate::data::exchange_loader::{map_bybit_interval, parse_bybit_klines};
-use crate::exchange::{CandleRequest, Exchange};
+use crate::exchange::rate_limit::{shared_limiter, RateLimiter};
+use crate::exchange::time_sync::{ClockSync, TimeSyncConfig};
+use crate::exchange::instrument::{InstrumentCache, InstrumentRules};
+use crate::exchange::{AmendRequest, CandleRequest, Exchange, OrderCheck};
 use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side};
 use crate::{Error, Result};
 use hmac::{Hmac, Mac};
@@ -7,7 +10,8 @@ use reqwest::blocking::Client;
 use reqwest::Method;
 use serde_json::{json, Value};
 use sha2::Sha256;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::time::Duration;
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -21,11 +25,15 @@ pub struct BybitConfig {
     pub category: String,
     pub account_type: String,
     pub default_symbol: Option<String>,
+    pub time_sync: TimeSyncConfig,
 }
 
 pub struct BybitExchange {
     client: Client,
     config: BybitConfig,
+    limiter: Arc<RateLimiter>,
+    clock: ClockSync,
+    instruments: InstrumentCache,
 }
 
 impl BybitExchange {
@@ -50,14 +58,32 @@ impl BybitExchange {
             .timeout(Duration::from_secs(config.timeout_secs.max(1)))
             .build()
             .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
-        Ok(Self { client, config })
+        let limiter = shared_limiter("bybit")?;
+        let clock = ClockSync::new("bybit", config.time_sync.clone());
+        Ok(Self {
+            client,
+            config,
+            limiter,
+            clock,
+            instruments: InstrumentCache::new(),
+        })
+    }
+
+    pub fn clock(&self) -> &ClockSync {
+        &self.clock
     }
 
-    fn timestamp_ms() -> Result<String> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|_| Error::new("system time before unix epoch"))?;
-        Ok(now.as_millis().to_string())
+    pub fn server_time_ms(&self) -> Result<i64> {
+        let json = self.public_request("/v5/market/time", Vec::new())?;
+        ensure_bybit_ok(&json)?;
+        json.get("time")
+            .and_then(|value| value.as_i64())
+            .ok_or_else(|| Error::new("bybit time missing"))
+    }
+
+    fn timestamp_ms(&self) -> Result<String> {
+        self.clock.sync_if_due(|| self.server_time_ms())?;
+        Ok(self.clock.now_ms()?.to_string())
     }
 
     fn hmac_sha256_hex(secret: &str, payload: &str) -> Result<String> {
@@ -75,7 +101,8 @@ impl BybitExchange {
         params: Vec<(String, String)>,
         body: Option<Value>,
     ) -> Result<Value> {
-        let timestamp = Self::timestamp_ms()?;
+        self.limiter.acquire(path, &params)?;
+        let timestamp = self.timestamp_ms()?;
         let recv_window = self.config.recv_window.to_string();
         let query = build_query_string(&params);
         let body_str = if let Some(body) = body {
@@ -119,6 +146,8 @@ impl BybitExchange {
         let response = request
             .send()
             .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
 
         if !response.status().is_success() {
             return Err(Error::new(format!(
@@ -133,6 +162,7 @@ impl BybitExchange {
     }
 
     fn public_request(&self, path: &str, params: Vec<(String, String)>) -> Result<Value> {
+        self.limiter.acquire(path, &params)?;
         let query = build_query_string(&params);
         let url = if query.is_empty() {
             format!("{}{}", self.config.base_url, path)
@@ -144,6 +174,8 @@ impl BybitExchange {
             .get(url)
             .send()
             .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
         if !response.status().is_success() {
             return Err(Error::new(format!(
                 "bybit response status: {}",
@@ -191,9 +223,23 @@ impl Exchange for BybitExchange {
             client_order_id: order.client_order_id.clone(),
             exchange_order_id,
             status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
         })
     }
 
+    fn test_order(&self, order: &OrderRequest, reference_price: f64) -> Result<OrderCheck> {
+        let rules = self.instruments.get_or_fetch(&order.symbol, || {
+            let params = vec![
+                ("category".to_string(), self.config.category.clone()),
+                ("symbol".to_string(), order.symbol.clone()),
+            ];
+            let json = self.public_request("/v5/market/instruments-info", params)?;
+            parse_instrument_rules(&json, &order.symbol)
+        })?;
+        Ok(OrderCheck::from_result(rules.check(order, reference_price)))
+    }
+
     fn cancel_order(&self, order_id: &str) -> Result<()> {
         let symbol = self
             .config
@@ -210,6 +256,25 @@ impl Exchange for BybitExchange {
         Ok(())
     }
 
+    fn amend_order(&self, req: &AmendRequest) -> Result<OrderAck> {
+        let body = amend_body(&self.config.category, req)?;
+        let json = self.signed_request(Method::POST, "/v5/order/amend", Vec::new(), Some(body))?;
+        ensure_bybit_ok(&json)?;
+        let exchange_order_id = json
+            .get("result")
+            .and_then(|value| value.get("orderId"))
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string());
+
+        Ok(OrderAck {
+            client_order_id: req.order.client_order_id.clone(),
+            exchange_order_id,
+            status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
+        })
+    }
+
     fn fetch_balances(&self) -> Result<Vec<Balance>> {
         let params = vec![("accountType".to_string(), self.config.account_type.clone())];
         let json = self.signed_request(
@@ -276,40 +341,24 @@ impl Exchange for BybitExchange {
             params.push(("symbol".to_string(), symbol.clone()));
         }
         let json = self.signed_request(Method::GET, "/v5/order/realtime", params, None)?;
-        ensure_bybit_ok(&json)?;
-        let list = json
-            .get("result")
-            .and_then(|value| value.get("list"))
-            .and_then(|value| value.as_array())
-            .ok_or_else(|| Error::new("bybit result.list missing"))?;
+        parse_order_list(&json)
+    }
 
-        let mut orders = Vec::new();
-        for item in list {
-            let client_order_id = item
-                .get("orderLinkId")
-                .and_then(|value| value.as_str())
-                .unwrap_or("")
-                .to_string();
-            if client_order_id.is_empty() {
-                continue;
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let mut params = vec![
+            ("category".to_string(), self.config.category.clone()),
+            ("orderLinkId".to_string(), client_order_id.to_string()),
+        ];
+        if let Some(symbol) = self.config.default_symbol.as_ref() {
+            params.push(("symbol".to_string(), symbol.clone()));
+        }
+        for path in ["/v5/order/realtime", "/v5/order/history"] {
+            let json = self.signed_request(Method::GET, path, params.clone(), None)?;
+            if let Some(order) = parse_order_list(&json)?.into_iter().next() {
+                return Ok(Some(order));
             }
-            let exchange_order_id = item
-                .get("orderId")
-                .and_then(|value| value.as_str())
-                .map(|value| value.to_string());
-            let status = item
-                .get("orderStatus")
-                .and_then(|value| value.as_str())
-                .map(parse_status)
-                .unwrap_or(OrderStatus::New);
-
-            orders.push(OrderAck {
-                client_order_id,
-                exchange_order_id,
-                status,
-            });
         }
-        Ok(orders)
+        Ok(None)
     }
 
     fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>> {
@@ -329,6 +378,24 @@ impl Exchange for BybitExchange {
     }
 }
 
+pub fn amend_body(category: &str, req: &AmendRequest) -> Result<Value> {
+    req.amended_limit()?;
+    let mut body = json!({
+        "category": category,
+        "symbol": req.order.symbol.clone(),
+        "orderLinkId": req.order.client_order_id.clone(),
+    });
+    if let Some(map) = body.as_object_mut() {
+        if let Some(quantity) = req.new_quantity {
+            map.insert("qty".to_string(), Value::String(quantity.to_string()));
+        }
+        if let Some(price) = req.new_price {
+            map.insert("price".to_string(), Value::String(price.to_string()));
+        }
+    }
+    Ok(body)
+}
+
 fn side_label(side: &Side) -> &'static str {
     match side {
         Side::Buy => "Buy",
@@ -343,6 +410,80 @@ fn order_type_label(order_type: &OrderType) -> &'static str {
     }
 }
 
+pub fn parse_instrument_rules(json: &Value, symbol: &str) -> Result<InstrumentRules> {
+    ensure_bybit_ok(json)?;
+    let item = json
+        .get("result")
+        .and_then(|value| value.get("list"))
+        .and_then(|value| value.as_array())
+        .and_then(|list| {
+            list.iter().find(|item| {
+                item.get("symbol").and_then(|value| value.as_str()) == Some(symbol)
+            })
+        })
+        .ok_or_else(|| Error::new(format!("bybit instrument not found: {symbol}")))?;
+    let lot = item
+        .get("lotSizeFilter")
+        .ok_or_else(|| Error::new("bybit lotSizeFilter missing"))?;
+    let field = |value: Option<&Value>| value.map(value_to_f64).transpose();
+    let lot_size = field(lot.get("qtyStep").or_else(|| lot.get("basePrecision")))?.unwrap_or(0.0);
+    Ok(InstrumentRules {
+        symbol: symbol.to_string(),
+        tick_size: field(item.get("priceFilter").and_then(|value| value.get("tickSize")))?
+            .unwrap_or(0.0),
+        lot_size,
+        min_qty: field(lot.get("minOrderQty"))?.unwrap_or(0.0),
+        max_qty: field(lot.get("maxOrderQty"))?.unwrap_or(0.0),
+        min_notional: field(lot.get("minOrderAmt").or_else(|| lot.get("minNotionalValue")))?
+            .unwrap_or(0.0),
+    })
+}
+
+pub fn parse_order_list(json: &Value) -> Result<Vec<OrderAck>> {
+    ensure_bybit_ok(json)?;
+    let list = json
+        .get("result")
+        .and_then(|value| value.get("list"))
+        .and_then(|value| value.as_array())
+        .ok_or_else(|| Error::new("bybit result.list missing"))?;
+
+    let mut orders = Vec::new();
+    for item in list {
+        let client_order_id = item
+            .get("orderLinkId")
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+            .to_string();
+        if client_order_id.is_empty() {
+            continue;
+        }
+        let exchange_order_id = item
+            .get("orderId")
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string());
+        let status = item
+            .get("orderStatus")
+            .and_then(|value| value.as_str())
+            .map(parse_status)
+            .unwrap_or(OrderStatus::New);
+        let number = |key: &str| {
+            item.get(key)
+                .and_then(|value| value_to_f64(value).ok())
+                .unwrap_or(0.0)
+        };
+        let (filled_qty, avg_price) = (number("cumExecQty"), number("avgPrice"));
+
+        orders.push(OrderAck {
+            client_order_id,
+            exchange_order_id,
+            status,
+            filled_qty,
+            avg_fill_price: (filled_qty > 0.0 && avg_price > 0.0).then_some(avg_price),
+        });
+    }
+    Ok(orders)
+}
+
 fn parse_status(status: &str) -> OrderStatus {
     match status {
         "New" => OrderStatus::New,
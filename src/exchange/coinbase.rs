@@ -0,0 +1,577 @@
+use crate::data::exchange_loader::{map_coinbase_granularity, parse_coinbase_candles};
+use crate::exchange::rate_limit::{shared_limiter, RateLimiter};
+use crate::exchange::time_sync::{ClockSync, TimeSyncConfig};
+use crate::exchange::{CandleRequest, Exchange, OrderCheck};
+use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side};
+use crate::{Error, Result};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use reqwest::blocking::Client;
+use reqwest::Method;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{EcdsaKeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+const JWT_TTL_SECS: i64 = 120;
+const EC_PUBLIC_KEY_OID: [u8; 9] = [0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+const PRIME256V1_OID: [u8; 10] = [0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+#[derive(Clone, Debug)]
+pub struct CoinbaseConfig {
+    pub base_url: String,
+    pub api_key: String,
+    pub api_secret: String,
+    pub timeout_secs: u64,
+    pub default_symbol: Option<String>,
+    pub time_sync: TimeSyncConfig,
+}
+
+pub struct CoinbaseExchange {
+    client: Client,
+    config: CoinbaseConfig,
+    limiter: Arc<RateLimiter>,
+    clock: ClockSync,
+    key_pair: EcdsaKeyPair,
+}
+
+impl CoinbaseExchange {
+    pub fn new(config: CoinbaseConfig) -> Result<Self> {
+        if config.base_url.trim().is_empty() {
+            return Err(Error::new("base_url must be set"));
+        }
+        if config.api_key.trim().is_empty() {
+            return Err(Error::new("api_key must be set"));
+        }
+        if config.api_secret.trim().is_empty() {
+            return Err(Error::new("api_secret must be set"));
+        }
+        let key_pair = signing_key(&config.api_secret)?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+        let limiter = shared_limiter("coinbase")?;
+        let clock = ClockSync::new("coinbase", config.time_sync.clone());
+        Ok(Self {
+            client,
+            config,
+            limiter,
+            clock,
+            key_pair,
+        })
+    }
+
+    pub fn clock(&self) -> &ClockSync {
+        &self.clock
+    }
+
+    pub fn server_time_ms(&self) -> Result<i64> {
+        let json = self.public_request("/api/v3/brokerage/time", Vec::new())?;
+        json.get("epochMillis")
+            .and_then(|value| match value {
+                Value::String(text) => text.parse::<i64>().ok(),
+                other => other.as_i64(),
+            })
+            .ok_or_else(|| Error::new("coinbase epochMillis missing"))
+    }
+
+    pub fn build_jwt(key_pair: &EcdsaKeyPair, api_key: &str, uri: &str, now_secs: i64) -> Result<String> {
+        let rng = SystemRandom::new();
+        let mut nonce = [0u8; 16];
+        rng.fill(&mut nonce)
+            .map_err(|_| Error::new("jwt nonce generation failed"))?;
+        let nonce: String = nonce.iter().map(|byte| format!("{byte:02x}")).collect();
+        let header = json!({
+            "alg": "ES256",
+            "kid": api_key,
+            "nonce": nonce,
+            "typ": "JWT",
+        });
+        let claims = json!({
+            "iss": "cdp",
+            "sub": api_key,
+            "nbf": now_secs,
+            "exp": now_secs + JWT_TTL_SECS,
+            "uri": uri,
+        });
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let signature = key_pair
+            .sign(&rng, signing_input.as_bytes())
+            .map_err(|_| Error::new("jwt signing failed"))?;
+        Ok(format!(
+            "{signing_input}.{}",
+            URL_SAFE_NO_PAD.encode(signature.as_ref())
+        ))
+    }
+
+    fn host(&self) -> &str {
+        let base = self.config.base_url.trim_end_matches('/');
+        base.split_once("://").map(|(_, host)| host).unwrap_or(base)
+    }
+
+    fn signed_request(
+        &self,
+        method: Method,
+        path: &str,
+        params: Vec<(String, String)>,
+        body: Option<Value>,
+    ) -> Result<Value> {
+        self.limiter.acquire(path, &params)?;
+        self.clock.sync_if_due(|| self.server_time_ms())?;
+        let uri = format!("{} {}{}", method.as_str(), self.host(), path);
+        let token = Self::build_jwt(
+            &self.key_pair,
+            &self.config.api_key,
+            &uri,
+            self.clock.now_ms()? / 1000,
+        )?;
+        let query = build_query_string(&params);
+        let url = if query.is_empty() {
+            format!("{}{}", self.config.base_url, path)
+        } else {
+            format!("{}{}?{}", self.config.base_url, path, query)
+        };
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("Authorization", format!("Bearer {token}"))
+            .header("Content-Type", "application/json");
+        if let Some(body) = body {
+            let body = serde_json::to_string(&body)
+                .map_err(|err| Error::new(format!("json encode failed: {err}")))?;
+            request = request.body(body);
+        }
+
+        let response = request
+            .send()
+            .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
+        let status = response.status();
+        let json = response
+            .json::<Value>()
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+        if !status.is_success() {
+            let reason = json
+                .get("message")
+                .or_else(|| json.get("error"))
+                .and_then(|value| value.as_str())
+                .unwrap_or("");
+            return Err(Error::new(format!("coinbase response status: {status} {reason}")));
+        }
+        Ok(json)
+    }
+
+    fn public_request(&self, path: &str, params: Vec<(String, String)>) -> Result<Value> {
+        self.limiter.acquire(path, &params)?;
+        let query = build_query_string(&params);
+        let url = if query.is_empty() {
+            format!("{}{}", self.config.base_url, path)
+        } else {
+            format!("{}{}?{}", self.config.base_url, path, query)
+        };
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
+        if !response.status().is_success() {
+            return Err(Error::new(format!(
+                "coinbase response status: {}",
+                response.status()
+            )));
+        }
+        response
+            .json::<Value>()
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))
+    }
+
+    fn list_orders(&self, open_only: bool) -> Result<Vec<OrderAck>> {
+        let mut orders = Vec::new();
+        self.scan_orders(open_only, |page| {
+            orders.extend(page);
+            false
+        })?;
+        Ok(orders)
+    }
+
+    fn scan_orders<F: FnMut(Vec<OrderAck>) -> bool>(&self, open_only: bool, mut visit: F) -> Result<()> {
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut params = Vec::new();
+            if open_only {
+                params.push(("order_status".to_string(), "OPEN".to_string()));
+            }
+            if let Some(symbol) = self.config.default_symbol.as_ref() {
+                params.push(("product_ids".to_string(), symbol.clone()));
+            }
+            if let Some(cursor) = cursor.as_ref() {
+                params.push(("cursor".to_string(), cursor.clone()));
+            }
+            let json = self.signed_request(
+                Method::GET,
+                "/api/v3/brokerage/orders/historical/batch",
+                params,
+                None,
+            )?;
+            if visit(parse_orders(&json)?) {
+                return Ok(());
+            }
+            match next_cursor(&json) {
+                Some(next) if cursor.as_ref() != Some(&next) => cursor = Some(next),
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+impl Exchange for CoinbaseExchange {
+    fn place_order(&self, order: &OrderRequest) -> Result<OrderAck> {
+        let json = self.signed_request(
+            Method::POST,
+            "/api/v3/brokerage/orders",
+            Vec::new(),
+            Some(order_body(order)),
+        )?;
+        let exchange_order_id = parse_order_response(&json)?;
+        Ok(OrderAck {
+            client_order_id: order.client_order_id.clone(),
+            exchange_order_id: Some(exchange_order_id),
+            status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
+        })
+    }
+
+    fn test_order(&self, order: &OrderRequest, _reference_price: f64) -> Result<OrderCheck> {
+        let mut body = order_body(order);
+        if let Some(map) = body.as_object_mut() {
+            map.remove("client_order_id");
+        }
+        let json = self.signed_request(
+            Method::POST,
+            "/api/v3/brokerage/orders/preview",
+            Vec::new(),
+            Some(body),
+        )?;
+        Ok(match preview_errors(&json) {
+            Some(reason) => OrderCheck::Rejected(reason),
+            None => OrderCheck::Accepted,
+        })
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let exchange_order_id = self
+            .list_orders(true)?
+            .into_iter()
+            .find(|ack| ack.client_order_id == order_id)
+            .and_then(|ack| ack.exchange_order_id)
+            .ok_or_else(|| Error::new(format!("coinbase open order not found: {order_id}")))?;
+        let body = json!({ "order_ids": [exchange_order_id] });
+        let json = self.signed_request(
+            Method::POST,
+            "/api/v3/brokerage/orders/batch_cancel",
+            Vec::new(),
+            Some(body),
+        )?;
+        let result = json
+            .get("results")
+            .and_then(|value| value.as_array())
+            .and_then(|list| list.first())
+            .ok_or_else(|| Error::new("coinbase cancel results missing"))?;
+        if result.get("success").and_then(|value| value.as_bool()) != Some(true) {
+            let reason = result
+                .get("failure_reason")
+                .and_then(|value| value.as_str())
+                .unwrap_or("cancel failed");
+            return Err(Error::new(format!("coinbase error: {reason}")));
+        }
+        Ok(())
+    }
+
+    fn fetch_balances(&self) -> Result<Vec<Balance>> {
+        let mut balances = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut params = vec![("limit".to_string(), "250".to_string())];
+            if let Some(cursor) = cursor.as_ref() {
+                params.push(("cursor".to_string(), cursor.clone()));
+            }
+            let json = self.signed_request(Method::GET, "/api/v3/brokerage/accounts", params, None)?;
+            balances.extend(parse_accounts(&json)?);
+            let has_next = json
+                .get("has_next")
+                .and_then(|value| value.as_bool())
+                .unwrap_or(false);
+            cursor = json
+                .get("cursor")
+                .and_then(|value| value.as_str())
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string());
+            if !has_next || cursor.is_none() {
+                break;
+            }
+        }
+        Ok(balances)
+    }
+
+    fn fetch_positions(&self) -> Result<Vec<Position>> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_open_orders(&self) -> Result<Vec<OrderAck>> {
+        self.list_orders(true)
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let mut found = None;
+        self.scan_orders(false, |page| {
+            found = page
+                .into_iter()
+                .find(|ack| ack.client_order_id == client_order_id);
+            found.is_some()
+        })?;
+        Ok(found)
+    }
+
+    fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>> {
+        let granularity = map_coinbase_granularity(&req.interval)?;
+        let params = vec![
+            ("start".to_string(), (req.start_time / 1000).to_string()),
+            ("end".to_string(), (req.end_time / 1000).to_string()),
+            ("granularity".to_string(), granularity),
+        ];
+        let path = format!("/api/v3/brokerage/market/products/{}/candles", req.symbol);
+        let json = self.public_request(&path, params)?;
+        let mut parsed = parse_coinbase_candles(
+            &serde_json::to_string(&json).map_err(|err| Error::new(format!("json encode failed: {err}")))?,
+        )?;
+        parsed.candles.sort_by_key(|candle| candle.time);
+        Ok(parsed.candles)
+    }
+}
+
+pub fn signing_key(pem: &str) -> Result<EcdsaKeyPair> {
+    let pem = pem.replace("\\n", "\n");
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+        .collect();
+    let der = STANDARD
+        .decode(body)
+        .map_err(|err| Error::new(format!("coinbase api_secret is not a PEM key: {err}")))?;
+    let pkcs8 = if pem.contains("BEGIN EC PRIVATE KEY") {
+        sec1_to_pkcs8(&der)
+    } else {
+        der
+    };
+    EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &pkcs8, &SystemRandom::new())
+        .map_err(|err| Error::new(format!("coinbase api_secret rejected: {err}")))
+}
+
+pub fn order_body(order: &OrderRequest) -> Value {
+    let configuration = match order.order_type {
+        OrderType::Market => json!({
+            "market_market_ioc": { "base_size": order.quantity.to_string() }
+        }),
+        OrderType::Limit { price } => json!({
+            "limit_limit_gtc": {
+                "base_size": order.quantity.to_string(),
+                "limit_price": price.to_string(),
+                "post_only": false,
+            }
+        }),
+    };
+    json!({
+        "client_order_id": order.client_order_id.clone(),
+        "product_id": order.symbol.clone(),
+        "side": side_label(&order.side),
+        "order_configuration": configuration,
+    })
+}
+
+pub fn parse_order_response(json: &Value) -> Result<String> {
+    if json.get("success").and_then(|value| value.as_bool()) == Some(true) {
+        return json
+            .get("success_response")
+            .and_then(|value| value.get("order_id"))
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string())
+            .ok_or_else(|| Error::new("coinbase order_id missing"));
+    }
+    let error = json.get("error_response");
+    let reason = ["message", "preview_failure_reason", "error"]
+        .iter()
+        .find_map(|key| {
+            error
+                .and_then(|value| value.get(*key))
+                .and_then(|value| value.as_str())
+                .filter(|value| !value.is_empty())
+        })
+        .unwrap_or("order rejected");
+    Err(Error::new(format!("coinbase error: {reason}")))
+}
+
+pub fn preview_errors(json: &Value) -> Option<String> {
+    let errors = json.get("errs").and_then(|value| value.as_array())?;
+    let messages: Vec<&str> = errors.iter().filter_map(|value| value.as_str()).collect();
+    if messages.is_empty() {
+        None
+    } else {
+        Some(messages.join("; "))
+    }
+}
+
+pub fn parse_accounts(json: &Value) -> Result<Vec<Balance>> {
+    let accounts = json
+        .get("accounts")
+        .and_then(|value| value.as_array())
+        .ok_or_else(|| Error::new("coinbase accounts missing"))?;
+    let mut balances = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        let asset = account
+            .get("currency")
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+            .to_string();
+        if asset.is_empty() {
+            continue;
+        }
+        let amount = |key: &str| -> Result<f64> {
+            match account.get(key).and_then(|value| value.get("value")) {
+                Some(value) => value_to_f64(value),
+                None => Ok(0.0),
+            }
+        };
+        balances.push(Balance {
+            asset,
+            free: amount("available_balance")?,
+            locked: amount("hold")?,
+        });
+    }
+    Ok(balances)
+}
+
+pub fn parse_orders(json: &Value) -> Result<Vec<OrderAck>> {
+    let orders = json
+        .get("orders")
+        .and_then(|value| value.as_array())
+        .ok_or_else(|| Error::new("coinbase orders missing"))?;
+    Ok(orders.iter().filter_map(parse_order).collect())
+}
+
+pub fn next_cursor(json: &Value) -> Option<String> {
+    if json.get("has_next").and_then(|value| value.as_bool()) != Some(true) {
+        return None;
+    }
+    json.get("cursor")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+}
+
+pub fn parse_order(item: &Value) -> Option<OrderAck> {
+    let client_order_id = item
+        .get("client_order_id")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.is_empty())?
+        .to_string();
+    let exchange_order_id = item
+        .get("order_id")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string());
+    let filled = item
+        .get("filled_size")
+        .and_then(|value| value_to_f64(value).ok())
+        .unwrap_or(0.0);
+    let status = item
+        .get("status")
+        .and_then(|value| value.as_str())
+        .map(|status| parse_status(status, filled))
+        .unwrap_or(OrderStatus::New);
+    let avg_price = item
+        .get("average_filled_price")
+        .and_then(|value| value_to_f64(value).ok())
+        .unwrap_or(0.0);
+    Some(OrderAck {
+        client_order_id,
+        exchange_order_id,
+        status,
+        filled_qty: filled,
+        avg_fill_price: (filled > 0.0 && avg_price > 0.0).then_some(avg_price),
+    })
+}
+
+fn parse_status(status: &str, filled: f64) -> OrderStatus {
+    match status {
+        "OPEN" if filled > 0.0 => OrderStatus::PartiallyFilled,
+        "OPEN" | "PENDING" | "QUEUED" => OrderStatus::New,
+        "FILLED" => OrderStatus::Filled,
+        "CANCELLED" | "EXPIRED" => OrderStatus::Canceled,
+        "FAILED" => OrderStatus::Rejected,
+        _ => OrderStatus::New,
+    }
+}
+
+fn sec1_to_pkcs8(sec1: &[u8]) -> Vec<u8> {
+    let mut algorithm = EC_PUBLIC_KEY_OID.to_vec();
+    algorithm.extend_from_slice(&PRIME256V1_OID);
+    let mut body = vec![0x02, 0x01, 0x00];
+    body.extend(der_element(0x30, &algorithm));
+    body.extend(der_element(0x04, sec1));
+    der_element(0x30, &body)
+}
+
+fn der_element(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|byte| *byte == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+fn side_label(side: &Side) -> &'static str {
+    match side {
+        Side::Buy => "BUY",
+        Side::Sell => "SELL",
+    }
+}
+
+fn build_query_string(params: &[(String, String)]) -> String {
+    params
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+fn value_to_f64(value: &Value) -> Result<f64> {
+    match value {
+        Value::Number(number) => number
+            .as_f64()
+            .ok_or_else(|| Error::new("number is not f64")),
+        Value::String(text) => text
+            .parse::<f64>()
+            .map_err(|err| Error::new(format!("invalid f64: {err}"))),
+        _ => Err(Error::new("unexpected value type for f64")),
+    }
+}
@@ -0,0 +1,99 @@
+use crate::models::{OrderRequest, OrderType};
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const STEP_TOLERANCE: f64 = 1e-6;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstrumentRules {
+    pub symbol: String,
+    pub tick_size: f64,
+    pub lot_size: f64,
+    pub min_qty: f64,
+    pub max_qty: f64,
+    pub min_notional: f64,
+}
+
+impl InstrumentRules {
+    pub fn check(&self, order: &OrderRequest, reference_price: f64) -> Result<()> {
+        if order.symbol != self.symbol {
+            return Err(Error::new(format!("unknown symbol {}", order.symbol)));
+        }
+        if order.quantity < self.min_qty {
+            return Err(Error::new(format!(
+                "quantity {} below minimum {}",
+                order.quantity, self.min_qty
+            )));
+        }
+        if self.max_qty > 0.0 && order.quantity > self.max_qty {
+            return Err(Error::new(format!(
+                "quantity {} above maximum {}",
+                order.quantity, self.max_qty
+            )));
+        }
+        if !is_step(order.quantity, self.lot_size) {
+            return Err(Error::new(format!(
+                "quantity {} not a multiple of lot size {}",
+                order.quantity, self.lot_size
+            )));
+        }
+        let price = match order.order_type {
+            OrderType::Limit { price } => {
+                if !is_step(price, self.tick_size) {
+                    return Err(Error::new(format!(
+                        "price {price} not a multiple of tick size {}",
+                        self.tick_size
+                    )));
+                }
+                price
+            }
+            OrderType::Market => reference_price,
+        };
+        let notional = order.quantity * price;
+        if notional < self.min_notional {
+            return Err(Error::new(format!(
+                "notional {notional} below minimum {}",
+                self.min_notional
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InstrumentCache {
+    rules: Mutex<HashMap<String, InstrumentRules>>,
+}
+
+impl InstrumentCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_or_fetch<F>(&self, symbol: &str, fetch: F) -> Result<InstrumentRules>
+    where
+        F: FnOnce() -> Result<InstrumentRules>,
+    {
+        if let Some(rules) = self.lock()?.get(symbol) {
+            return Ok(rules.clone());
+        }
+        let rules = fetch()?;
+        self.lock()?.insert(symbol.to_string(), rules.clone());
+        Ok(rules)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, InstrumentRules>>> {
+        self.rules
+            .lock()
+            .map_err(|_| Error::new("instrument cache lock poisoned"))
+    }
+}
+
+fn is_step(value: f64, step: f64) -> bool {
+    if step <= 0.0 {
+        return true;
+    }
+    let ratio = value / step;
+    (ratio - ratio.round()).abs() < STEP_TOLERANCE
+}
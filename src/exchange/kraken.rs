@@ -0,0 +1,415 @@
+use crate::data::exchange_loader::{map_kraken_interval, parse_kraken_ohlc};
+use crate::exchange::rate_limit::{shared_limiter, RateLimiter};
+use crate::exchange::{CandleRequest, Exchange, OrderCheck};
+use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side};
+use crate::{Error, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use reqwest::blocking::Client;
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha512};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+type HmacSha512 = Hmac<Sha512>;
+
+#[derive(Clone, Debug)]
+pub struct KrakenConfig {
+    pub base_url: String,
+    pub api_key: String,
+    pub api_secret: String,
+    pub timeout_secs: u64,
+    pub default_symbol: Option<String>,
+}
+
+pub struct KrakenExchange {
+    client: Client,
+    config: KrakenConfig,
+    limiter: Arc<RateLimiter>,
+    last_nonce: AtomicI64,
+}
+
+impl KrakenExchange {
+    pub fn new(config: KrakenConfig) -> Result<Self> {
+        if config.base_url.trim().is_empty() {
+            return Err(Error::new("base_url must be set"));
+        }
+        if config.api_key.trim().is_empty() {
+            return Err(Error::new("api_key must be set"));
+        }
+        if config.api_secret.trim().is_empty() {
+            return Err(Error::new("api_secret must be set"));
+        }
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
+        let limiter = shared_limiter("kraken")?;
+        Ok(Self {
+            client,
+            config,
+            limiter,
+            last_nonce: AtomicI64::new(0),
+        })
+    }
+
+    pub fn api_sign(secret: &str, path: &str, nonce: &str, post_data: &str) -> Result<String> {
+        let key = STANDARD
+            .decode(secret.trim())
+            .map_err(|err| Error::new(format!("kraken api_secret must be base64: {err}")))?;
+        let mut sha = Sha256::new();
+        sha.update(nonce.as_bytes());
+        sha.update(post_data.as_bytes());
+        let digest = sha.finalize();
+        let mut mac = HmacSha512::new_from_slice(&key).map_err(|_| Error::new("invalid key"))?;
+        mac.update(path.as_bytes());
+        mac.update(&digest);
+        Ok(STANDARD.encode(mac.finalize().into_bytes()))
+    }
+
+    fn next_nonce(&self) -> Result<String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| Error::new("system time before unix epoch"))?
+            .as_millis() as i64;
+        let mut last = self.last_nonce.load(Ordering::SeqCst);
+        loop {
+            let next = now.max(last + 1);
+            match self
+                .last_nonce
+                .compare_exchange(last, next, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => return Ok(next.to_string()),
+                Err(current) => last = current,
+            }
+        }
+    }
+
+    fn private_request(&self, method: &str, params: Vec<(String, String)>) -> Result<Value> {
+        let path = format!("/0/private/{method}");
+        self.limiter.acquire(&path, &params)?;
+        let nonce = self.next_nonce()?;
+        let mut form = vec![("nonce".to_string(), nonce.clone())];
+        form.extend(params);
+        let post_data = build_query_string(&form);
+        let sign = Self::api_sign(&self.config.api_secret, &path, &nonce, &post_data)?;
+
+        let url = format!("{}{}", self.config.base_url, path);
+        let response = self
+            .client
+            .post(url)
+            .header("API-Key", self.config.api_key.as_str())
+            .header("API-Sign", sign)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(post_data)
+            .send()
+            .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(&path, response.status(), response.headers())?;
+        if !response.status().is_success() {
+            return Err(Error::new(format!(
+                "kraken response status: {}",
+                response.status()
+            )));
+        }
+        response
+            .json::<Value>()
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))
+    }
+
+    fn public_request(&self, path: &str, params: Vec<(String, String)>) -> Result<Value> {
+        self.limiter.acquire(path, &params)?;
+        let query = build_query_string(&params);
+        let url = if query.is_empty() {
+            format!("{}{}", self.config.base_url, path)
+        } else {
+            format!("{}{}?{}", self.config.base_url, path, query)
+        };
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
+        if !response.status().is_success() {
+            return Err(Error::new(format!(
+                "kraken response status: {}",
+                response.status()
+            )));
+        }
+        response
+            .json::<Value>()
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))
+    }
+
+    fn query_orders(&self, method: &str, key: &str, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let params = vec![("cl_ord_id".to_string(), client_order_id.to_string())];
+        let json = self.private_request(method, params)?;
+        Ok(parse_orders(&json, key)?
+            .into_iter()
+            .map(|(_, ack)| ack)
+            .find(|ack| ack.client_order_id == client_order_id))
+    }
+}
+
+impl Exchange for KrakenExchange {
+    fn place_order(&self, order: &OrderRequest) -> Result<OrderAck> {
+        let json = self.private_request("AddOrder", order_params(order))?;
+        ensure_kraken_ok(&json)?;
+        let exchange_order_id = json
+            .get("result")
+            .and_then(|value| value.get("txid"))
+            .and_then(|value| value.as_array())
+            .and_then(|array| array.first())
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string());
+
+        Ok(OrderAck {
+            client_order_id: order.client_order_id.clone(),
+            exchange_order_id,
+            status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
+        })
+    }
+
+    fn test_order(&self, order: &OrderRequest, _reference_price: f64) -> Result<OrderCheck> {
+        let mut params = order_params(order);
+        params.push(("validate".to_string(), "true".to_string()));
+        let json = self.private_request("AddOrder", params)?;
+        Ok(match rejection_reason(&json) {
+            Some(reason) => OrderCheck::Rejected(reason),
+            None => OrderCheck::Accepted,
+        })
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let params = vec![("cl_ord_id".to_string(), order_id.to_string())];
+        let json = self.private_request("CancelOrder", params)?;
+        ensure_kraken_ok(&json)
+    }
+
+    fn fetch_balances(&self) -> Result<Vec<Balance>> {
+        let json = self.private_request("BalanceEx", Vec::new())?;
+        parse_balances(&json)
+    }
+
+    fn fetch_positions(&self) -> Result<Vec<Position>> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_open_orders(&self) -> Result<Vec<OrderAck>> {
+        let json = self.private_request("OpenOrders", Vec::new())?;
+        let orders = parse_orders(&json, "open")?;
+        let Some(symbol) = self.config.default_symbol.as_ref() else {
+            return Ok(orders.into_iter().map(|(_, ack)| ack).collect());
+        };
+        Ok(orders
+            .into_iter()
+            .filter(|(pair, _)| pair.is_empty() || same_pair(pair, symbol))
+            .map(|(_, ack)| ack)
+            .collect())
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        if let Some(ack) = self.query_orders("OpenOrders", "open", client_order_id)? {
+            return Ok(Some(ack));
+        }
+        self.query_orders("ClosedOrders", "closed", client_order_id)
+    }
+
+    fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>> {
+        let interval = map_kraken_interval(&req.interval)?;
+        let params = vec![
+            ("pair".to_string(), req.symbol.clone()),
+            ("interval".to_string(), interval),
+            ("since".to_string(), (req.start_time / 1000 - 1).to_string()),
+        ];
+        let json = self.public_request("/0/public/OHLC", params)?;
+        let parsed = parse_kraken_ohlc(
+            &serde_json::to_string(&json).map_err(|err| Error::new(format!("json encode failed: {err}")))?,
+        )?;
+        Ok(parsed
+            .candles
+            .into_iter()
+            .filter(|candle| candle.time * 1000 <= req.end_time)
+            .collect())
+    }
+}
+
+pub fn order_params(order: &OrderRequest) -> Vec<(String, String)> {
+    let mut params = vec![
+        ("ordertype".to_string(), order_type_label(&order.order_type).to_string()),
+        ("type".to_string(), side_label(&order.side).to_string()),
+        ("volume".to_string(), order.quantity.to_string()),
+        ("pair".to_string(), order.symbol.clone()),
+    ];
+    if let OrderType::Limit { price } = order.order_type {
+        params.push(("price".to_string(), price.to_string()));
+    }
+    params.push(("cl_ord_id".to_string(), order.client_order_id.clone()));
+    params
+}
+
+pub fn rejection_reason(json: &Value) -> Option<String> {
+    let errors = json.get("error").and_then(|value| value.as_array())?;
+    let messages: Vec<&str> = errors.iter().filter_map(|value| value.as_str()).collect();
+    if messages.is_empty() {
+        None
+    } else {
+        Some(messages.join("; "))
+    }
+}
+
+pub fn parse_balances(json: &Value) -> Result<Vec<Balance>> {
+    ensure_kraken_ok(json)?;
+    let result = json
+        .get("result")
+        .and_then(|value| value.as_object())
+        .ok_or_else(|| Error::new("kraken result missing"))?;
+    let mut balances = Vec::with_capacity(result.len());
+    for (asset, entry) in result {
+        let (total, hold) = match entry {
+            Value::Object(_) => (
+                value_to_f64(entry.get("balance").unwrap_or(&Value::Null))?,
+                match entry.get("hold_trade") {
+                    Some(value) => value_to_f64(value)?,
+                    None => 0.0,
+                },
+            ),
+            other => (value_to_f64(other)?, 0.0),
+        };
+        balances.push(Balance {
+            asset: normalize_asset(asset),
+            free: total - hold,
+            locked: hold,
+        });
+    }
+    Ok(balances)
+}
+
+pub fn parse_orders(json: &Value, key: &str) -> Result<Vec<(String, OrderAck)>> {
+    ensure_kraken_ok(json)?;
+    let orders = json
+        .get("result")
+        .and_then(|value| value.get(key))
+        .and_then(|value| value.as_object())
+        .ok_or_else(|| Error::new(format!("kraken result.{key} missing")))?;
+    Ok(orders
+        .iter()
+        .filter_map(|(txid, item)| {
+            let pair = item
+                .get("descr")
+                .and_then(|value| value.get("pair"))
+                .and_then(|value| value.as_str())
+                .unwrap_or("")
+                .to_string();
+            parse_order(txid, item).map(|ack| (pair, ack))
+        })
+        .collect())
+}
+
+pub fn parse_order(txid: &str, item: &Value) -> Option<OrderAck> {
+    let client_order_id = item
+        .get("cl_ord_id")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.is_empty())?
+        .to_string();
+    let executed = item
+        .get("vol_exec")
+        .and_then(|value| value_to_f64(value).ok())
+        .unwrap_or(0.0);
+    let status = item
+        .get("status")
+        .and_then(|value| value.as_str())
+        .map(|status| parse_status(status, executed))
+        .unwrap_or(OrderStatus::New);
+    let avg_price = item
+        .get("price")
+        .and_then(|value| value_to_f64(value).ok())
+        .unwrap_or(0.0);
+    Some(OrderAck {
+        client_order_id,
+        exchange_order_id: Some(txid.to_string()),
+        status,
+        filled_qty: executed,
+        avg_fill_price: (executed > 0.0 && avg_price > 0.0).then_some(avg_price),
+    })
+}
+
+pub fn normalize_asset(asset: &str) -> String {
+    let trimmed = match asset.split_once('.') {
+        Some((base, _)) => base,
+        None => asset,
+    };
+    let stripped = if trimmed.len() == 4 && (trimmed.starts_with('X') || trimmed.starts_with('Z')) {
+        &trimmed[1..]
+    } else {
+        trimmed
+    };
+    match stripped {
+        "XBT" => "BTC".to_string(),
+        "XDG" => "DOGE".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn same_pair(left: &str, right: &str) -> bool {
+    let canonical = |pair: &str| pair.replace('/', "").replace("XBT", "BTC").to_uppercase();
+    canonical(left) == canonical(right)
+}
+
+fn parse_status(status: &str, executed: f64) -> OrderStatus {
+    match status {
+        "pending" => OrderStatus::New,
+        "open" if executed > 0.0 => OrderStatus::PartiallyFilled,
+        "open" => OrderStatus::New,
+        "closed" => OrderStatus::Filled,
+        "canceled" | "expired" => OrderStatus::Canceled,
+        _ => OrderStatus::New,
+    }
+}
+
+fn side_label(side: &Side) -> &'static str {
+    match side {
+        Side::Buy => "buy",
+        Side::Sell => "sell",
+    }
+}
+
+fn order_type_label(order_type: &OrderType) -> &'static str {
+    match order_type {
+        OrderType::Market => "market",
+        OrderType::Limit { .. } => "limit",
+    }
+}
+
+fn build_query_string(params: &[(String, String)]) -> String {
+    params
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+fn ensure_kraken_ok(json: &Value) -> Result<()> {
+    match rejection_reason(json) {
+        Some(reason) => Err(Error::new(format!("kraken error: {reason}"))),
+        None => Ok(()),
+    }
+}
+
+fn value_to_f64(value: &Value) -> Result<f64> {
+    match value {
+        Value::Number(number) => number
+            .as_f64()
+            .ok_or_else(|| Error::new("number is not f64")),
+        Value::String(text) => text
+            .parse::<f64>()
+            .map_err(|err| Error::new(format!("invalid f64: {err}"))),
+        _ => Err(Error::new("unexpected value type for f64")),
+    }
+}
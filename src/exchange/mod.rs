@@ -1,12 +1,20 @@
 pub mod adapter;
 pub mod binance;
+pub mod binance_futures;
 pub mod bybit;
+pub mod coinbase;
+pub mod instrument;
+pub mod kraken;
 pub mod okx;
+pub mod open_orders;
+pub mod rate_limit;
 pub mod rest;
+pub mod sim;
 pub mod sync;
+pub mod time_sync;
 
-use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, Position};
-use crate::Result;
+use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position};
+use crate::{Error, Result};
 
 pub struct CandleRequest {
     pub symbol: String,
@@ -15,6 +23,58 @@ pub struct CandleRequest {
     pub end_time: i64,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct AmendRequest {
+    pub order: OrderRequest,
+    pub new_price: Option<f64>,
+    pub new_quantity: Option<f64>,
+}
+
+impl AmendRequest {
+    pub fn amended_limit(&self) -> Result<(f64, f64)> {
+        let price = match self.order.order_type {
+            OrderType::Limit { price } => price,
+            OrderType::Market => return Err(Error::new("only limit orders can be amended")),
+        };
+        if self.new_price.is_none() && self.new_quantity.is_none() {
+            return Err(Error::new("amend requires a new price or quantity"));
+        }
+        let price = self.new_price.unwrap_or(price);
+        let quantity = self.new_quantity.unwrap_or(self.order.quantity);
+        if price <= 0.0 {
+            return Err(Error::new("amend price must be positive"));
+        }
+        if quantity <= 0.0 {
+            return Err(Error::new("amend quantity must be positive"));
+        }
+        Ok((price, quantity))
+    }
+
+    pub fn amended(&self) -> Result<OrderRequest> {
+        let (price, quantity) = self.amended_limit()?;
+        Ok(OrderRequest {
+            order_type: OrderType::Limit { price },
+            quantity,
+            ..self.order.clone()
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OrderCheck {
+    Accepted,
+    Rejected(String),
+}
+
+impl OrderCheck {
+    pub fn from_result(result: Result<()>) -> Self {
+        match result {
+            Ok(()) => OrderCheck::Accepted,
+            Err(err) => OrderCheck::Rejected(err.message),
+        }
+    }
+}
+
 pub trait Exchange {
     fn place_order(&self, order: &OrderRequest) -> Result<OrderAck>;
     fn cancel_order(&self, order_id: &str) -> Result<()>;
@@ -22,6 +82,27 @@ pub trait Exchange {
     fn fetch_positions(&self) -> Result<Vec<Position>>;
     fn fetch_open_orders(&self) -> Result<Vec<OrderAck>>;
     fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>>;
+
+    fn amend_order(&self, req: &AmendRequest) -> Result<OrderAck> {
+        Err(Error::new(format!(
+            "amend_order not supported for {}",
+            req.order.client_order_id
+        )))
+    }
+
+    fn test_order(&self, order: &OrderRequest, _reference_price: f64) -> Result<OrderCheck> {
+        Err(Error::new(format!(
+            "test_order not supported for {}",
+            order.client_order_id
+        )))
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        Ok(self
+            .fetch_open_orders()?
+            .into_iter()
+            .find(|order| order.client_order_id == client_order_id))
+    }
 }
 
 pub fn new_order_ack(order: &OrderRequest) -> OrderAck {
@@ -29,5 +110,7 @@ pub fn new_order_ack(order: &OrderRequest) -> OrderAck {
         client_order_id: order.client_order_id.clone(),
         exchange_order_id: None,
         status: OrderStatus::New,
+        filled_qty: 0.0,
+        avg_fill_price: None,
     }
 }
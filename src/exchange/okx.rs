@@ -1,19 +1,25 @@
 use crate::data::exchange_loader::{map_okx_interval, parse_okx_candles};
-use crate::exchange::{CandleRequest, Exchange};
+use crate::exchange::rate_limit::{shared_limiter, RateLimiter};
+use crate::exchange::time_sync::{ClockSync, TimeSyncConfig};
+use crate::exchange::instrument::{InstrumentCache, InstrumentRules};
+use crate::exchange::{AmendRequest, CandleRequest, Exchange, OrderCheck};
 use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side};
 use crate::{Error, Result};
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
-use chrono::{SecondsFormat, Utc};
+use chrono::{DateTime, SecondsFormat};
 use hmac::{Hmac, Mac};
 use reqwest::blocking::Client;
 use reqwest::Method;
 use serde_json::{json, Value};
 use sha2::Sha256;
+use std::sync::Arc;
 use std::time::Duration;
 
 type HmacSha256 = Hmac<Sha256>;
 
+const ORDER_NOT_FOUND: &str = "51603";
+
 #[derive(Clone, Debug)]
 pub struct OkxConfig {
     pub base_url: String,
@@ -22,11 +28,15 @@ pub struct OkxConfig {
     pub passphrase: String,
     pub timeout_secs: u64,
     pub default_symbol: Option<String>,
+    pub time_sync: TimeSyncConfig,
 }
 
 pub struct OkxExchange {
     client: Client,
     config: OkxConfig,
+    limiter: Arc<RateLimiter>,
+    clock: ClockSync,
+    instruments: InstrumentCache,
 }
 
 impl OkxExchange {
@@ -47,11 +57,38 @@ impl OkxExchange {
             .timeout(Duration::from_secs(config.timeout_secs.max(1)))
             .build()
             .map_err(|err| Error::new(format!("http client build failed: {err}")))?;
-        Ok(Self { client, config })
+        let limiter = shared_limiter("okx")?;
+        let clock = ClockSync::new("okx", config.time_sync.clone());
+        Ok(Self {
+            client,
+            config,
+            limiter,
+            clock,
+            instruments: InstrumentCache::new(),
+        })
+    }
+
+    pub fn clock(&self) -> &ClockSync {
+        &self.clock
     }
 
-    fn timestamp() -> String {
-        Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+    pub fn server_time_ms(&self) -> Result<i64> {
+        let json = self.public_request("/api/v5/public/time", Vec::new())?;
+        ensure_okx_ok(&json)?;
+        json.get("data")
+            .and_then(|value| value.as_array())
+            .and_then(|array| array.first())
+            .and_then(|value| value.get("ts"))
+            .and_then(|value| value.as_str())
+            .and_then(|value| value.parse::<i64>().ok())
+            .ok_or_else(|| Error::new("okx ts missing"))
+    }
+
+    fn timestamp(&self) -> Result<String> {
+        self.clock.sync_if_due(|| self.server_time_ms())?;
+        let now = DateTime::from_timestamp_millis(self.clock.now_ms()?)
+            .ok_or_else(|| Error::new("invalid timestamp"))?;
+        Ok(now.to_rfc3339_opts(SecondsFormat::Millis, true))
     }
 
     fn sign(&self, timestamp: &str, method: &str, request_path: &str, body: &str) -> Result<String> {
@@ -71,7 +108,8 @@ impl OkxExchange {
         params: Vec<(String, String)>,
         body: Option<Value>,
     ) -> Result<Value> {
-        let timestamp = Self::timestamp();
+        self.limiter.acquire(path, &params)?;
+        let timestamp = self.timestamp()?;
         let query = build_query_string(&params);
         let request_path = if query.is_empty() {
             path.to_string()
@@ -103,6 +141,8 @@ impl OkxExchange {
         let response = request
             .send()
             .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
         if !response.status().is_success() {
             return Err(Error::new(format!(
                 "okx response status: {}",
@@ -116,6 +156,7 @@ impl OkxExchange {
     }
 
     fn public_request(&self, path: &str, params: Vec<(String, String)>) -> Result<Value> {
+        self.limiter.acquire(path, &params)?;
         let query = build_query_string(&params);
         let url = if query.is_empty() {
             format!("{}{}", self.config.base_url, path)
@@ -127,6 +168,8 @@ impl OkxExchange {
             .get(url)
             .send()
             .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        self.limiter
+            .observe(path, response.status(), response.headers())?;
         if !response.status().is_success() {
             return Err(Error::new(format!(
                 "okx response status: {}",
@@ -170,9 +213,23 @@ impl Exchange for OkxExchange {
             client_order_id: order.client_order_id.clone(),
             exchange_order_id,
             status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
         })
     }
 
+    fn test_order(&self, order: &OrderRequest, reference_price: f64) -> Result<OrderCheck> {
+        let rules = self.instruments.get_or_fetch(&order.symbol, || {
+            let params = vec![
+                ("instType".to_string(), "SPOT".to_string()),
+                ("instId".to_string(), order.symbol.clone()),
+            ];
+            let json = self.public_request("/api/v5/public/instruments", params)?;
+            parse_instrument_rules(&json, &order.symbol)
+        })?;
+        Ok(OrderCheck::from_result(rules.check(order, reference_price)))
+    }
+
     fn cancel_order(&self, order_id: &str) -> Result<()> {
         let symbol = self
             .config
@@ -189,6 +246,28 @@ impl Exchange for OkxExchange {
         Ok(())
     }
 
+    fn amend_order(&self, req: &AmendRequest) -> Result<OrderAck> {
+        let body = amend_body(req)?;
+        let json =
+            self.signed_request(Method::POST, "/api/v5/trade/amend-order", Vec::new(), Some(body))?;
+        ensure_okx_ok(&json)?;
+        let exchange_order_id = json
+            .get("data")
+            .and_then(|value| value.as_array())
+            .and_then(|array| array.first())
+            .and_then(|value| value.get("ordId"))
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string());
+
+        Ok(OrderAck {
+            client_order_id: req.order.client_order_id.clone(),
+            exchange_order_id,
+            status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
+        })
+    }
+
     fn fetch_balances(&self) -> Result<Vec<Balance>> {
         let json = self.signed_request(Method::GET, "/api/v5/account/balance", Vec::new(), None)?;
         ensure_okx_ok(&json)?;
@@ -227,33 +306,29 @@ impl Exchange for OkxExchange {
             .and_then(|value| value.as_array())
             .ok_or_else(|| Error::new("okx data missing"))?;
 
-        let mut orders = Vec::new();
-        for item in list {
-            let client_order_id = item
-                .get("clOrdId")
-                .and_then(|value| value.as_str())
-                .unwrap_or("")
-                .to_string();
-            if client_order_id.is_empty() {
-                continue;
-            }
-            let exchange_order_id = item
-                .get("ordId")
-                .and_then(|value| value.as_str())
-                .map(|value| value.to_string());
-            let status = item
-                .get("state")
-                .and_then(|value| value.as_str())
-                .map(parse_status)
-                .unwrap_or(OrderStatus::New);
-
-            orders.push(OrderAck {
-                client_order_id,
-                exchange_order_id,
-                status,
-            });
+        Ok(list.iter().filter_map(parse_order).collect())
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let symbol = self
+            .config
+            .default_symbol
+            .as_ref()
+            .ok_or_else(|| Error::new("default_symbol must be set for fetch_order"))?;
+        let params = vec![
+            ("instId".to_string(), symbol.clone()),
+            ("clOrdId".to_string(), client_order_id.to_string()),
+        ];
+        let json = self.signed_request(Method::GET, "/api/v5/trade/order", params, None)?;
+        if json.get("code").and_then(|value| value.as_str()) == Some(ORDER_NOT_FOUND) {
+            return Ok(None);
         }
-        Ok(orders)
+        ensure_okx_ok(&json)?;
+        Ok(json
+            .get("data")
+            .and_then(|value| value.as_array())
+            .and_then(|list| list.first())
+            .and_then(parse_order))
     }
 
     fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>> {
@@ -272,6 +347,23 @@ impl Exchange for OkxExchange {
     }
 }
 
+pub fn amend_body(req: &AmendRequest) -> Result<Value> {
+    req.amended_limit()?;
+    let mut body = json!({
+        "instId": req.order.symbol.clone(),
+        "clOrdId": req.order.client_order_id.clone(),
+    });
+    if let Some(map) = body.as_object_mut() {
+        if let Some(quantity) = req.new_quantity {
+            map.insert("newSz".to_string(), Value::String(quantity.to_string()));
+        }
+        if let Some(price) = req.new_price {
+            map.insert("newPx".to_string(), Value::String(price.to_string()));
+        }
+    }
+    Ok(body)
+}
+
 fn side_label(side: &Side) -> &'static str {
     match side {
         Side::Buy => "buy",
@@ -286,6 +378,64 @@ fn order_type_label(order_type: &OrderType) -> &'static str {
     }
 }
 
+pub fn parse_instrument_rules(json: &Value, symbol: &str) -> Result<InstrumentRules> {
+    ensure_okx_ok(json)?;
+    let item = json
+        .get("data")
+        .and_then(|value| value.as_array())
+        .and_then(|list| {
+            list.iter().find(|item| {
+                item.get("instId").and_then(|value| value.as_str()) == Some(symbol)
+            })
+        })
+        .ok_or_else(|| Error::new(format!("okx instrument not found: {symbol}")))?;
+    let field = |key: &str| -> Result<f64> {
+        match item.get(key) {
+            Some(Value::String(text)) if text.is_empty() => Ok(0.0),
+            Some(value) => value_to_f64(value),
+            None => Ok(0.0),
+        }
+    };
+    Ok(InstrumentRules {
+        symbol: symbol.to_string(),
+        tick_size: field("tickSz")?,
+        lot_size: field("lotSz")?,
+        min_qty: field("minSz")?,
+        max_qty: field("maxLmtSz")?,
+        min_notional: 0.0,
+    })
+}
+
+pub fn parse_order(item: &Value) -> Option<OrderAck> {
+    let client_order_id = item
+        .get("clOrdId")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.is_empty())?
+        .to_string();
+    let exchange_order_id = item
+        .get("ordId")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string());
+    let status = item
+        .get("state")
+        .and_then(|value| value.as_str())
+        .map(parse_status)
+        .unwrap_or(OrderStatus::New);
+    let number = |key: &str| {
+        item.get(key)
+            .and_then(|value| value_to_f64(value).ok())
+            .unwrap_or(0.0)
+    };
+    let (filled_qty, avg_price) = (number("accFillSz"), number("avgPx"));
+    Some(OrderAck {
+        client_order_id,
+        exchange_order_id,
+        status,
+        filled_qty,
+        avg_fill_price: (filled_qty > 0.0 && avg_price > 0.0).then_some(avg_price),
+    })
+}
+
 fn parse_status(status: &str) -> OrderStatus {
     match status {
         "live" => OrderStatus::New,
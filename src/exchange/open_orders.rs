@@ -0,0 +1,58 @@
+use crate::exchange::Exchange;
+use crate::models::OrderAck;
+use crate::{Error, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OrphanPolicy {
+    Cancel,
+    Adopt,
+}
+
+impl OrphanPolicy {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "cancel" => Ok(Self::Cancel),
+            "adopt" => Ok(Self::Adopt),
+            _ => Err(Error::new("orders.orphan_policy must be cancel or adopt")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OpenOrderSplit {
+    pub ours: Vec<OrderAck>,
+    pub foreign: Vec<OrderAck>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelReport {
+    pub canceled: Vec<String>,
+    pub failed: Vec<(String, String)>,
+}
+
+pub fn is_own_order(client_order_id: &str, prefix: &str) -> bool {
+    client_order_id
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.starts_with('-'))
+}
+
+pub fn classify_open_orders(open_orders: &[OrderAck], prefix: &str) -> OpenOrderSplit {
+    let (ours, foreign) = open_orders
+        .iter()
+        .cloned()
+        .partition(|order| is_own_order(&order.client_order_id, prefix));
+    OpenOrderSplit { ours, foreign }
+}
+
+pub fn cancel_orders(exchange: &dyn Exchange, orders: &[OrderAck]) -> CancelReport {
+    let mut report = CancelReport::default();
+    for order in orders {
+        match exchange.cancel_order(&order.client_order_id) {
+            Ok(()) => report.canceled.push(order.client_order_id.clone()),
+            Err(err) => report
+                .failed
+                .push((order.client_order_id.clone(), err.message)),
+        }
+    }
+    report
+}
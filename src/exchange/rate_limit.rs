@@ -0,0 +1,363 @@
+use crate::{Error, Result};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+pub type WeightFn = fn(&str, &[(String, String)]) -> f64;
+
+const BINANCE_USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-1m";
+const BYBIT_LIMIT_STATUS_HEADER: &str = "x-bapi-limit-status";
+const DEFAULT_BACKOFF_SECS: u64 = 1;
+
+struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64, window: Duration) -> Self {
+        let window_secs = window.as_secs_f64().max(0.001);
+        Self {
+            capacity,
+            refill_per_sec: capacity / window_secs,
+            tokens: capacity,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.updated = now;
+    }
+
+    fn wait_for(&self, cost: f64) -> Duration {
+        let cost = cost.min(self.capacity);
+        if self.tokens >= cost {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64((cost - self.tokens) / self.refill_per_sec)
+    }
+
+    fn cap_remaining(&mut self, remaining: f64) {
+        self.tokens = self.tokens.min(remaining.max(0.0));
+    }
+}
+
+struct EndpointBucket {
+    path_prefix: String,
+    bucket: TokenBucket,
+}
+
+struct LimiterState {
+    global: Option<TokenBucket>,
+    endpoints: Vec<EndpointBucket>,
+    blocked_until: Option<Instant>,
+}
+
+impl LimiterState {
+    fn endpoint_mut(&mut self, path: &str) -> Option<&mut TokenBucket> {
+        self.endpoints
+            .iter_mut()
+            .filter(|endpoint| path.starts_with(&endpoint.path_prefix))
+            .max_by_key(|endpoint| endpoint.path_prefix.len())
+            .map(|endpoint| &mut endpoint.bucket)
+    }
+}
+
+pub struct RateLimiter {
+    name: String,
+    weight_fn: WeightFn,
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            weight_fn: unit_weight,
+            state: Mutex::new(LimiterState {
+                global: None,
+                endpoints: Vec::new(),
+                blocked_until: None,
+            }),
+        }
+    }
+
+    pub fn with_global(mut self, capacity: f64, window: Duration) -> Self {
+        if let Ok(state) = self.state.get_mut() {
+            state.global = Some(TokenBucket::new(capacity, window));
+        }
+        self
+    }
+
+    pub fn with_endpoint(mut self, path_prefix: &str, capacity: f64, window: Duration) -> Self {
+        if let Ok(state) = self.state.get_mut() {
+            state.endpoints.push(EndpointBucket {
+                path_prefix: path_prefix.to_string(),
+                bucket: TokenBucket::new(capacity, window),
+            });
+        }
+        self
+    }
+
+    pub fn with_weight_fn(mut self, weight_fn: WeightFn) -> Self {
+        self.weight_fn = weight_fn;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn weight(&self, path: &str, params: &[(String, String)]) -> f64 {
+        (self.weight_fn)(path, params)
+    }
+
+    pub fn acquire(&self, path: &str, params: &[(String, String)]) -> Result<()> {
+        loop {
+            let wait = self.try_acquire(path, params)?;
+            if wait.is_zero() {
+                return Ok(());
+            }
+            std::thread::sleep(wait);
+        }
+    }
+
+    pub fn try_acquire(&self, path: &str, params: &[(String, String)]) -> Result<Duration> {
+        let weight = self.weight(path, params);
+        let now = Instant::now();
+        let mut state = self.lock()?;
+
+        if let Some(until) = state.blocked_until {
+            if until > now {
+                return Ok(until - now);
+            }
+            state.blocked_until = None;
+        }
+
+        let mut wait = Duration::ZERO;
+        if let Some(global) = state.global.as_mut() {
+            global.refill(now);
+            wait = wait.max(global.wait_for(weight));
+        }
+        if let Some(endpoint) = state.endpoint_mut(path) {
+            endpoint.refill(now);
+            wait = wait.max(endpoint.wait_for(1.0));
+        }
+        if !wait.is_zero() {
+            return Ok(wait);
+        }
+
+        if let Some(global) = state.global.as_mut() {
+            global.tokens -= weight.min(global.capacity);
+        }
+        if let Some(endpoint) = state.endpoint_mut(path) {
+            endpoint.tokens -= 1.0;
+        }
+        Ok(Duration::ZERO)
+    }
+
+    pub fn remaining(&self, path: &str) -> Result<f64> {
+        let now = Instant::now();
+        let mut state = self.lock()?;
+        let mut remaining = f64::INFINITY;
+        if let Some(global) = state.global.as_mut() {
+            global.refill(now);
+            remaining = remaining.min(global.tokens);
+        }
+        if let Some(endpoint) = state.endpoint_mut(path) {
+            endpoint.refill(now);
+            remaining = remaining.min(endpoint.tokens);
+        }
+        Ok(remaining)
+    }
+
+    pub fn observe_used_weight(&self, used: f64) -> Result<()> {
+        let now = Instant::now();
+        let mut state = self.lock()?;
+        if let Some(global) = state.global.as_mut() {
+            global.refill(now);
+            let remaining = global.capacity - used;
+            global.cap_remaining(remaining);
+        }
+        Ok(())
+    }
+
+    pub fn observe_remaining(&self, path: &str, remaining: f64) -> Result<()> {
+        let now = Instant::now();
+        let mut state = self.lock()?;
+        if let Some(endpoint) = state.endpoint_mut(path) {
+            endpoint.refill(now);
+            endpoint.cap_remaining(remaining);
+        } else if let Some(global) = state.global.as_mut() {
+            global.refill(now);
+            global.cap_remaining(remaining);
+        }
+        Ok(())
+    }
+
+    pub fn back_off(&self, delay: Duration) -> Result<()> {
+        let until = Instant::now() + delay;
+        let mut state = self.lock()?;
+        state.blocked_until = Some(match state.blocked_until {
+            Some(current) if current > until => current,
+            _ => until,
+        });
+        Ok(())
+    }
+
+    pub fn observe(&self, path: &str, status: StatusCode, headers: &HeaderMap) -> Result<()> {
+        if let Some(used) = header_f64(headers, BINANCE_USED_WEIGHT_HEADER) {
+            self.observe_used_weight(used)?;
+        }
+        if let Some(remaining) = header_f64(headers, BYBIT_LIMIT_STATUS_HEADER) {
+            self.observe_remaining(path, remaining)?;
+        }
+        if is_rate_limited(status) {
+            let delay = header_f64(headers, "retry-after")
+                .map(|secs| Duration::from_secs_f64(secs.max(0.0)))
+                .unwrap_or(Duration::from_secs(DEFAULT_BACKOFF_SECS));
+            self.back_off(delay)?;
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, LimiterState>> {
+        self.state
+            .lock()
+            .map_err(|_| Error::new(format!("{} rate limiter lock poisoned", self.name)))
+    }
+}
+
+pub fn shared_limiter(exchange: &str) -> Result<Arc<RateLimiter>> {
+    static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<RateLimiter>>>> = OnceLock::new();
+    let key = exchange.to_lowercase();
+    let mut limiters = LIMITERS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .map_err(|_| Error::new("rate limiter registry lock poisoned"))?;
+    let limiter = limiters
+        .entry(key.clone())
+        .or_insert_with(|| Arc::new(default_limiter(&key)));
+    Ok(Arc::clone(limiter))
+}
+
+pub fn default_limiter(exchange: &str) -> RateLimiter {
+    match exchange {
+        "binance" => binance_spot_limiter(),
+        "binance-futures" => binance_futures_limiter(),
+        "bybit" => bybit_limiter(),
+        "okx" => okx_limiter(),
+        "kraken" => kraken_limiter(),
+        "coinbase" => coinbase_limiter(),
+        other => RateLimiter::new(other),
+    }
+}
+
+pub fn binance_spot_limiter() -> RateLimiter {
+    RateLimiter::new("binance")
+        .with_global(6000.0, Duration::from_secs(60))
+        .with_endpoint("/api/v3/order", 50.0, Duration::from_secs(10))
+        .with_weight_fn(binance_request_weight)
+}
+
+pub fn binance_futures_limiter() -> RateLimiter {
+    RateLimiter::new("binance-futures")
+        .with_global(2400.0, Duration::from_secs(60))
+        .with_endpoint("/fapi/v1/order", 300.0, Duration::from_secs(10))
+        .with_weight_fn(binance_futures_request_weight)
+}
+
+pub fn bybit_limiter() -> RateLimiter {
+    RateLimiter::new("bybit")
+        .with_global(600.0, Duration::from_secs(5))
+        .with_endpoint("/v5/order/create", 10.0, Duration::from_secs(1))
+        .with_endpoint("/v5/order/cancel", 10.0, Duration::from_secs(1))
+        .with_endpoint("/v5/order/amend", 10.0, Duration::from_secs(1))
+        .with_endpoint("/v5/order/realtime", 50.0, Duration::from_secs(1))
+        .with_endpoint("/v5/order/history", 50.0, Duration::from_secs(1))
+        .with_endpoint("/v5/market/instruments-info", 50.0, Duration::from_secs(1))
+        .with_endpoint("/v5/account/wallet-balance", 50.0, Duration::from_secs(1))
+}
+
+pub fn okx_limiter() -> RateLimiter {
+    RateLimiter::new("okx")
+        .with_endpoint("/api/v5/market/candles", 40.0, Duration::from_secs(2))
+        .with_endpoint("/api/v5/market/history-candles", 20.0, Duration::from_secs(2))
+        .with_endpoint("/api/v5/trade/order", 60.0, Duration::from_secs(2))
+        .with_endpoint("/api/v5/trade/cancel-order", 60.0, Duration::from_secs(2))
+        .with_endpoint("/api/v5/trade/amend-order", 60.0, Duration::from_secs(2))
+        .with_endpoint("/api/v5/trade/orders-pending", 60.0, Duration::from_secs(2))
+        .with_endpoint("/api/v5/account/balance", 10.0, Duration::from_secs(2))
+        .with_endpoint("/api/v5/public/instruments", 20.0, Duration::from_secs(2))
+}
+
+pub fn kraken_limiter() -> RateLimiter {
+    RateLimiter::new("kraken")
+        .with_global(15.0, Duration::from_secs(45))
+        .with_endpoint("/0/public/OHLC", 1.0, Duration::from_secs(1))
+        .with_endpoint("/0/private/AddOrder", 60.0, Duration::from_secs(60))
+        .with_endpoint("/0/private/CancelOrder", 60.0, Duration::from_secs(60))
+}
+
+pub fn coinbase_limiter() -> RateLimiter {
+    RateLimiter::new("coinbase")
+        .with_global(30.0, Duration::from_secs(1))
+        .with_endpoint("/api/v3/brokerage/market", 10.0, Duration::from_secs(1))
+}
+
+pub fn binance_request_weight(path: &str, params: &[(String, String)]) -> f64 {
+    let has_symbol = params.iter().any(|(key, _)| key == "symbol");
+    match path {
+        "/api/v3/klines" => 2.0,
+        "/api/v3/account" => 20.0,
+        "/api/v3/exchangeInfo" => 20.0,
+        "/api/v3/openOrders" if has_symbol => 6.0,
+        "/api/v3/openOrders" => 80.0,
+        _ => 1.0,
+    }
+}
+
+pub fn binance_futures_request_weight(path: &str, params: &[(String, String)]) -> f64 {
+    let has_symbol = params.iter().any(|(key, _)| key == "symbol");
+    match path {
+        "/fapi/v1/klines" => {
+            let limit = params
+                .iter()
+                .find(|(key, _)| key == "limit")
+                .and_then(|(_, value)| value.parse::<u32>().ok())
+                .unwrap_or(500);
+            match limit {
+                0..=99 => 1.0,
+                100..=499 => 2.0,
+                500..=1000 => 5.0,
+                _ => 10.0,
+            }
+        }
+        "/fapi/v2/balance" | "/fapi/v2/positionRisk" => 5.0,
+        "/fapi/v1/income" => 30.0,
+        "/fapi/v1/openOrders" if has_symbol => 1.0,
+        "/fapi/v1/openOrders" => 40.0,
+        _ => 1.0,
+    }
+}
+
+fn unit_weight(_path: &str, _params: &[(String, String)]) -> f64 {
+    1.0
+}
+
+fn is_rate_limited(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418
+}
+
+fn header_f64(headers: &HeaderMap, name: &str) -> Option<f64> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|text| text.trim().parse::<f64>().ok())
+}
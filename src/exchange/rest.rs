@@ -1,8 +1,18 @@
 use crate::exchange::{CandleRequest, Exchange};
-use crate::models::{Balance, Candle, OrderAck, OrderRequest, Position};
+use crate::models::{Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side};
 use crate::{Error, Result};
+use hmac::{Hmac, Mac};
 use reqwest::blocking::Client;
-use std::time::Duration;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const API_KEY_HEADER: &str = "X-MERROW-APIKEY";
+pub const TIMESTAMP_HEADER: &str = "X-MERROW-TIMESTAMP";
+pub const SIGNATURE_HEADER: &str = "X-MERROW-SIGNATURE";
 
 #[derive(Clone, Debug)]
 pub struct RestExchangeConfig {
@@ -13,6 +23,124 @@ pub struct RestExchangeConfig {
     pub timeout_secs: u64,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RestOrder {
+    pub client_order_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub order_type: String,
+    pub price: Option<f64>,
+    pub quantity: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RestOrderAck {
+    pub client_order_id: String,
+    pub exchange_order_id: Option<String>,
+    pub status: String,
+    #[serde(default)]
+    pub filled_qty: f64,
+    #[serde(default)]
+    pub avg_fill_price: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RestBalance {
+    pub asset: String,
+    pub free: f64,
+    pub locked: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RestPosition {
+    pub symbol: String,
+    pub quantity: f64,
+    pub avg_price: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RestCandle {
+    pub time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl RestOrder {
+    pub fn from_request(order: &OrderRequest) -> Self {
+        let (order_type, price) = match order.order_type {
+            OrderType::Market => ("market", None),
+            OrderType::Limit { price } => ("limit", Some(price)),
+        };
+        Self {
+            client_order_id: order.client_order_id.clone(),
+            symbol: order.symbol.clone(),
+            side: side_label(&order.side).to_string(),
+            order_type: order_type.to_string(),
+            price,
+            quantity: order.quantity,
+        }
+    }
+
+    pub fn to_request(&self) -> Result<OrderRequest> {
+        let side = match self.side.as_str() {
+            "buy" => Side::Buy,
+            "sell" => Side::Sell,
+            _ => return Err(Error::new("side must be buy or sell")),
+        };
+        let order_type = match (self.order_type.as_str(), self.price) {
+            ("market", _) => OrderType::Market,
+            ("limit", Some(price)) => OrderType::Limit { price },
+            ("limit", None) => return Err(Error::new("limit order requires price")),
+            _ => return Err(Error::new("order_type must be market or limit")),
+        };
+        Ok(OrderRequest {
+            client_order_id: self.client_order_id.clone(),
+            symbol: self.symbol.clone(),
+            side,
+            order_type,
+            quantity: self.quantity,
+        })
+    }
+}
+
+impl RestOrderAck {
+    pub fn from_ack(ack: &OrderAck) -> Self {
+        Self {
+            client_order_id: ack.client_order_id.clone(),
+            exchange_order_id: ack.exchange_order_id.clone(),
+            status: status_label(&ack.status).to_string(),
+            filled_qty: ack.filled_qty,
+            avg_fill_price: ack.avg_fill_price,
+        }
+    }
+
+    pub fn to_ack(&self) -> Result<OrderAck> {
+        Ok(OrderAck {
+            client_order_id: self.client_order_id.clone(),
+            exchange_order_id: self.exchange_order_id.clone(),
+            status: parse_status(&self.status)?,
+            filled_qty: self.filled_qty,
+            avg_fill_price: self.avg_fill_price,
+        })
+    }
+}
+
+impl From<&Candle> for RestCandle {
+    fn from(candle: &Candle) -> Self {
+        Self {
+            time: candle.time,
+            open: candle.open,
+            high: candle.high,
+            low: candle.low,
+            close: candle.close,
+            volume: candle.volume,
+        }
+    }
+}
+
 pub struct RestExchange {
     client: Client,
     config: RestExchangeConfig,
@@ -43,35 +171,203 @@ impl RestExchange {
     pub fn client(&self) -> &Client {
         &self.client
     }
+
+    fn signed_request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+    ) -> Result<serde_json::Value> {
+        read_json(self.signed_response(method, path, body)?)
+    }
+
+    fn signed_response(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+    ) -> Result<reqwest::blocking::Response> {
+        self.ensure_auth()?;
+        let api_key = self.config.api_key.as_deref().unwrap_or("");
+        let api_secret = self.config.api_secret.as_deref().unwrap_or("");
+        let timestamp = timestamp_ms()?.to_string();
+        let body = body.unwrap_or_default();
+        let payload = sign_payload(&timestamp, method.as_str(), path, &body);
+        let signature = hmac_sha256_hex(api_secret, &payload)?;
+
+        let url = format!("{}{}", self.config.base_url, path);
+        let mut request = self
+            .client
+            .request(method, url)
+            .header(API_KEY_HEADER, api_key)
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(SIGNATURE_HEADER, signature);
+        if !body.is_empty() {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(body);
+        }
+        request
+            .send()
+            .map_err(|err| Error::new(format!("http request failed: {err}")))
+    }
+
+    fn public_request(&self, path: &str, params: Vec<(String, String)>) -> Result<serde_json::Value> {
+        let url = format!("{}{}", self.config.base_url, path);
+        let response = self
+            .client
+            .get(url)
+            .query(&params)
+            .send()
+            .map_err(|err| Error::new(format!("http request failed: {err}")))?;
+        read_json(response)
+    }
 }
 
 impl Exchange for RestExchange {
-    fn place_order(&self, _order: &OrderRequest) -> Result<OrderAck> {
-        self.ensure_auth()?;
-        Err(Error::new("place_order not implemented"))
+    fn place_order(&self, order: &OrderRequest) -> Result<OrderAck> {
+        let body = serde_json::to_string(&RestOrder::from_request(order))
+            .map_err(|err| Error::new(format!("json encode failed: {err}")))?;
+        let json = self.signed_request(Method::POST, "/api/v1/orders", Some(body))?;
+        let ack: RestOrderAck = serde_json::from_value(json)
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+        ack.to_ack()
     }
 
-    fn cancel_order(&self, _order_id: &str) -> Result<()> {
-        self.ensure_auth()?;
-        Err(Error::new("cancel_order not implemented"))
+    fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let path = format!("/api/v1/orders/{order_id}");
+        let _ = self.signed_request(Method::DELETE, &path, None)?;
+        Ok(())
     }
 
     fn fetch_balances(&self) -> Result<Vec<Balance>> {
-        self.ensure_auth()?;
-        Err(Error::new("fetch_balances not implemented"))
+        let json = self.signed_request(Method::GET, "/api/v1/balances", None)?;
+        let rows: Vec<RestBalance> = serde_json::from_value(json)
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| Balance {
+                asset: row.asset,
+                free: row.free,
+                locked: row.locked,
+            })
+            .collect())
     }
 
     fn fetch_positions(&self) -> Result<Vec<Position>> {
-        self.ensure_auth()?;
-        Err(Error::new("fetch_positions not implemented"))
+        let json = self.signed_request(Method::GET, "/api/v1/positions", None)?;
+        let rows: Vec<RestPosition> = serde_json::from_value(json)
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| Position {
+                symbol: row.symbol,
+                quantity: row.quantity,
+                avg_price: row.avg_price,
+            })
+            .collect())
     }
 
     fn fetch_open_orders(&self) -> Result<Vec<OrderAck>> {
-        self.ensure_auth()?;
-        Err(Error::new("fetch_open_orders not implemented"))
+        let json = self.signed_request(Method::GET, "/api/v1/orders/open", None)?;
+        let rows: Vec<RestOrderAck> = serde_json::from_value(json)
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+        rows.iter().map(RestOrderAck::to_ack).collect()
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let path = format!("/api/v1/orders/{client_order_id}");
+        let response = self.signed_response(Method::GET, &path, None)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let ack: RestOrderAck = serde_json::from_value(read_json(response)?)
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+        ack.to_ack().map(Some)
+    }
+
+    fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>> {
+        let params = vec![
+            ("symbol".to_string(), req.symbol.clone()),
+            ("interval".to_string(), req.interval.clone()),
+            ("start_time".to_string(), req.start_time.to_string()),
+            ("end_time".to_string(), req.end_time.to_string()),
+        ];
+        let json = self.public_request("/api/v1/candles", params)?;
+        let rows: Vec<RestCandle> = serde_json::from_value(json)
+            .map_err(|err| Error::new(format!("json parse failed: {err}")))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| Candle {
+                time: row.time,
+                open: row.open,
+                high: row.high,
+                low: row.low,
+                close: row.close,
+                volume: row.volume,
+            })
+            .collect())
+    }
+}
+
+pub fn sign_payload(timestamp: &str, method: &str, path: &str, body: &str) -> String {
+    format!("{timestamp}{method}{path}{body}")
+}
+
+pub fn hmac_sha256_hex(secret: &str, message: &str) -> Result<String> {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).map_err(|_| Error::new("invalid key"))?;
+    mac.update(message.as_bytes());
+    let result = mac.finalize().into_bytes();
+    let mut output = String::with_capacity(result.len() * 2);
+    for byte in result {
+        output.push_str(&format!("{:02x}", byte));
+    }
+    Ok(output)
+}
+
+pub fn status_label(status: &OrderStatus) -> &'static str {
+    match status {
+        OrderStatus::New => "new",
+        OrderStatus::PartiallyFilled => "partially_filled",
+        OrderStatus::Filled => "filled",
+        OrderStatus::Canceled => "canceled",
+        OrderStatus::Rejected => "rejected",
+    }
+}
+
+fn parse_status(status: &str) -> Result<OrderStatus> {
+    match status {
+        "new" => Ok(OrderStatus::New),
+        "partially_filled" => Ok(OrderStatus::PartiallyFilled),
+        "filled" => Ok(OrderStatus::Filled),
+        "canceled" => Ok(OrderStatus::Canceled),
+        "rejected" => Ok(OrderStatus::Rejected),
+        _ => Err(Error::new(format!("unknown order status: {status}"))),
+    }
+}
+
+fn side_label(side: &Side) -> &'static str {
+    match side {
+        Side::Buy => "buy",
+        Side::Sell => "sell",
     }
+}
 
-    fn fetch_candles(&self, _req: &CandleRequest) -> Result<Vec<Candle>> {
-        Err(Error::new("fetch_candles not implemented"))
+fn read_json(response: reqwest::blocking::Response) -> Result<serde_json::Value> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().unwrap_or_default();
+        return Err(Error::new(format!("rest response status: {status} {body}")));
     }
+    response
+        .json::<serde_json::Value>()
+        .map_err(|err| Error::new(format!("json parse failed: {err}")))
+}
+
+fn timestamp_ms() -> Result<u64> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| Error::new("system time before unix epoch"))?;
+    Ok(now.as_millis() as u64)
 }
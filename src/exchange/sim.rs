@@ -0,0 +1,491 @@
+use crate::backtest::fill::{fill_limit, fill_market, ExecutionCosts};
+use crate::exchange::{AmendRequest, CandleRequest, Exchange, OrderCheck};
+use crate::models::{
+    Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side, Trade,
+};
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    pub symbol: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub initial_quote: f64,
+    pub initial_base: f64,
+    pub fee_rate: f64,
+    pub slippage_bps: u32,
+    pub warmup_bars: usize,
+}
+
+struct SimOrder {
+    request: OrderRequest,
+    exchange_order_id: String,
+    status: OrderStatus,
+    locked: f64,
+    fill: Option<(f64, f64)>,
+}
+
+struct SimState {
+    cursor: Option<usize>,
+    balances: HashMap<String, Balance>,
+    avg_price: f64,
+    orders: Vec<SimOrder>,
+    trades: Vec<Trade>,
+    next_order_id: u64,
+}
+
+pub struct SimExchange {
+    config: SimConfig,
+    candles: Vec<Candle>,
+    state: Mutex<SimState>,
+}
+
+impl SimExchange {
+    pub fn new(config: SimConfig, candles: Vec<Candle>) -> Result<Self> {
+        if config.symbol.trim().is_empty() {
+            return Err(Error::new("sim symbol must be set"));
+        }
+        if config.base_asset.trim().is_empty() || config.quote_asset.trim().is_empty() {
+            return Err(Error::new("sim base_asset and quote_asset must be set"));
+        }
+        if config.initial_quote < 0.0 || config.initial_base < 0.0 {
+            return Err(Error::new("sim initial balances must be non-negative"));
+        }
+        if config.fee_rate < 0.0 {
+            return Err(Error::new("sim fee_rate must be non-negative"));
+        }
+        if candles.is_empty() {
+            return Err(Error::new("sim requires candle data"));
+        }
+
+        let mut balances = HashMap::new();
+        balances.insert(
+            config.quote_asset.clone(),
+            Balance::new(config.quote_asset.clone(), config.initial_quote, 0.0)?,
+        );
+        balances.insert(
+            config.base_asset.clone(),
+            Balance::new(config.base_asset.clone(), config.initial_base, 0.0)?,
+        );
+        let cursor = if config.warmup_bars == 0 {
+            None
+        } else {
+            Some(config.warmup_bars.min(candles.len()) - 1)
+        };
+        let avg_price = if config.initial_base > 0.0 {
+            cursor.map(|index| candles[index].close).unwrap_or(0.0)
+        } else {
+            0.0
+        };
+
+        Ok(Self {
+            config,
+            candles,
+            state: Mutex::new(SimState {
+                cursor,
+                balances,
+                avg_price,
+                orders: Vec::new(),
+                trades: Vec::new(),
+                next_order_id: 1,
+            }),
+        })
+    }
+
+    pub fn config(&self) -> &SimConfig {
+        &self.config
+    }
+
+    pub fn current_candle(&self) -> Result<Option<Candle>> {
+        let state = self.lock()?;
+        Ok(state.cursor.map(|index| self.candles[index].clone()))
+    }
+
+    pub fn trades(&self) -> Result<Vec<Trade>> {
+        Ok(self.lock()?.trades.clone())
+    }
+
+    pub fn advance(&self) -> Result<Option<Candle>> {
+        let mut state = self.lock()?;
+        let next = match state.cursor {
+            Some(index) => index + 1,
+            None => 0,
+        };
+        if next >= self.candles.len() {
+            return Ok(None);
+        }
+        state.cursor = Some(next);
+        let candle = self.candles[next].clone();
+        self.match_orders(&mut state, &candle)?;
+        Ok(Some(candle))
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, SimState>> {
+        self.state
+            .lock()
+            .map_err(|_| Error::new("sim state lock poisoned"))
+    }
+
+    fn costs(&self) -> ExecutionCosts {
+        ExecutionCosts {
+            fee_rate: self.config.fee_rate,
+            slippage_bps: self.config.slippage_bps,
+        }
+    }
+
+    fn match_orders(&self, state: &mut SimState, candle: &Candle) -> Result<()> {
+        let costs = self.costs();
+        let mut fills = Vec::new();
+        for (index, order) in state.orders.iter().enumerate() {
+            if !matches!(order.status, OrderStatus::New) {
+                continue;
+            }
+            let trade = match order.request.order_type {
+                OrderType::Market => fill_market(&order.request, candle, costs),
+                OrderType::Limit { .. } => fill_limit(&order.request, candle, costs),
+            };
+            if let Some(trade) = trade {
+                fills.push((index, trade));
+            }
+        }
+
+        for (index, trade) in fills {
+            self.release(state, index);
+            if !self.settle(state, &trade) {
+                state.orders[index].status = OrderStatus::Rejected;
+                continue;
+            }
+            state.orders[index].status = OrderStatus::Filled;
+            state.orders[index].fill = Some((trade.quantity, trade.price));
+            state.trades.push(trade);
+        }
+        Ok(())
+    }
+
+    fn settle(&self, state: &mut SimState, trade: &Trade) -> bool {
+        let quote = self.config.quote_asset.clone();
+        let base = self.config.base_asset.clone();
+        let value = trade.price * trade.quantity;
+        match trade.side {
+            Side::Buy => {
+                let cost = value + trade.fee;
+                let Some(quote_balance) = state.balances.get_mut(&quote) else {
+                    return false;
+                };
+                if cost > quote_balance.free {
+                    return false;
+                }
+                quote_balance.free -= cost;
+                let base_balance = state
+                    .balances
+                    .entry(base.clone())
+                    .or_insert_with(|| Balance {
+                        asset: base.clone(),
+                        free: 0.0,
+                        locked: 0.0,
+                    });
+                let held = base_balance.free + base_balance.locked;
+                let total_cost = state.avg_price * held + value;
+                base_balance.free += trade.quantity;
+                let new_held = held + trade.quantity;
+                state.avg_price = if new_held > 0.0 {
+                    total_cost / new_held
+                } else {
+                    0.0
+                };
+            }
+            Side::Sell => {
+                let Some(base_balance) = state.balances.get_mut(&base) else {
+                    return false;
+                };
+                if trade.quantity > base_balance.free {
+                    return false;
+                }
+                base_balance.free -= trade.quantity;
+                if base_balance.free + base_balance.locked <= 0.0 {
+                    state.avg_price = 0.0;
+                }
+                let quote_balance = state
+                    .balances
+                    .entry(quote.clone())
+                    .or_insert_with(|| Balance {
+                        asset: quote.clone(),
+                        free: 0.0,
+                        locked: 0.0,
+                    });
+                quote_balance.free += value - trade.fee;
+            }
+        }
+        true
+    }
+
+    fn release(&self, state: &mut SimState, index: usize) {
+        let locked = state.orders[index].locked;
+        if locked <= 0.0 {
+            return;
+        }
+        let asset = match state.orders[index].request.side {
+            Side::Buy => self.config.quote_asset.clone(),
+            Side::Sell => self.config.base_asset.clone(),
+        };
+        if let Some(balance) = state.balances.get_mut(&asset) {
+            let amount = balance.locked.min(locked);
+            balance.locked -= amount;
+            balance.free += amount;
+        }
+        state.orders[index].locked = 0.0;
+    }
+
+    fn lock_funds(&self, state: &mut SimState, index: usize, required: f64) -> bool {
+        let asset = match state.orders[index].request.side {
+            Side::Buy => self.config.quote_asset.clone(),
+            Side::Sell => self.config.base_asset.clone(),
+        };
+        match state.balances.get_mut(&asset) {
+            Some(balance) if balance.free >= required => {
+                balance.free -= required;
+                balance.locked += required;
+                state.orders[index].locked = required;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn required_funds(&self, order: &OrderRequest, price: f64) -> f64 {
+        match order.side {
+            Side::Buy => price * order.quantity * (1.0 + self.config.fee_rate),
+            Side::Sell => order.quantity,
+        }
+    }
+
+    fn validate(&self, order: &OrderRequest) -> Result<()> {
+        if order.symbol != self.config.symbol {
+            return Err(Error::new(format!("sim unknown symbol: {}", order.symbol)));
+        }
+        if order.client_order_id.trim().is_empty() {
+            return Err(Error::new("client_order_id must be set"));
+        }
+        if order.quantity <= 0.0 {
+            return Err(Error::new("order quantity must be positive"));
+        }
+        Ok(())
+    }
+
+    fn ack(order: &SimOrder) -> OrderAck {
+        OrderAck {
+            client_order_id: order.request.client_order_id.clone(),
+            exchange_order_id: Some(order.exchange_order_id.clone()),
+            status: order.status.clone(),
+            filled_qty: order.fill.map(|(quantity, _)| quantity).unwrap_or(0.0),
+            avg_fill_price: order.fill.map(|(_, price)| price),
+        }
+    }
+}
+
+impl Exchange for SimExchange {
+    fn place_order(&self, order: &OrderRequest) -> Result<OrderAck> {
+        self.validate(order)?;
+
+        let mut state = self.lock()?;
+        if let Some(existing) = state.orders.iter().find(|existing| {
+            existing.request.client_order_id == order.client_order_id
+                && matches!(existing.status, OrderStatus::New)
+        }) {
+            return Ok(Self::ack(existing));
+        }
+
+        let reference_price = match order.order_type {
+            OrderType::Limit { price } => price,
+            OrderType::Market => state
+                .cursor
+                .map(|index| self.candles[index].close)
+                .ok_or_else(|| Error::new("sim has no market price yet"))?,
+        };
+        if reference_price <= 0.0 {
+            return Err(Error::new("order price must be positive"));
+        }
+
+        let (asset, required) = match order.side {
+            Side::Buy => (
+                self.config.quote_asset.clone(),
+                reference_price * order.quantity * (1.0 + self.config.fee_rate),
+            ),
+            Side::Sell => (self.config.base_asset.clone(), order.quantity),
+        };
+        let exchange_order_id = format!("sim-{}", state.next_order_id);
+        state.next_order_id += 1;
+
+        let free = state
+            .balances
+            .get(&asset)
+            .map(|balance| balance.free)
+            .unwrap_or(0.0);
+        let (status, locked) = if required > free {
+            (OrderStatus::Rejected, 0.0)
+        } else {
+            if let Some(balance) = state.balances.get_mut(&asset) {
+                balance.free -= required;
+                balance.locked += required;
+            }
+            (OrderStatus::New, required)
+        };
+
+        let sim_order = SimOrder {
+            request: order.clone(),
+            exchange_order_id,
+            status,
+            locked,
+            fill: None,
+        };
+        let ack = Self::ack(&sim_order);
+        state.orders.push(sim_order);
+        Ok(ack)
+    }
+
+    fn test_order(&self, order: &OrderRequest, reference_price: f64) -> Result<OrderCheck> {
+        if let Err(err) = self.validate(order) {
+            return Ok(OrderCheck::Rejected(err.message));
+        }
+        let price = match order.order_type {
+            OrderType::Limit { price } => price,
+            OrderType::Market => reference_price,
+        };
+        let asset = match order.side {
+            Side::Buy => &self.config.quote_asset,
+            Side::Sell => &self.config.base_asset,
+        };
+        let required = self.required_funds(order, price);
+        let state = self.lock()?;
+        let free = state
+            .balances
+            .get(asset)
+            .map(|balance| balance.free)
+            .unwrap_or(0.0);
+        if required > free {
+            return Ok(OrderCheck::Rejected(format!(
+                "insufficient {asset}: required {required}, free {free}"
+            )));
+        }
+        Ok(OrderCheck::Accepted)
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let mut state = self.lock()?;
+        let index = state
+            .orders
+            .iter()
+            .position(|order| {
+                matches!(order.status, OrderStatus::New)
+                    && (order.request.client_order_id == order_id
+                        || order.exchange_order_id == order_id)
+            })
+            .ok_or_else(|| Error::new(format!("open order not found: {order_id}")))?;
+        self.release(&mut state, index);
+        state.orders[index].status = OrderStatus::Canceled;
+        Ok(())
+    }
+
+    fn amend_order(&self, req: &AmendRequest) -> Result<OrderAck> {
+        let client_order_id = &req.order.client_order_id;
+        let mut state = self.lock()?;
+        let index = state
+            .orders
+            .iter()
+            .position(|order| {
+                matches!(order.status, OrderStatus::New)
+                    && order.request.client_order_id == *client_order_id
+            })
+            .ok_or_else(|| Error::new(format!("open order not found: {client_order_id}")))?;
+        let amended = AmendRequest {
+            order: state.orders[index].request.clone(),
+            new_price: req.new_price,
+            new_quantity: req.new_quantity,
+        }
+        .amended()?;
+        let price = match amended.order_type {
+            OrderType::Limit { price } => price,
+            OrderType::Market => return Err(Error::new("only limit orders can be amended")),
+        };
+
+        let previous = state.orders[index].locked;
+        self.release(&mut state, index);
+        let required = self.required_funds(&amended, price);
+        if !self.lock_funds(&mut state, index, required) {
+            self.lock_funds(&mut state, index, previous);
+            return Err(Error::new(format!(
+                "insufficient balance to amend order: {client_order_id}"
+            )));
+        }
+        state.orders[index].request = amended;
+        Ok(Self::ack(&state.orders[index]))
+    }
+
+    fn fetch_balances(&self) -> Result<Vec<Balance>> {
+        let state = self.lock()?;
+        let mut balances: Vec<Balance> = state.balances.values().cloned().collect();
+        balances.sort_by(|a, b| a.asset.cmp(&b.asset));
+        Ok(balances)
+    }
+
+    fn fetch_positions(&self) -> Result<Vec<Position>> {
+        let state = self.lock()?;
+        let quantity = state
+            .balances
+            .get(&self.config.base_asset)
+            .map(|balance| balance.free + balance.locked)
+            .unwrap_or(0.0);
+        if quantity <= 0.0 {
+            return Ok(Vec::new());
+        }
+        Ok(vec![Position {
+            symbol: self.config.symbol.clone(),
+            quantity,
+            avg_price: state.avg_price,
+        }])
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let state = self.lock()?;
+        Ok(state
+            .orders
+            .iter()
+            .rev()
+            .find(|order| order.request.client_order_id == client_order_id)
+            .map(Self::ack))
+    }
+
+    fn fetch_open_orders(&self) -> Result<Vec<OrderAck>> {
+        let state = self.lock()?;
+        Ok(state
+            .orders
+            .iter()
+            .filter(|order| matches!(order.status, OrderStatus::New))
+            .map(Self::ack)
+            .collect())
+    }
+
+    fn fetch_candles(&self, req: &CandleRequest) -> Result<Vec<Candle>> {
+        if req.symbol != self.config.symbol {
+            return Err(Error::new(format!("sim unknown symbol: {}", req.symbol)));
+        }
+        if req.start_time > req.end_time {
+            return Err(Error::new("start_time must be <= end_time"));
+        }
+        self.advance()?;
+        let state = self.lock()?;
+        let Some(cursor) = state.cursor else {
+            return Ok(Vec::new());
+        };
+        let span_secs = (req.end_time - req.start_time) / 1000;
+        let current = self.candles[cursor].time;
+        let window_start = current - span_secs;
+        Ok(self.candles[..=cursor]
+            .iter()
+            .filter(|candle| candle.time >= window_start)
+            .cloned()
+            .collect())
+    }
+}
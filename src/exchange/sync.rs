@@ -3,16 +3,18 @@ use crate::models::{Balance, OrderAck, Position};
 use crate::Result;
 
 pub struct AccountSnapshot {
+    pub account: String,
     pub balances: Vec<Balance>,
     pub positions: Vec<Position>,
     pub open_orders: Vec<OrderAck>,
 }
 
-pub fn sync_account(exchange: &dyn Exchange) -> Result<AccountSnapshot> {
+pub fn sync_account(exchange: &dyn Exchange, account: &str) -> Result<AccountSnapshot> {
     let balances = exchange.fetch_balances()?;
     let positions = exchange.fetch_positions()?;
     let open_orders = exchange.fetch_open_orders()?;
     Ok(AccountSnapshot {
+        account: account.to_string(),
         balances,
         positions,
         open_orders,
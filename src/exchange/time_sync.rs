@@ -0,0 +1,153 @@
+use crate::app::metrics;
+use crate::{Error, Result};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+#[derive(Clone, Debug)]
+pub struct TimeSyncConfig {
+    pub interval_secs: u64,
+    pub alert_threshold_ms: i64,
+}
+
+impl Default for TimeSyncConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 300,
+            alert_threshold_ms: 1000,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClockSample {
+    pub offset_ms: i64,
+    pub latency_ms: i64,
+}
+
+struct ClockState {
+    sample: Option<ClockSample>,
+    last_attempt: Option<Instant>,
+}
+
+pub struct ClockSync {
+    name: String,
+    config: TimeSyncConfig,
+    state: Mutex<ClockState>,
+}
+
+impl ClockSync {
+    pub fn new(name: &str, config: TimeSyncConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            config,
+            state: Mutex::new(ClockState {
+                sample: None,
+                last_attempt: None,
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &TimeSyncConfig {
+        &self.config
+    }
+
+    pub fn sample(&self) -> Result<Option<ClockSample>> {
+        Ok(self.lock()?.sample)
+    }
+
+    pub fn offset_ms(&self) -> Result<i64> {
+        Ok(self.sample()?.map(|sample| sample.offset_ms).unwrap_or(0))
+    }
+
+    pub fn now_ms(&self) -> Result<i64> {
+        Ok(local_time_ms()? + self.offset_ms()?)
+    }
+
+    pub fn is_due(&self) -> Result<bool> {
+        if self.config.interval_secs == 0 {
+            return Ok(false);
+        }
+        let state = self.lock()?;
+        Ok(match state.last_attempt {
+            Some(last) => last.elapsed() >= Duration::from_secs(self.config.interval_secs),
+            None => true,
+        })
+    }
+
+    pub fn record(&self, sent_ms: i64, server_ms: i64, received_ms: i64) -> Result<ClockSample> {
+        if received_ms < sent_ms {
+            return Err(Error::new("time sync received before sent"));
+        }
+        let latency_ms = received_ms - sent_ms;
+        let offset_ms = server_ms - (sent_ms + latency_ms / 2);
+        let sample = ClockSample {
+            offset_ms,
+            latency_ms,
+        };
+        {
+            let mut state = self.lock()?;
+            state.sample = Some(sample);
+            state.last_attempt = Some(Instant::now());
+        }
+
+        let drifted = offset_ms.abs() > self.config.alert_threshold_ms;
+        metrics::record_clock_sync(offset_ms, latency_ms, drifted);
+        if drifted {
+            warn!(
+                exchange = %self.name,
+                offset_ms,
+                latency_ms,
+                threshold_ms = self.config.alert_threshold_ms,
+                "clock drift exceeds threshold"
+            );
+        }
+        Ok(sample)
+    }
+
+    pub fn sync<F>(&self, fetch_server_ms: F) -> Result<ClockSample>
+    where
+        F: FnOnce() -> Result<i64>,
+    {
+        let sent_ms = local_time_ms()?;
+        let result = fetch_server_ms();
+        let received_ms = local_time_ms()?;
+        match result {
+            Ok(server_ms) => self.record(sent_ms, server_ms, received_ms),
+            Err(err) => {
+                self.lock()?.last_attempt = Some(Instant::now());
+                Err(err)
+            }
+        }
+    }
+
+    pub fn sync_if_due<F>(&self, fetch_server_ms: F) -> Result<()>
+    where
+        F: FnOnce() -> Result<i64>,
+    {
+        if !self.is_due()? {
+            return Ok(());
+        }
+        if let Err(err) = self.sync(fetch_server_ms) {
+            warn!(
+                exchange = %self.name,
+                error = %err.message,
+                "time sync failed; keeping last offset"
+            );
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, ClockState>> {
+        self.state
+            .lock()
+            .map_err(|_| Error::new(format!("{} clock lock poisoned", self.name)))
+    }
+}
+
+pub fn local_time_ms() -> Result<i64> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| Error::new("system time before unix epoch"))?;
+    Ok(now.as_millis() as i64)
+}
@@ -2,5 +2,5 @@ pub mod types;
 
 pub use types::{
     Account, Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side,
-    Signal, Trade,
+    Signal, Trade, TradePrint,
 };
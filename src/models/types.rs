@@ -10,6 +10,13 @@ pub struct Candle {
     pub volume: f64,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct TradePrint {
+    pub time_ms: i64,
+    pub price: f64,
+    pub quantity: f64,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Side {
     Buy,
@@ -45,6 +52,8 @@ pub struct OrderAck {
     pub client_order_id: String,
     pub exchange_order_id: Option<String>,
     pub status: OrderStatus,
+    pub filled_qty: f64,
+    pub avg_fill_price: Option<f64>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
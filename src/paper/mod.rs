@@ -1,6 +1,7 @@
 use crate::backtest::{BacktestEngine, BacktestResult};
 use crate::config::Config;
 use crate::core::build_engine_bundle;
+use crate::core::circuit_breaker::{load_breaker_state, save_breaker_state};
 use crate::models::{Account, Candle, Position};
 use crate::{Error, Result};
 use serde::{Deserialize, Serialize};
@@ -43,6 +44,10 @@ pub fn run_paper_with_state(
     };
 
     let mut bundle = build_engine_bundle(config)?;
+    let breaker_path = config.circuit_breaker.state_path.as_str();
+    if let Some(state) = load_breaker_state(breaker_path)? {
+        bundle.order_flow.circuit_breaker_mut().restore(state);
+    }
     let engine = BacktestEngine;
     let result = engine.run_strategy_with_account(
         candles,
@@ -54,6 +59,7 @@ pub fn run_paper_with_state(
     )?;
 
     save_state(state_path, &result.account)?;
+    save_breaker_state(breaker_path, bundle.order_flow.circuit_breaker().state())?;
     Ok(result)
 }
 
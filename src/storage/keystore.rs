@@ -0,0 +1,213 @@
+use crate::{Error, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+
+const FORMAT_VERSION: u32 = 1;
+const KDF: &str = "pbkdf2-hmac-sha256";
+const CIPHER: &str = "chacha20-poly1305";
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+const AAD: &[u8] = b"merrow-keystore-v1";
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Credential {
+    pub exchange: String,
+    pub label: String,
+    pub api_key: String,
+    pub api_secret: String,
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credential")
+            .field("exchange", &self.exchange)
+            .field("label", &self.label)
+            .field("api_key", &"<redacted>")
+            .field("api_secret", &"<redacted>")
+            .field("passphrase", &self.passphrase.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SealedKeystore {
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+pub struct Keystore {
+    path: PathBuf,
+    iterations: u32,
+    credentials: Vec<Credential>,
+}
+
+impl Keystore {
+    pub fn open(path: impl Into<PathBuf>, passphrase: &str) -> Result<Self> {
+        let path = path.into();
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                iterations: DEFAULT_KDF_ITERATIONS,
+                credentials: Vec::new(),
+            });
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|err| Error::new(format!("keystore read failed: {err}")))?;
+        let sealed = serde_json::from_str::<SealedKeystore>(&content)
+            .map_err(|err| Error::new(format!("keystore parse failed: {err}")))?;
+        if sealed.version != FORMAT_VERSION || sealed.kdf != KDF || sealed.cipher != CIPHER {
+            return Err(Error::new(format!(
+                "keystore format not supported: v{} {} {}",
+                sealed.version, sealed.kdf, sealed.cipher
+            )));
+        }
+        let salt = decode(&sealed.salt, "salt")?;
+        let nonce = decode(&sealed.nonce, "nonce")?;
+        let mut buffer = decode(&sealed.ciphertext, "ciphertext")?;
+        let key = derive_key(passphrase, &salt, sealed.iterations)?;
+        let nonce = Nonce::try_assume_unique_for_key(&nonce)
+            .map_err(|_| Error::new("keystore nonce invalid"))?;
+        let plaintext = key
+            .open_in_place(nonce, Aad::from(AAD), &mut buffer)
+            .map_err(|_| Error::new("keystore decrypt failed: wrong passphrase or corrupted file"))?;
+        let credentials = serde_json::from_slice::<Vec<Credential>>(plaintext)
+            .map_err(|err| Error::new(format!("keystore payload parse failed: {err}")))?;
+        Ok(Self {
+            path,
+            iterations: sealed.iterations,
+            credentials,
+        })
+    }
+
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn credentials(&self) -> &[Credential] {
+        &self.credentials
+    }
+
+    pub fn get(&self, exchange: &str, label: &str) -> Option<&Credential> {
+        let exchange = exchange.to_lowercase();
+        self.credentials
+            .iter()
+            .find(|credential| credential.exchange == exchange && credential.label == label)
+    }
+
+    pub fn insert(&mut self, mut credential: Credential) {
+        credential.exchange = credential.exchange.to_lowercase();
+        self.remove(&credential.exchange, &credential.label);
+        self.credentials.push(credential);
+        self.credentials.sort_by(|left, right| {
+            (&left.exchange, &left.label).cmp(&(&right.exchange, &right.label))
+        });
+    }
+
+    pub fn remove(&mut self, exchange: &str, label: &str) -> bool {
+        let exchange = exchange.to_lowercase();
+        let before = self.credentials.len();
+        self.credentials
+            .retain(|credential| !(credential.exchange == exchange && credential.label == label));
+        self.credentials.len() != before
+    }
+
+    pub fn save(&self, passphrase: &str) -> Result<()> {
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut salt)
+            .and_then(|_| rng.fill(&mut nonce))
+            .map_err(|_| Error::new("keystore random generation failed"))?;
+        let key = derive_key(passphrase, &salt, self.iterations)?;
+        let mut buffer = serde_json::to_vec(&self.credentials)
+            .map_err(|err| Error::new(format!("keystore serialize failed: {err}")))?;
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(AAD),
+            &mut buffer,
+        )
+        .map_err(|_| Error::new("keystore encrypt failed"))?;
+        let sealed = SealedKeystore {
+            version: FORMAT_VERSION,
+            kdf: KDF.to_string(),
+            iterations: self.iterations,
+            salt: STANDARD.encode(salt),
+            cipher: CIPHER.to_string(),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(&buffer),
+        };
+        let content = serde_json::to_string_pretty(&sealed)
+            .map_err(|err| Error::new(format!("keystore serialize failed: {err}")))?;
+        write_private(&self.path, content.as_bytes())
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    if passphrase.is_empty() {
+        return Err(Error::new("keystore passphrase must be non-empty"));
+    }
+    let iterations =
+        NonZeroU32::new(iterations).ok_or_else(|| Error::new("keystore iterations must be > 0"))?;
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let unbound = UnboundKey::new(&CHACHA20_POLY1305, &key)
+        .map_err(|_| Error::new("keystore key setup failed"))?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+fn decode(value: &str, field: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(value)
+        .map_err(|err| Error::new(format!("keystore {field} decode failed: {err}")))
+}
+
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| Error::new(format!("keystore dir create failed: {err}")))?;
+    }
+    let staging = path.with_extension("tmp");
+    let mut options = OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&staging)
+        .map_err(|err| Error::new(format!("keystore open failed: {err}")))?;
+    file.write_all(content)
+        .and_then(|_| file.sync_all())
+        .map_err(|err| Error::new(format!("keystore write failed: {err}")))?;
+    fs::rename(&staging, path).map_err(|err| Error::new(format!("keystore rename failed: {err}")))
+}
@@ -1 +1,2 @@
+pub mod keystore;
 pub mod postgres;
@@ -1,6 +1,9 @@
 use crate::backtest::BacktestResult;
 use crate::config::Config;
 use crate::data::csv_loader::parse_time;
+use crate::data::fetch::{CandleSink, FetchJob};
+use crate::data::market_data::MarketDataProvider;
+use crate::exchange::sync::AccountSnapshot;
 use crate::models::{Account, Balance, Candle, OrderStatus, Side, Trade};
 use crate::{Error, Result};
 use chrono::{DateTime, TimeZone, Utc};
@@ -9,14 +12,32 @@ use serde_json::json;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const SCHEMA_SQL: &str = include_str!("../../DB_SCHEMA.sql");
+const POSITION_UPSERT: &str = "INSERT INTO positions (time, mode, account, symbol, qty, avg_price) \
+     VALUES ($1, $2, $3, $4, $5, $6) \
+     ON CONFLICT (time, mode, account, symbol) DO UPDATE SET qty = EXCLUDED.qty, avg_price = EXCLUDED.avg_price";
+const BALANCE_UPSERT: &str = "INSERT INTO balances (time, mode, account, asset, free, locked) \
+     VALUES ($1, $2, $3, $4, $5, $6) \
+     ON CONFLICT (time, mode, account, asset) DO UPDATE SET free = EXCLUDED.free, locked = EXCLUDED.locked";
+const PRICE_UPSERT: &str = "INSERT INTO prices (exchange, symbol, interval, time, open, high, low, close, volume) \
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+     ON CONFLICT (exchange, symbol, interval, time) DO UPDATE SET open = EXCLUDED.open, high = EXCLUDED.high, \
+     low = EXCLUDED.low, close = EXCLUDED.close, volume = EXCLUDED.volume";
 
 pub struct PostgresStorage {
     pub dsn: String,
+    pub exchange: String,
 }
 
 impl PostgresStorage {
     pub fn new(dsn: impl Into<String>) -> Self {
-        Self { dsn: dsn.into() }
+        Self::for_exchange(dsn, "default")
+    }
+
+    pub fn for_exchange(dsn: impl Into<String>, exchange: &str) -> Self {
+        Self {
+            dsn: dsn.into(),
+            exchange: exchange.to_lowercase(),
+        }
     }
 
     pub fn ensure_schema(&self) -> Result<()> {
@@ -112,29 +133,224 @@ impl PostgresStorage {
         Ok(())
     }
 
+    pub fn persist_account_snapshot(&self, snapshot: &AccountSnapshot, time: i64) -> Result<()> {
+        let mut client = self.connect()?;
+        let mut tx = client
+            .transaction()
+            .map_err(|err| Error::new(format!("db transaction failed: {err}")))?;
+        let time = to_timestamp(time)?;
+
+        let position_stmt = tx
+            .prepare(POSITION_UPSERT)
+            .map_err(|err| Error::new(format!("prepare positions failed: {err}")))?;
+        for position in &snapshot.positions {
+            tx.execute(
+                &position_stmt,
+                &[
+                    &time,
+                    &"live",
+                    &snapshot.account,
+                    &position.symbol,
+                    &position.quantity,
+                    &position.avg_price,
+                ],
+            )
+            .map_err(|err| Error::new(format!("insert positions failed: {err}")))?;
+        }
+
+        let balance_stmt = tx
+            .prepare(BALANCE_UPSERT)
+            .map_err(|err| Error::new(format!("prepare balances failed: {err}")))?;
+        for balance in &snapshot.balances {
+            tx.execute(
+                &balance_stmt,
+                &[
+                    &time,
+                    &"live",
+                    &snapshot.account,
+                    &balance.asset,
+                    &balance.free,
+                    &balance.locked,
+                ],
+            )
+            .map_err(|err| Error::new(format!("insert balances failed: {err}")))?;
+        }
+
+        tx.commit()
+            .map_err(|err| Error::new(format!("db commit failed: {err}")))
+    }
+
+    pub fn load_prices(
+        &self,
+        exchange: &str,
+        symbol: &str,
+        interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Candle>> {
+        let mut client = self.connect()?;
+        let start = to_timestamp(start)?;
+        let end = to_timestamp(end)?;
+        let rows = client
+            .query(
+                "SELECT time, open, high, low, close, volume FROM prices \
+                 WHERE exchange = $1 AND symbol = $2 AND interval = $3 AND time >= $4 AND time <= $5 \
+                 ORDER BY time",
+                &[&exchange.to_lowercase(), &symbol, &interval, &start, &end],
+            )
+            .map_err(|err| Error::new(format!("query prices failed: {err}")))?;
+        Ok(rows
+            .iter()
+            .map(|row| Candle {
+                time: row.get::<_, DateTime<Utc>>(0).timestamp(),
+                open: row.get(1),
+                high: row.get(2),
+                low: row.get(3),
+                close: row.get(4),
+                volume: row.get(5),
+            })
+            .collect())
+    }
+
+    pub fn latest_price_time(&self, exchange: &str, symbol: &str, interval: &str) -> Result<Option<i64>> {
+        let mut client = self.connect()?;
+        let row = client
+            .query_one(
+                "SELECT MAX(time) FROM prices WHERE exchange = $1 AND symbol = $2 AND interval = $3",
+                &[&exchange.to_lowercase(), &symbol, &interval],
+            )
+            .map_err(|err| Error::new(format!("query prices failed: {err}")))?;
+        let latest: Option<DateTime<Utc>> = row.get(0);
+        Ok(latest.map(|time| time.timestamp()))
+    }
+
+    pub fn price_times(
+        &self,
+        exchange: &str,
+        symbol: &str,
+        interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<i64>> {
+        let mut client = self.connect()?;
+        let rows = client
+            .query(
+                "SELECT time FROM prices WHERE exchange = $1 AND symbol = $2 AND interval = $3 \
+                 AND time >= $4 AND time <= $5 ORDER BY time",
+                &[
+                    &exchange.to_lowercase(),
+                    &symbol,
+                    &interval,
+                    &to_timestamp(start)?,
+                    &to_timestamp(end)?,
+                ],
+            )
+            .map_err(|err| Error::new(format!("query prices failed: {err}")))?;
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<_, DateTime<Utc>>(0).timestamp())
+            .collect())
+    }
+
+    pub fn upsert_prices(
+        &self,
+        exchange: &str,
+        symbol: &str,
+        interval: &str,
+        candles: &[Candle],
+    ) -> Result<usize> {
+        if candles.is_empty() {
+            return Ok(0);
+        }
+        let exchange = exchange.to_lowercase();
+        let mut client = self.connect()?;
+        let mut tx = client
+            .transaction()
+            .map_err(|err| Error::new(format!("db transaction failed: {err}")))?;
+        let stmt = tx
+            .prepare(PRICE_UPSERT)
+            .map_err(|err| Error::new(format!("prepare prices failed: {err}")))?;
+        for candle in candles {
+            let time = to_timestamp(candle.time)?;
+            tx.execute(
+                &stmt,
+                &[
+                    &exchange,
+                    &symbol,
+                    &interval,
+                    &time,
+                    &candle.open,
+                    &candle.high,
+                    &candle.low,
+                    &candle.close,
+                    &candle.volume,
+                ],
+            )
+            .map_err(|err| Error::new(format!("upsert prices failed: {err}")))?;
+        }
+        tx.commit()
+            .map_err(|err| Error::new(format!("db commit failed: {err}")))?;
+        Ok(candles.len())
+    }
+
     fn connect(&self) -> Result<Client> {
         Client::connect(&self.dsn, NoTls)
             .map_err(|err| Error::new(format!("postgres connect failed: {err}")))
     }
 }
 
+impl MarketDataProvider for PostgresStorage {
+    fn load_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Candle>> {
+        let candles = self.load_prices(&self.exchange, symbol, interval, start, end)?;
+        if candles.is_empty() {
+            return Err(Error::new(format!(
+                "no prices rows for {} {symbol} {interval} in the requested range",
+                self.exchange
+            )));
+        }
+        Ok(candles)
+    }
+}
+
+impl CandleSink for PostgresStorage {
+    fn last_time(&mut self, job: &FetchJob) -> Result<Option<i64>> {
+        self.latest_price_time(&job.exchange, &job.symbol, &job.interval)
+    }
+
+    fn stored_times(&mut self, job: &FetchJob) -> Result<Vec<i64>> {
+        self.price_times(&job.exchange, &job.symbol, &job.interval, job.start, job.end)
+    }
+
+    fn append(&mut self, job: &FetchJob, candles: &[Candle]) -> Result<usize> {
+        self.upsert_prices(&job.exchange, &job.symbol, &job.interval, candles)
+    }
+}
+
 fn insert_prices(tx: &mut postgres::Transaction<'_>, candles: &[Candle], config: &Config) -> Result<()> {
     if candles.is_empty() {
         return Ok(());
     }
     let stmt = tx
         .prepare(
-            "INSERT INTO prices (symbol, interval, time, open, high, low, close, volume) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+            "INSERT INTO prices (exchange, symbol, interval, time, open, high, low, close, volume) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
              ON CONFLICT DO NOTHING",
         )
         .map_err(|err| Error::new(format!("prepare prices failed: {err}")))?;
 
+    let exchange = config.exchange.to_lowercase();
     for candle in candles {
         let time = to_timestamp(candle.time)?;
         tx.execute(
             &stmt,
             &[
+                &exchange,
                 &config.symbol,
                 &config.data.candle_interval,
                 &time,
@@ -162,14 +378,14 @@ fn insert_orders_and_trades(
 
     let order_stmt = tx
         .prepare(
-            "INSERT INTO orders (id, time, mode, symbol, side, order_type, price, qty, status, exchange_order_id, client_order_id) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            "INSERT INTO orders (id, time, mode, account, symbol, side, order_type, price, qty, status, exchange_order_id, client_order_id) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
         )
         .map_err(|err| Error::new(format!("prepare orders failed: {err}")))?;
     let trade_stmt = tx
         .prepare(
-            "INSERT INTO trades (id, order_id, time, symbol, side, price, qty, fee, fee_asset) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            "INSERT INTO trades (id, order_id, time, account, symbol, side, price, qty, fee, fee_asset) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
         )
         .map_err(|err| Error::new(format!("prepare trades failed: {err}")))?;
 
@@ -190,6 +406,7 @@ fn insert_orders_and_trades(
                 &order_id,
                 &time,
                 &mode,
+                &config.account,
                 &trade.symbol,
                 &side,
                 &order_type,
@@ -208,6 +425,7 @@ fn insert_orders_and_trades(
                 &trade_id,
                 &order_id,
                 &time,
+                &config.account,
                 &trade.symbol,
                 &side,
                 &trade.price,
@@ -230,11 +448,7 @@ fn insert_positions(
 ) -> Result<()> {
     let snapshot_time = snapshot_time(candles)?;
     let stmt = tx
-        .prepare(
-            "INSERT INTO positions (time, mode, symbol, qty, avg_price) \
-             VALUES ($1, $2, $3, $4, $5) \
-             ON CONFLICT (time, mode, symbol) DO UPDATE SET qty = EXCLUDED.qty, avg_price = EXCLUDED.avg_price",
-        )
+        .prepare(POSITION_UPSERT)
         .map_err(|err| Error::new(format!("prepare positions failed: {err}")))?;
 
     for position in &account.positions {
@@ -243,6 +457,7 @@ fn insert_positions(
             &[
                 &snapshot_time,
                 &config.mode,
+                &config.account,
                 &position.symbol,
                 &position.quantity,
                 &position.avg_price,
@@ -262,11 +477,7 @@ fn insert_balances(
     let snapshot_time = snapshot_time(candles)?;
     let cash_asset = infer_cash_asset(&config.symbol);
     let stmt = tx
-        .prepare(
-            "INSERT INTO balances (time, mode, asset, free, locked) \
-             VALUES ($1, $2, $3, $4, $5) \
-             ON CONFLICT (time, mode, asset) DO UPDATE SET free = EXCLUDED.free, locked = EXCLUDED.locked",
-        )
+        .prepare(BALANCE_UPSERT)
         .map_err(|err| Error::new(format!("prepare balances failed: {err}")))?;
 
     let balance = Balance {
@@ -279,6 +490,7 @@ fn insert_balances(
         &[
             &snapshot_time,
             &config.mode,
+            &config.account,
             &balance.asset,
             &balance.free,
             &balance.locked,
@@ -291,6 +503,7 @@ fn insert_balances(
 fn build_params_json(config: &Config) -> serde_json::Value {
     json!({
         "mode": config.mode,
+        "account": config.account,
         "exchange": config.exchange,
         "symbol": config.symbol,
         "orders": {
@@ -298,6 +511,8 @@ fn build_params_json(config: &Config) -> serde_json::Value {
             "limit_price_offset_bps": config.orders.limit_price_offset_bps,
             "fee_rate": config.orders.fee_rate,
             "slippage_bps": config.orders.slippage_bps,
+            "reprice_threshold_bps": config.orders.reprice_threshold_bps,
+            "reprice_max_amends": config.orders.reprice_max_amends,
         },
         "triggers": {
             "time_enabled": config.triggers.time_enabled,
@@ -336,8 +551,27 @@ fn split_statements(sql: &str) -> Vec<String> {
         cleaned.push_str(line);
         cleaned.push('\n');
     }
-    cleaned
-        .split(';')
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    for (index, part) in cleaned.split("$$").enumerate() {
+        if index % 2 == 1 {
+            current.push_str("$$");
+            current.push_str(part);
+            current.push_str("$$");
+            continue;
+        }
+        let mut pieces = part.split(';');
+        if let Some(first) = pieces.next() {
+            current.push_str(first);
+        }
+        for piece in pieces {
+            statements.push(std::mem::take(&mut current));
+            current.push_str(piece);
+        }
+    }
+    statements.push(current);
+    statements
+        .into_iter()
         .map(|statement| statement.trim().to_string())
         .filter(|statement| !statement.is_empty())
         .collect()
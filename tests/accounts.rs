@@ -0,0 +1,112 @@
+use merrow::app::metrics;
+use merrow::config::{AccountConfig, AccountRiskConfig, Config};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn account(name: &str, exchange: Option<&str>, symbols: &[&str]) -> AccountConfig {
+    AccountConfig {
+        name: name.to_string(),
+        exchange: exchange.map(str::to_string),
+        symbols: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+        label: None,
+        risk: AccountRiskConfig::default(),
+    }
+}
+
+#[test]
+fn loads_account_profiles_from_config() {
+    let path = env::temp_dir().join("merrow_accounts.toml");
+    let content = r#"
+mode = "live"
+exchange = "binance"
+symbol = "BTCUSDT"
+
+[risk]
+max_trade_ratio = 0.5
+
+[[accounts]]
+name = "book-a"
+symbols = ["BTCUSDT", "ETHUSDT"]
+label = "sub-a"
+
+[accounts.risk]
+max_trade_ratio = 0.1
+
+[[accounts]]
+name = "book-b"
+exchange = "okx"
+"#;
+    fs::write(&path, content).expect("write config");
+    let config = Config::load(path.to_str().expect("path")).expect("load");
+
+    assert_eq!(config.account, "default");
+    assert_eq!(config.accounts.len(), 2);
+    let book_a = &config.accounts[0];
+    assert_eq!(book_a.label.as_deref(), Some("sub-a"));
+    assert_eq!(book_a.risk.max_trade_ratio, Some(0.1));
+    assert_eq!(config.account_symbols(book_a), vec!["BTCUSDT", "ETHUSDT"]);
+    let book_b = &config.accounts[1];
+    assert_eq!(book_b.exchange.as_deref(), Some("okx"));
+    assert_eq!(config.account_symbols(book_b), vec!["BTCUSDT"]);
+}
+
+#[test]
+fn account_config_overrides_exchange_risk_label_and_state_paths() {
+    let mut config = Config::default();
+    config.data.exchange_base_url = Some("https://api.binance.com".to_string());
+    let mut profile = account("book-a", Some("okx"), &["BTC-USDT"]);
+    profile.risk.max_trade_ratio = Some(0.1);
+
+    let derived = config.for_account(&profile, "BTC-USDT");
+    assert_eq!(derived.account, "book-a");
+    assert_eq!(derived.exchange, "okx");
+    assert_eq!(derived.symbol, "BTC-USDT");
+    assert_eq!(derived.keystore.label, "book-a");
+    assert!(derived.data.exchange_base_url.is_none());
+    assert!((derived.risk.max_trade_ratio - 0.1).abs() < 1e-9);
+    assert!(
+        (derived.risk.min_cash_reserve_ratio - config.risk.min_cash_reserve_ratio).abs() < 1e-9
+    );
+    assert_eq!(
+        Path::new(&derived.execution.journal_path),
+        Path::new("output/book-a/BTC-USDT/order_journal.jsonl")
+    );
+    assert_eq!(
+        Path::new(&derived.circuit_breaker.state_path),
+        Path::new("output/book-a/BTC-USDT/breaker_state.json")
+    );
+    assert_eq!(
+        derived.circuit_breaker.kill_switch_path,
+        config.circuit_breaker.kill_switch_path
+    );
+}
+
+#[test]
+fn account_names_must_be_unique_and_risk_bounded() {
+    let mut config = Config {
+        accounts: vec![account("book-a", None, &[]), account("book-a", None, &[])],
+        ..Config::default()
+    };
+    assert!(config.validate().is_err());
+
+    config.accounts = vec![account("book a", None, &[])];
+    assert!(config.validate().is_err());
+
+    let mut profile = account("book-a", None, &[]);
+    profile.risk.max_position_value_ratio = Some(1.5);
+    config.accounts = vec![profile];
+    assert!(config.validate().is_err());
+
+    config.accounts = vec![account("book-a", None, &["ETHUSDT"])];
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn live_metrics_are_tagged_by_account() {
+    metrics::record_live("book-metrics", true, 2, 1, 1);
+    metrics::record_live("book-metrics", false, 0, 0, 0);
+    let rendered = metrics::render();
+    assert!(rendered.contains("merrow_account_live_runs_total{account=\"book-metrics\"} 2"));
+    assert!(rendered.contains("merrow_account_orders_sent_total{account=\"book-metrics\"} 1"));
+}
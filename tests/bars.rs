@@ -0,0 +1,184 @@
+use merrow::config::Config;
+use merrow::data::bars::{bars_from_prints, build_bars, BarBuilder, BarKind, BarSpec};
+use merrow::models::{Candle, TradePrint};
+
+fn candle(time: i64, open: f64, close: f64, volume: f64) -> Candle {
+    Candle {
+        time,
+        open,
+        high: open.max(close) + 1.0,
+        low: open.min(close) - 1.0,
+        close,
+        volume,
+    }
+}
+
+fn spec(kind: BarKind, size: f64) -> BarSpec {
+    BarSpec::new(kind, size).expect("spec")
+}
+
+#[test]
+fn volume_bars_close_once_threshold_is_reached() {
+    let candles = vec![
+        candle(0, 100.0, 101.0, 4.0),
+        candle(60, 101.0, 103.0, 7.0),
+        candle(120, 103.0, 102.0, 12.0),
+        candle(180, 102.0, 104.0, 3.0),
+    ];
+    let bars = build_bars(&candles, spec(BarKind::Volume, 10.0));
+    assert_eq!(
+        bars,
+        vec![
+            Candle {
+                time: 0,
+                open: 100.0,
+                high: 104.0,
+                low: 99.0,
+                close: 103.0,
+                volume: 11.0,
+            },
+            Candle {
+                time: 120,
+                open: 103.0,
+                high: 104.0,
+                low: 101.0,
+                close: 102.0,
+                volume: 12.0,
+            },
+        ]
+    );
+
+    let mut builder = BarBuilder::new(spec(BarKind::Volume, 10.0));
+    for candle in &candles {
+        builder.push(candle);
+    }
+    assert_eq!(builder.forming().map(|bar| bar.volume), Some(3.0));
+}
+
+#[test]
+fn dollar_and_tick_bars_use_notional_and_counts() {
+    let candles = vec![
+        candle(0, 100.0, 100.0, 1.0),
+        candle(60, 100.0, 200.0, 1.0),
+        candle(120, 200.0, 200.0, 0.5),
+        candle(180, 200.0, 200.0, 0.5),
+    ];
+    let dollar = build_bars(&candles, spec(BarKind::Dollar, 300.0));
+    assert_eq!(
+        dollar.iter().map(|bar| bar.time).collect::<Vec<_>>(),
+        vec![0]
+    );
+
+    let ticks = build_bars(&candles, spec(BarKind::Tick, 2.0));
+    assert_eq!(ticks.len(), 2);
+    assert_eq!((ticks[1].time, ticks[1].volume), (120, 1.0));
+}
+
+#[test]
+fn renko_needs_two_bricks_to_reverse() {
+    let closes = [100.0, 104.0, 111.0, 106.0, 99.0, 89.0];
+    let candles: Vec<Candle> = closes
+        .iter()
+        .enumerate()
+        .map(|(index, close)| candle(index as i64 * 60, *close, *close, 1.0))
+        .collect();
+    let bricks = build_bars(&candles, spec(BarKind::Renko, 5.0));
+    assert_eq!(
+        bricks
+            .iter()
+            .map(|brick| (brick.time, brick.open, brick.close))
+            .collect::<Vec<_>>(),
+        vec![
+            (120, 100.0, 105.0),
+            (121, 105.0, 110.0),
+            (240, 105.0, 100.0),
+            (300, 100.0, 95.0),
+            (301, 95.0, 90.0),
+        ]
+    );
+    assert_eq!(bricks[0].volume, 3.0);
+    assert_eq!(bricks[1].volume, 0.0);
+}
+
+#[test]
+fn heikin_ashi_smooths_open_and_close() {
+    let candles = vec![
+        Candle {
+            time: 0,
+            open: 10.0,
+            high: 14.0,
+            low: 8.0,
+            close: 12.0,
+            volume: 1.0,
+        },
+        Candle {
+            time: 60,
+            open: 12.0,
+            high: 16.0,
+            low: 11.0,
+            close: 15.0,
+            volume: 2.0,
+        },
+    ];
+    let bars = build_bars(&candles, spec(BarKind::HeikinAshi, 0.0));
+    assert_eq!((bars[0].open, bars[0].close), (11.0, 11.0));
+    assert_eq!((bars[1].open, bars[1].close), (11.0, 13.5));
+    assert_eq!((bars[1].high, bars[1].low), (16.0, 11.0));
+    assert_eq!(bars[1].volume, 2.0);
+}
+
+#[test]
+fn trade_prints_build_tick_and_volume_bars() {
+    let prints: Vec<TradePrint> = [(1_000, 10.0, 1.0), (1_500, 12.0, 2.0), (61_000, 9.0, 1.0)]
+        .iter()
+        .map(|(time_ms, price, quantity)| TradePrint {
+            time_ms: *time_ms,
+            price: *price,
+            quantity: *quantity,
+        })
+        .collect();
+    let ticks = bars_from_prints(&prints, spec(BarKind::Tick, 2.0)).expect("ticks");
+    assert_eq!(
+        ticks,
+        vec![Candle {
+            time: 1,
+            open: 10.0,
+            high: 12.0,
+            low: 10.0,
+            close: 12.0,
+            volume: 3.0,
+        }]
+    );
+    let volume = bars_from_prints(&prints, spec(BarKind::Volume, 1.0)).expect("volume");
+    assert_eq!(volume.len(), 3);
+    assert_eq!(volume[2].time, 61);
+    assert!(bars_from_prints(&prints, spec(BarKind::HeikinAshi, 0.0)).is_err());
+}
+
+#[test]
+fn bar_config_is_validated() {
+    assert_eq!(
+        BarKind::parse("Heikin_Ashi").expect("kind"),
+        BarKind::HeikinAshi
+    );
+    assert!(BarKind::parse("range").is_err());
+
+    let mut config = Config::default();
+    assert_eq!(config.data.bars, "time");
+    assert!(config.validate().is_ok());
+    config.data.bars = "volume".to_string();
+    assert!(config.validate().is_err());
+    config.data.bar_size = 50.0;
+    assert!(config.validate().is_ok());
+    assert_eq!(
+        BarSpec::from_config(&config.data).expect("spec"),
+        spec(BarKind::Volume, 50.0)
+    );
+    config.data.bars = "heikin_ashi".to_string();
+    config.data.bar_size = 0.0;
+    assert!(config.validate().is_ok());
+
+    config.mode = "paper".to_string();
+    let err = config.validate().expect_err("paper bars");
+    assert!(err.message.contains("only supported in backtest mode"));
+}
@@ -0,0 +1,89 @@
+use merrow::exchange::binance_futures::{
+    parse_funding_income, parse_futures_balances, parse_position_risk, BinanceFuturesConfig,
+    BinanceFuturesExchange,
+};
+use merrow::exchange::rate_limit::binance_futures_request_weight;
+use merrow::exchange::time_sync::TimeSyncConfig;
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> Value {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests");
+    path.push("fixtures");
+    path.push(name);
+    let content = fs::read_to_string(&path).expect("read fixture");
+    serde_json::from_str(&content).expect("parse fixture")
+}
+
+fn config() -> BinanceFuturesConfig {
+    BinanceFuturesConfig {
+        base_url: "https://fapi.binance.com".to_string(),
+        api_key: "key".to_string(),
+        api_secret: "secret".to_string(),
+        recv_window: 5000,
+        timeout_secs: 5,
+        default_symbol: Some("BTCUSDT".to_string()),
+        leverage: Some(5),
+        margin_type: Some("isolated".to_string()),
+        reduce_only_sells: true,
+        time_sync: TimeSyncConfig::default(),
+    }
+}
+
+#[test]
+fn parses_open_positions_from_position_risk() {
+    let positions = parse_position_risk(&fixture("binance_futures_position_risk.json"))
+        .expect("parse");
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].symbol, "BTCUSDT");
+    assert_eq!(positions[0].quantity, 0.25);
+    assert_eq!(positions[0].avg_price, 42000.5);
+}
+
+#[test]
+fn parses_funding_payments_in_time_order() {
+    let payments = parse_funding_income(&fixture("binance_futures_income.json")).expect("parse");
+    assert_eq!(payments.len(), 2);
+    assert_eq!(payments[0].time, 1704067200);
+    assert_eq!(payments[0].amount, 0.13);
+    assert_eq!(payments[1].amount, -0.42);
+    assert_eq!(payments[1].asset, "USDT");
+}
+
+#[test]
+fn parses_futures_balances() {
+    let json = json!([
+        {"asset": "USDT", "balance": "1000.0", "availableBalance": "750.0"},
+        {"asset": "BNB", "balance": "0.5", "availableBalance": "0.5"}
+    ]);
+    let balances = parse_futures_balances(&json).expect("parse");
+    assert_eq!(balances[0].free, 750.0);
+    assert_eq!(balances[0].locked, 250.0);
+    assert_eq!(balances[1].locked, 0.0);
+}
+
+#[test]
+fn futures_weights_scale_with_kline_limit() {
+    let limit = |value: &str| vec![("limit".to_string(), value.to_string())];
+    assert_eq!(binance_futures_request_weight("/fapi/v1/klines", &limit("50")), 1.0);
+    assert_eq!(binance_futures_request_weight("/fapi/v1/klines", &limit("1000")), 5.0);
+    assert_eq!(binance_futures_request_weight("/fapi/v1/klines", &limit("1500")), 10.0);
+    assert_eq!(binance_futures_request_weight("/fapi/v2/positionRisk", &[]), 5.0);
+    assert_eq!(binance_futures_request_weight("/fapi/v1/openOrders", &[]), 40.0);
+}
+
+#[test]
+fn rejects_invalid_leverage_and_margin_type() {
+    assert!(BinanceFuturesExchange::new(config()).is_ok());
+
+    let mut bad_leverage = config();
+    bad_leverage.leverage = Some(200);
+    assert!(BinanceFuturesExchange::new(bad_leverage).is_err());
+
+    let mut bad_margin = config();
+    bad_margin.margin_type = Some("portfolio".to_string());
+    assert!(BinanceFuturesExchange::new(bad_margin).is_err());
+}
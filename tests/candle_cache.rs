@@ -0,0 +1,160 @@
+use merrow::config::Config;
+use merrow::data::candle_cache::{
+    merge_ranges, missing_ranges, CacheKey, CachedSeries, CandleCache,
+};
+use merrow::data::exchange_loader::load_candles_from_exchange;
+use merrow::data::market_data::cache_market;
+use merrow::models::Candle;
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("merrow_candle_cache_tests").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn candle(time: i64) -> Candle {
+    Candle {
+        time,
+        open: time as f64,
+        high: time as f64 + 2.0,
+        low: time as f64 - 2.0,
+        close: time as f64 + 1.0,
+        volume: 1.5,
+    }
+}
+
+fn minute_candles(from: i64, to: i64) -> Vec<Candle> {
+    (from..=to)
+        .filter(|time| time % 60 == 0)
+        .map(candle)
+        .collect()
+}
+
+#[test]
+fn computes_missing_ranges_around_coverage() {
+    let coverage = merge_ranges(vec![(300, 599), (0, 119), (120, 179)]);
+    assert_eq!(coverage, vec![(0, 179), (300, 599)]);
+    assert_eq!(missing_ranges(&coverage, 0, 900), vec![(180, 299), (600, 900)]);
+    assert_eq!(missing_ranges(&coverage, 60, 170), Vec::<(i64, i64)>::new());
+    assert_eq!(missing_ranges(&[], 10, 20), vec![(10, 20)]);
+}
+
+#[test]
+fn fetches_only_uncovered_ranges_and_then_serves_from_disk() {
+    let cache = CandleCache::new(temp_dir("incremental"));
+    let key = CacheKey::new("Binance", "BTCUSDT", "1m");
+    let calls = RefCell::new(Vec::new());
+    let fetch = |from: i64, to: i64| {
+        calls.borrow_mut().push((from, to));
+        Ok(minute_candles(from, to))
+    };
+
+    let first = cache.load_range(&key, 0, 599, i64::MAX, fetch).expect("first");
+    assert_eq!(first.len(), 10);
+    let second = cache.load_range(&key, 300, 1199, i64::MAX, fetch).expect("second");
+    assert_eq!(second.len(), 15);
+    assert_eq!(second[0].time, 300);
+    let third = cache.load_range(&key, 0, 1199, i64::MAX, fetch).expect("third");
+    assert_eq!(third.len(), 20);
+
+    assert_eq!(*calls.borrow(), vec![(0, 599), (600, 1199)]);
+    let series = cache.read(&key).expect("read");
+    assert_eq!(series.coverage, vec![(0, 1199)]);
+    assert!(series.covers(0, 1199));
+}
+
+#[test]
+fn keeps_unsettled_candles_out_of_the_store() {
+    let cache = CandleCache::new(temp_dir("unsettled"));
+    let key = CacheKey::new("okx", "BTC-USDT", "1m");
+    let candles = cache
+        .load_range(&key, 0, 299, 179, |from, to| Ok(minute_candles(from, to)))
+        .expect("load");
+    assert_eq!(candles.len(), 5);
+
+    let series = cache.read(&key).expect("read");
+    assert_eq!(series.coverage, vec![(0, 179)]);
+    assert_eq!(series.candles.len(), 3);
+    assert!(!series.covers(0, 299));
+}
+
+#[test]
+fn round_trips_columnar_file_and_rejects_truncation() {
+    let dir = temp_dir("round_trip");
+    let cache = CandleCache::new(&dir);
+    let key = CacheKey::new("bybit", "ETH/USDT", "5m");
+    let series = CachedSeries {
+        coverage: vec![(0, 600)],
+        candles: vec![candle(0), candle(300), candle(600)],
+    };
+    cache.write(&key, &series).expect("write");
+    let path = cache.path(&key);
+    assert!(path.starts_with(&dir));
+    assert_eq!(path.file_name().and_then(|name| name.to_str()), Some("5m.bin"));
+    assert_eq!(cache.read(&key).expect("read"), series);
+
+    let bytes = fs::read(&path).expect("bytes");
+    fs::write(&path, &bytes[..bytes.len() - 8]).expect("truncate");
+    assert!(cache.read(&key).is_err());
+}
+
+#[test]
+fn exchange_loader_reads_covered_ranges_without_network() {
+    let dir = temp_dir("loader");
+    let cache = CandleCache::new(&dir);
+    let key = CacheKey::for_market("binance", "default@127.0.0.1:9", "BTCUSDT", "1m");
+    cache
+        .write(
+            &key,
+            &CachedSeries {
+                coverage: vec![(1_704_067_200, 1_704_070_800)],
+                candles: minute_candles(1_704_067_200, 1_704_070_800),
+            },
+        )
+        .expect("seed");
+
+    let mut config = Config::default();
+    config.data.source = "exchange".to_string();
+    config.data.candle_interval = "1m".to_string();
+    config.data.exchange_base_url = Some("http://127.0.0.1:9".to_string());
+    config.data.cache_dir = Some(dir.to_string_lossy().to_string());
+    config.backtest.start_time = Some("1704067200".to_string());
+    config.backtest.end_time = Some("1704069000".to_string());
+
+    let candles = load_candles_from_exchange(&config).expect("cached load");
+    assert_eq!(candles.len(), 31);
+    assert_eq!(candles.first().map(|candle| candle.time), Some(1_704_067_200));
+    assert_eq!(candles.last().map(|candle| candle.time), Some(1_704_069_000));
+}
+
+#[test]
+fn spot_and_linear_markets_do_not_share_cache_entries() {
+    let dir = temp_dir("markets");
+    let cache = CandleCache::new(&dir);
+    let mut config = Config::default();
+    config.exchange = "bybit".to_string();
+    config.data.exchange_category = Some("spot".to_string());
+    let spot = CacheKey::for_market("bybit", &cache_market(&config), "BTCUSDT", "1m");
+    config.data.exchange_category = Some("linear".to_string());
+    let linear = CacheKey::for_market("bybit", &cache_market(&config), "BTCUSDT", "1m");
+    config.data.exchange_base_url = Some("https://api-testnet.bybit.com/".to_string());
+    let testnet = CacheKey::for_market("bybit", &cache_market(&config), "BTCUSDT", "1m");
+
+    assert_ne!(cache.path(&spot), cache.path(&linear));
+    assert_ne!(cache.path(&linear), cache.path(&testnet));
+
+    cache
+        .write(
+            &spot,
+            &CachedSeries {
+                coverage: vec![(0, 120)],
+                candles: minute_candles(0, 120),
+            },
+        )
+        .expect("write spot");
+    assert_eq!(cache.read(&linear).expect("read linear"), CachedSeries::default());
+    assert_eq!(cache.read(&spot).expect("read spot").candles.len(), 3);
+}
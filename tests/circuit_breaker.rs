@@ -0,0 +1,224 @@
+use merrow::backtest::BacktestEngine;
+use merrow::config::Config;
+use merrow::core::circuit_breaker::{
+    load_breaker_state, save_breaker_state, CircuitBreaker, CircuitBreakerLimits, HaltReason,
+    KillSwitch,
+};
+use merrow::core::order_flow::OrderFlow;
+use merrow::core::risk::{RiskLimits, RiskManager};
+use merrow::core::strategies::ThresholdStrategy;
+use merrow::core::triggers::{PriceTrigger, TriggerEngine};
+use merrow::core::{StrategyContext, TriggerMode};
+use merrow::models::{Account, Candle, Position, Side, Signal};
+use std::env;
+use std::fs;
+
+fn candle(time: i64, open: f64, high: f64, low: f64, close: f64) -> Candle {
+    Candle {
+        time,
+        open,
+        high,
+        low,
+        close,
+        volume: 1.0,
+    }
+}
+
+fn open_risk() -> RiskManager {
+    RiskManager::new(RiskLimits {
+        max_trade_ratio: 1.0,
+        min_cash_reserve_ratio: 0.0,
+        max_position_value_ratio: 1.0,
+    })
+    .expect("risk manager")
+}
+
+#[test]
+fn drawdown_from_high_water_mark_halts() {
+    let mut breaker = CircuitBreaker::new(CircuitBreakerLimits {
+        max_drawdown_ratio: 0.1,
+        ..CircuitBreakerLimits::default()
+    })
+    .expect("breaker");
+
+    breaker.observe_equity(0, 1000.0);
+    breaker.observe_equity(60, 1200.0);
+    breaker.observe_equity(120, 1100.0);
+    assert!(!breaker.is_halted());
+    breaker.observe_equity(180, 1080.0);
+    assert_eq!(breaker.halt_reason(), Some(HaltReason::Drawdown));
+    assert!(breaker.check_entry(180).is_err());
+}
+
+#[test]
+fn daily_loss_halt_clears_on_next_day() {
+    let mut breaker = CircuitBreaker::new(CircuitBreakerLimits {
+        max_daily_loss_ratio: 0.05,
+        ..CircuitBreakerLimits::default()
+    })
+    .expect("breaker");
+
+    breaker.observe_equity(0, 1000.0);
+    breaker.observe_equity(3600, 940.0);
+    assert_eq!(breaker.halt_reason(), Some(HaltReason::DailyLoss));
+
+    breaker.observe_equity(86_400, 945.0);
+    assert!(!breaker.is_halted());
+}
+
+#[test]
+fn consecutive_losses_halt_and_win_resets_streak() {
+    let mut breaker = CircuitBreaker::new(CircuitBreakerLimits {
+        max_consecutive_losses: 2,
+        ..CircuitBreakerLimits::default()
+    })
+    .expect("breaker");
+
+    breaker.record_trade_pnl(1, -1.0);
+    breaker.record_trade_pnl(2, 5.0);
+    breaker.record_trade_pnl(3, -1.0);
+    assert!(!breaker.is_halted());
+    breaker.record_trade_pnl(4, -2.0);
+    assert_eq!(breaker.halt_reason(), Some(HaltReason::ConsecutiveLosses));
+}
+
+#[test]
+fn order_window_blocks_entries_until_window_passes() {
+    let mut breaker = CircuitBreaker::new(CircuitBreakerLimits {
+        max_orders_per_window: 2,
+        order_window_secs: 3600,
+        ..CircuitBreakerLimits::default()
+    })
+    .expect("breaker");
+
+    breaker.record_order(0);
+    breaker.record_order(60);
+    assert!(breaker.check_entry(120).is_err());
+    assert!(!breaker.is_halted());
+    assert!(breaker.check_entry(3601).is_ok());
+}
+
+#[test]
+fn halted_order_flow_drops_buys_but_keeps_sells() {
+    let mut config = Config::default();
+    config.orders.order_type = "market".to_string();
+    config.strategy.rebuy_cash_ratio = 0.5;
+
+    let mut breaker = CircuitBreaker::new(CircuitBreakerLimits::default()).expect("breaker");
+    breaker.trip(HaltReason::Drawdown, 1);
+    let mut flow = OrderFlow::with_circuit_breaker(open_risk(), breaker);
+
+    let account = Account {
+        cash: 1000.0,
+        positions: vec![Position {
+            symbol: config.symbol.clone(),
+            quantity: 2.0,
+            avg_price: 100.0,
+        }],
+    };
+    let candle = candle(1, 100.0, 100.0, 100.0, 100.0);
+    let history = vec![candle.clone()];
+    let ctx = StrategyContext {
+        candle: &candle,
+        history: &history,
+        account: &account,
+        timeframes: &[],
+        now: 1,
+    };
+
+    let buys = flow.plan(Signal::Buy, &ctx, &config).expect("plan buy");
+    assert!(buys.is_empty());
+    let sells = flow.plan(Signal::Sell, &ctx, &config).expect("plan sell");
+    assert_eq!(sells.len(), 1);
+    assert_eq!(sells[0].side, Side::Sell);
+}
+
+#[test]
+fn backtest_flattens_position_after_drawdown_halt() {
+    let mut config = Config::default();
+    config.orders.order_type = "market".to_string();
+    config.orders.slippage_bps = 0;
+    config.orders.fee_rate = 0.0;
+    config.strategy.buy_cash_ratio = 1.0;
+
+    let candles = vec![
+        candle(1, 100.0, 100.0, 100.0, 100.0),
+        candle(2, 100.0, 100.0, 100.0, 100.0),
+        candle(3, 100.0, 100.0, 100.0, 100.0),
+        candle(4, 90.0, 95.0, 89.0, 90.0),
+        candle(5, 90.0, 90.0, 50.0, 50.0),
+        candle(6, 50.0, 50.0, 50.0, 50.0),
+        candle(7, 50.0, 50.0, 50.0, 50.0),
+    ];
+
+    let trigger = PriceTrigger::new(3, 0.05, 0.05);
+    let trigger_engine = TriggerEngine::new(TriggerMode::Any, vec![Box::new(trigger)]);
+    let mut strategy = ThresholdStrategy::new(3, 0.05, 0.05);
+    let breaker = CircuitBreaker::new(CircuitBreakerLimits {
+        max_drawdown_ratio: 0.2,
+        flatten_on_halt: true,
+        ..CircuitBreakerLimits::default()
+    })
+    .expect("breaker");
+    let mut order_flow = OrderFlow::with_circuit_breaker(open_risk(), breaker);
+
+    let result = BacktestEngine
+        .run_strategy(
+            &candles,
+            &config,
+            &trigger_engine,
+            &mut strategy,
+            &mut order_flow,
+            1000.0,
+        )
+        .expect("run strategy");
+
+    assert_eq!(
+        order_flow.circuit_breaker().halt_reason(),
+        Some(HaltReason::Drawdown)
+    );
+    let last = result.trades.last().expect("flatten trade");
+    assert_eq!(last.side, Side::Sell);
+    let remaining: f64 = result
+        .account
+        .positions
+        .iter()
+        .map(|pos| pos.quantity)
+        .sum();
+    assert_eq!(remaining, 0.0);
+}
+
+#[test]
+fn kill_switch_detects_file_and_flag() {
+    let mut path = env::temp_dir();
+    path.push("merrow_kill_switch_test");
+    let _ = fs::remove_file(&path);
+    let path_str = path.to_str().expect("path").to_string();
+
+    let switch = KillSwitch::new(Some(path_str.clone()), false);
+    assert!(!switch.is_engaged());
+    fs::write(&path, "halt").expect("write kill switch");
+    assert!(switch.is_engaged());
+    fs::remove_file(&path).expect("remove kill switch");
+
+    assert!(KillSwitch::new(None, true).is_engaged());
+}
+
+#[test]
+fn halt_state_persists_across_restarts() {
+    let mut path = env::temp_dir();
+    path.push("merrow_breaker_state_test.json");
+    let _ = fs::remove_file(&path);
+    let path_str = path.to_str().expect("path");
+
+    let mut breaker = CircuitBreaker::new(CircuitBreakerLimits::default()).expect("breaker");
+    breaker.trip(HaltReason::KillSwitch, 42);
+    save_breaker_state(path_str, breaker.state()).expect("save");
+
+    let state = load_breaker_state(path_str).expect("load").expect("state");
+    let restored =
+        CircuitBreaker::with_state(CircuitBreakerLimits::default(), state).expect("restore");
+    assert_eq!(restored.halt_reason(), Some(HaltReason::KillSwitch));
+    assert_eq!(restored.state().halted_at, Some(42));
+    let _ = fs::remove_file(&path);
+}
@@ -52,6 +52,16 @@ fn data_source_must_be_known() {
     assert!(result.is_err());
 }
 
+#[test]
+fn postgres_source_needs_no_csv_path() {
+    let mut config = Config::default();
+    config.data.source = "postgres".to_string();
+    config.data.csv_path = None;
+    assert!(config.validate().is_ok());
+    config.storage.postgres_dsn = " ".to_string();
+    assert!(config.validate().is_err());
+}
+
 #[test]
 fn order_type_must_be_known() {
     let mut config = Config::default();
@@ -111,3 +121,44 @@ fn exchange_limit_must_be_positive() {
     let result = config.validate();
     assert!(result.is_err());
 }
+
+#[test]
+fn client_id_prefix_must_be_alphanumeric() {
+    let mut config = Config::default();
+    config.orders.client_id_prefix = "bad-prefix".to_string();
+    assert!(config.validate().is_err());
+    config.orders.client_id_prefix = "bot1".to_string();
+    config.orders.orphan_policy = "ignore".to_string();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn reconciliation_drift_ratio_must_be_a_ratio() {
+    let mut config = Config::default();
+    config.reconciliation.max_drift_ratio = 1.5;
+    assert!(config.validate().is_err());
+    config.reconciliation.max_drift_ratio = 0.02;
+    config.reconciliation.report_path = String::new();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn keystore_path_and_label_must_be_non_empty() {
+    let mut config = Config::default();
+    config.keystore.path = Some(" ".to_string());
+    assert!(config.validate().is_err());
+    config.keystore.path = Some("secrets/keystore.json".to_string());
+    assert!(config.validate().is_ok());
+    config.keystore.label = String::new();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn timeframes_require_time_bars() {
+    let mut config = Config::default();
+    config.data.timeframes = vec!["4h".to_string()];
+    assert!(config.validate().is_ok());
+    config.data.bars = "volume".to_string();
+    config.data.bar_size = 100.0;
+    assert!(config.validate().is_err());
+}
@@ -1,6 +1,14 @@
-use merrow::data::csv_loader::{load_candles_from_csv, parse_time};
+use merrow::config::{Config, CsvConfig};
+use chrono::FixedOffset;
+use merrow::data::csv_loader::{
+    load_candles_from_csv, load_csv_with_schema, parse_time, parse_timezone, CandleExtras,
+    DataTimeZone,
+};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use std::env;
 use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
 
 fn fixture_path(name: &str) -> PathBuf {
@@ -42,3 +50,159 @@ fn rejects_invalid_row() {
 
     let _ = fs::remove_file(&path);
 }
+
+fn custom_schema() -> CsvConfig {
+    CsvConfig {
+        delimiter: ";".to_string(),
+        time_column: "open_time".to_string(),
+        open_column: "o".to_string(),
+        high_column: "h".to_string(),
+        low_column: "l".to_string(),
+        close_column: "c".to_string(),
+        volume_column: "vol".to_string(),
+        quote_volume_column: Some("quote_vol".to_string()),
+        trade_count_column: Some("trades".to_string()),
+        time_unit: "ms".to_string(),
+        ..CsvConfig::default()
+    }
+}
+
+#[test]
+fn loads_custom_schema_with_extra_columns() {
+    let path = fixture_path("candles_custom.csv");
+    let loaded = load_csv_with_schema(path.to_str().expect("path"), &custom_schema()).expect("load");
+
+    assert_eq!(
+        loaded.candles.iter().map(|candle| candle.time).collect::<Vec<_>>(),
+        vec![1_704_067_200, 1_704_067_260, 1_704_067_320]
+    );
+    assert_eq!(loaded.candles[1].close, 106.0);
+    assert_eq!(
+        loaded.extras[0],
+        CandleExtras {
+            time: 1_704_067_200,
+            quote_volume: Some(105.25),
+            trade_count: Some(9),
+        }
+    );
+}
+
+#[test]
+fn gzip_input_matches_plain_input() {
+    let plain = fixture_path("candles_custom.csv");
+    let gzipped = fixture_path("candles_custom.csv.gz");
+    let schema = custom_schema();
+    let expected = load_csv_with_schema(plain.to_str().expect("path"), &schema).expect("plain");
+    let loaded = load_csv_with_schema(gzipped.to_str().expect("path"), &schema).expect("gzip");
+    assert_eq!(loaded.candles, expected.candles);
+    assert_eq!(loaded.extras, expected.extras);
+}
+
+#[test]
+fn multi_member_gzip_is_read_in_full() {
+    let plain = fixture_path("candles_custom.csv");
+    let content = fs::read_to_string(&plain).expect("read");
+    let split = content.find("1704067200000").expect("second row");
+    let mut bytes = Vec::new();
+    for part in [&content[..split], &content[split..]] {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(part.as_bytes()).expect("compress");
+        bytes.extend(encoder.finish().expect("finish"));
+    }
+    let dir = env::temp_dir().join("merrow_csv_loader_tests");
+    fs::create_dir_all(&dir).expect("dir");
+    let path = dir.join("multi_member.csv.gz");
+    fs::write(&path, bytes).expect("write");
+
+    let schema = custom_schema();
+    let expected = load_csv_with_schema(plain.to_str().expect("path"), &schema).expect("plain");
+    let loaded = load_csv_with_schema(path.to_str().expect("path"), &schema).expect("gzip");
+    assert_eq!(loaded.candles, expected.candles);
+}
+
+#[test]
+fn strftime_format_applies_timezone() {
+    let mut path = env::temp_dir();
+    path.push("merrow_custom_time.csv");
+    let content = "Date,Open,High,Low,Close,Volume\n01/01/2024 08:00,100,110,90,105,1\n";
+    fs::write(&path, content).expect("write temp");
+
+    let schema = CsvConfig {
+        time_column: "Date".to_string(),
+        time_format: "%d/%m/%Y %H:%M".to_string(),
+        timezone: "+08:00".to_string(),
+        ..CsvConfig::default()
+    };
+    let loaded = load_csv_with_schema(path.to_str().expect("path"), &schema).expect("load");
+    assert_eq!(loaded.candles[0].time, 1_704_067_200);
+    assert_eq!(loaded.extras[0].quote_volume, None);
+
+    let missing = CsvConfig {
+        volume_column: "qty".to_string(),
+        ..schema
+    };
+    assert!(load_csv_with_schema(path.to_str().expect("path"), &missing).is_err());
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn named_timezone_follows_daylight_saving() {
+    let mut path = env::temp_dir();
+    path.push("merrow_new_york_time.csv");
+    let content = "Date,Open,High,Low,Close,Volume\n\
+        2024-01-15 09:30,100,110,90,105,1\n\
+        2024-07-15 09:30,100,110,90,105,1\n\
+        2024-11-03 01:30,100,110,90,105,1\n";
+    fs::write(&path, content).expect("write temp");
+
+    let schema = CsvConfig {
+        time_column: "Date".to_string(),
+        time_format: "%Y-%m-%d %H:%M".to_string(),
+        timezone: "America/New_York".to_string(),
+        ..CsvConfig::default()
+    };
+    let loaded = load_csv_with_schema(path.to_str().expect("path"), &schema).expect("load");
+    let times: Vec<i64> = loaded.candles.iter().map(|candle| candle.time).collect();
+    // EST is UTC-5, EDT is UTC-4; the repeated fall-back hour resolves to its first (EDT) pass.
+    assert_eq!(times, vec![1_705_329_000, 1_721_050_200, 1_730_611_800]);
+
+    let gap = "Date,Open,High,Low,Close,Volume\n2024-03-10 02:30,100,110,90,105,1\n";
+    fs::write(&path, gap).expect("write temp");
+    assert!(load_csv_with_schema(path.to_str().expect("path"), &schema).is_err());
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn parse_time_accepts_naive_datetimes_as_utc() {
+    let expected = 1_704_067_200;
+    assert_eq!(parse_time("2024-01-01").expect("date"), expected);
+    assert_eq!(parse_time("2024-01-01 00:00:00").expect("space"), expected);
+    assert_eq!(parse_time("2024-01-01T00:00:00").expect("naive"), expected);
+    assert!(parse_time("01/01/2024").is_err());
+}
+
+#[test]
+fn csv_schema_is_validated() {
+    assert_eq!(
+        parse_timezone("-05:30").expect("offset"),
+        DataTimeZone::Fixed(FixedOffset::west_opt(19_800).expect("offset"))
+    );
+    assert!(matches!(parse_timezone("Asia/Taipei"), Ok(DataTimeZone::Named(_))));
+    let unknown = parse_timezone("Mars/Olympus").expect_err("unknown zone");
+    assert!(unknown.message.contains("IANA"));
+
+    let mut config = Config::default();
+    assert!(config.validate().is_ok());
+    config.data.csv.delimiter = "||".to_string();
+    assert!(config.validate().is_err());
+    config.data.csv = CsvConfig {
+        time_unit: "minutes".to_string(),
+        ..CsvConfig::default()
+    };
+    assert!(config.validate().is_err());
+    config.data.csv.time_unit = "ns".to_string();
+    config.data.csv.timezone = "+25:00".to_string();
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,196 @@
+use merrow::config::Config;
+use merrow::data::quality::{
+    check_candles, forward_fill, Gap, GapPolicy, QualityThresholds, Run,
+};
+use merrow::models::Candle;
+
+fn candle(time: i64, close: f64, volume: f64) -> Candle {
+    Candle {
+        time,
+        open: close,
+        high: close + 1.0,
+        low: close - 1.0,
+        close,
+        volume,
+    }
+}
+
+fn thresholds() -> QualityThresholds {
+    QualityThresholds {
+        max_spike_ratio: 0.2,
+        max_stale_bars: 3,
+        max_zero_volume_bars: 2,
+    }
+}
+
+#[test]
+fn clean_series_has_no_findings() {
+    let candles: Vec<Candle> = (0..10)
+        .map(|index| candle(index * 60, 100.0 + index as f64, 1.0))
+        .collect();
+    let report = check_candles(&candles, 60, &thresholds());
+    assert!(report.is_clean());
+    assert_eq!(report.candles, 10);
+    assert_eq!(report.first, Some(0));
+    assert_eq!(report.last, Some(540));
+}
+
+#[test]
+fn reports_gaps_and_misaligned_bars() {
+    let candles = vec![
+        candle(0, 100.0, 1.0),
+        candle(60, 101.0, 1.0),
+        candle(300, 102.0, 1.0),
+        candle(390, 103.0, 1.0),
+        candle(420, 104.0, 1.0),
+    ];
+    let report = check_candles(&candles, 60, &thresholds());
+    assert_eq!(
+        report.gaps,
+        vec![
+            Gap {
+                after: 60,
+                before: 300,
+                missing: 3,
+            },
+            Gap {
+                after: 300,
+                before: 390,
+                missing: 1,
+            },
+        ]
+    );
+    assert_eq!(report.missing_bars(), 4);
+    assert_eq!(report.misaligned, vec![390]);
+    assert!(report.has_holes());
+}
+
+#[test]
+fn weekly_bars_align_to_monday() {
+    let week = 604_800;
+    let monday = 345_600;
+    let candles = vec![
+        candle(monday, 100.0, 1.0),
+        candle(monday + week, 101.0, 1.0),
+        candle(monday + 3 * week, 102.0, 1.0),
+        candle(4 * week, 103.0, 1.0),
+    ];
+    let report = check_candles(&candles, week, &thresholds());
+    assert_eq!(
+        report.gaps,
+        vec![Gap {
+            after: monday + week,
+            before: monday + 3 * week,
+            missing: 1,
+        }]
+    );
+    assert_eq!(report.misaligned, vec![4 * week]);
+}
+
+#[test]
+fn monthly_bars_follow_calendar_months() {
+    let month = 2_592_000;
+    let jan = 1_704_067_200;
+    let feb = 1_706_745_600;
+    let mar = 1_709_251_200;
+    let may = 1_714_521_600;
+    let candles = vec![candle(jan, 100.0, 1.0), candle(feb, 101.0, 1.0), candle(mar, 102.0, 1.0)];
+    assert!(check_candles(&candles, month, &thresholds()).is_clean());
+
+    let candles = vec![
+        candle(jan, 100.0, 1.0),
+        candle(feb, 101.0, 1.0),
+        candle(may, 102.0, 1.0),
+        candle(may + 86_400, 103.0, 1.0),
+    ];
+    let report = check_candles(&candles, month, &thresholds());
+    assert_eq!(
+        report.gaps,
+        vec![Gap {
+            after: feb,
+            before: may,
+            missing: 2,
+        }]
+    );
+    assert_eq!(report.misaligned, vec![may + 86_400]);
+
+    let filled = forward_fill(&candles[..3], month);
+    let times: Vec<i64> = filled.iter().map(|candle| candle.time).collect();
+    assert_eq!(times, vec![jan, feb, mar, 1_711_929_600, may]);
+}
+
+#[test]
+fn flags_spikes_stale_closes_and_zero_volume_runs() {
+    let candles = vec![
+        candle(0, 100.0, 1.0),
+        candle(60, 150.0, 1.0),
+        candle(120, 100.0, 0.0),
+        candle(180, 100.0, 0.0),
+        candle(240, 100.0, 0.0),
+        candle(300, 101.0, 1.0),
+        candle(360, 102.0, 0.0),
+    ];
+    let report = check_candles(&candles, 60, &thresholds());
+    assert!(!report.has_holes());
+    assert_eq!(report.spikes.len(), 2);
+    assert_eq!(report.spikes[0].time, 60);
+    assert!((report.spikes[0].ratio - 0.5).abs() < 1e-9);
+    assert_eq!(
+        report.stale_runs,
+        vec![Run {
+            start: 120,
+            end: 240,
+            bars: 3,
+        }]
+    );
+    assert_eq!(
+        report.zero_volume_runs,
+        vec![Run {
+            start: 120,
+            end: 240,
+            bars: 3,
+        }]
+    );
+    assert_eq!(report.outlier_count(), 4);
+}
+
+#[test]
+fn zero_thresholds_disable_checks() {
+    let candles = vec![candle(0, 100.0, 0.0), candle(60, 100.0, 0.0), candle(120, 300.0, 0.0)];
+    let disabled = QualityThresholds {
+        max_spike_ratio: 0.0,
+        max_stale_bars: 0,
+        max_zero_volume_bars: 0,
+    };
+    assert!(check_candles(&candles, 60, &disabled).is_clean());
+}
+
+#[test]
+fn forward_fill_repeats_previous_close_with_zero_volume() {
+    let candles = vec![candle(0, 100.0, 1.0), candle(180, 103.0, 2.0)];
+    let filled = forward_fill(&candles, 60);
+    assert_eq!(filled.len(), 4);
+    assert_eq!(filled[1].time, 60);
+    assert_eq!(filled[2].time, 120);
+    for bar in &filled[1..3] {
+        assert_eq!((bar.open, bar.high, bar.low, bar.close), (100.0, 100.0, 100.0, 100.0));
+        assert_eq!(bar.volume, 0.0);
+    }
+    assert_eq!(filled[3], candles[1]);
+    assert!(!check_candles(&filled, 60, &thresholds()).has_holes());
+}
+
+#[test]
+fn gap_policy_parses_and_defaults_to_fail() {
+    assert_eq!(GapPolicy::parse("FILL").expect("fill"), GapPolicy::Fill);
+    assert_eq!(GapPolicy::parse("warn").expect("warn"), GapPolicy::Warn);
+    assert!(GapPolicy::parse("skip").is_err());
+
+    let mut config = Config::default();
+    assert_eq!(config.data.gap_policy, "fail");
+    let thresholds = QualityThresholds::from_config(&config.data);
+    assert_eq!(thresholds.max_stale_bars, config.data.max_stale_bars as usize);
+    assert!(config.validate().is_ok());
+    config.data.gap_policy = "skip".to_string();
+    assert!(config.validate().is_err());
+}
@@ -1,7 +1,9 @@
 use merrow::data::exchange_loader::{
-    map_bybit_interval, map_okx_interval, parse_binance_klines, parse_bybit_klines,
-    parse_okx_candles,
+    map_bybit_interval, map_coinbase_granularity, map_kraken_interval, map_okx_interval,
+    parse_binance_agg_trades, parse_binance_klines, parse_bybit_klines, parse_bybit_trades,
+    parse_coinbase_candles, parse_kraken_ohlc, parse_okx_candles, parse_okx_trades,
 };
+use merrow::models::TradePrint;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -50,6 +52,56 @@ fn parses_okx_candles() {
     assert_eq!(parsed.oldest_start_ms, Some(1704067200000));
 }
 
+#[test]
+fn parses_kraken_ohlc() {
+    let path = fixture_path("kraken_ohlc.json");
+    let content = fs::read_to_string(&path).expect("read fixture");
+    let parsed = parse_kraken_ohlc(&content).expect("parse");
+
+    assert_eq!(parsed.candles.len(), 2);
+    assert_eq!(parsed.candles[0].time, 1704067200);
+    assert_eq!(parsed.candles[0].close, 105.0);
+    assert_eq!(parsed.candles[1].close, 108.0);
+    assert_eq!(parsed.candles[1].volume, 2.0);
+    assert_eq!(parsed.last, Some(1704067260));
+}
+
+#[test]
+fn rejects_kraken_errors() {
+    let payload = r#"{"error":["EQuery:Unknown asset pair"],"result":{}}"#;
+    let err = parse_kraken_ohlc(payload).err().expect("error");
+    assert_eq!(err.message, "kraken error: EQuery:Unknown asset pair");
+}
+
+#[test]
+fn parses_coinbase_candles() {
+    let path = fixture_path("coinbase_candles.json");
+    let content = fs::read_to_string(&path).expect("read fixture");
+    let parsed = parse_coinbase_candles(&content).expect("parse");
+
+    assert_eq!(parsed.candles.len(), 2);
+    assert_eq!(parsed.candles[0].close, 108.0);
+    assert_eq!(parsed.candles[1].close, 105.0);
+    assert_eq!(parsed.candles[1].time, 1704067200);
+    assert_eq!(parsed.oldest_start_ms, Some(1704067200000));
+}
+
+#[test]
+fn maps_kraken_intervals() {
+    assert_eq!(map_kraken_interval("1m").expect("1m"), "1");
+    assert_eq!(map_kraken_interval("4h").expect("4h"), "240");
+    assert_eq!(map_kraken_interval("1D").expect("1D"), "1440");
+    assert!(map_kraken_interval("3m").is_err());
+}
+
+#[test]
+fn maps_coinbase_granularities() {
+    assert_eq!(map_coinbase_granularity("1m").expect("1m"), "ONE_MINUTE");
+    assert_eq!(map_coinbase_granularity("6h").expect("6h"), "SIX_HOUR");
+    assert_eq!(map_coinbase_granularity("1d").expect("1d"), "ONE_DAY");
+    assert!(map_coinbase_granularity("1w").is_err());
+}
+
 #[test]
 fn maps_okx_intervals() {
     assert_eq!(map_okx_interval("1m").expect("1m"), "1m");
@@ -67,3 +119,30 @@ fn maps_bybit_intervals() {
     assert_eq!(map_bybit_interval("1M").expect("1M"), "M");
     assert!(map_bybit_interval("7m").is_err());
 }
+
+#[test]
+fn parses_exchange_trade_prints() {
+    let read = |name: &str| fs::read_to_string(fixture_path(name)).expect("read fixture");
+
+    let binance = parse_binance_agg_trades(&read("binance_agg_trades.json")).expect("binance");
+    assert_eq!(binance.len(), 2);
+    assert_eq!(binance[1].id, "26130");
+    assert_eq!(
+        binance[1].print,
+        TradePrint {
+            time_ms: 1704067200456,
+            price: 42000.2,
+            quantity: 0.25,
+        }
+    );
+
+    let bybit = parse_bybit_trades(&read("bybit_trades.json")).expect("bybit");
+    assert_eq!(bybit[0].id, "2100000000007764263");
+    assert_eq!(bybit[1].print.time_ms, 1704067200500);
+    assert_eq!(bybit[1].print.quantity, 0.01);
+
+    let okx = parse_okx_trades(&read("okx_trades.json")).expect("okx");
+    assert_eq!(okx[1].id, "242720719");
+    assert_eq!(okx[1].print.price, 42000.8);
+    assert!(parse_okx_trades(r#"{"code":"50011","data":[]}"#).is_err());
+}
@@ -10,6 +10,8 @@ impl Exchange for MockExchange {
             client_order_id: "c1".to_string(),
             exchange_order_id: None,
             status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
         })
     }
 
@@ -38,6 +40,8 @@ impl Exchange for MockExchange {
             client_order_id: "c2".to_string(),
             exchange_order_id: Some("e2".to_string()),
             status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
         }])
     }
 
@@ -49,7 +53,8 @@ impl Exchange for MockExchange {
 #[test]
 fn sync_account_collects_balances_positions_orders() {
     let exchange = MockExchange;
-    let snapshot = sync_account(&exchange).expect("sync");
+    let snapshot = sync_account(&exchange, "sub-a").expect("sync");
+    assert_eq!(snapshot.account, "sub-a");
     assert_eq!(snapshot.balances.len(), 1);
     assert_eq!(snapshot.positions.len(), 1);
     assert_eq!(snapshot.open_orders.len(), 1);
@@ -0,0 +1,207 @@
+use merrow::backtest::BacktestEngine;
+use merrow::config::Config;
+use merrow::core::execution::{
+    implementation_shortfall_bps, volume_profile, ExecutionAlgo, ExecutionScheduler, ParentOrder,
+};
+use merrow::core::order_flow::OrderFlow;
+use merrow::core::risk::{RiskLimits, RiskManager};
+use merrow::core::strategies::ThresholdStrategy;
+use merrow::core::triggers::{PriceTrigger, TriggerEngine};
+use merrow::core::TriggerMode;
+use merrow::models::{Candle, OrderRequest, OrderType, Side};
+
+fn candle(time: i64, price: f64, volume: f64) -> Candle {
+    Candle {
+        time,
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        volume,
+    }
+}
+
+fn order(quantity: f64, order_type: OrderType) -> OrderRequest {
+    OrderRequest {
+        client_order_id: "order-1".to_string(),
+        symbol: "BTCUSDT".to_string(),
+        side: Side::Buy,
+        order_type,
+        quantity,
+    }
+}
+
+#[test]
+fn twap_slices_evenly_over_time() {
+    let algo = ExecutionAlgo::Twap {
+        slices: 4,
+        interval_secs: 60,
+    };
+    let mut parent = ParentOrder::new(&order(8.0, OrderType::Market), &algo, 100.0, 1_000, &[])
+        .expect("parent");
+    let due_times: Vec<i64> = parent.schedule().iter().map(|slice| slice.due_time).collect();
+    assert_eq!(due_times, vec![1_000, 1_060, 1_120, 1_180]);
+
+    let first = parent.due_children(1_000, 100.0).expect("children");
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].client_order_id, "order-1-1");
+    assert!((first[0].quantity - 2.0).abs() < 1e-9);
+
+    let caught_up = parent.due_children(1_130, 100.0).expect("children");
+    assert_eq!(caught_up.len(), 2);
+    assert!(parent.due_children(1_130, 100.0).expect("children").is_empty());
+}
+
+#[test]
+fn vwap_follows_historical_volume_profile() {
+    let day = 86_400;
+    let history = vec![
+        candle(0, 100.0, 30.0),
+        candle(60, 100.0, 10.0),
+        candle(day, 100.0, 30.0),
+        candle(day + 60, 100.0, 10.0),
+    ];
+    let weights = volume_profile(&history, 2 * day, 60, 2);
+    assert!((weights[0] - 0.75).abs() < 1e-9);
+    assert!((weights[1] - 0.25).abs() < 1e-9);
+
+    let algo = ExecutionAlgo::Vwap {
+        slices: 2,
+        interval_secs: 60,
+    };
+    let parent = ParentOrder::new(&order(4.0, OrderType::Market), &algo, 100.0, 2 * day, &history)
+        .expect("parent");
+    assert!((parent.schedule()[0].quantity - 3.0).abs() < 1e-9);
+    assert!((parent.schedule()[1].quantity - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn iceberg_refills_visible_clip_after_fill() {
+    let algo = ExecutionAlgo::Iceberg { visible_ratio: 0.25 };
+    let mut scheduler = ExecutionScheduler::new();
+    scheduler
+        .submit(&order(4.0, OrderType::Limit { price: 99.0 }), &algo, 100.0, 0, &[])
+        .expect("submit");
+
+    let clip = scheduler.due_children(0, 100.0).expect("clip");
+    assert_eq!(clip.len(), 1);
+    assert_eq!(clip[0].order_type, OrderType::Limit { price: 99.0 });
+    assert!((clip[0].quantity - 1.0).abs() < 1e-9);
+    assert!(scheduler.due_children(60, 100.0).expect("clip").is_empty());
+
+    assert!(scheduler.record_fill(&clip[0].client_order_id, 1.0, 99.0, 0.0));
+    let refill = scheduler.due_children(120, 100.0).expect("refill");
+    assert_eq!(refill.len(), 1);
+    assert_eq!(refill[0].client_order_id, "order-1-2");
+}
+
+#[test]
+fn booked_fills_realize_pnl_against_cost_basis() {
+    let mut scheduler = ExecutionScheduler::new();
+    let mut buy = order(2.0, OrderType::Market);
+    buy.client_order_id = "buy-1".to_string();
+    let mut sell = order(2.0, OrderType::Market);
+    sell.client_order_id = "sell-1".to_string();
+    sell.side = Side::Sell;
+    for request in [&buy, &sell] {
+        scheduler
+            .submit(request, &ExecutionAlgo::Immediate, 100.0, 0, &[])
+            .expect("submit");
+    }
+    let children = scheduler.due_children(0, 100.0).expect("children");
+    assert_eq!(children.len(), 2);
+
+    assert_eq!(scheduler.book_fill("buy-1", 1.0, 100.0), None);
+    assert_eq!(scheduler.book_fill("buy-1", 1.0, 110.0), None);
+    assert!((scheduler.cost_basis()[0].avg_price - 105.0).abs() < 1e-9);
+
+    let pnl = scheduler.book_fill("sell-1", 1.0, 100.0).expect("pnl");
+    assert!((pnl + 5.0).abs() < 1e-9);
+    assert_eq!(scheduler.book_fill("unknown", 1.0, 100.0), None);
+
+    let restored: ExecutionScheduler =
+        serde_json::from_str(&serde_json::to_string(&scheduler).expect("json")).expect("parse");
+    assert!((restored.cost_basis()[0].quantity - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn tracked_flatten_fill_reduces_basis_and_realizes_pnl() {
+    let mut scheduler = ExecutionScheduler::new();
+    let mut buy = order(2.0, OrderType::Market);
+    buy.client_order_id = "buy-1".to_string();
+    scheduler
+        .submit(&buy, &ExecutionAlgo::Immediate, 100.0, 0, &[])
+        .expect("submit");
+    scheduler.due_children(0, 100.0).expect("children");
+    scheduler.book_fill("buy-1", 2.0, 100.0);
+    scheduler.record_fill("buy-1", 2.0, 100.0, 0.0);
+
+    let mut flatten = order(2.0, OrderType::Market);
+    flatten.client_order_id = "flatten-1".to_string();
+    flatten.side = Side::Sell;
+    scheduler.track_placed(&flatten, 90.0, 60).expect("track");
+    assert_eq!(scheduler.working_children()[0].client_order_id, "flatten-1");
+    assert!(scheduler.due_children(120, 90.0).expect("children").is_empty());
+
+    let pnl = scheduler.book_fill("flatten-1", 2.0, 90.0).expect("pnl");
+    assert!((pnl + 20.0).abs() < 1e-9);
+    assert!(scheduler.cost_basis().is_empty());
+}
+
+#[test]
+fn shortfall_is_positive_when_execution_is_worse_than_arrival() {
+    assert!((implementation_shortfall_bps(&Side::Buy, 100.0, 101.0) - 100.0).abs() < 1e-9);
+    assert!((implementation_shortfall_bps(&Side::Sell, 100.0, 101.0) + 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn backtest_runs_twap_and_reports_shortfall() {
+    let mut config = Config::default();
+    config.orders.order_type = "market".to_string();
+    config.orders.slippage_bps = 0;
+    config.orders.fee_rate = 0.0;
+    config.triggers.time_enabled = false;
+    config.execution.algo = "twap".to_string();
+    config.execution.slices = 2;
+    config.execution.slice_interval_secs = 60;
+
+    let candles = vec![
+        candle(60, 100.0, 1.0),
+        candle(120, 100.0, 1.0),
+        candle(180, 100.0, 1.0),
+        candle(240, 90.0, 1.0),
+        candle(300, 95.0, 1.0),
+        candle(360, 96.0, 1.0),
+    ];
+
+    let trigger = PriceTrigger::new(3, 0.05, 0.05);
+    let trigger_engine = TriggerEngine::new(TriggerMode::Any, vec![Box::new(trigger)]);
+    let mut strategy = ThresholdStrategy::new(3, 0.05, 0.05);
+    let limits = RiskLimits {
+        max_trade_ratio: 1.0,
+        min_cash_reserve_ratio: 0.0,
+        max_position_value_ratio: 1.0,
+    };
+    let mut order_flow = OrderFlow::new(RiskManager::new(limits).expect("risk manager"));
+
+    let result = BacktestEngine
+        .run_strategy(
+            &candles,
+            &config,
+            &trigger_engine,
+            &mut strategy,
+            &mut order_flow,
+            1000.0,
+        )
+        .expect("run strategy");
+
+    assert_eq!(result.trades.len(), 2);
+    assert!((result.trades[0].price - 95.0).abs() < 1e-9);
+    assert!((result.trades[1].price - 96.0).abs() < 1e-9);
+    assert_eq!(result.executions.len(), 1);
+    let report = &result.executions[0];
+    assert_eq!(report.algo, "twap");
+    assert!((report.arrival_price - 90.0).abs() < 1e-9);
+    assert!((report.avg_fill_price - 95.5).abs() < 1e-9);
+    assert!((report.shortfall_bps - 5.5 / 90.0 * 10_000.0).abs() < 1e-6);
+}
@@ -0,0 +1,189 @@
+use merrow::data::csv_loader::load_candles_from_csv;
+use merrow::data::fetch::{
+    missing_spans, run_fetch_job, CandleSink, CsvSink, FetchJob, FetchProgress,
+};
+use merrow::models::Candle;
+use merrow::Error;
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("merrow_fetch_tests").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn job(start: i64, end: i64) -> FetchJob {
+    FetchJob {
+        exchange: "Binance".to_string(),
+        symbol: "BTCUSDT".to_string(),
+        interval: "1h".to_string(),
+        interval_secs: 3600,
+        start,
+        end,
+    }
+}
+
+fn candle(time: i64) -> Candle {
+    Candle {
+        time,
+        open: 100.0,
+        high: 110.0,
+        low: 90.0,
+        close: 105.0,
+        volume: 1.5,
+    }
+}
+
+fn hourly(from: i64, to: i64) -> Vec<Candle> {
+    let first = (from + 3599) / 3600 * 3600;
+    (first..=to).step_by(3600).map(candle).collect()
+}
+
+#[test]
+fn writes_csv_in_loader_format_with_progress() {
+    let dir = temp_dir("progress");
+    let mut sink = CsvSink::new(&dir);
+    let job = job(0, 3600 * 2499);
+    let mut seen: Vec<FetchProgress> = Vec::new();
+    let summary = run_fetch_job(&job, i64::MAX, &mut sink, |from, to| Ok(hourly(from, to)), |p| {
+        seen.push(p.clone())
+    })
+    .expect("fetch");
+
+    assert_eq!(summary.chunks, 3);
+    assert_eq!(summary.written, 2500);
+    assert_eq!(summary.resumed_from, None);
+    assert_eq!(seen.len(), 3);
+    assert!((seen[2].percent() - 100.0).abs() < 1e-9);
+    assert!(seen[0].percent() < seen[1].percent());
+
+    let path = sink.path(&job);
+    assert!(path.ends_with("binance_BTCUSDT_1h.csv"));
+    let content = fs::read_to_string(&path).expect("read");
+    assert!(content.starts_with("time,open,high,low,close,volume\n"));
+    let candles = load_candles_from_csv(&path.to_string_lossy()).expect("load");
+    assert_eq!(candles.len(), 2500);
+    assert_eq!(candles[0], candle(0));
+}
+
+#[test]
+fn resumes_after_last_stored_candle() {
+    let dir = temp_dir("resume");
+    let mut sink = CsvSink::new(&dir);
+    let job = job(0, 3600 * 1999);
+
+    let mut calls = 0;
+    let result = run_fetch_job(
+        &job,
+        i64::MAX,
+        &mut sink,
+        |from, to| {
+            calls += 1;
+            if calls == 2 {
+                return Err(Error::new("http request failed: connection reset"));
+            }
+            Ok(hourly(from, to))
+        },
+        |_| {},
+    );
+    assert!(result.is_err());
+    assert_eq!(sink.last_time(&job).expect("last"), Some(3600 * 999));
+
+    let mut requested = Vec::new();
+    let summary = run_fetch_job(
+        &job,
+        i64::MAX,
+        &mut sink,
+        |from, to| {
+            requested.push((from, to));
+            Ok(hourly(from, to))
+        },
+        |_| {},
+    )
+    .expect("resume");
+    assert_eq!(summary.resumed_from, Some(3600 * 999));
+    assert_eq!(summary.written, 1000);
+    assert_eq!(requested, vec![(3600 * 1000, 3600 * 1999)]);
+
+    let candles = load_candles_from_csv(&sink.path(&job).to_string_lossy()).expect("load");
+    assert_eq!(candles.len(), 2000);
+    assert!(candles.windows(2).all(|pair| pair[1].time - pair[0].time == 3600));
+
+    let again = run_fetch_job(&job, i64::MAX, &mut sink, |_, _| panic!("no fetch"), |_| {})
+        .expect("complete");
+    assert_eq!(again.written, 0);
+    assert_eq!(again.chunks, 0);
+}
+
+#[test]
+fn fetches_range_before_and_between_stored_data() {
+    let dir = temp_dir("earlier");
+    let mut sink = CsvSink::new(&dir);
+    let seeded = job(3600 * 50, 3600 * 99);
+    run_fetch_job(&seeded, i64::MAX, &mut sink, |from, to| Ok(hourly(from, to)), |_| {})
+        .expect("later half");
+    let seeded = job(3600 * 120, 3600 * 129);
+    run_fetch_job(&seeded, i64::MAX, &mut sink, |from, to| Ok(hourly(from, to)), |_| {})
+        .expect("detached tail");
+
+    let wide = job(0, 3600 * 149);
+    let mut requested = Vec::new();
+    let summary = run_fetch_job(
+        &wide,
+        i64::MAX,
+        &mut sink,
+        |from, to| {
+            requested.push((from, to));
+            Ok(hourly(from, to))
+        },
+        |_| {},
+    )
+    .expect("wide range");
+    assert_eq!(
+        requested,
+        vec![(0, 3600 * 50 - 1), (3600 * 100, 3600 * 120 - 1), (3600 * 130, 3600 * 149)]
+    );
+    assert_eq!(summary.written, 50 + 20 + 20);
+
+    let candles = load_candles_from_csv(&sink.path(&wide).to_string_lossy()).expect("load");
+    assert_eq!(candles.len(), 150);
+    assert!(candles.windows(2).all(|pair| pair[1].time - pair[0].time == 3600));
+    let content = fs::read_to_string(sink.path(&wide)).expect("read");
+    assert_eq!(content.matches("time,open").count(), 1);
+    let times: Vec<i64> = content
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').next().unwrap().parse().unwrap())
+        .collect();
+    assert!(times.windows(2).all(|pair| pair[0] < pair[1]));
+
+    assert!(missing_spans(&wide, &sink.stored_times(&wide).expect("stored")).is_empty());
+}
+
+#[test]
+fn skips_unsettled_candles_and_out_of_range_rows() {
+    let dir = temp_dir("settled");
+    let mut sink = CsvSink::new(&dir);
+    let job = job(3600, 3600 * 10);
+    let summary = run_fetch_job(
+        &job,
+        3600 * 5,
+        &mut sink,
+        |from, to| Ok(hourly(from - 3600, to + 3600 * 3)),
+        |_| {},
+    )
+    .expect("fetch");
+    assert_eq!(summary.written, 5);
+    assert_eq!(sink.last_time(&job).expect("last"), Some(3600 * 5));
+}
+
+#[test]
+fn rejects_invalid_jobs() {
+    let mut sink = CsvSink::new(temp_dir("invalid"));
+    let inverted = job(7200, 3600);
+    assert!(run_fetch_job(&inverted, i64::MAX, &mut sink, |_, _| Ok(Vec::new()), |_| {}).is_err());
+    let mut zero = job(0, 3600);
+    zero.interval_secs = 0;
+    assert!(run_fetch_job(&zero, i64::MAX, &mut sink, |_, _| Ok(Vec::new()), |_| {}).is_err());
+}
@@ -0,0 +1,201 @@
+use merrow::core::journal::{IntentJournal, IntentOutcome, JournalEntry};
+use merrow::exchange::sim::{SimConfig, SimExchange};
+use merrow::exchange::Exchange;
+use merrow::models::{Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Side};
+use serde_json::json;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn journal_path(name: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time")
+        .as_nanos();
+    std::env::temp_dir().join(format!("merrow_journal_{name}_{nanos}/orders.jsonl"))
+}
+
+fn candle(time: i64, price: f64) -> Candle {
+    Candle {
+        time,
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        volume: 1.0,
+    }
+}
+
+fn sim() -> SimExchange {
+    let config = SimConfig {
+        symbol: "BTCUSDT".to_string(),
+        base_asset: "BTC".to_string(),
+        quote_asset: "USDT".to_string(),
+        initial_quote: 1000.0,
+        initial_base: 0.0,
+        fee_rate: 0.0,
+        slippage_bps: 0,
+        warmup_bars: 1,
+    };
+    SimExchange::new(config, vec![candle(60, 100.0), candle(120, 100.0)]).expect("sim")
+}
+
+fn buy(id: &str, order_type: OrderType) -> OrderRequest {
+    OrderRequest {
+        client_order_id: id.to_string(),
+        symbol: "BTCUSDT".to_string(),
+        side: Side::Buy,
+        order_type,
+        quantity: 1.0,
+    }
+}
+
+#[test]
+fn intents_stay_pending_until_ack_or_missing() {
+    let journal = IntentJournal::new(journal_path("pending"));
+    journal.record_intent(&buy("a", OrderType::Market), 100.0, 1).expect("intent");
+    journal.record_intent(&buy("b", OrderType::Market), 100.0, 1).expect("intent");
+    journal
+        .record_intent(&buy("c", OrderType::Limit { price: 99.0 }), 100.0, 1)
+        .expect("intent");
+    let ack = OrderAck {
+        client_order_id: "a".to_string(),
+        exchange_order_id: Some("1".to_string()),
+        status: OrderStatus::New,
+        filled_qty: 0.0,
+        avg_fill_price: None,
+    };
+    journal.record_ack(&ack, 2).expect("ack");
+    journal.record_error("b", "timeout", 2).expect("error");
+
+    let pending = journal.pending().expect("pending");
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0].order.client_order_id, "b");
+
+    journal.record_missing("b", 3).expect("missing");
+    let pending = journal.pending().expect("pending");
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].order.client_order_id, "c");
+    assert_eq!(pending[0].order.order_type, OrderType::Limit { price: 99.0 });
+    assert_eq!(journal.entries().expect("entries").len(), 6);
+}
+
+#[test]
+fn torn_final_line_is_ignored() {
+    let path = journal_path("torn");
+    let journal = IntentJournal::new(&path);
+    journal.record_intent(&buy("a", OrderType::Market), 100.0, 1).expect("intent");
+    let mut file = OpenOptions::new().append(true).open(&path).expect("open");
+    file.write_all(br#"{"event":"ack","time":2,"ack":{"client_"#)
+        .expect("write");
+
+    assert_eq!(journal.entries().expect("entries").len(), 1);
+    assert_eq!(journal.pending().expect("pending").len(), 1);
+}
+
+#[test]
+fn corrupt_middle_line_is_an_error() {
+    let path = journal_path("corrupt");
+    let journal = IntentJournal::new(&path);
+    journal.record_intent(&buy("a", OrderType::Market), 100.0, 1).expect("intent");
+    let mut file = OpenOptions::new().append(true).open(&path).expect("open");
+    file.write_all(b"not json\n").expect("write");
+    journal.record_intent(&buy("b", OrderType::Market), 100.0, 1).expect("intent");
+
+    assert!(journal.entries().is_err());
+}
+
+#[test]
+fn recovery_resolves_pending_intents_against_exchange() {
+    let path = journal_path("recover");
+    let journal = IntentJournal::new(&path);
+    let exchange = sim();
+
+    let sent = buy("merrow-1-1", OrderType::Market);
+    journal.record_intent(&sent, 100.0, 60).expect("intent");
+    exchange.place_order(&sent).expect("place");
+    let lost = buy("merrow-1-2", OrderType::Market);
+    journal.record_intent(&lost, 100.0, 60).expect("intent");
+    exchange.advance().expect("advance");
+
+    let recovered = journal.recover(&exchange, 120).expect("recover");
+    assert_eq!(recovered.len(), 2);
+    match &recovered[0].outcome {
+        IntentOutcome::Sent(ack) => assert_eq!(ack.status, OrderStatus::Filled),
+        IntentOutcome::Unsent => panic!("filled order reported unsent"),
+    }
+    assert!(!recovered[0].is_open());
+    assert!(matches!(recovered[1].outcome, IntentOutcome::Unsent));
+
+    assert!(journal.pending().expect("pending").is_empty());
+    assert!(journal.entries().expect("entries").is_empty());
+    assert!(fs::metadata(&path).expect("journal kept").is_file());
+}
+
+#[test]
+fn journal_entries_are_tagged_json_lines() {
+    let path = journal_path("format");
+    let journal = IntentJournal::new(&path);
+    journal
+        .record_intent(&buy("a", OrderType::Limit { price: 99.0 }), 100.0, 7)
+        .expect("intent");
+    let line = fs::read_to_string(&path).expect("read");
+    let value: serde_json::Value = serde_json::from_str(line.trim()).expect("json");
+    assert_eq!(value["event"], json!("intent"));
+    assert_eq!(value["order"]["client_order_id"], json!("a"));
+    assert_eq!(value["order"]["price"], json!(99.0));
+    assert!(matches!(
+        journal.entries().expect("entries")[0],
+        JournalEntry::Intent { time: 7, .. }
+    ));
+}
+
+#[test]
+fn sim_looks_up_closed_orders_by_client_id() {
+    let exchange = sim();
+    exchange
+        .place_order(&buy("c1", OrderType::Limit { price: 90.0 }))
+        .expect("place");
+    exchange.cancel_order("c1").expect("cancel");
+
+    let ack = exchange.fetch_order("c1").expect("fetch").expect("found");
+    assert_eq!(ack.status, OrderStatus::Canceled);
+    assert!(exchange.fetch_order("missing").expect("fetch").is_none());
+}
+
+#[test]
+fn parses_exchange_order_queries() {
+    let binance = merrow::exchange::binance::parse_order(&json!({
+        "clientOrderId": "merrow-1-1",
+        "orderId": 42,
+        "status": "FILLED",
+        "executedQty": "2.0",
+        "cummulativeQuoteQty": "201.0"
+    }))
+    .expect("binance order");
+    assert_eq!(binance.exchange_order_id.as_deref(), Some("42"));
+    assert_eq!(binance.status, OrderStatus::Filled);
+    assert_eq!(binance.filled_qty, 2.0);
+    assert_eq!(binance.avg_fill_price, Some(100.5));
+
+    let okx = merrow::exchange::okx::parse_order(&json!({
+        "clOrdId": "merrow-1-2",
+        "ordId": "7",
+        "state": "canceled",
+        "accFillSz": "0.4",
+        "avgPx": "99"
+    }))
+    .expect("okx order");
+    assert_eq!(okx.status, OrderStatus::Canceled);
+    assert_eq!(okx.filled_qty, 0.4);
+    assert_eq!(okx.avg_fill_price, Some(99.0));
+
+    let bybit = merrow::exchange::bybit::parse_order_list(&json!({
+        "retCode": 0,
+        "result": { "list": [] }
+    }))
+    .expect("bybit list");
+    assert!(bybit.is_empty());
+    assert!(merrow::exchange::binance::parse_order(&json!({ "orderId": 1 })).is_none());
+}
@@ -0,0 +1,109 @@
+use merrow::storage::keystore::{Credential, Keystore};
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("merrow_keystore_tests");
+    fs::create_dir_all(&dir).expect("temp dir");
+    let path = dir.join(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn credential(exchange: &str, label: &str, secret: &str) -> Credential {
+    Credential {
+        exchange: exchange.to_string(),
+        label: label.to_string(),
+        api_key: format!("{exchange}-key"),
+        api_secret: secret.to_string(),
+        passphrase: None,
+    }
+}
+
+#[test]
+fn round_trips_credentials_through_encrypted_file() {
+    let path = temp_path("round_trip.json");
+    let mut keystore = Keystore::open(&path, "hunter2").expect("open").with_iterations(1_000);
+    keystore.insert(credential("Binance", "main", "s3cret-binance"));
+    let mut okx = credential("okx", "main", "s3cret-okx");
+    okx.passphrase = Some("okx-pass".to_string());
+    keystore.insert(okx);
+    keystore.save("hunter2").expect("save");
+
+    let raw = fs::read_to_string(&path).expect("read");
+    assert!(!raw.contains("s3cret"));
+    assert!(!raw.contains("okx-pass"));
+
+    let reopened = Keystore::open(&path, "hunter2").expect("reopen");
+    assert_eq!(reopened.credentials().len(), 2);
+    let binance = reopened.get("binance", "main").expect("binance");
+    assert_eq!(binance.api_secret, "s3cret-binance");
+    let okx = reopened.get("OKX", "main").expect("okx");
+    assert_eq!(okx.passphrase.as_deref(), Some("okx-pass"));
+}
+
+#[test]
+fn wrong_passphrase_is_rejected() {
+    let path = temp_path("wrong_passphrase.json");
+    let mut keystore = Keystore::open(&path, "right").expect("open").with_iterations(1_000);
+    keystore.insert(credential("bybit", "main", "secret"));
+    keystore.save("right").expect("save");
+
+    let err = Keystore::open(&path, "wrong").err().expect("must fail");
+    assert!(err.message.contains("decrypt failed"));
+}
+
+#[test]
+fn tampered_ciphertext_is_rejected() {
+    let path = temp_path("tampered.json");
+    let mut keystore = Keystore::open(&path, "pass").expect("open").with_iterations(1_000);
+    keystore.insert(credential("bybit", "main", "secret"));
+    keystore.save("pass").expect("save");
+
+    let mut sealed: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).expect("read")).expect("json");
+    let ciphertext = sealed["ciphertext"].as_str().expect("ciphertext").to_string();
+    let flipped = if ciphertext.starts_with('A') { "B" } else { "A" };
+    sealed["ciphertext"] = serde_json::Value::String(format!("{flipped}{}", &ciphertext[1..]));
+    fs::write(&path, sealed.to_string()).expect("write");
+
+    assert!(Keystore::open(&path, "pass").is_err());
+}
+
+#[test]
+fn insert_replaces_and_remove_drops_by_exchange_and_label() {
+    let path = temp_path("replace.json");
+    let mut keystore = Keystore::open(&path, "pass").expect("open");
+    keystore.insert(credential("binance", "main", "old"));
+    keystore.insert(credential("binance", "sub", "other"));
+    keystore.insert(credential("binance", "main", "new"));
+    assert_eq!(keystore.credentials().len(), 2);
+    assert_eq!(keystore.get("binance", "main").expect("main").api_secret, "new");
+
+    assert!(keystore.remove("binance", "main"));
+    assert!(!keystore.remove("binance", "main"));
+    assert!(keystore.get("binance", "main").is_none());
+    assert!(keystore.get("binance", "sub").is_some());
+}
+
+#[test]
+fn debug_output_redacts_secrets() {
+    let mut item = credential("okx", "main", "very-secret");
+    item.passphrase = Some("pass-phrase".to_string());
+    let rendered = format!("{item:?}");
+    assert!(rendered.contains("okx"));
+    assert!(!rendered.contains("okx-key"));
+    assert!(!rendered.contains("very-secret"));
+    assert!(!rendered.contains("pass-phrase"));
+}
+
+#[cfg(unix)]
+#[test]
+fn keystore_file_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+    let path = temp_path("private.json");
+    let keystore = Keystore::open(&path, "pass").expect("open").with_iterations(1_000);
+    keystore.save("pass").expect("save");
+    let mode = fs::metadata(&path).expect("metadata").permissions().mode();
+    assert_eq!(mode & 0o077, 0);
+}
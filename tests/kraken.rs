@@ -0,0 +1,96 @@
+use merrow::exchange::kraken::{
+    normalize_asset, order_params, parse_balances, parse_orders, rejection_reason, KrakenExchange,
+};
+use merrow::models::{OrderRequest, OrderStatus, OrderType, Side};
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> Value {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests");
+    path.push("fixtures");
+    path.push(name);
+    let content = fs::read_to_string(&path).expect("read fixture");
+    serde_json::from_str(&content).expect("parse fixture")
+}
+
+#[test]
+fn api_sign_matches_documented_vector() {
+    let secret = "kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==";
+    let nonce = "1616492376594";
+    let post_data = "nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25";
+    let signature =
+        KrakenExchange::api_sign(secret, "/0/private/AddOrder", nonce, post_data).expect("sign");
+    assert_eq!(
+        signature,
+        "4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ=="
+    );
+}
+
+#[test]
+fn api_sign_rejects_non_base64_secret() {
+    assert!(KrakenExchange::api_sign("not base64!", "/0/private/Balance", "1", "nonce=1").is_err());
+}
+
+#[test]
+fn parses_balances_with_holds_and_normalized_assets() {
+    let mut balances = parse_balances(&fixture("kraken_balance.json")).expect("parse");
+    balances.sort_by(|left, right| left.asset.cmp(&right.asset));
+    let assets: Vec<&str> = balances.iter().map(|balance| balance.asset.as_str()).collect();
+    assert_eq!(assets, vec!["BTC", "USD", "USDT"]);
+    assert!((balances[0].free - 0.4).abs() < 1e-9);
+    assert!((balances[0].locked - 0.1).abs() < 1e-9);
+    assert_eq!(balances[1].free, 1250.5);
+    assert_eq!(balances[2].locked, 0.0);
+}
+
+#[test]
+fn parses_open_orders_carrying_client_ids() {
+    let orders = parse_orders(&fixture("kraken_open_orders.json"), "open").expect("parse");
+    assert_eq!(orders.len(), 1);
+    let (pair, ack) = &orders[0];
+    assert_eq!(pair, "XBTUSD");
+    assert_eq!(ack.client_order_id, "merrow-1704067200-1");
+    assert_eq!(ack.exchange_order_id.as_deref(), Some("OQCLML-BW3P3-BUCMWZ"));
+    assert_eq!(ack.status, OrderStatus::PartiallyFilled);
+}
+
+#[test]
+fn builds_limit_order_params_with_client_id() {
+    let order = OrderRequest {
+        client_order_id: "merrow-1-1".to_string(),
+        symbol: "XBTUSD".to_string(),
+        side: Side::Buy,
+        order_type: OrderType::Limit { price: 37500.0 },
+        quantity: 1.25,
+    };
+    let params = order_params(&order);
+    let find = |key: &str| {
+        params
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    };
+    assert_eq!(find("ordertype"), Some("limit"));
+    assert_eq!(find("type"), Some("buy"));
+    assert_eq!(find("volume"), Some("1.25"));
+    assert_eq!(find("price"), Some("37500"));
+    assert_eq!(find("cl_ord_id"), Some("merrow-1-1"));
+}
+
+#[test]
+fn reports_errors_and_normalizes_assets() {
+    let json = json!({ "error": ["EOrder:Insufficient funds"], "result": {} });
+    assert_eq!(
+        rejection_reason(&json).as_deref(),
+        Some("EOrder:Insufficient funds")
+    );
+    assert!(rejection_reason(&json!({ "error": [], "result": {} })).is_none());
+    assert!(parse_balances(&json).is_err());
+    assert_eq!(normalize_asset("XXDG"), "DOGE");
+    assert_eq!(normalize_asset("ZEUR"), "EUR");
+    assert_eq!(normalize_asset("ETH2.S"), "ETH2");
+    assert_eq!(normalize_asset("SOL"), "SOL");
+}
@@ -0,0 +1,116 @@
+use merrow::config::Config;
+use merrow::data::candle_cache::CandleCache;
+use merrow::data::market_data::{provider_from_config, CachedProvider, CsvProvider, MarketDataProvider};
+use merrow::models::Candle;
+use merrow::Result;
+use std::cell::RefCell;
+use std::fs;
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn candle(time: i64) -> Candle {
+    Candle {
+        time,
+        open: 100.0,
+        high: 101.0,
+        low: 99.0,
+        close: 100.5,
+        volume: 1.0,
+    }
+}
+
+struct CountingProvider {
+    calls: RefCell<Vec<(String, String, i64, i64)>>,
+}
+
+impl MarketDataProvider for CountingProvider {
+    fn load_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Candle>> {
+        self.calls
+            .borrow_mut()
+            .push((symbol.to_string(), interval.to_string(), start, end));
+        Ok((start..=end).filter(|time| time % 60 == 0).map(candle).collect())
+    }
+}
+
+#[test]
+fn csv_provider_filters_by_time_range() {
+    let provider = CsvProvider::new(fixture("candles.csv"));
+    let all = provider
+        .load_candles("BTCUSDT", "1m", i64::MIN, i64::MAX)
+        .expect("all");
+    assert!(all.len() >= 2);
+    let last = all.last().expect("last").time;
+    let ranged = provider.load_candles("BTCUSDT", "1m", last, last).expect("range");
+    assert_eq!(ranged.len(), 1);
+    assert_eq!(ranged[0].time, last);
+
+    let recent = provider.load_recent("BTCUSDT", "1m", 1, 0).expect("recent");
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].time, last);
+}
+
+#[test]
+fn default_recent_query_uses_time_range_and_trims() {
+    let provider = CountingProvider {
+        calls: RefCell::new(Vec::new()),
+    };
+    let candles = provider.load_recent("ETHUSDT", "1m", 3, 600).expect("recent");
+    assert_eq!(
+        candles.iter().map(|candle| candle.time).collect::<Vec<_>>(),
+        vec![480, 540, 600]
+    );
+    assert_eq!(
+        provider.calls.borrow().as_slice(),
+        &[("ETHUSDT".to_string(), "1m".to_string(), 420, 600)]
+    );
+}
+
+#[test]
+fn cached_provider_only_queries_missing_ranges() {
+    let dir = std::env::temp_dir().join("merrow_market_data_tests").join("cached");
+    let _ = fs::remove_dir_all(&dir);
+    let inner = CountingProvider {
+        calls: RefCell::new(Vec::new()),
+    };
+    let provider = CachedProvider::new(CandleCache::new(&dir), "Binance", inner);
+
+    let first = provider.load_candles("BTCUSDT", "1m", 0, 599).expect("first");
+    assert_eq!(first.len(), 10);
+    let second = provider.load_candles("BTCUSDT", "1m", 300, 899).expect("second");
+    assert_eq!(second.len(), 10);
+    let again = provider.load_candles("BTCUSDT", "1m", 0, 899).expect("again");
+    assert_eq!(again.len(), 15);
+}
+
+#[test]
+fn factory_follows_data_source() {
+    let mut config = Config::default();
+    config.data.source = "csv".to_string();
+    config.data.csv_path = Some(fixture("candles.csv"));
+    let provider = provider_from_config(&config).expect("csv provider");
+    assert!(!provider
+        .load_candles(&config.symbol, "1m", i64::MIN, i64::MAX)
+        .expect("load")
+        .is_empty());
+
+    config.data.csv_path = None;
+    assert!(provider_from_config(&config).is_err());
+    config.data.source = "exchange".to_string();
+    assert!(provider_from_config(&config).is_ok());
+    config.data.source = "postgres".to_string();
+    assert!(provider_from_config(&config).is_ok());
+    config.data.source = "parquet".to_string();
+    assert!(provider_from_config(&config).is_err());
+    config.data.parquet_path = Some("data/BTCUSDT_1m.parquet".to_string());
+    assert!(provider_from_config(&config).is_ok());
+    config.data.source = "sqlite".to_string();
+    assert!(provider_from_config(&config).is_err());
+}
@@ -0,0 +1,137 @@
+use merrow::core::execution::{ExecutionAlgo, ExecutionScheduler};
+use merrow::core::order_builder::OrderBuilder;
+use merrow::exchange::open_orders::{
+    cancel_orders, classify_open_orders, is_own_order, OrphanPolicy,
+};
+use merrow::exchange::{CandleRequest, Exchange};
+use merrow::models::{
+    Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side,
+};
+use merrow::{Error, Result};
+use std::sync::Mutex;
+
+struct BookExchange {
+    open: Mutex<Vec<String>>,
+}
+
+impl Exchange for BookExchange {
+    fn place_order(&self, order: &OrderRequest) -> Result<OrderAck> {
+        self.open.lock().unwrap().push(order.client_order_id.clone());
+        Ok(ack(&order.client_order_id))
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let mut open = self.open.lock().unwrap();
+        match open.iter().position(|id| id == order_id) {
+            Some(index) => {
+                open.remove(index);
+                Ok(())
+            }
+            None => Err(Error::new(format!("unknown order: {order_id}"))),
+        }
+    }
+
+    fn fetch_balances(&self) -> Result<Vec<Balance>> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_positions(&self) -> Result<Vec<Position>> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_open_orders(&self) -> Result<Vec<OrderAck>> {
+        Ok(self.open.lock().unwrap().iter().map(|id| ack(id)).collect())
+    }
+
+    fn fetch_candles(&self, _req: &CandleRequest) -> Result<Vec<Candle>> {
+        Ok(Vec::new())
+    }
+}
+
+fn ack(id: &str) -> OrderAck {
+    OrderAck {
+        client_order_id: id.to_string(),
+        exchange_order_id: None,
+        status: OrderStatus::New,
+        filled_qty: 0.0,
+        avg_fill_price: None,
+    }
+}
+
+#[test]
+fn own_orders_match_prefix_and_separator() {
+    assert!(is_own_order("merrow-1700000000-1", "merrow"));
+    assert!(is_own_order("merrow-1-2", "merrow"));
+    assert!(!is_own_order("merrowx-1", "merrow"));
+    assert!(!is_own_order("manual-order", "merrow"));
+    assert!(!is_own_order("merrow", "merrow"));
+}
+
+#[test]
+fn classify_splits_ours_from_foreign() {
+    let open = vec![ack("merrow-1-1"), ack("web-123"), ack("merrow-1-2")];
+    let split = classify_open_orders(&open, "merrow");
+    let ours: Vec<&str> = split
+        .ours
+        .iter()
+        .map(|order| order.client_order_id.as_str())
+        .collect();
+    assert_eq!(ours, vec!["merrow-1-1", "merrow-1-2"]);
+    assert_eq!(split.foreign.len(), 1);
+    assert_eq!(split.foreign[0].client_order_id, "web-123");
+}
+
+#[test]
+fn cancel_orders_reports_failures() {
+    let exchange = BookExchange {
+        open: Mutex::new(vec!["merrow-1-1".to_string(), "web-123".to_string()]),
+    };
+    let report = cancel_orders(&exchange, &[ack("merrow-1-1"), ack("merrow-1-9")]);
+    assert_eq!(report.canceled, vec!["merrow-1-1".to_string()]);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, "merrow-1-9");
+    let remaining = exchange.fetch_open_orders().expect("open");
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].client_order_id, "web-123");
+}
+
+#[test]
+fn orphan_policy_parses_known_values() {
+    assert_eq!(OrphanPolicy::parse("cancel").expect("cancel"), OrphanPolicy::Cancel);
+    assert_eq!(OrphanPolicy::parse("adopt").expect("adopt"), OrphanPolicy::Adopt);
+    assert!(OrphanPolicy::parse("keep").is_err());
+}
+
+#[test]
+fn scheduler_tracks_working_and_adopted_orders() {
+    let mut scheduler = ExecutionScheduler::new();
+    let order = OrderRequest {
+        client_order_id: "merrow-1-1".to_string(),
+        symbol: "BTCUSDT".to_string(),
+        side: Side::Buy,
+        order_type: OrderType::Limit { price: 100.0 },
+        quantity: 1.0,
+    };
+    scheduler
+        .submit(&order, &ExecutionAlgo::Immediate, 100.0, 0, &[])
+        .expect("submit");
+    scheduler.due_children(0, 100.0).expect("children");
+    assert!(scheduler.tracks("merrow-1-1"));
+    assert!(!scheduler.tracks("merrow-0-4"));
+
+    scheduler.adopt("merrow-0-4");
+    scheduler.adopt("merrow-0-4");
+    assert_eq!(scheduler.adopted(), &["merrow-0-4".to_string()]);
+    assert!(scheduler.tracks("merrow-0-4"));
+
+    scheduler.retain_adopted(|id| id != "merrow-0-4");
+    assert!(scheduler.adopted().is_empty());
+}
+
+#[test]
+fn order_builder_uses_configured_prefix() {
+    let mut builder = OrderBuilder::with_prefix("bot1-42");
+    let order = builder.build_flatten("BTCUSDT", 1.0).expect("flatten");
+    assert_eq!(order.client_order_id, "bot1-42-1");
+    assert!(is_own_order(&order.client_order_id, "bot1"));
+}
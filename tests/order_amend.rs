@@ -0,0 +1,248 @@
+use merrow::backtest::BacktestEngine;
+use merrow::config::Config;
+use merrow::core::execution::{ExecutionAlgo, ExecutionScheduler};
+use merrow::core::order_flow::OrderFlow;
+use merrow::core::repricing::RepricePolicy;
+use merrow::core::risk::{RiskLimits, RiskManager};
+use merrow::core::strategies::ThresholdStrategy;
+use merrow::core::triggers::{PriceTrigger, TriggerEngine};
+use merrow::core::TriggerMode;
+use merrow::exchange::binance::{cancel_replace_params, parse_cancel_replace};
+use merrow::exchange::binance_futures::modify_order_params;
+use merrow::exchange::sim::{SimConfig, SimExchange};
+use merrow::exchange::{bybit, okx, AmendRequest, Exchange};
+use merrow::models::{Candle, OrderRequest, OrderStatus, OrderType, Side};
+use serde_json::json;
+
+fn limit(id: &str, side: Side, price: f64, quantity: f64) -> OrderRequest {
+    OrderRequest {
+        client_order_id: id.to_string(),
+        symbol: "BTCUSDT".to_string(),
+        side,
+        order_type: OrderType::Limit { price },
+        quantity,
+    }
+}
+
+fn amend(order: OrderRequest, new_price: Option<f64>, new_quantity: Option<f64>) -> AmendRequest {
+    AmendRequest {
+        order,
+        new_price,
+        new_quantity,
+    }
+}
+
+fn param<'a>(params: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str())
+}
+
+fn candle(time: i64, high: f64, low: f64, close: f64) -> Candle {
+    Candle {
+        time,
+        open: close,
+        high,
+        low,
+        close,
+        volume: 1.0,
+    }
+}
+
+#[test]
+fn amend_request_validates_inputs() {
+    let market = OrderRequest {
+        order_type: OrderType::Market,
+        ..limit("c1", Side::Buy, 100.0, 1.0)
+    };
+    assert!(amend(market, Some(101.0), None).amended().is_err());
+    assert!(amend(limit("c1", Side::Buy, 100.0, 1.0), None, None).amended().is_err());
+    assert!(amend(limit("c1", Side::Buy, 100.0, 1.0), Some(0.0), None).amended().is_err());
+
+    let amended = amend(limit("c1", Side::Buy, 100.0, 1.0), None, Some(2.0))
+        .amended()
+        .expect("amended");
+    assert_eq!(amended.order_type, OrderType::Limit { price: 100.0 });
+    assert_eq!(amended.quantity, 2.0);
+}
+
+#[test]
+fn binance_cancel_replace_keeps_client_order_id() {
+    let req = amend(limit("c1", Side::Sell, 100.0, 0.5), Some(101.5), None);
+    let params = cancel_replace_params(&req).expect("params");
+    assert_eq!(param(&params, "cancelReplaceMode"), Some("STOP_ON_FAILURE"));
+    assert_eq!(param(&params, "cancelOrigClientOrderId"), Some("c1"));
+    assert_eq!(param(&params, "newClientOrderId"), Some("c1"));
+    assert_eq!(param(&params, "side"), Some("SELL"));
+    assert_eq!(param(&params, "price"), Some("101.5"));
+    assert_eq!(param(&params, "quantity"), Some("0.5"));
+
+    let ok = json!({
+        "cancelResult": "SUCCESS",
+        "newOrderResult": "SUCCESS",
+        "cancelResponse": { "origClientOrderId": "c1" },
+        "newOrderResponse": { "orderId": 42, "clientOrderId": "c1" }
+    });
+    let ack = parse_cancel_replace(&ok, "c1").expect("ack");
+    assert_eq!(ack.exchange_order_id.as_deref(), Some("42"));
+    assert_eq!(ack.status, OrderStatus::New);
+
+    let failed = json!({
+        "cancelResult": "FAILURE",
+        "newOrderResult": "NOT_ATTEMPTED"
+    });
+    assert!(parse_cancel_replace(&failed, "c1").is_err());
+}
+
+#[test]
+fn futures_modify_and_v5_amend_bodies() {
+    let req = amend(limit("c1", Side::Buy, 100.0, 0.5), Some(99.0), None);
+    let params = modify_order_params(&req).expect("params");
+    assert_eq!(param(&params, "origClientOrderId"), Some("c1"));
+    assert_eq!(param(&params, "price"), Some("99"));
+    assert_eq!(param(&params, "quantity"), Some("0.5"));
+
+    let body = bybit::amend_body("spot", &req).expect("bybit body");
+    assert_eq!(body["orderLinkId"], "c1");
+    assert_eq!(body["price"], "99");
+    assert!(body.get("qty").is_none());
+
+    let okx_req = amend(
+        OrderRequest {
+            symbol: "BTC-USDT".to_string(),
+            ..limit("c1", Side::Buy, 100.0, 0.5)
+        },
+        None,
+        Some(0.25),
+    );
+    let body = okx::amend_body(&okx_req).expect("okx body");
+    assert_eq!(body["instId"], "BTC-USDT");
+    assert_eq!(body["clOrdId"], "c1");
+    assert_eq!(body["newSz"], "0.25");
+    assert!(body.get("newPx").is_none());
+}
+
+#[test]
+fn reprice_policy_chases_reference_beyond_threshold() {
+    let policy = RepricePolicy {
+        threshold_bps: 50,
+        offset_bps: 10,
+        max_amends: 2,
+    };
+    let order = limit("c1", Side::Buy, 99.9, 1.0);
+    assert!(policy.reprice(&order, 100.2, 0).is_none());
+
+    let req = policy.reprice(&order, 101.0, 0).expect("reprice");
+    let price = req.new_price.expect("price");
+    assert!((price - 101.0 * 0.999).abs() < 1e-9);
+    assert!(policy.reprice(&order, 101.0, 2).is_none());
+    assert!(RepricePolicy::disabled().reprice(&order, 101.0, 0).is_none());
+}
+
+#[test]
+fn scheduler_tracks_amended_children() {
+    let policy = RepricePolicy {
+        threshold_bps: 50,
+        offset_bps: 0,
+        max_amends: 1,
+    };
+    let mut scheduler = ExecutionScheduler::new();
+    scheduler
+        .submit(&limit("p1", Side::Buy, 100.0, 1.0), &ExecutionAlgo::Immediate, 100.0, 0, &[])
+        .expect("submit");
+    scheduler.due_children(0, 100.0).expect("children");
+
+    let requests = scheduler
+        .reprice_requests(&policy, "BTCUSDT", 102.0)
+        .expect("requests");
+    assert_eq!(requests.len(), 1);
+    assert!(scheduler.record_amend("p1", 102.0));
+    let child = scheduler.working_child("p1").expect("child");
+    assert_eq!(child.amends, 1);
+    assert_eq!(child.reference_price, 102.0);
+    assert!(scheduler
+        .reprice_requests(&policy, "BTCUSDT", 105.0)
+        .expect("requests")
+        .is_empty());
+}
+
+#[test]
+fn sim_amend_moves_locked_funds() {
+    let config = SimConfig {
+        symbol: "BTCUSDT".to_string(),
+        base_asset: "BTC".to_string(),
+        quote_asset: "USDT".to_string(),
+        initial_quote: 1000.0,
+        initial_base: 0.0,
+        fee_rate: 0.0,
+        slippage_bps: 0,
+        warmup_bars: 1,
+    };
+    let sim = SimExchange::new(config, vec![candle(60, 101.0, 99.0, 100.0)]).expect("sim");
+    let order = limit("c1", Side::Buy, 90.0, 2.0);
+    sim.place_order(&order).expect("place");
+
+    let ack = sim
+        .amend_order(&amend(order.clone(), Some(95.0), None))
+        .expect("amend");
+    assert_eq!(ack.status, OrderStatus::New);
+    let usdt = sim
+        .fetch_balances()
+        .expect("balances")
+        .into_iter()
+        .find(|balance| balance.asset == "USDT")
+        .expect("usdt");
+    assert_eq!((usdt.free, usdt.locked), (810.0, 190.0));
+
+    assert!(sim.amend_order(&amend(order, None, Some(20.0))).is_err());
+    assert!(sim
+        .amend_order(&amend(limit("missing", Side::Buy, 90.0, 1.0), Some(91.0), None))
+        .is_err());
+}
+
+#[test]
+fn backtest_reprices_resting_limit_until_filled() {
+    let mut config = Config::default();
+    config.orders.order_type = "limit".to_string();
+    config.orders.limit_price_offset_bps = 0;
+    config.orders.slippage_bps = 0;
+    config.orders.fee_rate = 0.0;
+    config.orders.reprice_threshold_bps = 50;
+    config.orders.reprice_max_amends = 3;
+    config.triggers.time_enabled = false;
+
+    let candles = vec![
+        candle(60, 100.0, 100.0, 100.0),
+        candle(120, 100.0, 100.0, 100.0),
+        candle(180, 100.0, 100.0, 100.0),
+        candle(240, 90.0, 90.0, 90.0),
+        candle(300, 93.0, 92.0, 92.5),
+        candle(360, 93.0, 92.0, 92.5),
+    ];
+
+    let trigger = PriceTrigger::new(3, 0.05, 0.05);
+    let trigger_engine = TriggerEngine::new(TriggerMode::Any, vec![Box::new(trigger)]);
+    let mut strategy = ThresholdStrategy::new(3, 0.05, 0.05);
+    let limits = RiskLimits {
+        max_trade_ratio: 1.0,
+        min_cash_reserve_ratio: 0.0,
+        max_position_value_ratio: 1.0,
+    };
+    let mut order_flow = OrderFlow::new(RiskManager::new(limits).expect("risk manager"));
+
+    let result = BacktestEngine
+        .run_strategy(
+            &candles,
+            &config,
+            &trigger_engine,
+            &mut strategy,
+            &mut order_flow,
+            1000.0,
+        )
+        .expect("run strategy");
+
+    assert_eq!(result.trades.len(), 1);
+    assert_eq!(result.trades[0].time, 360);
+    assert!((result.trades[0].price - 92.5).abs() < 1e-9);
+}
@@ -36,6 +36,7 @@ fn buy_signal_creates_market_order() {
         candle: &candle,
         history: &history,
         account: &account,
+        timeframes: &[],
         now: 1,
     };
 
@@ -63,6 +64,7 @@ fn limit_order_applies_offset() {
         candle: &candle,
         history: &history,
         account: &account,
+        timeframes: &[],
         now: 1,
     };
 
@@ -91,6 +93,7 @@ fn sell_signal_creates_sell_and_rebuy_orders() {
         candle: &candle,
         history: &history,
         account: &account,
+        timeframes: &[],
         now: 1,
     };
 
@@ -116,6 +119,7 @@ fn hold_signal_creates_no_orders() {
         candle: &candle,
         history: &history,
         account: &account,
+        timeframes: &[],
         now: 1,
     };
 
@@ -37,6 +37,7 @@ fn risk_blocks_order_exceeding_max_trade_ratio() {
         candle: &candle,
         history: &history,
         account: &account,
+        timeframes: &[],
         now: 1,
     };
 
@@ -68,6 +69,7 @@ fn risk_allows_order_within_limits() {
         candle: &candle,
         history: &history,
         account: &account,
+        timeframes: &[],
         now: 1,
     };
 
@@ -101,6 +103,7 @@ fn sell_signal_passes_risk_checks() {
         candle: &candle,
         history: &history,
         account: &account,
+        timeframes: &[],
         now: 1,
     };
 
@@ -0,0 +1,382 @@
+use merrow::backtest::{run_routed, BacktestOrder, BacktestVenue};
+use merrow::core::execution::{ExecutionAlgo, ExecutionScheduler};
+use merrow::core::order_router::{
+    load_route_state, plan_routes, save_route_state, OrderRouter, RouterConfig, RouterVenue,
+    SmartOrderRouter, VenueSnapshot,
+};
+use merrow::exchange::{CandleRequest, Exchange};
+use merrow::models::{
+    Balance, Candle, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side,
+};
+use merrow::{Error, Result};
+use std::sync::{Arc, Mutex};
+
+struct MockVenue {
+    close: f64,
+    volume: f64,
+    quote_free: f64,
+    fail_quotes: bool,
+    time_out: Arc<Mutex<bool>>,
+    placed: Arc<Mutex<Vec<OrderRequest>>>,
+    canceled: Arc<Mutex<Vec<String>>>,
+    open: Arc<Mutex<Vec<OrderAck>>>,
+    closed: Arc<Mutex<Vec<OrderAck>>>,
+}
+
+impl Exchange for MockVenue {
+    fn place_order(&self, order: &OrderRequest) -> Result<OrderAck> {
+        self.placed.lock().unwrap().push(order.clone());
+        let ack = OrderAck {
+            client_order_id: order.client_order_id.clone(),
+            exchange_order_id: Some(format!("x-{}", order.client_order_id)),
+            status: OrderStatus::New,
+            filled_qty: 0.0,
+            avg_fill_price: None,
+        };
+        self.open.lock().unwrap().push(ack.clone());
+        if *self.time_out.lock().unwrap() {
+            return Err(Error::new("http request failed: operation timed out"));
+        }
+        Ok(ack)
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<()> {
+        self.canceled.lock().unwrap().push(order_id.to_string());
+        self.open
+            .lock()
+            .unwrap()
+            .retain(|order| order.client_order_id != order_id);
+        Ok(())
+    }
+
+    fn fetch_balances(&self) -> Result<Vec<Balance>> {
+        Ok(vec![Balance {
+            asset: "USDT".to_string(),
+            free: self.quote_free,
+            locked: 0.0,
+        }])
+    }
+
+    fn fetch_positions(&self) -> Result<Vec<Position>> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_open_orders(&self) -> Result<Vec<OrderAck>> {
+        Ok(self.open.lock().unwrap().clone())
+    }
+
+    fn fetch_order(&self, client_order_id: &str) -> Result<Option<OrderAck>> {
+        let open = self.open.lock().unwrap().clone();
+        let closed = self.closed.lock().unwrap().clone();
+        Ok(open
+            .into_iter()
+            .chain(closed)
+            .find(|order| order.client_order_id == client_order_id))
+    }
+
+    fn fetch_candles(&self, _req: &CandleRequest) -> Result<Vec<Candle>> {
+        if self.fail_quotes {
+            return Err(Error::new("venue unavailable"));
+        }
+        Ok(vec![candle(1, self.close, self.volume)])
+    }
+}
+
+fn candle(time: i64, price: f64, volume: f64) -> Candle {
+    Candle {
+        time,
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        volume,
+    }
+}
+
+fn snapshot(venue: &str, price: f64, fee_rate: f64, liquidity: f64, available: f64) -> VenueSnapshot {
+    VenueSnapshot {
+        venue: venue.to_string(),
+        price,
+        fee_rate,
+        liquidity,
+        available,
+        healthy: true,
+    }
+}
+
+fn buy(id: &str, quantity: f64) -> OrderRequest {
+    OrderRequest {
+        client_order_id: id.to_string(),
+        symbol: "BTCUSDT".to_string(),
+        side: Side::Buy,
+        order_type: OrderType::Market,
+        quantity,
+    }
+}
+
+#[derive(Clone, Default)]
+struct VenueLog {
+    time_out: Arc<Mutex<bool>>,
+    placed: Arc<Mutex<Vec<OrderRequest>>>,
+    canceled: Arc<Mutex<Vec<String>>>,
+    open: Arc<Mutex<Vec<OrderAck>>>,
+    closed: Arc<Mutex<Vec<OrderAck>>>,
+}
+
+impl VenueLog {
+    fn close(&self, client_order_id: &str, status: OrderStatus, filled_qty: f64, price: f64) {
+        let mut open = self.open.lock().unwrap();
+        let index = open
+            .iter()
+            .position(|order| order.client_order_id == client_order_id)
+            .expect("open order");
+        let order = open.remove(index);
+        self.closed.lock().unwrap().push(OrderAck {
+            status,
+            filled_qty,
+            avg_fill_price: Some(price),
+            ..order
+        });
+    }
+}
+
+fn venue(name: &str, close: f64, volume: f64, fail_quotes: bool) -> (RouterVenue, VenueLog) {
+    venue_with_log(name, close, volume, fail_quotes, VenueLog::default())
+}
+
+fn venue_with_log(
+    name: &str,
+    close: f64,
+    volume: f64,
+    fail_quotes: bool,
+    log: VenueLog,
+) -> (RouterVenue, VenueLog) {
+    let exchange = MockVenue {
+        close,
+        volume,
+        quote_free: 1_000_000.0,
+        fail_quotes,
+        time_out: Arc::clone(&log.time_out),
+        placed: Arc::clone(&log.placed),
+        canceled: Arc::clone(&log.canceled),
+        open: Arc::clone(&log.open),
+        closed: Arc::clone(&log.closed),
+    };
+    let venue = RouterVenue {
+        name: name.to_string(),
+        exchange: Box::new(exchange),
+        symbol: "BTCUSDT".to_string(),
+        base_asset: "BTC".to_string(),
+        quote_asset: "USDT".to_string(),
+        fee_rate: 0.001,
+    };
+    (venue, log)
+}
+
+fn router_config(max_venue_failures: u32) -> RouterConfig {
+    RouterConfig {
+        symbol: "BTCUSDT".to_string(),
+        max_participation: 0.5,
+        max_venue_failures,
+        quote_interval: "1m".to_string(),
+        quote_window_secs: 120,
+    }
+}
+
+#[test]
+fn plan_prefers_lowest_fee_adjusted_price_for_buys() {
+    let venues = vec![
+        snapshot("a", 100.0, 0.01, 10.0, 1_000_000.0),
+        snapshot("b", 100.5, 0.0, 10.0, 1_000_000.0),
+    ];
+    let plan = plan_routes(&Side::Buy, 1.0, &venues);
+    assert_eq!(plan.legs.len(), 1);
+    assert_eq!(plan.legs[0].venue, "b");
+    assert_eq!(plan.unrouted, 0.0);
+}
+
+#[test]
+fn plan_prefers_highest_price_for_sells() {
+    let venues = vec![
+        snapshot("a", 100.0, 0.0, 10.0, 5.0),
+        snapshot("b", 101.0, 0.0, 10.0, 5.0),
+    ];
+    let plan = plan_routes(&Side::Sell, 2.0, &venues);
+    assert_eq!(plan.legs[0].venue, "b");
+}
+
+#[test]
+fn plan_splits_when_liquidity_is_exhausted() {
+    let venues = vec![
+        snapshot("a", 100.0, 0.0, 2.0, 1_000_000.0),
+        snapshot("b", 101.0, 0.0, 10.0, 1_000_000.0),
+    ];
+    let plan = plan_routes(&Side::Buy, 5.0, &venues);
+    assert_eq!(plan.legs.len(), 2);
+    assert_eq!(plan.legs[0].venue, "a");
+    assert!((plan.legs[0].quantity - 2.0).abs() < 1e-9);
+    assert!((plan.legs[1].quantity - 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn plan_respects_balances_and_health() {
+    let mut down = snapshot("a", 90.0, 0.0, 10.0, 1_000_000.0);
+    down.healthy = false;
+    let venues = vec![down, snapshot("b", 100.0, 0.0, 10.0, 200.0)];
+    let plan = plan_routes(&Side::Buy, 3.0, &venues);
+    assert_eq!(plan.legs.len(), 1);
+    assert_eq!(plan.legs[0].venue, "b");
+    assert!((plan.legs[0].quantity - 2.0).abs() < 1e-9);
+    assert!((plan.unrouted - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn router_splits_order_across_venues_and_cancels_children() {
+    let (cheap, cheap_log) = venue("cheap", 100.0, 4.0, false);
+    let (deep, deep_log) = venue("deep", 101.0, 100.0, false);
+    let router = SmartOrderRouter::new(router_config(3), vec![cheap, deep]).expect("router");
+
+    let routed = router.route_order(&buy("p1", 5.0)).expect("route");
+    assert_eq!(routed.legs.len(), 2);
+    assert_eq!(routed.ack.status, OrderStatus::New);
+    {
+        let cheap_orders = cheap_log.placed.lock().unwrap();
+        let deep_orders = deep_log.placed.lock().unwrap();
+        assert_eq!(cheap_orders[0].client_order_id, "p1-1");
+        assert!((cheap_orders[0].quantity - 2.0).abs() < 1e-9);
+        assert_eq!(deep_orders[0].client_order_id, "p1-2");
+        assert!((deep_orders[0].quantity - 3.0).abs() < 1e-9);
+    }
+
+    router.cancel_order("p1").expect("cancel");
+    assert_eq!(*cheap_log.canceled.lock().unwrap(), vec!["p1-1".to_string()]);
+    assert_eq!(*deep_log.canceled.lock().unwrap(), vec!["p1-2".to_string()]);
+}
+
+#[test]
+fn split_children_survive_a_new_cycle() {
+    let path = std::env::temp_dir()
+        .join("merrow_order_router_tests")
+        .join("route_state.json");
+    let _ = std::fs::remove_file(&path);
+    let path = path.to_string_lossy().into_owned();
+
+    let (cheap, cheap_log) = venue("cheap", 100.0, 4.0, false);
+    let (deep, deep_log) = venue("deep", 101.0, 100.0, false);
+    let first = SmartOrderRouter::new(router_config(3), vec![cheap, deep]).expect("router");
+    let mut scheduler = ExecutionScheduler::new();
+    scheduler
+        .submit(&buy("p4", 5.0), &ExecutionAlgo::Immediate, 100.0, 0, &[])
+        .expect("submit");
+    for child in scheduler.due_children(0, 100.0).expect("children") {
+        first.place_order(&child).expect("place");
+    }
+    save_route_state(&path, &first.routed_children().expect("children")).expect("save");
+
+    let (cheap, _) = venue_with_log("cheap", 100.0, 4.0, false, cheap_log.clone());
+    let (deep, _) = venue_with_log("deep", 101.0, 100.0, false, deep_log.clone());
+    let second = SmartOrderRouter::new(router_config(3), vec![cheap, deep]).expect("router");
+    second
+        .restore_children(load_route_state(&path).expect("load").expect("state"))
+        .expect("restore");
+
+    let open = second.fetch_open_orders().expect("open orders");
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].client_order_id, "p4");
+    assert!(scheduler.tracks(&open[0].client_order_id));
+    let fetched = second.fetch_order("p4").expect("fetch").expect("order");
+    assert_eq!(fetched.status, OrderStatus::New);
+
+    second.cancel_order("p4").expect("cancel");
+    assert_eq!(*cheap_log.canceled.lock().unwrap(), vec!["p4-1".to_string()]);
+    assert_eq!(*deep_log.canceled.lock().unwrap(), vec!["p4-2".to_string()]);
+    assert!(second.fetch_open_orders().expect("open orders").is_empty());
+}
+
+#[test]
+fn split_order_status_merges_leg_fills() {
+    let (cheap, cheap_log) = venue("cheap", 100.0, 4.0, false);
+    let (deep, deep_log) = venue("deep", 101.0, 100.0, false);
+    let router = SmartOrderRouter::new(router_config(3), vec![cheap, deep]).expect("router");
+    router.route_order(&buy("p5", 5.0)).expect("route");
+
+    cheap_log.close("p5-1", OrderStatus::Filled, 2.0, 100.0);
+    let partial = router.fetch_order("p5").expect("fetch").expect("order");
+    assert_eq!(partial.status, OrderStatus::PartiallyFilled);
+    assert!((partial.filled_qty - 2.0).abs() < 1e-9);
+
+    deep_log.close("p5-2", OrderStatus::Canceled, 1.0, 103.0);
+    let done = router.fetch_order("p5").expect("fetch").expect("order");
+    assert_eq!(done.client_order_id, "p5");
+    assert_eq!(done.status, OrderStatus::Canceled);
+    assert!((done.filled_qty - 3.0).abs() < 1e-9);
+    assert!((done.avg_fill_price.expect("avg") - 101.0).abs() < 1e-9);
+}
+
+#[test]
+fn timed_out_child_is_kept_and_resolved_by_lookup() {
+    let (cheap, cheap_log) = venue("cheap", 100.0, 4.0, false);
+    let (deep, deep_log) = venue("deep", 101.0, 100.0, false);
+    *deep_log.time_out.lock().unwrap() = true;
+    let router = SmartOrderRouter::new(router_config(3), vec![cheap, deep]).expect("router");
+
+    let routed = router.route_order(&buy("p6", 5.0)).expect("route");
+    assert_eq!(routed.ack.status, OrderStatus::New);
+    let children = router.routed_children().expect("children");
+    let ids: Vec<&str> = children["p6"]
+        .iter()
+        .map(|child| child.client_order_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["p6-1", "p6-2"]);
+
+    cheap_log.close("p6-1", OrderStatus::Filled, 2.0, 100.0);
+    deep_log.close("p6-2", OrderStatus::Filled, 3.0, 101.0);
+    let status = router.fetch_order("p6").expect("fetch").expect("order");
+    assert_eq!(status.status, OrderStatus::Filled);
+    assert!((status.filled_qty - 5.0).abs() < 1e-9);
+
+    *cheap_log.time_out.lock().unwrap() = true;
+    router.route_order(&buy("p7", 1.0)).expect("route");
+    router.cancel_order("p7").expect("cancel");
+    assert_eq!(*cheap_log.canceled.lock().unwrap(), vec!["p7".to_string()]);
+}
+
+#[test]
+fn router_skips_unhealthy_venues() {
+    let (broken, broken_log) = venue("broken", 90.0, 100.0, true);
+    let (backup, backup_log) = venue("backup", 100.0, 100.0, false);
+    let router = SmartOrderRouter::new(router_config(1), vec![broken, backup]).expect("router");
+
+    let ack = router.route(buy("p2", 1.0)).expect("route");
+    assert_eq!(ack.status, OrderStatus::New);
+    assert!(broken_log.placed.lock().unwrap().is_empty());
+    assert_eq!(backup_log.placed.lock().unwrap()[0].client_order_id, "p2");
+    assert!(!router.is_healthy("broken").expect("health"));
+}
+
+#[test]
+fn routed_backtest_fills_legs_per_venue() {
+    let venue = |name: &str, price: f64, volume: f64| BacktestVenue {
+        name: name.to_string(),
+        candles: vec![candle(1, price, volume), candle(2, price, volume)],
+        fee_rate: 0.0,
+        slippage_bps: 0,
+        quote_balance: 10_000.0,
+        base_balance: 0.0,
+    };
+    let venues = vec![venue("a", 100.0, 4.0), venue("b", 102.0, 100.0)];
+    let orders = vec![BacktestOrder {
+        submit_index: 0,
+        order: buy("p3", 5.0),
+    }];
+
+    let result = run_routed(&venues, orders, 0.5).expect("routed backtest");
+    assert_eq!(result.trades.len(), 2);
+    assert_eq!(result.trades[0].venue, "a");
+    assert!((result.trades[0].trade.quantity - 2.0).abs() < 1e-9);
+    assert_eq!(result.trades[1].venue, "b");
+    assert!((result.trades[1].trade.price - 102.0).abs() < 1e-9);
+    assert!((result.balances[0].quote_balance - 9_800.0).abs() < 1e-9);
+    assert!((result.balances[1].base_balance - 3.0).abs() < 1e-9);
+    assert!(result.unrouted.is_empty());
+}
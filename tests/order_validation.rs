@@ -0,0 +1,177 @@
+use merrow::exchange::instrument::{InstrumentCache, InstrumentRules};
+use merrow::exchange::sim::{SimConfig, SimExchange};
+use merrow::exchange::{Exchange, OrderCheck};
+use merrow::models::{Candle, OrderRequest, OrderType, Side};
+use serde_json::json;
+use std::cell::Cell;
+
+fn rules() -> InstrumentRules {
+    InstrumentRules {
+        symbol: "BTCUSDT".to_string(),
+        tick_size: 0.1,
+        lot_size: 0.001,
+        min_qty: 0.001,
+        max_qty: 100.0,
+        min_notional: 5.0,
+    }
+}
+
+fn order(side: Side, order_type: OrderType, quantity: f64) -> OrderRequest {
+    OrderRequest {
+        client_order_id: "merrow-1-1".to_string(),
+        symbol: "BTCUSDT".to_string(),
+        side,
+        order_type,
+        quantity,
+    }
+}
+
+fn rejection(result: merrow::Result<()>) -> String {
+    result.expect_err("should reject").message
+}
+
+#[test]
+fn instrument_rules_accept_well_formed_orders() {
+    let rules = rules();
+    assert!(rules
+        .check(&order(Side::Buy, OrderType::Limit { price: 100.1 }, 0.05), 100.0)
+        .is_ok());
+    assert!(rules.check(&order(Side::Sell, OrderType::Market, 0.06), 100.0).is_ok());
+}
+
+#[test]
+fn instrument_rules_explain_rejections() {
+    let rules = rules();
+    assert!(rejection(rules.check(&order(Side::Buy, OrderType::Market, 0.0005), 100.0))
+        .contains("below minimum"));
+    assert!(rejection(rules.check(&order(Side::Buy, OrderType::Market, 0.0555), 100.0))
+        .contains("lot size"));
+    assert!(rejection(rules.check(
+        &order(Side::Buy, OrderType::Limit { price: 100.05 }, 0.1),
+        100.0
+    ))
+    .contains("tick size"));
+    assert!(rejection(rules.check(&order(Side::Buy, OrderType::Market, 0.01), 100.0))
+        .contains("notional"));
+    assert!(rejection(rules.check(&order(Side::Buy, OrderType::Market, 101.0), 100.0))
+        .contains("above maximum"));
+}
+
+#[test]
+fn instrument_cache_fetches_once_per_symbol() {
+    let cache = InstrumentCache::new();
+    let calls = Cell::new(0);
+    for _ in 0..3 {
+        let cached = cache
+            .get_or_fetch("BTCUSDT", || {
+                calls.set(calls.get() + 1);
+                Ok(rules())
+            })
+            .expect("rules");
+        assert_eq!(cached, rules());
+    }
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn parses_bybit_and_okx_instrument_rules() {
+    let bybit = merrow::exchange::bybit::parse_instrument_rules(
+        &json!({
+            "retCode": 0,
+            "result": { "list": [{
+                "symbol": "BTCUSDT",
+                "lotSizeFilter": {
+                    "basePrecision": "0.000001",
+                    "minOrderQty": "0.000048",
+                    "maxOrderQty": "71.73956243",
+                    "minOrderAmt": "1"
+                },
+                "priceFilter": { "tickSize": "0.01" }
+            }]}
+        }),
+        "BTCUSDT",
+    )
+    .expect("bybit rules");
+    assert_eq!(bybit.tick_size, 0.01);
+    assert_eq!(bybit.lot_size, 0.000001);
+    assert_eq!(bybit.min_notional, 1.0);
+
+    let okx = merrow::exchange::okx::parse_instrument_rules(
+        &json!({
+            "code": "0",
+            "data": [{
+                "instId": "BTC-USDT",
+                "tickSz": "0.1",
+                "lotSz": "0.00000001",
+                "minSz": "0.00001",
+                "maxLmtSz": "9999999999"
+            }]
+        }),
+        "BTC-USDT",
+    )
+    .expect("okx rules");
+    assert_eq!(okx.min_qty, 0.00001);
+    assert_eq!(okx.min_notional, 0.0);
+    assert!(merrow::exchange::okx::parse_instrument_rules(&json!({"code": "0", "data": []}), "ETH-USDT").is_err());
+}
+
+#[test]
+fn binance_test_orders_reuse_order_params() {
+    let params = merrow::exchange::binance::order_params(&order(
+        Side::Buy,
+        OrderType::Limit { price: 100.0 },
+        0.5,
+    ));
+    assert!(params.contains(&("type".to_string(), "LIMIT".to_string())));
+    assert!(params.contains(&("newClientOrderId".to_string(), "merrow-1-1".to_string())));
+    let reason = merrow::exchange::binance::rejection_reason(&json!({
+        "code": -1013,
+        "msg": "Filter failure: LOT_SIZE"
+    }));
+    assert_eq!(reason.as_deref(), Some("code -1013: Filter failure: LOT_SIZE"));
+}
+
+#[test]
+fn sim_test_order_checks_funds_without_locking() {
+    let config = SimConfig {
+        symbol: "BTCUSDT".to_string(),
+        base_asset: "BTC".to_string(),
+        quote_asset: "USDT".to_string(),
+        initial_quote: 1000.0,
+        initial_base: 0.0,
+        fee_rate: 0.0,
+        slippage_bps: 0,
+        warmup_bars: 1,
+    };
+    let candle = Candle {
+        time: 60,
+        open: 100.0,
+        high: 100.0,
+        low: 100.0,
+        close: 100.0,
+        volume: 1.0,
+    };
+    let sim = SimExchange::new(config, vec![candle]).expect("sim");
+
+    let check = sim
+        .test_order(&order(Side::Buy, OrderType::Market, 5.0), 100.0)
+        .expect("check");
+    assert_eq!(check, OrderCheck::Accepted);
+    let check = sim
+        .test_order(&order(Side::Buy, OrderType::Market, 20.0), 100.0)
+        .expect("check");
+    assert!(matches!(check, OrderCheck::Rejected(reason) if reason.contains("insufficient USDT")));
+    let check = sim
+        .test_order(&order(Side::Sell, OrderType::Market, 1.0), 100.0)
+        .expect("check");
+    assert!(matches!(check, OrderCheck::Rejected(_)));
+
+    assert!(sim.fetch_open_orders().expect("open").is_empty());
+    let usdt = sim
+        .fetch_balances()
+        .expect("balances")
+        .into_iter()
+        .find(|balance| balance.asset == "USDT")
+        .expect("usdt");
+    assert_eq!((usdt.free, usdt.locked), (1000.0, 0.0));
+}
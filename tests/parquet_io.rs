@@ -0,0 +1,223 @@
+use merrow::app::report::{parquet_table_path, round_trips, write_output, RoundTrip};
+use merrow::backtest::{BacktestMetrics, BacktestResult, EquityPoint};
+use merrow::config::{Config, CsvConfig};
+use merrow::data::market_data::provider_from_config;
+use merrow::data::parquet_loader::load_candles_from_parquet;
+use merrow::models::{Account, Side, Trade};
+use parquet::data_type::{DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::writer::SerializedFileWriter;
+use parquet::record::Field;
+use parquet::schema::parser::parse_message_type;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("merrow_parquet_tests").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+    dir
+}
+
+fn trade(time: i64, side: Side, price: f64, quantity: f64) -> Trade {
+    Trade {
+        time,
+        symbol: "BTCUSDT".to_string(),
+        side,
+        price,
+        quantity,
+        fee: 0.1,
+    }
+}
+
+fn sample_result() -> BacktestResult {
+    BacktestResult {
+        trades: vec![
+            trade(60, Side::Buy, 100.0, 1.0),
+            trade(120, Side::Buy, 110.0, 1.0),
+            trade(180, Side::Sell, 120.0, 1.5),
+            trade(240, Side::Sell, 130.0, 0.5),
+            trade(300, Side::Buy, 90.0, 1.0),
+        ],
+        account: Account {
+            cash: 1_000.0,
+            positions: Vec::new(),
+        },
+        metrics: BacktestMetrics {
+            return_rate: 0.0,
+            max_drawdown: 0.0,
+            win_rate: 1.0,
+            trade_count: 5,
+            sharpe: 0.0,
+        },
+        equity_curve: vec![
+            EquityPoint {
+                time: 60,
+                equity: 1_000.0,
+            },
+            EquityPoint {
+                time: 120,
+                equity: 1_010.0,
+            },
+        ],
+        trade_pnls: vec![None, None, Some(15.0), Some(12.5), None],
+        executions: Vec::new(),
+    }
+}
+
+fn read_rows(path: &str) -> Vec<Vec<(String, Field)>> {
+    let reader = SerializedFileReader::new(File::open(path).expect("open")).expect("reader");
+    reader
+        .get_row_iter(None)
+        .expect("rows")
+        .map(|row| {
+            row.expect("row")
+                .get_column_iter()
+                .map(|(name, field)| (name.clone(), field.clone()))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn round_trips_close_when_position_is_flat() {
+    let trips = round_trips(&sample_result());
+    assert_eq!(
+        trips,
+        vec![RoundTrip {
+            symbol: "BTCUSDT".to_string(),
+            entry_time: 60,
+            exit_time: 240,
+            quantity: 2.0,
+            entry_price: 105.0,
+            exit_price: 122.5,
+            fees: 0.4,
+            pnl: 27.5,
+        }]
+    );
+}
+
+#[test]
+fn writes_parquet_tables_next_to_output_path() {
+    let dir = temp_dir("report");
+    let path = dir.join("report.parquet");
+    let path = path.to_str().expect("path");
+    write_output(path, "parquet", &sample_result()).expect("write parquet");
+
+    let trades_path = parquet_table_path(path, "trades");
+    assert!(trades_path.ends_with("report_trades.parquet"));
+    let trades = read_rows(&trades_path);
+    assert_eq!(trades.len(), 5);
+    assert_eq!(
+        trades[0][1],
+        ("symbol".to_string(), Field::Str("BTCUSDT".to_string()))
+    );
+    assert_eq!(trades[0][6], ("pnl".to_string(), Field::Null));
+    assert_eq!(trades[2][6], ("pnl".to_string(), Field::Double(15.0)));
+
+    let equity = read_rows(&parquet_table_path(path, "equity"));
+    assert_eq!(equity.len(), 2);
+    assert_eq!(equity[1][1], ("equity".to_string(), Field::Double(1_010.0)));
+
+    let trips = read_rows(&parquet_table_path(path, "round_trips"));
+    assert_eq!(trips.len(), 1);
+    assert_eq!(trips[0][2], ("exit_time".to_string(), Field::Long(240)));
+}
+
+fn write_candles(path: &PathBuf, message: &str, times: &[i64]) {
+    let schema = Arc::new(parse_message_type(message).expect("schema"));
+    let props = Arc::new(WriterProperties::builder().build());
+    let file = File::create(path).expect("create");
+    let mut writer = SerializedFileWriter::new(file, schema, props).expect("writer");
+    let mut group = writer.next_row_group().expect("group");
+    let mut index = 0;
+    while let Some(mut column) = group.next_column().expect("column") {
+        if index == 0 {
+            column
+                .typed::<Int64Type>()
+                .write_batch(times, None, None)
+                .expect("times");
+        } else {
+            let values: Vec<f64> = times
+                .iter()
+                .enumerate()
+                .map(|(row, _)| match index {
+                    2 => 110.0 + row as f64,
+                    3 => 90.0,
+                    5 => 1.0,
+                    _ => 100.0 + row as f64,
+                })
+                .collect();
+            column
+                .typed::<DoubleType>()
+                .write_batch(&values, None, None)
+                .expect("values");
+        }
+        column.close().expect("close column");
+        index += 1;
+    }
+    group.close().expect("close group");
+    writer.close().expect("close writer");
+}
+
+#[test]
+fn loads_candles_with_timestamp_and_epoch_columns() {
+    let dir = temp_dir("candles");
+    let timestamps = dir.join("timestamps.parquet");
+    write_candles(
+        &timestamps,
+        "message candles {
+            REQUIRED INT64 open_time (TIMESTAMP_MILLIS);
+            REQUIRED DOUBLE open;
+            REQUIRED DOUBLE high;
+            REQUIRED DOUBLE low;
+            REQUIRED DOUBLE close;
+            REQUIRED DOUBLE volume;
+        }",
+        &[1_704_067_260_000, 1_704_067_200_000],
+    );
+    let schema = CsvConfig {
+        time_column: "open_time".to_string(),
+        ..CsvConfig::default()
+    };
+    let candles =
+        load_candles_from_parquet(timestamps.to_str().expect("path"), &schema).expect("load");
+    assert_eq!(
+        candles.iter().map(|candle| candle.time).collect::<Vec<_>>(),
+        vec![1_704_067_200, 1_704_067_260]
+    );
+    assert_eq!(candles[0].close, 101.0);
+
+    let epochs = dir.join("epochs.parquet");
+    write_candles(
+        &epochs,
+        "message candles {
+            REQUIRED INT64 time;
+            REQUIRED DOUBLE open;
+            REQUIRED DOUBLE high;
+            REQUIRED DOUBLE low;
+            REQUIRED DOUBLE close;
+            REQUIRED DOUBLE volume;
+        }",
+        &[1_704_067_200, 1_704_067_260, 1_704_067_320],
+    );
+    let mut config = Config::default();
+    config.data.source = "parquet".to_string();
+    config.data.parquet_path = Some(epochs.to_string_lossy().into_owned());
+    assert!(config.validate().is_ok());
+    let provider = provider_from_config(&config).expect("provider");
+    let recent = provider
+        .load_recent(&config.symbol, "1m", 2, 0)
+        .expect("recent");
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[1].time, 1_704_067_320);
+
+    assert!(
+        load_candles_from_parquet(timestamps.to_str().expect("path"), &CsvConfig::default())
+            .is_err()
+    );
+    config.data.parquet_path = None;
+    assert!(config.validate().is_err());
+}
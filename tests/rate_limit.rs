@@ -0,0 +1,86 @@
+use merrow::exchange::rate_limit::{
+    binance_request_weight, binance_spot_limiter, shared_limiter, RateLimiter,
+};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn binance_weights_follow_endpoint_and_params() {
+    assert_eq!(binance_request_weight("/api/v3/klines", &[]), 2.0);
+    assert_eq!(binance_request_weight("/api/v3/account", &[]), 20.0);
+    assert_eq!(binance_request_weight("/api/v3/openOrders", &[]), 80.0);
+    let with_symbol = params(&[("symbol", "BTCUSDT")]);
+    assert_eq!(binance_request_weight("/api/v3/openOrders", &with_symbol), 6.0);
+    assert_eq!(binance_request_weight("/api/v3/order", &with_symbol), 1.0);
+}
+
+#[test]
+fn global_bucket_charges_request_weight() {
+    let limiter = RateLimiter::new("test")
+        .with_global(30.0, Duration::from_secs(60))
+        .with_weight_fn(binance_request_weight);
+
+    assert!(limiter.try_acquire("/api/v3/account", &[]).expect("acquire").is_zero());
+    let remaining = limiter.remaining("/api/v3/account").expect("remaining");
+    assert!((remaining - 10.0).abs() < 0.1);
+    let wait = limiter.try_acquire("/api/v3/account", &[]).expect("acquire");
+    assert!(wait > Duration::from_secs(15));
+    assert!(limiter.try_acquire("/api/v3/klines", &[]).expect("acquire").is_zero());
+}
+
+#[test]
+fn endpoint_bucket_limits_requests_independently() {
+    let limiter = RateLimiter::new("test")
+        .with_global(100.0, Duration::from_secs(60))
+        .with_endpoint("/v5/order/create", 2.0, Duration::from_secs(60));
+
+    assert!(limiter.try_acquire("/v5/order/create", &[]).expect("acquire").is_zero());
+    assert!(limiter.try_acquire("/v5/order/create", &[]).expect("acquire").is_zero());
+    assert!(!limiter.try_acquire("/v5/order/create", &[]).expect("acquire").is_zero());
+    assert!(limiter.try_acquire("/v5/market/kline", &[]).expect("acquire").is_zero());
+}
+
+#[test]
+fn used_weight_header_drains_local_budget() {
+    let limiter = binance_spot_limiter();
+    let mut headers = HeaderMap::new();
+    headers.insert("X-MBX-USED-WEIGHT-1M", HeaderValue::from_static("5999"));
+    limiter
+        .observe("/api/v3/klines", StatusCode::OK, &headers)
+        .expect("observe");
+
+    assert!(limiter.remaining("/api/v3/klines").expect("remaining") < 2.0);
+    assert!(!limiter.try_acquire("/api/v3/klines", &[]).expect("acquire").is_zero());
+}
+
+#[test]
+fn too_many_requests_blocks_until_retry_after() {
+    let limiter = RateLimiter::new("test");
+    let mut headers = HeaderMap::new();
+    headers.insert("Retry-After", HeaderValue::from_static("30"));
+    limiter
+        .observe("/any", StatusCode::TOO_MANY_REQUESTS, &headers)
+        .expect("observe");
+
+    let wait = limiter.try_acquire("/any", &[]).expect("acquire");
+    assert!(wait > Duration::from_secs(25));
+}
+
+#[test]
+fn shared_limiter_is_reused_per_exchange() {
+    let first = shared_limiter("Binance").expect("limiter");
+    let second = shared_limiter("binance").expect("limiter");
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(first.name(), "binance");
+    let other = shared_limiter("okx").expect("limiter");
+    assert!(!Arc::ptr_eq(&first, &other));
+}
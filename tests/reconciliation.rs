@@ -0,0 +1,184 @@
+use merrow::core::reconciliation::{
+    load_expected_state, reconcile, save_expected_state, BreakKind, ExpectedState,
+    ReconcileScope,
+};
+use merrow::exchange::sync::AccountSnapshot;
+use merrow::models::{Balance, OrderAck, OrderRequest, OrderStatus, OrderType, Position, Side};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn scope(derivatives: bool) -> ReconcileScope {
+    ReconcileScope {
+        symbol: "BTCUSDT".to_string(),
+        base_asset: "BTC".to_string(),
+        quote_asset: "USDT".to_string(),
+        client_id_prefix: "merrow".to_string(),
+        fee_rate: 0.0,
+        max_drift_ratio: 0.01,
+        derivatives,
+        shared_quote: false,
+    }
+}
+
+fn balance(asset: &str, free: f64) -> Balance {
+    Balance {
+        asset: asset.to_string(),
+        free,
+        locked: 0.0,
+    }
+}
+
+fn ack(id: &str) -> OrderAck {
+    OrderAck {
+        client_order_id: id.to_string(),
+        exchange_order_id: None,
+        status: OrderStatus::New,
+        filled_qty: 0.0,
+        avg_fill_price: None,
+    }
+}
+
+fn snapshot(btc: f64, usdt: f64, open: &[&str]) -> AccountSnapshot {
+    AccountSnapshot {
+        account: "default".to_string(),
+        balances: vec![balance("BTC", btc), balance("USDT", usdt)],
+        positions: Vec::new(),
+        open_orders: open.iter().map(|id| ack(id)).collect(),
+    }
+}
+
+fn buy(id: &str, quantity: f64, price: f64) -> OrderRequest {
+    OrderRequest {
+        client_order_id: id.to_string(),
+        symbol: "BTCUSDT".to_string(),
+        side: Side::Buy,
+        order_type: OrderType::Limit { price },
+        quantity,
+    }
+}
+
+#[test]
+fn first_run_is_a_baseline_without_breaks() {
+    let report = reconcile(None, &snapshot(1.0, 1_000.0, &["manual"]), &scope(false), 10);
+    assert!(report.baseline);
+    assert!(report.breaks.is_empty());
+    assert!(!report.has_breach());
+}
+
+#[test]
+fn filled_order_matches_exchange_balances() {
+    let scope = scope(false);
+    let mut expected = ExpectedState::from_snapshot(&snapshot(0.0, 1_000.0, &[]), &scope, None, 0);
+    expected.record_order(&buy("merrow-1-1", 1.0, 100.0), 100.0);
+
+    let report = reconcile(Some(&expected), &snapshot(1.0, 900.0, &[]), &scope, 60);
+    assert_eq!(report.assumed_fills, vec!["merrow-1-1".to_string()]);
+    assert!(report.breaks.is_empty());
+}
+
+#[test]
+fn manual_trade_breaches_tolerance() {
+    let scope = scope(false);
+    let expected = ExpectedState::from_snapshot(&snapshot(1.0, 1_000.0, &[]), &scope, None, 0);
+
+    let report = reconcile(Some(&expected), &snapshot(0.5, 1_050.0, &[]), &scope, 60);
+    assert!(report.has_breach());
+    let base = report
+        .breaks
+        .iter()
+        .find(|item| item.key == "BTC")
+        .expect("base break");
+    assert_eq!(base.kind, BreakKind::Balance);
+    assert_eq!(base.cause, "manual_trade");
+    assert!((base.drift_ratio - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn small_drift_is_reported_without_breach() {
+    let scope = scope(false);
+    let expected = ExpectedState::from_snapshot(&snapshot(1.0, 1_000.0, &[]), &scope, None, 0);
+
+    let report = reconcile(Some(&expected), &snapshot(1.0, 999.0, &[]), &scope, 60);
+    assert_eq!(report.breaks.len(), 1);
+    assert!(!report.has_breach());
+}
+
+#[test]
+fn shared_quote_ignores_quote_balance_drift() {
+    let mut scope = scope(false);
+    scope.shared_quote = true;
+    let expected = ExpectedState::from_snapshot(&snapshot(1.0, 1_000.0, &[]), &scope, None, 0);
+
+    let report = reconcile(Some(&expected), &snapshot(1.0, 500.0, &[]), &scope, 60);
+    assert!(report.breaks.is_empty());
+    let report = reconcile(Some(&expected), &snapshot(0.5, 500.0, &[]), &scope, 60);
+    assert_eq!(report.breaks.len(), 1);
+    assert_eq!(report.breaks[0].key, "BTC");
+}
+
+#[test]
+fn classifies_unknown_and_missing_orders() {
+    let scope = scope(false);
+    let previous = snapshot(1.0, 1_000.0, &["merrow-1-1"]);
+    let expected = ExpectedState::from_snapshot(&previous, &scope, None, 0);
+
+    let current = snapshot(1.0, 1_000.0, &["merrow-2-1", "web-7"]);
+    let report = reconcile(Some(&expected), &current, &scope, 60);
+    let causes: Vec<(BreakKind, &str, &str)> = report
+        .breaks
+        .iter()
+        .map(|item| (item.kind, item.key.as_str(), item.cause.as_str()))
+        .collect();
+    assert_eq!(
+        causes,
+        vec![
+            (BreakKind::MissingOrder, "merrow-1-1", "closed_untracked"),
+            (BreakKind::UnknownOrder, "merrow-2-1", "orphan_order"),
+            (BreakKind::UnknownOrder, "web-7", "manual_order"),
+        ]
+    );
+    assert!(!report.has_breach());
+}
+
+#[test]
+fn derivatives_reconcile_positions() {
+    let scope = scope(true);
+    let mut flat = snapshot(0.0, 1_000.0, &[]);
+    flat.positions.push(Position {
+        symbol: "BTCUSDT".to_string(),
+        quantity: 0.0,
+        avg_price: 0.0,
+    });
+    let mut expected = ExpectedState::from_snapshot(&flat, &scope, None, 0);
+    expected.record_order(&buy("merrow-1-1", 2.0, 100.0), 100.0);
+
+    let mut long = snapshot(0.0, 1_000.0, &[]);
+    long.positions.push(Position {
+        symbol: "BTCUSDT".to_string(),
+        quantity: 1.0,
+        avg_price: 100.0,
+    });
+    let report = reconcile(Some(&expected), &long, &scope, 60);
+    assert_eq!(report.breaks.len(), 1);
+    assert_eq!(report.breaks[0].kind, BreakKind::Position);
+    assert_eq!(report.breaks[0].cause, "fill_mismatch");
+    assert!(report.has_breach());
+}
+
+#[test]
+fn expected_state_round_trips_through_disk() {
+    let scope = scope(false);
+    let mut state = ExpectedState::from_snapshot(&snapshot(1.0, 1_000.0, &[]), &scope, None, 5);
+    state.record_order(&buy("merrow-1-1", 1.0, 100.0), 100.0);
+    state.record_amend("merrow-1-1", 101.0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("merrow_reconcile_{nanos}/state.json"));
+    let path = path.to_string_lossy().to_string();
+    save_expected_state(&path, &state).expect("save");
+    let loaded = load_expected_state(&path).expect("load").expect("state");
+    assert_eq!(loaded, state);
+    assert_eq!(loaded.open_orders[0].fill.as_ref().map(|fill| fill.price), Some(101.0));
+}
@@ -37,6 +37,7 @@ fn sample_result() -> BacktestResult {
             equity: 900.0,
         }],
         trade_pnls: vec![None],
+        executions: Vec::new(),
     }
 }
 
@@ -0,0 +1,181 @@
+use merrow::backtest::BacktestEngine;
+use merrow::config::Config;
+use merrow::core::order_flow::OrderFlow;
+use merrow::core::risk::{RiskLimits, RiskManager};
+use merrow::core::strategy::Strategy;
+use merrow::core::triggers::{TimeTrigger, TriggerEngine};
+use merrow::core::{StrategyContext, TriggerMode};
+use merrow::data::resample::{build_timeframes, resample, Timeframe};
+use merrow::models::{Candle, Signal};
+
+fn candle(time: i64, open: f64, high: f64, low: f64, close: f64, volume: f64) -> Candle {
+    Candle {
+        time,
+        open,
+        high,
+        low,
+        close,
+        volume,
+    }
+}
+
+fn minutes(start: i64, count: i64) -> Vec<Candle> {
+    (0..count)
+        .map(|index| {
+            let price = 100.0 + index as f64;
+            candle(start + index * 60, price, price + 2.0, price - 1.0, price + 1.0, 1.0)
+        })
+        .collect()
+}
+
+#[test]
+fn aggregates_ohlcv_on_aligned_buckets() {
+    let base = vec![
+        candle(0, 10.0, 12.0, 9.0, 11.0, 1.0),
+        candle(300, 11.0, 15.0, 10.0, 14.0, 2.0),
+        candle(600, 14.0, 14.5, 8.0, 9.0, 3.0),
+        candle(900, 9.0, 10.0, 8.5, 9.5, 4.0),
+    ];
+    let bars = resample(&base, "5m", "15m").expect("resample");
+    assert_eq!(bars.len(), 1);
+    assert_eq!(bars[0], candle(0, 10.0, 15.0, 8.0, 9.0, 6.0));
+
+    let mut timeframe = Timeframe::new("15m", "5m").expect("timeframe");
+    for item in &base {
+        timeframe.push(item);
+    }
+    assert_eq!(timeframe.forming(), Some(&candle(900, 9.0, 10.0, 8.5, 9.5, 4.0)));
+}
+
+#[test]
+fn aligns_to_interval_boundaries_and_skips_partial_history() {
+    let base = minutes(600, 50);
+    let bars = resample(&base, "1m", "15m").expect("resample");
+    assert_eq!(bars.iter().map(|bar| bar.time).collect::<Vec<_>>(), vec![900, 1800, 2700]);
+    assert_eq!(bars[0].open, base[5].open);
+    assert_eq!(bars[0].close, base[19].close);
+    assert_eq!(bars[0].volume, 15.0);
+}
+
+#[test]
+fn chained_resampling_matches_direct() {
+    let base = minutes(0, 60 * 24 * 2);
+    let fifteen = resample(&base, "1m", "15m").expect("15m");
+    let four_hours = resample(&fifteen, "15m", "4h").expect("4h");
+    let direct = resample(&base, "1m", "4h").expect("direct");
+    assert_eq!(four_hours, direct);
+    assert_eq!(direct.len(), 12);
+    let daily = resample(&four_hours, "4h", "1d").expect("1d");
+    assert_eq!(daily.len(), 2);
+    assert_eq!(daily[1].time, 86_400);
+    assert_eq!(daily[1].volume, 1440.0);
+}
+
+#[test]
+fn weekly_bars_start_on_monday() {
+    let monday = 1_704_067_200;
+    let sunday = monday - 86_400;
+    let base: Vec<Candle> = (0..9)
+        .map(|day| candle(sunday + day * 86_400, 1.0, 1.0, 1.0, 1.0, 1.0))
+        .collect();
+    let bars = resample(&base, "1d", "1w").expect("weekly");
+    assert_eq!(bars.iter().map(|bar| bar.time).collect::<Vec<_>>(), vec![monday]);
+    assert_eq!(bars[0].volume, 7.0);
+}
+
+#[test]
+fn gaps_close_the_previous_bucket_and_stale_candles_are_ignored() {
+    let mut timeframe = Timeframe::new("1h", "15m").expect("timeframe");
+    timeframe.push(&candle(0, 1.0, 2.0, 0.5, 1.5, 1.0));
+    timeframe.push(&candle(7200, 3.0, 3.0, 3.0, 3.0, 1.0));
+    assert_eq!(timeframe.candles().len(), 1);
+    assert_eq!(timeframe.candles()[0].close, 1.5);
+    timeframe.push(&candle(900, 9.0, 9.0, 9.0, 9.0, 9.0));
+    assert_eq!(timeframe.candles().len(), 1);
+    assert_eq!(timeframe.forming().map(|bar| bar.time), Some(7200));
+}
+
+#[test]
+fn rejects_timeframes_that_are_not_multiples_of_base() {
+    assert!(Timeframe::new("5m", "15m").is_err());
+    assert!(Timeframe::new("1h", "45").is_err());
+    assert!(Timeframe::new("1h", "1h").is_ok());
+    assert!(build_timeframes(&["4h".to_string(), "7m".to_string()], "1m").is_err());
+}
+
+struct RecordingStrategy {
+    seen: Vec<Option<(usize, i64)>>,
+}
+
+impl Strategy for RecordingStrategy {
+    fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        let hourly = ctx.timeframe("1h").expect("1h timeframe");
+        if let Some(last) = hourly.last() {
+            assert!(last.time + 3600 <= ctx.now + 900);
+        }
+        self.seen.push(hourly.last().map(|bar| (hourly.len(), bar.time)));
+        assert!(ctx.timeframe("1d").is_none());
+        vec![Signal::Hold]
+    }
+}
+
+#[test]
+fn strategy_context_exposes_completed_higher_timeframes_only() {
+    let mut config = Config::default();
+    config.data.candle_interval = "15m".to_string();
+    config.data.timeframes = vec!["1h".to_string()];
+    let candles: Vec<Candle> = (0..9)
+        .map(|index| candle(index * 900, 100.0, 101.0, 99.0, 100.0, 1.0))
+        .collect();
+
+    let trigger_engine = TriggerEngine::new(TriggerMode::Any, vec![Box::new(TimeTrigger::new(15))]);
+    let mut strategy = RecordingStrategy { seen: Vec::new() };
+    let risk = RiskManager::new(RiskLimits {
+        max_trade_ratio: 1.0,
+        min_cash_reserve_ratio: 0.0,
+        max_position_value_ratio: 1.0,
+    })
+    .expect("risk manager");
+    let mut order_flow = OrderFlow::new(risk);
+
+    BacktestEngine
+        .run_strategy(&candles, &config, &trigger_engine, &mut strategy, &mut order_flow, 1000.0)
+        .expect("run strategy");
+
+    assert_eq!(
+        strategy.seen,
+        vec![
+            None,
+            None,
+            None,
+            Some((1, 0)),
+            Some((1, 0)),
+            Some((1, 0)),
+            Some((1, 0)),
+            Some((2, 3600)),
+            Some((2, 3600)),
+        ]
+    );
+}
+
+#[test]
+fn monthly_buckets_follow_calendar_months() {
+    let jan = 1_704_067_200;
+    let feb = 1_706_745_600;
+    let mar = 1_709_251_200;
+    let days: Vec<Candle> = (0..61)
+        .map(|index| candle(jan + index * 86_400, 10.0, 11.0, 9.0, 10.0, 1.0))
+        .collect();
+    let bars = resample(&days, "1d", "1M").expect("resample");
+    let summary: Vec<(i64, f64)> = bars.iter().map(|bar| (bar.time, bar.volume)).collect();
+    assert_eq!(summary, vec![(jan, 31.0), (feb, 29.0)]);
+    assert_eq!(bars_forming_time(&days), Some(mar));
+}
+
+fn bars_forming_time(days: &[Candle]) -> Option<i64> {
+    let mut timeframe = Timeframe::new("1M", "1d").expect("timeframe");
+    for day in days {
+        timeframe.push(day);
+    }
+    timeframe.forming().map(|bar| bar.time)
+}
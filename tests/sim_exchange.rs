@@ -0,0 +1,227 @@
+use merrow::app::sim_server::{router, SimServerAuth};
+use merrow::core::journal::{IntentJournal, IntentOutcome};
+use merrow::exchange::rest::{RestExchange, RestExchangeConfig};
+use merrow::exchange::sim::{SimConfig, SimExchange};
+use merrow::exchange::{CandleRequest, Exchange};
+use merrow::models::{Candle, OrderRequest, OrderStatus, OrderType, Side};
+
+fn candle(time: i64, open: f64, high: f64, low: f64, close: f64) -> Candle {
+    Candle {
+        time,
+        open,
+        high,
+        low,
+        close,
+        volume: 1.0,
+    }
+}
+
+fn candles() -> Vec<Candle> {
+    vec![
+        candle(60, 100.0, 101.0, 99.0, 100.0),
+        candle(120, 100.0, 102.0, 98.0, 101.0),
+        candle(180, 102.0, 103.0, 95.0, 96.0),
+        candle(240, 96.0, 97.0, 94.0, 95.0),
+    ]
+}
+
+fn sim_config() -> SimConfig {
+    SimConfig {
+        symbol: "BTCUSDT".to_string(),
+        base_asset: "BTC".to_string(),
+        quote_asset: "USDT".to_string(),
+        initial_quote: 1000.0,
+        initial_base: 0.0,
+        fee_rate: 0.0,
+        slippage_bps: 0,
+        warmup_bars: 1,
+    }
+}
+
+fn order(id: &str, side: Side, order_type: OrderType, quantity: f64) -> OrderRequest {
+    OrderRequest {
+        client_order_id: id.to_string(),
+        symbol: "BTCUSDT".to_string(),
+        side,
+        order_type,
+        quantity,
+    }
+}
+
+fn balance(exchange: &dyn Exchange, asset: &str) -> (f64, f64) {
+    exchange
+        .fetch_balances()
+        .expect("balances")
+        .into_iter()
+        .find(|balance| balance.asset == asset)
+        .map(|balance| (balance.free, balance.locked))
+        .unwrap_or((0.0, 0.0))
+}
+
+#[test]
+fn market_order_fills_on_next_replayed_bar() {
+    let sim = SimExchange::new(sim_config(), candles()).expect("sim");
+    let ack = sim
+        .place_order(&order("c1", Side::Buy, OrderType::Market, 2.0))
+        .expect("place");
+    assert_eq!(ack.status, OrderStatus::New);
+    assert_eq!(sim.fetch_open_orders().expect("open").len(), 1);
+
+    sim.advance().expect("advance");
+    assert!(sim.fetch_open_orders().expect("open").is_empty());
+    let filled = sim.fetch_order("c1").expect("fetch").expect("order");
+    assert_eq!(filled.status, OrderStatus::Filled);
+    assert_eq!(filled.filled_qty, 2.0);
+    assert_eq!(filled.avg_fill_price, Some(100.0));
+    assert_eq!(balance(&sim, "BTC"), (2.0, 0.0));
+    assert_eq!(balance(&sim, "USDT"), (800.0, 0.0));
+    let positions = sim.fetch_positions().expect("positions");
+    assert_eq!(positions[0].quantity, 2.0);
+    assert_eq!(positions[0].avg_price, 100.0);
+}
+
+#[test]
+fn limit_order_rests_until_crossed_and_cancel_releases_funds() {
+    let sim = SimExchange::new(sim_config(), candles()).expect("sim");
+    sim.place_order(&order("c1", Side::Buy, OrderType::Limit { price: 96.0 }, 1.0))
+        .expect("place");
+    sim.place_order(&order("c2", Side::Buy, OrderType::Limit { price: 90.0 }, 1.0))
+        .expect("place");
+    assert_eq!(balance(&sim, "USDT"), (814.0, 186.0));
+
+    sim.advance().expect("advance");
+    assert_eq!(sim.fetch_open_orders().expect("open").len(), 2);
+    sim.advance().expect("advance");
+    let open = sim.fetch_open_orders().expect("open");
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].client_order_id, "c2");
+
+    sim.cancel_order("c2").expect("cancel");
+    assert_eq!(balance(&sim, "USDT"), (904.0, 0.0));
+    assert!(sim.cancel_order("c2").is_err());
+}
+
+#[test]
+fn insufficient_balance_is_rejected() {
+    let sim = SimExchange::new(sim_config(), candles()).expect("sim");
+    let ack = sim
+        .place_order(&order("c1", Side::Sell, OrderType::Market, 1.0))
+        .expect("place");
+    assert_eq!(ack.status, OrderStatus::Rejected);
+    let ack = sim
+        .place_order(&order("c2", Side::Buy, OrderType::Market, 50.0))
+        .expect("place");
+    assert_eq!(ack.status, OrderStatus::Rejected);
+}
+
+#[test]
+fn fetch_candles_replays_one_bar_per_request() {
+    let sim = SimExchange::new(sim_config(), candles()).expect("sim");
+    let req = CandleRequest {
+        symbol: "BTCUSDT".to_string(),
+        interval: "1m".to_string(),
+        start_time: 0,
+        end_time: 600_000,
+    };
+    assert_eq!(sim.fetch_candles(&req).expect("candles").len(), 2);
+    assert_eq!(sim.fetch_candles(&req).expect("candles").len(), 3);
+    assert_eq!(sim.current_candle().expect("current").map(|c| c.time), Some(180));
+}
+
+fn spawn_server(sim: SimExchange) -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    listener.set_nonblocking(true).expect("nonblocking");
+    let addr = listener.local_addr().expect("addr");
+    let auth = SimServerAuth {
+        api_key: Some("key".to_string()),
+        api_secret: Some("secret".to_string()),
+    };
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).expect("listener");
+            axum::serve(listener, router(sim, auth)).await.expect("serve");
+        });
+    });
+    format!("http://{addr}")
+}
+
+fn rest_client(base_url: &str, api_secret: &str) -> RestExchange {
+    RestExchange::new(RestExchangeConfig {
+        base_url: base_url.to_string(),
+        api_key: Some("key".to_string()),
+        api_secret: Some(api_secret.to_string()),
+        passphrase: None,
+        timeout_secs: 5,
+    })
+    .expect("client")
+}
+
+fn candle_request() -> CandleRequest {
+    CandleRequest {
+        symbol: "BTCUSDT".to_string(),
+        interval: "1m".to_string(),
+        start_time: 0,
+        end_time: 600_000,
+    }
+}
+
+#[test]
+fn rest_client_round_trips_through_sim_server() {
+    let base_url = spawn_server(SimExchange::new(sim_config(), candles()).expect("sim"));
+    let client = rest_client(&base_url, "secret");
+
+    let ack = client
+        .place_order(&order("c1", Side::Buy, OrderType::Market, 1.0))
+        .expect("place");
+    assert_eq!(ack.status, OrderStatus::New);
+    assert_eq!(client.fetch_open_orders().expect("open").len(), 1);
+
+    let candles = client.fetch_candles(&candle_request()).expect("candles");
+    assert_eq!(candles.last().map(|c| c.time), Some(120));
+    assert!(client.fetch_open_orders().expect("open").is_empty());
+    assert_eq!(balance(&client, "BTC"), (1.0, 0.0));
+    assert_eq!(client.fetch_positions().expect("positions").len(), 1);
+
+    let bad_client = rest_client(&base_url, "wrong");
+    assert!(bad_client.fetch_balances().is_err());
+}
+
+#[test]
+fn journal_recovers_filled_order_through_sim_server() {
+    let base_url = spawn_server(SimExchange::new(sim_config(), candles()).expect("sim"));
+    let client = rest_client(&base_url, "secret");
+    let path = std::env::temp_dir().join(format!(
+        "merrow_sim_server_journal_tests/{}.jsonl",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let journal = IntentJournal::new(&path);
+
+    let filled = order("c1", Side::Buy, OrderType::Market, 1.0);
+    let lost = order("c2", Side::Buy, OrderType::Market, 1.0);
+    journal.record_intent(&filled, 100.0, 60).expect("intent");
+    journal.record_intent(&lost, 100.0, 60).expect("intent");
+    client.place_order(&filled).expect("place");
+    journal.record_error("c1", "timeout", 60).expect("error");
+    client.fetch_candles(&candle_request()).expect("candles");
+
+    assert!(client.fetch_order("missing").expect("lookup").is_none());
+    assert_eq!(journal.pending().expect("pending").len(), 2);
+    let recovered = journal.recover(&client, 120).expect("recover");
+    assert_eq!(recovered.len(), 2);
+    match &recovered[0].outcome {
+        IntentOutcome::Sent(ack) => {
+            assert_eq!(ack.client_order_id, "c1");
+            assert_eq!(ack.status, OrderStatus::Filled);
+            assert!((ack.filled_qty - 1.0).abs() < 1e-9);
+            assert!((ack.avg_fill_price.expect("avg price") - 100.0).abs() < 1e-9);
+        }
+        IntentOutcome::Unsent => panic!("filled order reported unsent"),
+    }
+    assert!(matches!(recovered[1].outcome, IntentOutcome::Unsent));
+    assert!(journal.pending().expect("pending").is_empty());
+}
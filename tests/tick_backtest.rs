@@ -0,0 +1,231 @@
+use merrow::backtest::{BacktestEngine, TickBacktestResult};
+use merrow::config::Config;
+use merrow::core::order_flow::OrderFlow;
+use merrow::core::risk::{RiskLimits, RiskManager};
+use merrow::core::strategy::Strategy;
+use merrow::core::triggers::{TimeTrigger, TriggerEngine};
+use merrow::core::{StrategyContext, TriggerMode};
+use merrow::data::trade_loader::{load_backtest_trades, load_trades_from_csv, parse_time_ms};
+use merrow::models::{Candle, Signal, TradePrint};
+use std::path::PathBuf;
+
+fn fixture_path(name: &str) -> String {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests");
+    path.push("fixtures");
+    path.push(name);
+    path.to_string_lossy().into_owned()
+}
+
+fn print(time_ms: i64, price: f64, quantity: f64) -> TradePrint {
+    TradePrint {
+        time_ms,
+        price,
+        quantity,
+    }
+}
+
+struct ScriptedStrategy {
+    signals: Vec<Signal>,
+    seen: Vec<(i64, i64)>,
+}
+
+impl Strategy for ScriptedStrategy {
+    fn on_tick(&mut self, ctx: &StrategyContext) -> Vec<Signal> {
+        self.seen.push((ctx.candle.time, ctx.now));
+        if self.signals.is_empty() {
+            return vec![Signal::Hold];
+        }
+        vec![self.signals.remove(0)]
+    }
+}
+
+fn run(
+    config: &Config,
+    prints: &[TradePrint],
+    signals: Vec<Signal>,
+) -> (TickBacktestResult, Vec<(i64, i64)>) {
+    let trigger_engine = TriggerEngine::new(TriggerMode::Any, vec![Box::new(TimeTrigger::new(1))]);
+    let mut strategy = ScriptedStrategy {
+        signals,
+        seen: Vec::new(),
+    };
+    let risk = RiskManager::new(RiskLimits {
+        max_trade_ratio: 1.0,
+        min_cash_reserve_ratio: 0.0,
+        max_position_value_ratio: 1.0,
+    })
+    .expect("risk manager");
+    let mut order_flow = OrderFlow::new(risk);
+    let result = BacktestEngine
+        .run_strategy_on_prints(
+            prints,
+            config,
+            &trigger_engine,
+            &mut strategy,
+            &mut order_flow,
+            1000.0,
+        )
+        .expect("run tick backtest");
+    (result, strategy.seen)
+}
+
+fn market_config(latency_ms: u32) -> Config {
+    let mut config = Config::default();
+    config.orders.order_type = "market".to_string();
+    config.orders.slippage_bps = 0;
+    config.orders.fee_rate = 0.0;
+    config.backtest.granularity = "trade".to_string();
+    config.backtest.latency_ms = latency_ms;
+    config
+}
+
+#[test]
+fn market_orders_fill_on_first_print_after_latency() {
+    let prints = vec![
+        print(1_000, 100.0, 1.0),
+        print(30_000, 101.0, 1.0),
+        print(60_500, 102.0, 1.0),
+        print(61_000, 103.0, 1.0),
+        print(62_000, 104.0, 1.0),
+        print(125_000, 105.0, 1.0),
+    ];
+
+    let (tick, seen) = run(&market_config(1_500), &prints, vec![Signal::Buy]);
+    assert_eq!(seen, vec![(0, 60), (60, 120), (120, 180)]);
+    assert_eq!(
+        tick.bars[0],
+        Candle {
+            time: 0,
+            open: 100.0,
+            high: 101.0,
+            low: 100.0,
+            close: 101.0,
+            volume: 2.0,
+        }
+    );
+    assert_eq!(tick.bars.len(), 3);
+    assert_eq!(tick.result.trades.len(), 1);
+    assert_eq!(tick.result.trades[0].price, 104.0);
+    assert_eq!(tick.result.trades[0].time, 62);
+    assert_eq!(tick.result.equity_curve.len(), 3);
+
+    let (tick, _) = run(&market_config(0), &prints, vec![Signal::Buy]);
+    assert_eq!(tick.result.trades[0].price, 102.0);
+    assert_eq!(tick.result.trades[0].time, 60);
+}
+
+#[test]
+fn limit_orders_fill_against_print_volume_in_time_order() {
+    let mut config = market_config(0);
+    config.orders.order_type = "limit".to_string();
+    config.orders.limit_price_offset_bps = 100;
+    let prints = vec![
+        print(1_000, 100.0, 1.0),
+        print(59_000, 100.0, 1.0),
+        print(60_100, 99.5, 5.0),
+        print(61_000, 99.0, 0.3),
+        print(62_000, 98.0, 10.0),
+    ];
+
+    let (tick, _) = run(&config, &prints, vec![Signal::Buy]);
+    let fills: Vec<(i64, f64, f64)> = tick
+        .result
+        .trades
+        .iter()
+        .map(|trade| (trade.time, trade.price, trade.quantity))
+        .collect();
+    assert_eq!(fills.len(), 2);
+    assert_eq!(fills[0].0, 61);
+    assert!((fills[0].1 - 99.0).abs() < 1e-9);
+    assert!((fills[0].2 - 0.3).abs() < 1e-9);
+    assert_eq!(fills[1].0, 62);
+    assert!((fills[1].2 - 4.7).abs() < 1e-9);
+    let position = &tick.result.account.positions[0];
+    assert!((position.quantity - 5.0).abs() < 1e-9);
+}
+
+#[test]
+fn activity_bars_close_on_the_print_that_completes_them() {
+    let mut config = market_config(0);
+    config.data.bars = "tick".to_string();
+    config.data.bar_size = 2.0;
+    let prints = vec![
+        print(1_000, 100.0, 1.0),
+        print(60_000, 101.0, 1.0),
+        print(61_000, 102.0, 1.0),
+        print(120_000, 103.0, 1.0),
+        print(150_000, 104.0, 1.0),
+    ];
+
+    let (tick, seen) = run(&config, &prints, vec![Signal::Buy]);
+    assert_eq!(seen, vec![(1, 60), (61, 120)]);
+    assert_eq!(tick.result.trades[0].price, 102.0);
+
+    let mut unsorted = prints.clone();
+    unsorted.swap(0, 1);
+    let trigger_engine = TriggerEngine::new(TriggerMode::Any, Vec::new());
+    let risk = RiskManager::new(RiskLimits {
+        max_trade_ratio: 1.0,
+        min_cash_reserve_ratio: 0.0,
+        max_position_value_ratio: 1.0,
+    })
+    .expect("risk manager");
+    let mut strategy = ScriptedStrategy {
+        signals: Vec::new(),
+        seen: Vec::new(),
+    };
+    let result = BacktestEngine.run_strategy_on_prints(
+        &unsorted,
+        &config,
+        &trigger_engine,
+        &mut strategy,
+        &mut OrderFlow::new(risk),
+        1000.0,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn loads_trade_prints_from_csv_layouts() {
+    let prints = load_trades_from_csv(&fixture_path("trades.csv"), b',').expect("trades");
+    assert_eq!(
+        prints,
+        vec![
+            print(1_704_067_200_500, 100.0, 1.5),
+            print(1_704_067_201_250, 100.5, 0.2),
+            print(1_704_067_262_000, 101.0, 0.4),
+        ]
+    );
+
+    let agg = load_trades_from_csv(&fixture_path("binance_agg_trades.csv"), b',').expect("agg");
+    assert_eq!(agg.len(), 3);
+    assert_eq!(agg[1], print(1_704_067_200_456, 42000.2, 0.25));
+
+    assert_eq!(parse_time_ms("1704067200").expect("seconds"), 1_704_067_200_000);
+    assert_eq!(
+        parse_time_ms("1704067200123456789").expect("nanos"),
+        1_704_067_200_123
+    );
+    assert_eq!(
+        parse_time_ms("1585180700.0647").expect("fractional"),
+        1_585_180_700_065
+    );
+}
+
+#[test]
+fn trade_granularity_is_validated_and_filtered_to_range() {
+    let mut config = Config::default();
+    config.backtest.granularity = "tick".to_string();
+    assert!(config.validate().is_err());
+    config.backtest.granularity = "trade".to_string();
+    assert!(config.validate().is_err());
+    config.data.trades_path = Some(fixture_path("trades.csv"));
+    assert!(config.validate().is_ok());
+
+    config.backtest.start_time = Some("2024-01-01T00:00:00Z".to_string());
+    config.backtest.end_time = Some("2024-01-01T00:01:00Z".to_string());
+    let prints = load_backtest_trades(&config).expect("load");
+    assert_eq!(prints.len(), 2);
+    assert_eq!(prints[1].time_ms, 1_704_067_201_250);
+}
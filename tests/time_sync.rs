@@ -0,0 +1,67 @@
+use merrow::app::metrics;
+use merrow::exchange::time_sync::{local_time_ms, ClockSync, TimeSyncConfig};
+use merrow::Error;
+
+#[test]
+fn offset_uses_round_trip_midpoint() {
+    let clock = ClockSync::new("test", TimeSyncConfig::default());
+    let sample = clock.record(1_000, 1_550, 1_100).expect("record");
+    assert_eq!(sample.latency_ms, 100);
+    assert_eq!(sample.offset_ms, 500);
+    assert_eq!(clock.offset_ms().expect("offset"), 500);
+}
+
+#[test]
+fn now_applies_server_offset() {
+    let clock = ClockSync::new("test", TimeSyncConfig::default());
+    clock
+        .sync(|| Ok(local_time_ms()? - 60_000))
+        .expect("sync");
+    let drift = local_time_ms().expect("local") - clock.now_ms().expect("now");
+    assert!((59_000..=61_000).contains(&drift));
+}
+
+#[test]
+fn sync_is_due_only_after_interval() {
+    let clock = ClockSync::new("test", TimeSyncConfig::default());
+    assert!(clock.is_due().expect("due"));
+    clock.record(0, 0, 0).expect("record");
+    assert!(!clock.is_due().expect("due"));
+
+    let disabled = ClockSync::new(
+        "test",
+        TimeSyncConfig {
+            interval_secs: 0,
+            alert_threshold_ms: 1000,
+        },
+    );
+    assert!(!disabled.is_due().expect("due"));
+}
+
+#[test]
+fn failed_sync_keeps_previous_offset() {
+    let clock = ClockSync::new("test", TimeSyncConfig::default());
+    clock.record(1_000, 1_200, 1_000).expect("record");
+    assert!(clock.sync(|| Err(Error::new("offline"))).is_err());
+    assert_eq!(clock.offset_ms().expect("offset"), 200);
+}
+
+#[test]
+fn drift_beyond_threshold_is_exported_as_alert() {
+    let clock = ClockSync::new(
+        "test",
+        TimeSyncConfig {
+            interval_secs: 300,
+            alert_threshold_ms: 500,
+        },
+    );
+    clock.record(0, 2_000, 0).expect("record");
+    let rendered = metrics::render();
+    let alerts = rendered
+        .lines()
+        .find(|line| line.starts_with("merrow_clock_drift_alerts_total "))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|value| value.parse::<u64>().ok())
+        .expect("alert metric");
+    assert!(alerts >= 1);
+}